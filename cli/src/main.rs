@@ -1,22 +1,49 @@
 use anyhow::anyhow;
 use ethabi::{
-	decode, encode,
+	canonical_signature, decode, encode,
 	param_type::{ParamType, Reader},
 	token::{LenientTokenizer, StrictTokenizer, Token, Tokenizer},
-	Contract, Event, Function, Hash,
+	AbiError, Contract, Event, Function, Hash,
 };
 use itertools::Itertools;
+use serde_json::json;
 use sha3::{Digest, Keccak256};
-use std::fs::File;
+use std::{
+	fs::File,
+	io::{self, BufRead},
+};
 use structopt::StructOpt;
 
 #[derive(StructOpt, Debug)]
 /// Ethereum ABI coder.
-enum Opt {
+struct Opt {
+	/// Print the output as JSON.
+	#[structopt(long, global = true)]
+	json: bool,
+	/// Decode every line of the standard input independently, printing a JSON result per line.
+	#[structopt(long, global = true)]
+	batch: bool,
+	/// Also load the JSON ABIs of Vyper and old solc versions, e.g. without `type` or with units.
+	#[structopt(long, global = true)]
+	lenient_abi: bool,
+	#[structopt(subcommand)]
+	command: Command,
+}
+
+#[derive(StructOpt, Debug)]
+enum Command {
 	/// Encode ABI call.
 	Encode(Encode),
 	/// Decode ABI call result.
 	Decode(Decode),
+	/// Compute the selector of a function or error, or the topic hash of an event.
+	Selector {
+		/// Declaration such as `transfer(address,uint)` or `event Transfer(address,address,uint)`,
+		/// or the path of a JSON ABI file.
+		signature_or_abi_path: String,
+		/// Name or signature of a function, event or error of the ABI file.
+		name_or_signature: Option<String>,
+	},
 }
 
 #[derive(StructOpt, Debug)]
@@ -31,6 +58,17 @@ enum Encode {
 		#[structopt(short, long)]
 		lenient: bool,
 	},
+	/// Load constructor from JSON ABI file and append its params to the bytecode.
+	Constructor {
+		abi_path: String,
+		/// Hex encoded contract bytecode.
+		code: String,
+		#[structopt(short, number_of_values = 1)]
+		params: Vec<String>,
+		/// Allow short representation of input params.
+		#[structopt(short, long)]
+		lenient: bool,
+	},
 	/// Specify types of input params inline.
 	Params {
 		/// Pairs of types directly followed by params in the form:
@@ -44,26 +82,60 @@ enum Encode {
 	},
 }
 
+/// Data omitted from the decode commands is read from the standard input, and so is the JSON ABI
+/// whose path is `-`.
 #[derive(StructOpt, Debug)]
 enum Decode {
 	/// Load function from JSON ABI file.
-	Function { abi_path: String, function_name_or_signature: String, data: String },
+	Function { abi_path: String, function_name_or_signature: String, data: Option<String> },
+	/// Decode the return data of a function of a JSON ABI file, printing named outputs.
+	Output { abi_path: String, function_name_or_signature: String, data: Option<String> },
 	/// Specify types of input params inline.
 	Params {
 		#[structopt(short, name = "type", number_of_values = 1)]
 		types: Vec<String>,
-		data: String,
+		data: Option<String>,
 	},
+	/// Decode call data, finding the function by its selector in a JSON ABI file.
+	Calldata { abi_path: String, data: Option<String> },
 	/// Decode event log.
+	///
+	/// Data read from the standard input may be preceded by topics, separated by whitespace, which
+	/// follow those given with `-l`.
 	Log {
 		abi_path: String,
 		event_name_or_signature: String,
 		#[structopt(short = "l", name = "topic", number_of_values = 1)]
 		topics: Vec<String>,
-		data: String,
+		data: Option<String>,
 	},
 }
 
+impl Decode {
+	fn abi_path(&self) -> Option<&str> {
+		match self {
+			Decode::Function { abi_path, .. }
+			| Decode::Output { abi_path, .. }
+			| Decode::Calldata { abi_path, .. }
+			| Decode::Log { abi_path, .. } => Some(abi_path),
+			Decode::Params { .. } => None,
+		}
+	}
+
+	fn data(&self) -> Option<&str> {
+		match self {
+			Decode::Function { data, .. }
+			| Decode::Output { data, .. }
+			| Decode::Params { data, .. }
+			| Decode::Calldata { data, .. }
+			| Decode::Log { data, .. } => data.as_deref(),
+		}
+	}
+}
+
+/// Decoder of the data of a decode command, with the ABI it needs loaded once.
+type Decoder = Box<dyn Fn(&str, bool) -> anyhow::Result<String>>;
+
 fn main() -> anyhow::Result<()> {
 	println!("{}", execute(std::env::args())?);
 
@@ -75,26 +147,162 @@ where
 	I: IntoIterator,
 	I::Item: Into<std::ffi::OsString> + Clone,
 {
-	let opt = Opt::from_iter(args);
+	execute_with_stdin(args, &mut io::stdin().lock())
+}
 
-	match opt {
-		Opt::Encode(Encode::Function { abi_path, function_name_or_signature, params, lenient }) => {
-			encode_input(&abi_path, &function_name_or_signature, &params, lenient)
+fn execute_with_stdin<I>(args: I, stdin: &mut dyn BufRead) -> anyhow::Result<String>
+where
+	I: IntoIterator,
+	I::Item: Into<std::ffi::OsString> + Clone,
+{
+	let Opt { json, batch, lenient_abi, command } = Opt::from_iter(args);
+
+	match command {
+		Command::Decode(decode) => decode_stdin(decode, json, batch, lenient_abi, stdin),
+		_ if batch => Err(anyhow!("only the decode commands have a batch mode")),
+		Command::Encode(Encode::Function { abi_path, function_name_or_signature, params, lenient }) => {
+			let function = load_function(&load_contract(&abi_path, lenient_abi, stdin)?, &function_name_or_signature)?;
+			encode_input(&function, &params, lenient, json)
+		}
+		Command::Encode(Encode::Constructor { abi_path, code, params, lenient }) => {
+			encode_constructor(&load_contract(&abi_path, lenient_abi, stdin)?, &abi_path, &code, &params, lenient, json)
+		}
+		Command::Encode(Encode::Params { params, lenient }) => encode_params(&params, lenient, json),
+		Command::Selector { signature_or_abi_path, name_or_signature } => {
+			let item = match name_or_signature {
+				None => Item::parse(&signature_or_abi_path)?,
+				Some(name_or_signature) => load_item(
+					&load_contract(&signature_or_abi_path, lenient_abi, stdin)?,
+					&signature_or_abi_path,
+					&name_or_signature,
+				)?,
+			};
+			selector(&item, json)
+		}
+	}
+}
+
+/// Runs a decode command on its data, read from the standard input if it is not given, or on
+/// every non empty line of the standard input in batch mode.
+fn decode_stdin(
+	decode: Decode,
+	json: bool,
+	batch: bool,
+	lenient_abi: bool,
+	stdin: &mut dyn BufRead,
+) -> anyhow::Result<String> {
+	let data = decode.data().map(str::to_owned);
+	if decode.abi_path() == Some("-") && (batch || data.is_none()) {
+		return Err(anyhow!("the ABI and the data cannot both be read from the standard input"));
+	}
+	if batch && data.is_some() {
+		return Err(anyhow!("the data is read from the standard input in batch mode"));
+	}
+
+	let decoder: Decoder = match decode {
+		Decode::Function { abi_path, function_name_or_signature, .. } => {
+			let function = load_function(&load_contract(&abi_path, lenient_abi, stdin)?, &function_name_or_signature)?;
+			Box::new(move |data, json| decode_call_output(&function, data, json))
 		}
-		Opt::Encode(Encode::Params { params, lenient }) => encode_params(&params, lenient),
-		Opt::Decode(Decode::Function { abi_path, function_name_or_signature, data }) => {
-			decode_call_output(&abi_path, &function_name_or_signature, &data)
+		Decode::Output { abi_path, function_name_or_signature, .. } => {
+			let function = load_function(&load_contract(&abi_path, lenient_abi, stdin)?, &function_name_or_signature)?;
+			Box::new(move |data, json| decode_named_output(&function, data, json))
+		}
+		Decode::Params { types, .. } => {
+			let types: Vec<ParamType> = types.iter().map(|s| Reader::read(s)).collect::<Result<_, _>>()?;
+			Box::new(move |data, json| decode_params(&types, data, json))
+		}
+		Decode::Calldata { abi_path, .. } => {
+			let contract = load_contract(&abi_path, lenient_abi, stdin)?;
+			Box::new(move |data, json| decode_calldata(&contract, data, json))
+		}
+		Decode::Log { abi_path, event_name_or_signature, topics, .. } => {
+			let event = load_event(&load_contract(&abi_path, lenient_abi, stdin)?, &event_name_or_signature)?;
+			Box::new(move |input, json| {
+				let mut fields: Vec<&str> = input.split_whitespace().collect();
+				let data = fields.pop().unwrap_or_default();
+				let topics: Vec<&str> = topics.iter().map(String::as_str).chain(fields).collect();
+				decode_log(&event, &topics, data, json)
+			})
+		}
+	};
+
+	match data {
+		Some(data) => decoder(&data, json),
+		None if batch => {
+			let mut results = Vec::new();
+			for line in stdin.lines() {
+				let line = line?;
+				if line.trim().is_empty() {
+					continue;
+				}
+				let result = decoder(line.trim(), true);
+				results.push(result.unwrap_or_else(|err| json!({ "error": err.to_string() }).to_string()));
+			}
+			Ok(results.join("\n"))
+		}
+		None => {
+			let mut data = String::new();
+			stdin.read_to_string(&mut data)?;
+			decoder(data.trim(), json)
+		}
+	}
+}
+
+/// Function, event or error whose selector or topic hash is computed.
+enum Item {
+	Function(Function),
+	Event(Event),
+	Error(AbiError),
+}
+
+impl Item {
+	/// Parses a declaration, a function unless it starts with `event` or `error`.
+	fn parse(declaration: &str) -> anyhow::Result<Self> {
+		let declaration = declaration.trim();
+		let item = match declaration.split_whitespace().next() {
+			Some("event") => Item::Event(Event::parse(declaration)?),
+			Some("error") => Item::Error(AbiError::parse(declaration)?),
+			_ => Item::Function(Function::parse(declaration)?),
+		};
+		Ok(item)
+	}
+
+	fn canonical_signature(&self) -> String {
+		match self {
+			Item::Function(function) => function.canonical_signature(),
+			Item::Event(event) => event.canonical_signature(),
+			Item::Error(error) => {
+				let kinds = error.inputs.iter().map(|param| param.kind.clone()).collect::<Vec<_>>();
+				canonical_signature(&error.name, &kinds)
+			}
 		}
-		Opt::Decode(Decode::Params { types, data }) => decode_params(&types, &data),
-		Opt::Decode(Decode::Log { abi_path, event_name_or_signature, topics, data }) => {
-			decode_log(&abi_path, &event_name_or_signature, &topics, &data)
+	}
+
+	/// Returns whether the value is a selector or a topic, and the value.
+	fn hash(&self) -> (&'static str, Vec<u8>) {
+		match self {
+			Item::Function(function) => ("selector", function.selector().to_vec()),
+			Item::Event(event) => ("topic", event.signature().as_bytes().to_vec()),
+			Item::Error(error) => ("selector", error.selector().to_vec()),
 		}
 	}
 }
 
-fn load_function(path: &str, name_or_signature: &str) -> anyhow::Result<Function> {
-	let file = File::open(path)?;
-	let contract = Contract::load(file)?;
+/// Loads the JSON ABI at `path`, or from the standard input if it is `-`, normalizing the ABIs
+/// of Vyper and old solc versions if `lenient`.
+fn load_contract(path: &str, lenient: bool, stdin: &mut dyn BufRead) -> anyhow::Result<Contract> {
+	let reader: Box<dyn io::Read + '_> = match path {
+		"-" => Box::new(stdin),
+		path => Box::new(File::open(path)?),
+	};
+	match lenient {
+		true => Ok(Contract::load_lenient(reader)?),
+		false => Ok(Contract::load(reader)?),
+	}
+}
+
+fn load_function(contract: &Contract, name_or_signature: &str) -> anyhow::Result<Function> {
 	let params_start = name_or_signature.find('(');
 
 	match params_start {
@@ -125,9 +333,7 @@ fn load_function(path: &str, name_or_signature: &str) -> anyhow::Result<Function
 	}
 }
 
-fn load_event(path: &str, name_or_signature: &str) -> anyhow::Result<Event> {
-	let file = File::open(path)?;
-	let contract = Contract::load(file)?;
+fn load_event(contract: &Contract, name_or_signature: &str) -> anyhow::Result<Event> {
 	let params_start = name_or_signature.find('(');
 
 	match params_start {
@@ -169,19 +375,46 @@ fn parse_tokens(params: &[(ParamType, &str)], lenient: bool) -> anyhow::Result<V
 		.map_err(From::from)
 }
 
-fn encode_input(path: &str, name_or_signature: &str, values: &[String], lenient: bool) -> anyhow::Result<String> {
-	let function = load_function(path, name_or_signature)?;
-
+fn encode_input(function: &Function, values: &[String], lenient: bool, json: bool) -> anyhow::Result<String> {
 	let params: Vec<_> =
 		function.inputs.iter().map(|param| param.kind.clone()).zip(values.iter().map(|v| v as &str)).collect();
 
 	let tokens = parse_tokens(&params, lenient)?;
 	let result = function.encode_input(&tokens)?;
 
-	Ok(hex::encode(&result))
+	Ok(format_encoded(&result, json))
 }
 
-fn encode_params(params: &[String], lenient: bool) -> anyhow::Result<String> {
+fn encode_constructor(
+	contract: &Contract,
+	path: &str,
+	code: &str,
+	values: &[String],
+	lenient: bool,
+	json: bool,
+) -> anyhow::Result<String> {
+	let code = parse_hex(code)?;
+
+	let result = match contract.constructor {
+		Some(ref constructor) => {
+			let params: Vec<_> = constructor
+				.inputs
+				.iter()
+				.map(|param| param.kind.clone())
+				.zip(values.iter().map(|v| v as &str))
+				.collect();
+
+			let tokens = parse_tokens(&params, lenient)?;
+			constructor.encode_input(code, &tokens)?
+		}
+		None if values.is_empty() => code,
+		None => return Err(anyhow!("`{}` has no constructor taking params", path)),
+	};
+
+	Ok(format_encoded(&result, json))
+}
+
+fn encode_params(params: &[String], lenient: bool, json: bool) -> anyhow::Result<String> {
 	assert_eq!(params.len() % 2, 0);
 
 	let params = params
@@ -193,17 +426,20 @@ fn encode_params(params: &[String], lenient: bool) -> anyhow::Result<String> {
 	let tokens = parse_tokens(params.as_slice(), lenient)?;
 	let result = encode(&tokens);
 
-	Ok(hex::encode(&result))
+	Ok(format_encoded(&result, json))
 }
 
-fn decode_call_output(path: &str, name_or_signature: &str, data: &str) -> anyhow::Result<String> {
-	let function = load_function(path, name_or_signature)?;
-	let data: Vec<u8> = hex::decode(&data)?;
-	let tokens = function.decode_output(&data)?;
-	let types = function.outputs;
+fn decode_call_output(function: &Function, data: &str, json: bool) -> anyhow::Result<String> {
+	let tokens = function.decode_output(&parse_hex(data)?)?;
+	let types = &function.outputs;
 
 	assert_eq!(types.len(), tokens.len());
 
+	if json {
+		let params = types.iter().zip(tokens.iter()).map(|(ty, to)| format_json_param(Some(&ty.name), &ty.kind, to));
+		return Ok(json!(params.collect::<Vec<_>>()).to_string());
+	}
+
 	let result = types
 		.iter()
 		.zip(tokens.iter())
@@ -214,27 +450,92 @@ fn decode_call_output(path: &str, name_or_signature: &str, data: &str) -> anyhow
 	Ok(result)
 }
 
-fn decode_params(types: &[String], data: &str) -> anyhow::Result<String> {
-	let types: Vec<ParamType> = types.iter().map(|s| Reader::read(s)).collect::<Result<_, _>>()?;
+fn decode_named_output(function: &Function, data: &str, json: bool) -> anyhow::Result<String> {
+	let tokens = function.decode_output(&parse_hex(data)?)?;
 
-	let data: Vec<u8> = hex::decode(&data)?;
+	if json {
+		let params = function
+			.outputs
+			.iter()
+			.zip(tokens.iter())
+			.map(|(param, token)| format_json_param(Some(&param.name), &param.kind, token));
+		return Ok(json!({ "function": function.signature(), "outputs": params.collect::<Vec<_>>() }).to_string());
+	}
 
-	let tokens = decode(&types, &data)?;
+	// unnamed outputs are shown by their position
+	let result = function
+		.outputs
+		.iter()
+		.zip(tokens.iter())
+		.enumerate()
+		.map(|(i, (param, token))| match param.name.is_empty() {
+			true => format!("{} {}", i, token),
+			false => format!("{} {}", param.name, token),
+		})
+		.collect::<Vec<String>>()
+		.join("\n");
+
+	Ok(result)
+}
+
+fn decode_params(types: &[ParamType], data: &str, json: bool) -> anyhow::Result<String> {
+	let tokens = decode(types, &parse_hex(data)?)?;
 
 	assert_eq!(types.len(), tokens.len());
 
+	if json {
+		let params = types.iter().zip(tokens.iter()).map(|(ty, to)| format_json_param(None, ty, to));
+		return Ok(json!(params.collect::<Vec<_>>()).to_string());
+	}
+
 	let result =
 		types.iter().zip(tokens.iter()).map(|(ty, to)| format!("{} {}", ty, to)).collect::<Vec<String>>().join("\n");
 
 	Ok(result)
 }
 
-fn decode_log(path: &str, name_or_signature: &str, topics: &[String], data: &str) -> anyhow::Result<String> {
-	let event = load_event(path, name_or_signature)?;
+fn decode_calldata(contract: &Contract, data: &str, json: bool) -> anyhow::Result<String> {
+	let data = parse_hex(data)?;
+	if data.len() < 4 {
+		return Err(anyhow!("calldata of {} bytes is shorter than a selector", data.len()));
+	}
+
+	let mut selector = [0u8; 4];
+	selector.copy_from_slice(&data[..4]);
+	let function = contract.function_by_selector(selector)?;
+	let tokens = function.decode_call(&data)?;
+
+	if json {
+		let params = function
+			.inputs
+			.iter()
+			.zip(tokens.iter())
+			.map(|(param, token)| format_json_param(Some(&param.name), &param.kind, token));
+		return Ok(json!({ "function": function.signature(), "params": params.collect::<Vec<_>>() }).to_string());
+	}
+
+	let result = std::iter::once(function.signature())
+		.chain(function.inputs.iter().zip(tokens.iter()).map(|(param, token)| format!("{} {}", param.name, token)))
+		.collect::<Vec<String>>()
+		.join("\n");
+
+	Ok(result)
+}
+
+fn decode_log(event: &Event, topics: &[&str], data: &str, json: bool) -> anyhow::Result<String> {
 	let topics: Vec<Hash> = topics.iter().map(|t| t.parse()).collect::<Result<_, _>>()?;
-	let data = hex::decode(data)?;
+	let data = parse_hex(data)?;
 	let decoded = event.parse_log((topics, data).into())?;
 
+	if json {
+		let params = event
+			.inputs
+			.iter()
+			.zip(decoded.params.iter())
+			.map(|(param, log_param)| format_json_param(Some(&log_param.name), &param.kind, &log_param.value));
+		return Ok(json!(params.collect::<Vec<_>>()).to_string());
+	}
+
 	let result = decoded
 		.params
 		.into_iter()
@@ -245,13 +546,138 @@ fn decode_log(path: &str, name_or_signature: &str, topics: &[String], data: &str
 	Ok(result)
 }
 
+fn selector(item: &Item, json: bool) -> anyhow::Result<String> {
+	let (kind, hash) = item.hash();
+
+	match json {
+		true => {
+			Ok(json!({ "signature": item.canonical_signature(), kind: format!("0x{}", hex::encode(hash)) }).to_string())
+		}
+		false => Ok(hex::encode(hash)),
+	}
+}
+
+/// Finds the function, event or error of the ABI at `path` named `name_or_signature`, or with
+/// the same canonical signature as it.
+fn load_item(contract: &Contract, path: &str, name_or_signature: &str) -> anyhow::Result<Item> {
+	let name = name_or_signature.split('(').next().unwrap_or_default().trim();
+	let items = contract
+		.functions()
+		.filter(|function| function.name == name)
+		.cloned()
+		.map(Item::Function)
+		.chain(contract.events().filter(|event| event.name == name).cloned().map(Item::Event))
+		.chain(contract.errors().filter(|error| error.name == name).cloned().map(Item::Error));
+
+	let mut items: Vec<_> = match name_or_signature.contains('(') {
+		true => {
+			// types are spelled canonically whatever the kind of item
+			let signature = Function::parse(name_or_signature)?.canonical_signature();
+			items.filter(|item| item.canonical_signature() == signature).collect()
+		}
+		false => items.collect(),
+	};
+	match items.len() {
+		0 => Err(anyhow!("no function, event or error `{}` in `{}`", name_or_signature, path)),
+		1 => Ok(items.remove(0)),
+		_ => {
+			Err(anyhow!("More than one item found for name `{}`, try providing the full signature", name_or_signature))
+		}
+	}
+}
+
+/// Parses hex data, optionally `0x` prefixed.
+fn parse_hex(data: &str) -> anyhow::Result<Vec<u8>> {
+	let data = data.trim();
+	Ok(hex::decode(data.strip_prefix("0x").unwrap_or(data))?)
+}
+
+fn format_encoded(data: &[u8], json: bool) -> String {
+	match json {
+		true => json!({ "data": format!("0x{}", hex::encode(data)) }).to_string(),
+		false => hex::encode(data),
+	}
+}
+
+fn format_json_param(name: Option<&str>, kind: &ParamType, value: &Token) -> serde_json::Value {
+	json!({ "name": name, "type": kind.to_string(), "value": format_json_value(value) })
+}
+
+/// Formats arrays and tuples as JSON arrays of their values, and the other tokens as strings.
+fn format_json_value(value: &Token) -> serde_json::Value {
+	match value {
+		Token::Array(tokens) | Token::FixedArray(tokens) | Token::Tuple(tokens) => {
+			tokens.iter().map(format_json_value).collect()
+		}
+		_ => value.to_string().into(),
+	}
+}
+
 fn hash_signature(sig: &str) -> Hash {
-	Hash::from_slice(Keccak256::digest(&sig.replace(" ", "").as_bytes()).as_slice())
+	Hash::from_slice(&Keccak256::digest(sig.replace(" ", "").as_bytes()))
 }
 
 #[cfg(test)]
 mod tests {
-	use super::execute;
+	use super::{execute, execute_with_stdin};
+
+	#[test]
+	fn constructor_encode() {
+		let command =
+			"ethabi encode constructor ../res/constructor.abi 0x6060 -p 0x1111111111111111111111111111111111111111"
+				.split(' ');
+		let expected = "60600000000000000000000000001111111111111111111111111111111111111111";
+		assert_eq!(execute(command).unwrap(), expected);
+
+		let command = "ethabi encode constructor ../res/constructor.abi 6060".split(' ');
+		assert!(execute(command).is_err());
+
+		let command = "ethabi encode constructor ../res/test.abi 6060".split(' ');
+		assert_eq!(execute(command).unwrap(), "6060");
+	}
+
+	#[test]
+	fn json_output() {
+		let command = "ethabi encode params -v bool 1 --json".split(' ');
+		let expected = r#"{"data":"0x0000000000000000000000000000000000000000000000000000000000000001"}"#;
+		assert_eq!(execute(command).unwrap(), expected);
+
+		let command =
+			"ethabi --json decode params -t bool 0000000000000000000000000000000000000000000000000000000000000001"
+				.split(' ');
+		let expected = r#"[{"name":null,"type":"bool","value":"true"}]"#;
+		assert_eq!(execute(command).unwrap(), expected);
+
+		let command = "ethabi decode log ../res/event.abi Event -l 0000000000000000000000000000000000000000000000000000000000000001 0000000000000000000000004444444444444444444444444444444444444444 --json".split(' ');
+		let expected = r#"[{"name":"a","type":"bool","value":"true"},{"name":"b","type":"address","value":"4444444444444444444444444444444444444444"}]"#;
+		assert_eq!(execute(command).unwrap(), expected);
+
+		// arrays and tuples nest
+		let command = ["ethabi", "encode", "params", "-v", "(bool,bool[])", "(true,[false,true])"];
+		let data = execute(command).unwrap();
+		let command = ["ethabi", "--json", "decode", "params", "-t", "(bool,bool[])", &data];
+		let expected = r#"[{"name":null,"type":"(bool,bool[])","value":["true",["false","true"]]}]"#;
+		assert_eq!(execute(command).unwrap(), expected);
+	}
+
+	#[test]
+	fn selector() {
+		let command = "ethabi selector transfer(address,uint)".split(' ');
+		assert_eq!(execute(command).unwrap(), "a9059cbb");
+
+		let command = ["ethabi", "selector", "event Transfer(address indexed from, address indexed to, uint value)"];
+		let expected = "ddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef";
+		assert_eq!(execute(command).unwrap(), expected);
+
+		let command = "ethabi selector ../res/eip20.abi Transfer".split(' ');
+		assert_eq!(execute(command).unwrap(), expected);
+
+		let command = "ethabi selector ../res/eip20.abi transfer(address,uint) --json".split(' ');
+		assert_eq!(execute(command).unwrap(), r#"{"selector":"0xa9059cbb","signature":"transfer(address,uint256)"}"#);
+
+		let command = "ethabi selector ../res/eip20.abi transfer(address)".split(' ');
+		assert!(execute(command).is_err());
+	}
 
 	#[test]
 	fn simple_encode() {
@@ -283,7 +709,14 @@ mod tests {
 		let result = execute(command);
 		assert!(result.is_err());
 		let err = result.unwrap_err();
-		assert_eq!(err.to_string(), "Uint parse error: InvalidCharacter");
+		assert_eq!(err.to_string(), "`123abc` is not a decimal integer");
+	}
+
+	#[test]
+	fn uint_encode_scientific_notation() {
+		let command = "ethabi encode params -v uint256 1.5e18 --lenient".split(' ');
+		let expected = "00000000000000000000000000000000000000000000000014d1120d7b160000";
+		assert_eq!(execute(command).unwrap(), expected);
 	}
 
 	#[test]
@@ -389,6 +822,48 @@ mod tests {
 		assert_eq!(execute(command).unwrap(), expected);
 	}
 
+	#[test]
+	fn address_checksum() {
+		let command =
+			"ethabi decode params -t address 0000000000000000000000005aaeb6053f3e94c9b9a09f33669435e7ef1beaed"
+				.split(' ');
+		assert_eq!(execute(command).unwrap(), "address 5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed");
+
+		let command = "ethabi encode params -v address 0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed".split(' ');
+		assert_eq!(execute(command).unwrap(), "0000000000000000000000005aaeb6053f3e94c9b9a09f33669435e7ef1beaed");
+
+		// a mistyped checksum is rejected, unless the address is written in a single case
+		let command = "ethabi encode params -v address 0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAeD".split(' ');
+		assert!(execute(command).is_err());
+		let command = "ethabi encode params -v address 0x5aaeb6053f3e94c9b9a09f33669435e7ef1beaed".split(' ');
+		assert!(execute(command).is_ok());
+	}
+
+	#[test]
+	fn calldata_decode() {
+		let command =
+			"ethabi decode calldata ../res/test.abi 0x455575780000000000000000000000000000000000000000000000000000000000000001"
+				.split(' ');
+		let expected = "foo(bool)
+a true";
+		assert_eq!(execute(command).unwrap(), expected);
+
+		let command = "ethabi decode calldata ../res/test.abi 00000000".split(' ');
+		assert_eq!(execute(command).unwrap_err().to_string(), "no function has the selector 0x00000000");
+	}
+
+	#[test]
+	fn output_decode() {
+		let command = "ethabi decode output ../res/eip20.abi balanceOf 0x000000000000000000000000000000000000000000000000000000000000002a"
+			.split(' ');
+		assert_eq!(execute(command).unwrap(), "balance 2a");
+
+		let command =
+			"ethabi decode output ../res/foo.abi bar 0000000000000000000000000000000000000000000000000000000000000001"
+				.split(' ');
+		assert_eq!(execute(command).unwrap(), "0 true");
+	}
+
 	#[test]
 	fn int_decode() {
 		let command = "ethabi decode params -t int256 fffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffe"
@@ -442,4 +917,55 @@ b 4444444444444444444444444444444444444444";
 		let command = "ethabi decode log ../res/event.abi Nope(bool,address) -l 0000000000000000000000000000000000000000000000000000000000000000 0000000000000000000000004444444444444444444444444444444444444444".split(' ');
 		assert!(execute(command).is_err());
 	}
+
+	#[test]
+	fn stdin_input() {
+		let abi = std::fs::read_to_string("../res/eip20.abi").unwrap();
+		let command =
+			"ethabi decode output - balanceOf 0x000000000000000000000000000000000000000000000000000000000000002a"
+				.split(' ');
+		assert_eq!(execute_with_stdin(command, &mut abi.as_bytes()).unwrap(), "balance 2a");
+
+		let data = "0x000000000000000000000000000000000000000000000000000000000000002a\n";
+		let command = "ethabi decode output ../res/eip20.abi balanceOf".split(' ');
+		assert_eq!(execute_with_stdin(command, &mut data.as_bytes()).unwrap(), "balance 2a");
+
+		let command = "ethabi decode output - balanceOf".split(' ');
+		assert!(execute_with_stdin(command, &mut data.as_bytes()).is_err());
+	}
+
+	#[test]
+	fn lenient_abi() {
+		let abi = r#"[{"name":"balanceOf","inputs":[{"name":"owner","type":"address"}],"outputs":[{"name":"balance","type":"uint256","unit":"wei"}],"constant":"true"}]"#;
+		let command = "ethabi selector - balanceOf".split(' ');
+		assert!(execute_with_stdin(command, &mut abi.as_bytes()).is_err());
+
+		let command = "ethabi --lenient-abi selector - balanceOf".split(' ');
+		assert_eq!(execute_with_stdin(command, &mut abi.as_bytes()).unwrap(), "70a08231");
+	}
+
+	#[test]
+	fn batch_decode() {
+		let input = "
+0000000000000000000000000000000000000000000000000000000000000001
+0x0000000000000000000000000000000000000000000000000000000000000000
+
+00
+";
+		let command = "ethabi decode params -t bool --batch".split(' ');
+		let expected = r#"[{"name":null,"type":"bool","value":"true"}]
+[{"name":null,"type":"bool","value":"false"}]
+{"error":"Invalid data length 1, expected a multiple of 32 bytes"}"#;
+		assert_eq!(execute_with_stdin(command, &mut input.as_bytes()).unwrap(), expected);
+
+		let input = "0000000000000000000000000000000000000000000000000000000000000001 0000000000000000000000004444444444444444444444444444444444444444\n";
+		let command = "ethabi decode log ../res/event.abi Event --batch".split(' ');
+		let expected = r#"[{"name":"a","type":"bool","value":"true"},{"name":"b","type":"address","value":"4444444444444444444444444444444444444444"}]"#;
+		assert_eq!(execute_with_stdin(command, &mut input.as_bytes()).unwrap(), expected);
+
+		let command = "ethabi encode params -v bool 1 --batch".split(' ');
+		assert!(execute_with_stdin(command, &mut "".as_bytes()).is_err());
+		let command = "ethabi decode params -t bool 00 --batch".split(' ');
+		assert!(execute_with_stdin(command, &mut "".as_bytes()).is_err());
+	}
 }
@@ -10,7 +10,8 @@ use proc_macro2::TokenStream;
 use quote::quote;
 
 use super::{
-	from_template_param, get_template_names, input_names, rust_type, template_param_type, to_ethabi_param_vec, to_token,
+	from_template_param, get_template_names, input_names, rust_type, template_param_type, to_ethabi_param_vec,
+	to_token, Integers,
 };
 
 /// Structure used to generate contract's constructor interface.
@@ -19,19 +20,33 @@ pub struct Constructor {
 	inputs_definitions: Vec<TokenStream>,
 	tokenize: Vec<TokenStream>,
 	recreate_inputs: TokenStream,
+	input_names: Vec<syn::Ident>,
+	/// Creation bytecode of the contract, if known.
+	bytecode: Option<Vec<u8>>,
 }
 
 impl<'a> From<&'a ethabi::Constructor> for Constructor {
 	fn from(c: &'a ethabi::Constructor) -> Self {
+		Constructor::new(c, Integers::Ethabi)
+	}
+}
+
+impl Constructor {
+	/// Creates the interface of contract's constructor, with integers represented as `integers`.
+	pub fn new(c: &ethabi::Constructor, integers: Integers) -> Self {
 		// [param0, hello_world, param2]
 		let input_names = input_names(&c.inputs);
 
 		// [T0: Into<Uint>, T1: Into<Bytes>, T2: IntoIterator<Item = U2>, U2 = Into<Uint>]
-		let inputs_declarations =
-			c.inputs.iter().enumerate().map(|(index, param)| template_param_type(&param.kind, index)).collect();
+		let inputs_declarations = c
+			.inputs
+			.iter()
+			.enumerate()
+			.map(|(index, param)| template_param_type(&param.kind, index, integers))
+			.collect();
 
 		// [Uint, Bytes, Vec<Uint>]
-		let kinds: Vec<_> = c.inputs.iter().map(|param| rust_type(&param.kind)).collect();
+		let kinds: Vec<_> = c.inputs.iter().map(|param| rust_type(&param.kind, integers)).collect();
 
 		// [T0, T1, T2]
 		let template_names: Vec<_> = get_template_names(&kinds);
@@ -48,7 +63,7 @@ impl<'a> From<&'a ethabi::Constructor> for Constructor {
 		let tokenize: Vec<_> = input_names
 			.iter()
 			.zip(c.inputs.iter())
-			.map(|(param_name, param)| to_token(&from_template_param(&param.kind, &param_name), &param.kind))
+			.map(|(param_name, param)| to_token(&from_template_param(&param.kind, param_name), &param.kind, integers))
 			.collect();
 
 		Constructor {
@@ -56,20 +71,40 @@ impl<'a> From<&'a ethabi::Constructor> for Constructor {
 			inputs_definitions,
 			tokenize,
 			recreate_inputs: to_ethabi_param_vec(&c.inputs),
+			input_names,
+			bytecode: None,
 		}
 	}
-}
 
-impl Constructor {
+	/// Also generates the `BYTECODE` of the contract and `deploy_data`, encoding deployments with it.
+	pub fn with_bytecode(mut self, bytecode: &[u8]) -> Self {
+		self.bytecode = Some(bytecode.to_vec());
+		self
+	}
+
 	/// Generates contract constructor interface.
 	pub fn generate(&self) -> TokenStream {
 		let declarations = &self.inputs_declarations;
 		let definitions = &self.inputs_definitions;
 		let tokenize = &self.tokenize;
 		let recreate_inputs = &self.recreate_inputs;
+		let deploy_data = self.bytecode.as_ref().map(|bytecode| {
+			let bytecode = proc_macro2::Literal::byte_string(bytecode);
+			let arguments = &definitions[1..];
+			let names = &self.input_names;
+			quote! {
+				/// Creation bytecode of the contract.
+				pub const BYTECODE: &[u8] = #bytecode;
+
+				/// Encodes the deployment of the contract, `BYTECODE` followed by the constructor arguments.
+				pub fn deploy_data<#(#declarations),*>(#(#arguments),*) -> ethabi::Bytes {
+					constructor(BYTECODE.to_vec(), #(#names),*)
+				}
+			}
+		});
 
 		quote! {
-			/// Encodes a call to contract's constructor.
+			/// Encodes the deployment of the contract, its creation `code` followed by the constructor arguments.
 			pub fn constructor<#(#declarations),*>(#(#definitions),*) -> ethabi::Bytes {
 				let c = ethabi::Constructor {
 					inputs: #recreate_inputs,
@@ -77,6 +112,8 @@ impl Constructor {
 				let tokens = vec![#(#tokenize),*];
 				c.encode_input(code, &tokens).expect(INTERNAL_ERR)
 			}
+
+			#deploy_data
 		}
 	}
 }
@@ -93,7 +130,27 @@ mod tests {
 		let c = Constructor::from(&ethabi_constructor);
 
 		let expected = quote! {
-			/// Encodes a call to contract's constructor.
+			/// Encodes the deployment of the contract, its creation `code` followed by the constructor arguments.
+			pub fn constructor<>(code: ethabi::Bytes) -> ethabi::Bytes {
+				let c = ethabi::Constructor {
+					inputs: vec![],
+				};
+				let tokens = vec![];
+				c.encode_input(code, &tokens).expect(INTERNAL_ERR)
+			}
+		};
+
+		assert_eq!(expected.to_string(), c.generate().to_string());
+	}
+
+	#[test]
+	fn test_bytecode() {
+		let ethabi_constructor = ethabi::Constructor { inputs: vec![] };
+
+		let c = Constructor::from(&ethabi_constructor).with_bytecode(&[0x60, 0x80]);
+
+		let expected = quote! {
+			/// Encodes the deployment of the contract, its creation `code` followed by the constructor arguments.
 			pub fn constructor<>(code: ethabi::Bytes) -> ethabi::Bytes {
 				let c = ethabi::Constructor {
 					inputs: vec![],
@@ -101,6 +158,14 @@ mod tests {
 				let tokens = vec![];
 				c.encode_input(code, &tokens).expect(INTERNAL_ERR)
 			}
+
+			/// Creation bytecode of the contract.
+			pub const BYTECODE: &[u8] = b"`\x80";
+
+			/// Encodes the deployment of the contract, `BYTECODE` followed by the constructor arguments.
+			pub fn deploy_data<>() -> ethabi::Bytes {
+				constructor(BYTECODE.to_vec(),)
+			}
 		};
 
 		assert_eq!(expected.to_string(), c.generate().to_string());
@@ -109,18 +174,23 @@ mod tests {
 	#[test]
 	fn test_one_param() {
 		let ethabi_constructor = ethabi::Constructor {
-			inputs: vec![ethabi::Param { name: "foo".into(), kind: ethabi::ParamType::Uint(256) }],
+			inputs: vec![ethabi::Param {
+				name: "foo".into(),
+				kind: ethabi::ParamType::Uint(256),
+				internal_type: None,
+				components: vec![],
+			}],
 		};
 
 		let c = Constructor::from(&ethabi_constructor);
 
 		let expected = quote! {
-			/// Encodes a call to contract's constructor.
+			/// Encodes the deployment of the contract, its creation `code` followed by the constructor arguments.
 			pub fn constructor<T0: Into<ethabi::Uint> >(code: ethabi::Bytes, foo: T0) -> ethabi::Bytes {
 				let c = ethabi::Constructor {
 					inputs: vec![ethabi::Param {
 						name: "foo".to_owned(),
-						kind: ethabi::ParamType::Uint(256usize)
+						kind: ethabi::ParamType::Uint(256usize), internal_type: None, components: vec![]
 					}],
 				};
 				let tokens = vec![ethabi::Token::Uint(foo.into())];
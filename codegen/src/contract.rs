@@ -0,0 +1,306 @@
+// Copyright 2015-2019 Parity Technologies
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use anyhow::anyhow;
+use ethabi::Result;
+use heck::CamelCase;
+use proc_macro2::TokenStream;
+use quote::quote;
+use std::collections::{HashMap, HashSet};
+
+use crate::{constructor::Constructor, error::Error, event::Event, function::Function, types::generate_types, Options};
+
+/// Structure used to generate rust interface for solidity contract.
+pub struct Contract {
+	constructor: Constructor,
+	functions: Vec<Function>,
+	events: Vec<Event>,
+	errors: Vec<Error>,
+	/// Structs and enum aliases of the params, named after their `internalType`.
+	types: Vec<TokenStream>,
+	/// Whether to generate the `Caller` trait and the `Instance` calling through it.
+	caller: bool,
+	/// Path of the ethabi crate, if it is not `ethabi`.
+	crate_path: Option<syn::Path>,
+}
+
+impl Contract {
+	/// Creates the interface of a contract, generated according to `options`.
+	///
+	/// Fails if the structs and enums of the contract cannot be given distinct names, or if the
+	/// crate path of `options` is not a valid path.
+	pub fn new(c: &ethabi::Contract, options: Options) -> Result<Self> {
+		let integers = options.integers;
+		Ok(Contract {
+			// contracts without a constructor are deployed with their code only
+			constructor: {
+				let constructor = match c.constructor {
+					Some(ref constructor) => Constructor::new(constructor, integers),
+					None => Constructor::new(&ethabi::Constructor { inputs: vec![] }, integers),
+				};
+				match options.bytecode {
+					Some(ref bytecode) => constructor.with_bytecode(bytecode),
+					None => constructor,
+				}
+			},
+			functions: disambiguate(
+				c.functions()
+					.map(|function| {
+						let mut function = Function::new(function, integers);
+						if options.builders {
+							function = function.with_builder();
+						}
+						if options.structs {
+							function = function.with_output_struct();
+						}
+						if options.serde {
+							function = function.with_serde();
+						}
+						function
+					})
+					.collect(),
+				Function::module_name,
+				Function::overload,
+			),
+			events: disambiguate(
+				c.events()
+					.map(|event| match options.serde {
+						true => Event::new(event, integers).with_serde(),
+						false => Event::new(event, integers),
+					})
+					.collect(),
+				Event::module_name,
+				Event::overload,
+			),
+			errors: disambiguate(
+				c.errors().map(|error| Error::new(error, integers)).collect(),
+				Error::module_name,
+				Error::overload,
+			),
+			types: generate_types(c, integers, options.serde)?,
+			caller: options.futures,
+			crate_path: match options.crate_path {
+				Some(ref path) => {
+					Some(syn::parse_str(path).map_err(|_| anyhow!("`{}` is not a valid crate path", path))?)
+				}
+				None => None,
+			},
+		})
+	}
+
+	/// Generates rust interface for a contract.
+	pub fn generate(&self) -> TokenStream {
+		let constructor = self.constructor.generate();
+		let functions: Vec<_> = self.functions.iter().map(Function::generate).collect();
+		let events: Vec<_> = self.events.iter().map(Event::generate_event).collect();
+		let logs: Vec<_> = self.events.iter().map(Event::generate_log).collect();
+		let errors: Vec<_> = self.errors.iter().map(Error::generate).collect();
+		let types = match self.types.is_empty() {
+			true => quote! {},
+			false => {
+				let types = &self.types;
+				quote! {
+					/// Contract's structs and enums, named after the `internalType` of the params.
+					pub mod types {
+						use super::{ethabi, INTERNAL_ERR};
+						#(#types)*
+					}
+				}
+			}
+		};
+		let caller = match self.caller {
+			true => self.generate_caller(),
+			false => quote! {},
+		};
+		// the generated modules reach ethabi through this import, so that it can be renamed
+		let import = match self.crate_path {
+			Some(ref path) => quote! { use #path as ethabi; },
+			None => quote! { use ethabi; },
+		};
+		quote! {
+			#import
+			const INTERNAL_ERR: &'static str = "`ethabi_derive` internal error";
+
+			#constructor
+
+			#caller
+
+			#types
+
+			/// Contract's functions.
+			pub mod functions {
+				use super::{ethabi, INTERNAL_ERR};
+				#(#functions)*
+			}
+
+			/// Contract's events.
+			pub mod events {
+				use super::{ethabi, INTERNAL_ERR};
+				#(#events)*
+			}
+
+			/// Contract's logs.
+			pub mod logs {
+				use super::{ethabi, INTERNAL_ERR};
+				#(#logs)*
+			}
+
+			/// Contract's custom errors.
+			pub mod errors {
+				use super::{ethabi, INTERNAL_ERR};
+				#(#errors)*
+			}
+		}
+	}
+
+	/// Generates the `Caller` trait of transports and the `Instance` calling the functions
+	/// through one.
+	fn generate_caller(&self) -> TokenStream {
+		let methods = self.functions.iter().map(Function::generate_method);
+		quote! {
+			/// Transport of the calls of an `Instance`, e.g. as `eth_call` requests.
+			pub trait Caller {
+				/// Future resolving to the output of a call.
+				type Future: std::future::Future<Output = ethabi::Result<ethabi::Bytes>>;
+
+				/// Calls the contract at `to` with the call data `data`.
+				fn call(&self, to: ethabi::Address, data: ethabi::Bytes) -> Self::Future;
+			}
+
+			/// Contract deployed at `address`, with a method per function calling it through `caller`.
+			#[derive(Debug, Clone)]
+			pub struct Instance<C> {
+				/// Transport of the calls.
+				pub caller: C,
+				/// Address of the contract.
+				pub address: ethabi::Address,
+			}
+
+			#[allow(clippy::too_many_arguments, clippy::duplicate_underscore_argument)]
+			impl<C: Caller> Instance<C> {
+				#(#methods)*
+			}
+		}
+	}
+}
+
+/// Gives the items sharing a module name, like overloads or names only differing in case,
+/// their position among each other so that none of them clobbers another.
+///
+/// Items are in ABI order, sorted by name, so the generated names are stable for a given ABI.
+/// Positions whose suffixed name would clash with another item, e.g. `transfer_1` with a
+/// `transfer_1` function or the `Transfer1` log of a `Transfer1` event, are skipped.
+fn disambiguate<T>(items: Vec<T>, module_name: fn(&T) -> &str, overload: fn(T, usize) -> T) -> Vec<T> {
+	let mut counts = HashMap::new();
+	for item in &items {
+		*counts.entry(module_name(item).to_owned()).or_insert(0) += 1;
+	}
+
+	let mut taken = counts.keys().map(|name| name.to_camel_case()).collect::<HashSet<_>>();
+	let mut positions = HashMap::new();
+	items
+		.into_iter()
+		.map(|item| {
+			let name = module_name(&item).to_owned();
+			if counts[&name] == 1 {
+				return item;
+			}
+			let position = positions.entry(name.clone()).or_insert(0);
+			while !taken.insert(format!("{}_{}", name, position).to_camel_case()) {
+				*position += 1;
+			}
+			*position += 1;
+			overload(item, *position - 1)
+		})
+		.collect()
+}
+
+#[cfg(test)]
+mod test {
+	use quote::quote;
+
+	use super::{disambiguate, Contract};
+	use crate::Options;
+
+	#[test]
+	fn test_no_body() {
+		let ethabi_contract = ethabi::Contract {
+			constructor: None,
+			functions: Default::default(),
+			events: Default::default(),
+			errors: Default::default(),
+			fallback: None,
+			receive: false,
+		};
+
+		let c = Contract::new(&ethabi_contract, Options::default()).unwrap();
+
+		let expected = quote! {
+			use ethabi;
+			const INTERNAL_ERR: &'static str = "`ethabi_derive` internal error";
+
+			/// Encodes the deployment of the contract, its creation `code` followed by the constructor arguments.
+			pub fn constructor<>(code: ethabi::Bytes) -> ethabi::Bytes {
+				let c = ethabi::Constructor {
+					inputs: vec![],
+				};
+				let tokens = vec![];
+				c.encode_input(code, &tokens).expect(INTERNAL_ERR)
+			}
+
+			/// Contract's functions.
+			pub mod functions {
+				use super::{ethabi, INTERNAL_ERR};
+			}
+
+			/// Contract's events.
+			pub mod events {
+				use super::{ethabi, INTERNAL_ERR};
+			}
+
+			/// Contract's logs.
+			pub mod logs {
+				use super::{ethabi, INTERNAL_ERR};
+			}
+
+			/// Contract's custom errors.
+			pub mod errors {
+				use super::{ethabi, INTERNAL_ERR};
+			}
+		};
+
+		assert_eq!(expected.to_string(), c.generate().to_string());
+	}
+
+	#[test]
+	fn test_invalid_crate_path() {
+		let contract = ethabi::Contract::load(&b"[]"[..]).unwrap();
+		let options = Options { crate_path: Some("::my crate".into()), ..Default::default() };
+		let err = Contract::new(&contract, options).err().unwrap();
+		assert_eq!(err.to_string(), "`::my crate` is not a valid crate path");
+	}
+
+	#[test]
+	fn test_disambiguate() {
+		let names = ["transfer", "transfer", "transfer_1", "Transfer", "approve"].map(String::from).to_vec();
+		let names = disambiguate(names, String::as_str, |name, index| format!("{}_{}", name, index));
+		assert_eq!(names, ["transfer_0", "transfer_2", "transfer_1", "Transfer", "approve"]);
+	}
+
+	#[test]
+	fn test_overloaded_errors() {
+		let abi = r#"[
+			{"type":"error","name":"Unauthorized","inputs":[{"name":"caller","type":"address"}]},
+			{"type":"error","name":"Unauthorized","inputs":[{"name":"role","type":"uint256"}]}
+		]"#;
+		let contract = ethabi::Contract::load(abi.as_bytes()).unwrap();
+		let generated = Contract::new(&contract, Options::default()).unwrap().generate().to_string();
+		assert!(generated.contains("pub mod unauthorized_0"));
+		assert!(generated.contains("pub mod unauthorized_1"));
+	}
+}
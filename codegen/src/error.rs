@@ -0,0 +1,210 @@
+// Copyright 2015-2019 Parity Technologies
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use heck::SnakeCase;
+use proc_macro2::{Span, TokenStream};
+use quote::quote;
+
+use super::{
+	from_template_param, from_token, get_output_kinds, get_template_names, input_names, rust_type, template_param_type,
+	to_syntax_string, to_token, Integers,
+};
+
+/// Structure used to generate contract's custom error interface.
+pub struct Error {
+	/// Name of the generated module.
+	module_name: String,
+	/// Error selector.
+	selector: [u8; 4],
+	inputs_declarations: Vec<TokenStream>,
+	inputs_definitions: Vec<TokenStream>,
+	tokenize: Vec<TokenStream>,
+	kinds: Vec<TokenStream>,
+	decode_result: TokenStream,
+	decode_implementation: TokenStream,
+}
+
+impl<'a> From<&'a ethabi::AbiError> for Error {
+	fn from(e: &'a ethabi::AbiError) -> Self {
+		Error::new(e, Integers::Ethabi)
+	}
+}
+
+impl Error {
+	/// Creates the interface of contract's custom error, with integers represented as `integers`.
+	pub fn new(e: &ethabi::AbiError, integers: Integers) -> Self {
+		// [param0, hello_world, param2]
+		let input_names = input_names(&e.inputs);
+
+		// [T0: Into<Uint>, T1: Into<Bytes>, T2: IntoIterator<Item = U2>, U2 = Into<Uint>]
+		let inputs_declarations = e
+			.inputs
+			.iter()
+			.enumerate()
+			.map(|(index, param)| template_param_type(&param.kind, index, integers))
+			.collect();
+
+		// [Uint, Bytes, Vec<Uint>]
+		let kinds: Vec<_> = e.inputs.iter().map(|param| rust_type(&param.kind, integers)).collect();
+
+		// [T0, T1, T2]
+		let template_names: Vec<_> = get_template_names(&kinds);
+
+		// [param0: T0, hello_world: T1, param2: T2]
+		let inputs_definitions = input_names
+			.iter()
+			.zip(template_names.iter())
+			.map(|(param_name, template_name)| quote! { #param_name: #template_name })
+			.collect();
+
+		// [Token::Uint(param0.into()), Token::Bytes(hello_world.into()), Token::Array(param2.into_iter().map(Into::into).collect())]
+		let tokenize: Vec<_> = input_names
+			.iter()
+			.zip(e.inputs.iter())
+			.map(|(param_name, param)| to_token(&from_template_param(&param.kind, param_name), &param.kind, integers))
+			.collect();
+
+		// [ethabi::ParamType::Uint(256usize), ethabi::ParamType::Bytes]
+		let kinds: Vec<_> = e.inputs.iter().map(|param| to_syntax_string(&param.kind)).collect();
+
+		let decode_implementation = match e.inputs.len() {
+			0 => quote! {
+				ethabi::decode(&[], &data[4..])?;
+				Ok(())
+			},
+			1 => {
+				let o = quote! { out };
+				let from_first = from_token(&e.inputs[0].kind, &o, integers);
+				quote! {
+					let out = ethabi::decode(&[#(#kinds),*], &data[4..])?.into_iter().next().expect(INTERNAL_ERR);
+					let value = #from_first;
+					Ok(value)
+				}
+			}
+			_ => {
+				let o = quote! { out.next().expect(INTERNAL_ERR) };
+				let outs: Vec<_> = e.inputs.iter().map(|param| from_token(&param.kind, &o, integers)).collect();
+
+				quote! {
+					let mut out = ethabi::decode(&[#(#kinds),*], &data[4..])?.into_iter();
+					Ok(( #(#outs),* ))
+				}
+			}
+		};
+
+		Error {
+			module_name: e.name.to_snake_case(),
+			selector: e.selector(),
+			kinds,
+			inputs_declarations,
+			inputs_definitions,
+			tokenize,
+			decode_result: get_output_kinds(&e.inputs, integers),
+			decode_implementation,
+		}
+	}
+
+	/// Name of the generated error module.
+	pub fn module_name(&self) -> &str {
+		&self.module_name
+	}
+
+	/// Suffixes the module name with the position of the error among the errors it would
+	/// otherwise clash with, e.g. `unauthorized_1`, so that every overload gets its own module.
+	pub fn overload(mut self, index: usize) -> Self {
+		self.module_name = format!("{}_{}", self.module_name, index);
+		self
+	}
+
+	/// Generates the interface for contract's custom error.
+	pub fn generate(&self) -> TokenStream {
+		let module_name = syn::Ident::new(&self.module_name, Span::call_site());
+		let declarations = &self.inputs_declarations;
+		let definitions = &self.inputs_definitions;
+		let tokenize = &self.tokenize;
+		let decode_result = &self.decode_result;
+		let decode_implementation = &self.decode_implementation;
+		let selector = &self.selector;
+		// errors without params decode nothing which could fail
+		let import = match self.kinds.is_empty() {
+			true => quote! { use super::ethabi; },
+			false => quote! { use super::{ethabi, INTERNAL_ERR}; },
+		};
+
+		quote! {
+			pub mod #module_name {
+				#import
+
+				/// Error selector, the first 4 bytes of the revert data.
+				pub const SELECTOR: [u8; 4] = [#(#selector),*];
+
+				/// Encodes the revert data of the error.
+				pub fn encode<#(#declarations),*>(#(#definitions),*) -> ethabi::Bytes {
+					let tokens = vec![#(#tokenize),*];
+					SELECTOR.iter().copied().chain(ethabi::encode(&tokens)).collect()
+				}
+
+				/// Decodes the revert data of the error, failing if it does not start with its selector.
+				pub fn decode(data: &[u8]) -> ethabi::Result<#decode_result> {
+					if data.len() < 4 || data[..4] != SELECTOR {
+						return Err(ethabi::Error::InvalidData);
+					}
+					#decode_implementation
+				}
+			}
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::Error;
+	use quote::quote;
+
+	#[test]
+	fn test_error_with_one_param() {
+		let ethabi_error = ethabi::AbiError {
+			name: "Unauthorized".into(),
+			inputs: vec![ethabi::Param {
+				name: "caller".into(),
+				kind: ethabi::ParamType::Address,
+				internal_type: None,
+				components: vec![],
+			}],
+		};
+
+		let e = Error::from(&ethabi_error);
+
+		let expected = quote! {
+			pub mod unauthorized {
+				use super::{ethabi, INTERNAL_ERR};
+
+				/// Error selector, the first 4 bytes of the revert data.
+				pub const SELECTOR: [u8; 4] = [142u8, 74u8, 35u8, 214u8];
+
+				/// Encodes the revert data of the error.
+				pub fn encode<T0: Into<ethabi::Address> >(caller: T0) -> ethabi::Bytes {
+					let tokens = vec![ethabi::Token::Address(caller.into())];
+					SELECTOR.iter().copied().chain(ethabi::encode(&tokens)).collect()
+				}
+
+				/// Decodes the revert data of the error, failing if it does not start with its selector.
+				pub fn decode(data: &[u8]) -> ethabi::Result<ethabi::Address> {
+					if data.len() < 4 || data[..4] != SELECTOR {
+						return Err(ethabi::Error::InvalidData);
+					}
+					let out = ethabi::decode(&[ethabi::ParamType::Address], &data[4..])?.into_iter().next().expect(INTERNAL_ERR);
+					let value = out.into_address().ok_or(ethabi::Error::InvalidData)?;
+					Ok(value)
+				}
+			}
+		};
+
+		assert_eq!(expected.to_string(), e.generate().to_string());
+	}
+}
@@ -0,0 +1,622 @@
+// Copyright 2015-2019 Parity Technologies
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use heck::{CamelCase, SnakeCase};
+use proc_macro2::{Span, TokenStream};
+use quote::quote;
+
+use super::{
+	from_token, get_template_names, rust_type, to_ethabi_components, to_internal_type, to_syntax_string, to_token,
+	Integers,
+};
+
+/// Structure used to generate contract's event interface.
+pub struct Event {
+	name: String,
+	module_name: String,
+	log_name: String,
+	signature: Option<[u8; 32]>,
+	canonical_signature: String,
+	log_fields: Vec<TokenStream>,
+	recreate_inputs_quote: TokenStream,
+	log_init: Vec<TokenStream>,
+	topic_count: usize,
+	data_kinds: Vec<TokenStream>,
+	wildcard_filter_params: Vec<TokenStream>,
+	filter_declarations: Vec<TokenStream>,
+	filter_definitions: Vec<TokenStream>,
+	filter_init: Vec<TokenStream>,
+	anonymous: bool,
+	/// Solidity declaration of the event, for the docs.
+	declaration: String,
+	/// Whether the log derives `serde::Serialize`.
+	serde: bool,
+}
+
+impl<'a> From<&'a ethabi::Event> for Event {
+	fn from(e: &'a ethabi::Event) -> Self {
+		Event::new(e, Integers::Ethabi)
+	}
+}
+
+impl Event {
+	/// Creates the interface of contract's event, with integers represented as `integers`.
+	pub fn new(e: &ethabi::Event, integers: Integers) -> Self {
+		let names: Vec<_> = e
+			.inputs
+			.iter()
+			.enumerate()
+			.map(|(index, param)| {
+				if param.name.is_empty() {
+					if param.indexed {
+						syn::Ident::new(&format!("topic{}", index), Span::call_site())
+					} else {
+						syn::Ident::new(&format!("param{}", index), Span::call_site())
+					}
+				} else {
+					syn::Ident::new(&param.name.to_snake_case(), Span::call_site())
+				}
+			})
+			.collect();
+		// indexed params of dynamic types are only available as the hash stored in their topic
+		let kinds: Vec<_> = e
+			.inputs
+			.iter()
+			.map(|param| match param.indexed && param.kind.is_hashed_in_topic() {
+				true => quote! { ethabi::Hash },
+				false => rust_type(&param.kind, integers),
+			})
+			.collect();
+		let log_fields =
+			names.iter().zip(kinds.iter()).map(|(param_name, kind)| quote! { pub #param_name: #kind }).collect();
+
+		let topic_iter = quote! { topics.next().expect(INTERNAL_ERR) };
+		let data_iter = quote! { data.next().expect(INTERNAL_ERR) };
+
+		let to_log: Vec<_> = e
+			.inputs
+			.iter()
+			.map(|param| match (param.indexed, param.kind.is_hashed_in_topic()) {
+				(true, true) => topic_iter.clone(),
+				(true, false) => {
+					let kind = to_syntax_string(&param.kind);
+					let token = quote! { ethabi::decode(&[#kind], #topic_iter.as_bytes())?.remove(0) };
+					from_token(&param.kind, &token, integers)
+				}
+				(false, _) => from_token(&param.kind, &data_iter, integers),
+			})
+			.collect();
+
+		let log_init =
+			names.iter().zip(to_log.iter()).map(|(param_name, convert)| quote! { #param_name: #convert }).collect();
+
+		let topic_kinds: Vec<_> =
+			e.inputs.iter().filter(|param| param.indexed).map(|param| rust_type(&param.kind, integers)).collect();
+		let topic_names: Vec<_> = e
+			.inputs
+			.iter()
+			.enumerate()
+			.filter(|&(_, param)| param.indexed)
+			.map(|(index, param)| {
+				if param.name.is_empty() {
+					syn::Ident::new(&format!("topic{}", index), Span::call_site())
+				} else {
+					syn::Ident::new(&param.name.to_snake_case(), Span::call_site())
+				}
+			})
+			.collect();
+
+		// [T0, T1, T2]
+		let template_names: Vec<_> = get_template_names(&topic_kinds);
+
+		let filter_declarations: Vec<_> = topic_kinds
+			.iter()
+			.zip(template_names.iter())
+			.map(|(kind, template_name)| quote! { #template_name: Into<ethabi::Topic<#kind>> })
+			.collect();
+
+		let filter_definitions: Vec<_> = topic_names
+			.iter()
+			.zip(template_names.iter())
+			.map(|(param_name, template_name)| quote! { #param_name: #template_name })
+			.collect();
+
+		// The number of parameters that creates a filter which matches anything.
+		let wildcard_filter_params: Vec<_> = filter_definitions.iter().map(|_| quote! { ethabi::Topic::Any }).collect();
+
+		let filter_init: Vec<_> = topic_names
+			.iter()
+			.zip(e.inputs.iter().filter(|p| p.indexed))
+			.enumerate()
+			// anonymous events have no signature topic, leaving room for a fourth indexed param
+			.take(if e.anonymous { 4 } else { 3 })
+			.map(|(index, (param_name, param))| {
+				let topic = syn::Ident::new(&format!("topic{}", index), Span::call_site());
+				let i = quote! { i };
+				let to_token = to_token(&i, &param.kind, integers);
+				quote! { #topic: #param_name.into().map(|#i| #to_token), }
+			})
+			.collect();
+
+		let event_inputs = &e
+			.inputs
+			.iter()
+			.map(|x| {
+				let name = &x.name;
+				let kind = to_syntax_string(&x.kind);
+				let indexed = x.indexed;
+				let internal_type = to_internal_type(&x.internal_type);
+				let components = to_ethabi_components(&x.components);
+
+				quote! {
+					ethabi::EventParam {
+						name: #name.to_owned(),
+						kind: #kind,
+						indexed: #indexed,
+						internal_type: #internal_type,
+						components: #components
+					}
+				}
+			})
+			.collect::<Vec<_>>();
+		let recreate_inputs_quote = quote! { vec![ #(#event_inputs),* ] };
+
+		let topic_count = e.inputs.iter().filter(|param| param.indexed).count();
+		let data_kinds =
+			e.inputs.iter().filter(|param| !param.indexed).map(|param| to_syntax_string(&param.kind)).collect();
+
+		Event {
+			name: e.name.clone(),
+			module_name: e.name.to_snake_case(),
+			log_name: e.name.to_camel_case(),
+			signature: match e.anonymous {
+				true => None,
+				false => Some(e.signature().0),
+			},
+			canonical_signature: e.canonical_signature(),
+			log_fields,
+			recreate_inputs_quote,
+			log_init,
+			topic_count,
+			data_kinds,
+			anonymous: e.anonymous,
+			wildcard_filter_params,
+			filter_declarations,
+			filter_definitions,
+			filter_init,
+			declaration: declaration(e),
+			serde: false,
+		}
+	}
+
+	/// Name of the generated event module.
+	pub fn module_name(&self) -> &str {
+		&self.module_name
+	}
+
+	/// Suffixes the module and log names with the position of the event among the events
+	/// they would otherwise clash with, e.g. `transfer_1` and `Transfer1`.
+	pub fn overload(mut self, index: usize) -> Self {
+		self.module_name = format!("{}_{}", self.module_name, index);
+		self.log_name = format!("{}{}", self.log_name, index);
+		self
+	}
+
+	/// Also derives `serde::Serialize` for the log.
+	pub fn with_serde(mut self) -> Self {
+		self.serde = true;
+		self
+	}
+
+	/// Generates event log struct.
+	pub fn generate_log(&self) -> TokenStream {
+		let name = syn::Ident::new(&self.log_name, Span::call_site());
+		let log_fields = &self.log_fields;
+		let serde = match self.serde {
+			true => quote! { #[derive(serde::Serialize)] },
+			false => quote! {},
+		};
+
+		quote! {
+			#[derive(Debug, Clone, PartialEq)]
+			#serde
+			pub struct #name {
+				#(#log_fields),*
+			}
+		}
+	}
+
+	/// Generates rust interface for contract's event.
+	pub fn generate_event(&self) -> TokenStream {
+		let name_as_string = &self.name.to_camel_case();
+		let name = syn::Ident::new(&self.module_name, Span::call_site());
+		let camel_name = syn::Ident::new(&self.log_name, Span::call_site());
+		let recreate_inputs_quote = &self.recreate_inputs_quote;
+		let anonymous = &self.anonymous;
+		let log_init = &self.log_init;
+		let topic_count = &self.topic_count;
+		let data_kinds = &self.data_kinds;
+		let (signature, check_signature) = match self.signature {
+			None => (quote! {}, quote! {}),
+			Some(ref signature) => (
+				quote! {
+					/// Bytes of `SIGNATURE`, which unlike a hash can be a pattern, e.g. `match topic.0 { TOPIC0 => .. }`.
+					pub const TOPIC0: [u8; 32] = [#(#signature),*];
+
+					/// Event signature hash, the first topic of its logs.
+					pub const SIGNATURE: ethabi::Hash = ethabi::Hash { 0: TOPIC0 };
+				},
+				quote! {
+					if topics.next() != Some(SIGNATURE) {
+						return Err(ethabi::Error::InvalidData);
+					}
+				},
+			),
+		};
+		let filter_init = &self.filter_init;
+		let filter_declarations = &self.filter_declarations;
+		let filter_definitions = &self.filter_definitions;
+		let wildcard_filter_params = &self.wildcard_filter_params;
+		let declaration = format!("`{}`", self.declaration);
+		let canonical_signature = &self.canonical_signature;
+
+		quote! {
+			#[doc = #declaration]
+			pub mod #name {
+				use super::{ethabi, INTERNAL_ERR};
+
+				#signature
+
+				/// Canonical signature of the event, hashed into `SIGNATURE` unless it is anonymous.
+				pub const CANONICAL_SIGNATURE: &str = #canonical_signature;
+
+				pub fn event() -> ethabi::Event {
+					ethabi::Event {
+						name: #name_as_string.into(),
+						inputs: #recreate_inputs_quote,
+						anonymous: #anonymous,
+					}
+				}
+
+				pub fn filter<#(#filter_declarations),*>(#(#filter_definitions),*) -> ethabi::TopicFilter {
+					let raw = ethabi::RawTopicFilter {
+						#(#filter_init)*
+						..Default::default()
+					};
+
+					let e = event();
+					e.filter(raw).expect(INTERNAL_ERR)
+				}
+
+				pub fn wildcard_filter() -> ethabi::TopicFilter {
+					filter(#(#wildcard_filter_params),*)
+				}
+
+				/// Parses a raw log of this event.
+				///
+				#[doc = #declaration]
+				pub fn parse_log(log: ethabi::RawLog) -> ethabi::Result<super::super::logs::#camel_name> {
+					let mut topics = log.topics.into_iter();
+					#check_signature
+					if topics.len() != #topic_count {
+						return Err(ethabi::Error::InvalidData);
+					}
+					let mut data = ethabi::decode(&[#(#data_kinds),*], &log.data)?.into_iter();
+					let result = super::super::logs::#camel_name {
+						#(#log_init),*
+					};
+					Ok(result)
+				}
+			}
+		}
+	}
+}
+
+/// Reconstructs the Solidity declaration of an event, e.g. `event Transfer(address indexed from, uint256 value)`.
+fn declaration(e: &ethabi::Event) -> String {
+	let params: Vec<_> = e
+		.inputs
+		.iter()
+		.map(|param| {
+			let mut declaration = param.kind.to_string();
+			if param.indexed {
+				declaration.push_str(" indexed");
+			}
+			if !param.name.is_empty() {
+				declaration.push(' ');
+				declaration.push_str(&param.name);
+			}
+			declaration
+		})
+		.collect();
+
+	let mut declaration = format!("event {}({})", e.name, params.join(", "));
+	if e.anonymous {
+		declaration.push_str(" anonymous");
+	}
+	declaration
+}
+
+#[cfg(test)]
+mod tests {
+	use super::Event;
+	use quote::quote;
+
+	#[test]
+	fn test_empty_log() {
+		let ethabi_event = ethabi::Event { name: "hello".into(), inputs: vec![], anonymous: false };
+
+		let e = Event::from(&ethabi_event);
+
+		let expected = quote! {
+			#[derive(Debug, Clone, PartialEq)]
+			pub struct Hello {}
+		};
+
+		assert_eq!(expected.to_string(), e.generate_log().to_string());
+	}
+
+	#[test]
+	fn test_empty_event() {
+		let ethabi_event = ethabi::Event { name: "hello".into(), inputs: vec![], anonymous: false };
+
+		let e = Event::from(&ethabi_event);
+
+		let expected = quote! {
+			#[doc = "`event hello()`"]
+			pub mod hello {
+				use super::{ethabi, INTERNAL_ERR};
+
+				/// Bytes of `SIGNATURE`, which unlike a hash can be a pattern, e.g. `match topic.0 { TOPIC0 => .. }`.
+				pub const TOPIC0: [u8; 32] = [
+					25u8, 255u8, 29u8, 33u8, 14u8, 6u8, 165u8, 62u8, 229u8, 14u8, 91u8, 173u8, 37u8, 250u8, 80u8, 154u8,
+					107u8, 0u8, 237u8, 57u8, 86u8, 149u8, 247u8, 217u8, 184u8, 43u8, 104u8, 21u8, 93u8, 158u8, 16u8, 101u8
+				];
+
+				/// Event signature hash, the first topic of its logs.
+				pub const SIGNATURE: ethabi::Hash = ethabi::Hash { 0: TOPIC0 };
+
+				/// Canonical signature of the event, hashed into `SIGNATURE` unless it is anonymous.
+				pub const CANONICAL_SIGNATURE: &str = "hello()";
+
+				pub fn event() -> ethabi::Event {
+					ethabi::Event {
+						name: "Hello".into(),
+						inputs: vec![],
+						anonymous: false,
+					}
+				}
+
+				pub fn filter<>() -> ethabi::TopicFilter {
+					let raw = ethabi::RawTopicFilter {
+						..Default::default()
+					};
+
+					let e = event();
+					e.filter(raw).expect(INTERNAL_ERR)
+				}
+
+				pub fn wildcard_filter() -> ethabi::TopicFilter {
+					filter()
+				}
+
+				/// Parses a raw log of this event.
+				///
+				#[doc = "`event hello()`"]
+				pub fn parse_log(log: ethabi::RawLog) -> ethabi::Result<super::super::logs::Hello> {
+					let mut topics = log.topics.into_iter();
+					if topics.next() != Some(SIGNATURE) {
+						return Err(ethabi::Error::InvalidData);
+					}
+					if topics.len() != 0usize {
+						return Err(ethabi::Error::InvalidData);
+					}
+					let mut data = ethabi::decode(&[], &log.data)?.into_iter();
+					let result = super::super::logs::Hello {};
+					Ok(result)
+				}
+			}
+		};
+
+		assert_eq!(expected.to_string(), e.generate_event().to_string());
+	}
+
+	#[test]
+	fn test_event_with_one_input() {
+		let ethabi_event = ethabi::Event {
+			name: "one".into(),
+			inputs: vec![ethabi::EventParam {
+				name: "foo".into(),
+				kind: ethabi::ParamType::Address,
+				indexed: true,
+				internal_type: None,
+				components: vec![],
+			}],
+			anonymous: false,
+		};
+
+		let e = Event::from(&ethabi_event);
+
+		let expected = quote! {
+			#[doc = "`event one(address indexed foo)`"]
+			pub mod one {
+				use super::{ethabi, INTERNAL_ERR};
+
+				/// Bytes of `SIGNATURE`, which unlike a hash can be a pattern, e.g. `match topic.0 { TOPIC0 => .. }`.
+				pub const TOPIC0: [u8; 32] = [
+					242u8, 136u8, 154u8, 196u8, 193u8, 137u8, 107u8, 13u8, 185u8, 251u8, 115u8, 123u8, 176u8, 143u8, 246u8, 233u8,
+					171u8, 71u8, 223u8, 216u8, 191u8, 53u8, 192u8, 221u8, 120u8, 140u8, 192u8, 19u8, 121u8, 40u8, 22u8, 66u8
+				];
+
+				/// Event signature hash, the first topic of its logs.
+				pub const SIGNATURE: ethabi::Hash = ethabi::Hash { 0: TOPIC0 };
+
+				/// Canonical signature of the event, hashed into `SIGNATURE` unless it is anonymous.
+				pub const CANONICAL_SIGNATURE: &str = "one(address)";
+
+				pub fn event() -> ethabi::Event {
+					ethabi::Event {
+						name: "One".into(),
+						inputs: vec![ethabi::EventParam {
+							name: "foo".to_owned(),
+							kind: ethabi::ParamType::Address,
+							indexed: true, internal_type: None, components: vec![]
+						}],
+						anonymous: false,
+					}
+				}
+
+				pub fn filter<T0: Into<ethabi::Topic<ethabi::Address>>>(foo: T0) -> ethabi::TopicFilter {
+					let raw = ethabi::RawTopicFilter {
+						topic0: foo.into().map(|i| ethabi::Token::Address(i)),
+						..Default::default()
+					};
+
+					let e = event();
+					e.filter(raw).expect(INTERNAL_ERR)
+				}
+
+				pub fn wildcard_filter() -> ethabi::TopicFilter {
+					filter(ethabi::Topic::Any)
+				}
+
+				/// Parses a raw log of this event.
+				///
+				#[doc = "`event one(address indexed foo)`"]
+				pub fn parse_log(log: ethabi::RawLog) -> ethabi::Result<super::super::logs::One> {
+					let mut topics = log.topics.into_iter();
+					if topics.next() != Some(SIGNATURE) {
+						return Err(ethabi::Error::InvalidData);
+					}
+					if topics.len() != 1usize {
+						return Err(ethabi::Error::InvalidData);
+					}
+					let mut data = ethabi::decode(&[], &log.data)?.into_iter();
+					let result = super::super::logs::One {
+						foo: ethabi::decode(&[ethabi::ParamType::Address], topics.next().expect(INTERNAL_ERR).as_bytes())?
+							.remove(0)
+							.into_address()
+							.ok_or(ethabi::Error::InvalidData)?
+					};
+					Ok(result)
+				}
+			}
+		};
+
+		assert_eq!(expected.to_string(), e.generate_event().to_string());
+	}
+
+	#[test]
+	fn test_log_with_one_field() {
+		let ethabi_event = ethabi::Event {
+			name: "one".into(),
+			inputs: vec![ethabi::EventParam {
+				name: "foo".into(),
+				kind: ethabi::ParamType::Address,
+				indexed: false,
+				internal_type: None,
+				components: vec![],
+			}],
+			anonymous: false,
+		};
+
+		let e = Event::from(&ethabi_event);
+
+		let expected = quote! {
+			#[derive(Debug, Clone, PartialEq)]
+			pub struct One {
+				pub foo: ethabi::Address
+			}
+		};
+
+		assert_eq!(expected.to_string(), e.generate_log().to_string());
+	}
+
+	#[test]
+	fn test_log_with_multiple_field() {
+		let ethabi_event = ethabi::Event {
+			name: "many".into(),
+			inputs: vec![
+				ethabi::EventParam {
+					name: "foo".into(),
+					kind: ethabi::ParamType::Address,
+					indexed: false,
+					internal_type: None,
+					components: vec![],
+				},
+				ethabi::EventParam {
+					name: "bar".into(),
+					kind: ethabi::ParamType::Array(Box::new(ethabi::ParamType::String)),
+					indexed: false,
+					internal_type: None,
+					components: vec![],
+				},
+				ethabi::EventParam {
+					name: "xyz".into(),
+					kind: ethabi::ParamType::Uint(256),
+					indexed: false,
+					internal_type: None,
+					components: vec![],
+				},
+			],
+			anonymous: false,
+		};
+
+		let e = Event::from(&ethabi_event);
+
+		let expected = quote! {
+			#[derive(Debug, Clone, PartialEq)]
+			pub struct Many {
+				pub foo: ethabi::Address,
+				pub bar: Vec<String>,
+				pub xyz: ethabi::Uint
+			}
+		};
+
+		assert_eq!(expected.to_string(), e.generate_log().to_string());
+	}
+
+	#[test]
+	fn test_log_with_indexed_dynamic_field() {
+		let ethabi_event = ethabi::Event {
+			name: "one".into(),
+			inputs: vec![ethabi::EventParam {
+				name: "foo".into(),
+				kind: ethabi::ParamType::String,
+				indexed: true,
+				internal_type: None,
+				components: vec![],
+			}],
+			anonymous: false,
+		};
+
+		let e = Event::from(&ethabi_event);
+
+		let expected = quote! {
+			#[derive(Debug, Clone, PartialEq)]
+			pub struct One {
+				pub foo: ethabi::Hash
+			}
+		};
+
+		assert_eq!(expected.to_string(), e.generate_log().to_string());
+	}
+
+	#[test]
+	fn test_anonymous_event_has_no_signature() {
+		let ethabi_event = ethabi::Event { name: "hello".into(), inputs: vec![], anonymous: true };
+
+		let generated = Event::from(&ethabi_event).generate_event().to_string();
+
+		assert!(!generated.contains(&quote! { pub const SIGNATURE }.to_string()));
+		assert!(!generated.contains(&quote! { pub const TOPIC0 }.to_string()));
+		assert!(generated.contains(&quote! { pub const CANONICAL_SIGNATURE: &str = "hello()"; }.to_string()));
+		let declaration = quote! { #[doc = "`event hello() anonymous`"] };
+		assert!(generated.starts_with(&declaration.to_string()));
+	}
+}
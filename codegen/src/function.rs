@@ -0,0 +1,886 @@
+// Copyright 2015-2019 Parity Technologies
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use heck::SnakeCase;
+use proc_macro2::{Span, TokenStream};
+use quote::quote;
+
+use super::{
+	from_template_param, from_token, get_output_kinds, get_template_names, input_names, rust_type, template_param_type,
+	to_ethabi_param_vec, to_token, Integers,
+};
+
+struct TemplateParam {
+	/// Template param declaration.
+	///
+	/// ```text
+	/// [T0: Into<Uint>, T1: Into<Bytes>, T2: IntoIterator<Item = U2>, U2 = Into<Uint>]
+	/// ```
+	declaration: TokenStream,
+	/// Template param definition.
+	///
+	/// ```text
+	/// [param0: T0, hello_world: T1, param2: T2]
+	/// ```
+	definition: TokenStream,
+}
+
+struct Inputs {
+	/// Collects template params into vector.
+	///
+	/// ```text
+	/// [Token::Uint(param0.into()), Token::Bytes(hello_world.into()), Token::Array(param2.into_iter().map(Into::into).collect())]
+	/// ```
+	tokenize: Vec<TokenStream>,
+	/// Template params.
+	template_params: Vec<TemplateParam>,
+	/// Input names, also the fields of the builder.
+	///
+	/// ```text
+	/// [param0, hello_world, param2]
+	/// ```
+	names: Vec<syn::Ident>,
+	/// Decoding implementation of the call data, selector excluded.
+	implementation: TokenStream,
+	/// Decode result.
+	result: TokenStream,
+	/// Quote used to recreate `Vec<ethabi::Param>`
+	recreate_quote: TokenStream,
+}
+
+struct Outputs {
+	/// Decoding implementation.
+	implementation: TokenStream,
+	/// Decode result.
+	result: TokenStream,
+	/// Quote used to recreate `Vec<ethabi::Param>`.
+	recreate_quote: TokenStream,
+	/// Output names, also the fields of the output struct.
+	names: Vec<syn::Ident>,
+	/// Rust types of the outputs.
+	kinds: Vec<TokenStream>,
+	/// Conversions of the decoded tokens, in order, into the Rust values of the outputs.
+	conversions: Vec<TokenStream>,
+}
+
+/// Structure used to generate contract's function interface.
+pub struct Function {
+	/// Function name.
+	name: String,
+	/// Name of the generated module.
+	module_name: String,
+	/// Function selector.
+	selector: [u8; 4],
+	/// Canonical signature, e.g. `transfer(address,uint256)`.
+	canonical_signature: String,
+	/// Function input params.
+	inputs: Inputs,
+	/// Function output params.
+	outputs: Outputs,
+	/// Constant function.
+	constant: bool,
+	/// Function state mutability.
+	state_mutability: TokenStream,
+	/// Solidity declaration of the function, for the docs.
+	declaration: String,
+	/// What the state mutability of the function allows, for the docs.
+	mutability_note: Option<&'static str>,
+	/// Whether to generate a builder of calls with a setter per input.
+	builder: bool,
+	/// Whether the outputs are decoded to an `Output` struct.
+	output_struct: bool,
+	/// Whether the output struct derives `serde::Serialize`.
+	serde: bool,
+}
+
+impl<'a> From<&'a ethabi::Function> for Function {
+	fn from(f: &'a ethabi::Function) -> Self {
+		Function::new(f, Integers::Ethabi)
+	}
+}
+
+fn state_mutability(state_mutability: ethabi::StateMutability) -> TokenStream {
+	match state_mutability {
+		ethabi::StateMutability::Pure => quote! { ethabi::StateMutability::Pure },
+		ethabi::StateMutability::View => quote! { ethabi::StateMutability::View },
+		ethabi::StateMutability::NonPayable => quote! { ethabi::StateMutability::NonPayable },
+		ethabi::StateMutability::Payable => quote! { ethabi::StateMutability::Payable },
+	}
+}
+
+impl Function {
+	/// Creates the interface of contract's function, with integers represented as `integers`.
+	pub fn new(f: &ethabi::Function, integers: Integers) -> Self {
+		let output_names = input_names(&f.outputs);
+		// [param0, hello_world, param2]
+		let input_names = input_names(&f.inputs);
+
+		// [T0: Into<Uint>, T1: Into<Bytes>, T2: IntoIterator<Item = U2>, U2 = Into<Uint>]
+		let declarations =
+			f.inputs.iter().enumerate().map(|(index, param)| template_param_type(&param.kind, index, integers));
+
+		// [Uint, Bytes, Vec<Uint>]
+		let kinds: Vec<_> = f.inputs.iter().map(|param| rust_type(&param.kind, integers)).collect();
+
+		// [T0, T1, T2]
+		let template_names: Vec<_> = get_template_names(&kinds);
+
+		// [param0: T0, hello_world: T1, param2: T2]
+		let definitions = input_names
+			.iter()
+			.zip(template_names.iter())
+			.map(|(param_name, template_name)| quote! { #param_name: #template_name });
+
+		let template_params = declarations
+			.zip(definitions)
+			.map(|(declaration, definition)| TemplateParam { declaration, definition })
+			.collect();
+
+		// [Token::Uint(param0.into()), Token::Bytes(hello_world.into()), Token::Array(param2.into_iter().map(Into::into).collect())]
+		let tokenize: Vec<_> = input_names
+			.iter()
+			.zip(f.inputs.iter())
+			.map(|(param_name, param)| to_token(&from_template_param(&param.kind, param_name), &param.kind, integers))
+			.collect();
+
+		let input_result = get_output_kinds(&f.inputs, integers);
+		let input_implementation = match f.inputs.len() {
+			0 => quote! { Ok(()) },
+			_ => decode_implementation(&f.inputs, quote! { function().decode_input(&data[4..])? }, integers),
+		};
+
+		let output_result = get_output_kinds(&f.outputs, integers);
+		let output_implementation = match f.outputs.len() {
+			0 => quote! {
+				let _output = output;
+				Ok(())
+			},
+			_ => decode_implementation(&f.outputs, quote! { self.0.decode_output(output)? }, integers),
+		};
+
+		Function {
+			name: f.name.clone(),
+			module_name: f.name.to_snake_case(),
+			selector: f.short_signature(),
+			canonical_signature: f.canonical_signature(),
+			inputs: Inputs {
+				tokenize,
+				template_params,
+				names: input_names,
+				implementation: input_implementation,
+				result: input_result,
+				recreate_quote: to_ethabi_param_vec(&f.inputs),
+			},
+			outputs: Outputs {
+				implementation: output_implementation,
+				result: output_result,
+				recreate_quote: to_ethabi_param_vec(&f.outputs),
+				names: output_names,
+				kinds: f.outputs.iter().map(|param| rust_type(&param.kind, integers)).collect(),
+				conversions: f
+					.outputs
+					.iter()
+					.map(|param| from_token(&param.kind, &quote! { out.next().expect(INTERNAL_ERR) }, integers))
+					.collect(),
+			},
+			constant: f.constant,
+			state_mutability: state_mutability(f.state_mutability),
+			declaration: declaration(f),
+			mutability_note: match f.state_mutability {
+				ethabi::StateMutability::Pure => {
+					Some("The function is `pure`, it neither reads nor modifies the state.")
+				}
+				ethabi::StateMutability::View => {
+					Some("The function is `view`, it reads but does not modify the state.")
+				}
+				ethabi::StateMutability::Payable => Some("The function is `payable`, calls may send ether."),
+				ethabi::StateMutability::NonPayable => None,
+			},
+			builder: false,
+			output_struct: false,
+			serde: false,
+		}
+	}
+
+	/// Name of the generated function module.
+	pub fn module_name(&self) -> &str {
+		&self.module_name
+	}
+
+	/// Suffixes the module name with the position of the function among the functions it
+	/// would otherwise clash with, e.g. `transfer_1`, so that every overload gets its own module.
+	pub fn overload(mut self, index: usize) -> Self {
+		self.module_name = format!("{}_{}", self.module_name, index);
+		self
+	}
+
+	/// Also generates a builder of calls to the function, with a `with_<input>` setter per input.
+	pub fn with_builder(mut self) -> Self {
+		self.builder = true;
+		self
+	}
+
+	/// Decodes the outputs to an `Output` struct with a field per output, if there are several.
+	pub fn with_output_struct(mut self) -> Self {
+		if self.outputs.names.len() > 1 {
+			let names = &self.outputs.names;
+			let conversions = &self.outputs.conversions;
+			self.outputs.implementation = quote! {
+				let mut out = self.0.decode_output(output)?.into_iter();
+				Ok(Output { #(#names: #conversions),* })
+			};
+			self.outputs.result = quote! { Output };
+			self.output_struct = true;
+		}
+		self
+	}
+
+	/// Also derives `serde::Serialize` for the output struct.
+	pub fn with_serde(mut self) -> Self {
+		self.serde = true;
+		self
+	}
+
+	/// Generates the struct of the outputs.
+	fn generate_output_struct(&self) -> TokenStream {
+		let names = &self.outputs.names;
+		let kinds = &self.outputs.kinds;
+		let serde = match self.serde {
+			true => quote! { #[derive(serde::Serialize)] },
+			false => quote! {},
+		};
+
+		quote! {
+			/// Outputs of the function.
+			#[derive(Debug, Clone, PartialEq)]
+			#serde
+			pub struct Output {
+				#(pub #names: #kinds),*
+			}
+		}
+	}
+
+	/// Generates the builder of calls to the function.
+	fn generate_builder(&self) -> TokenStream {
+		let names = &self.inputs.names;
+		let setters = self.inputs.template_params.iter().zip(names).zip(&self.inputs.tokenize).map(
+			|((template_param, name), tokenize)| {
+				let setter = syn::Ident::new(&format!("with_{}", name), Span::call_site());
+				let doc = format!("Sets the `{}` input.", name);
+				let declaration = &template_param.declaration;
+				let definition = &template_param.definition;
+				quote! {
+					#[doc = #doc]
+					pub fn #setter<#declaration>(mut self, #definition) -> Self {
+						self.#name = Some(#tokenize);
+						self
+					}
+				}
+			},
+		);
+		let missing = names.iter().map(|name| name.to_string());
+
+		quote! {
+			/// Builder of calls to the function, with a setter per input.
+			#[derive(Debug, Clone, Default)]
+			pub struct Builder {
+				#(#names: Option<ethabi::Token>),*
+			}
+
+			impl Builder {
+				#(#setters)*
+
+				/// Encodes the call, failing with `ethabi::Error::MissingArgument` if an input was not set.
+				pub fn encode(self) -> ethabi::Result<ethabi::Bytes> {
+					let tokens: &[ethabi::Token] =
+						&[#(self.#names.ok_or_else(|| ethabi::Error::MissingArgument(#missing.into()))?),*];
+					Ok(encode_call(&function(), tokens))
+				}
+			}
+
+			/// Starts building a call to the function.
+			pub fn builder() -> Builder {
+				Builder::default()
+			}
+		}
+	}
+
+	/// Generates the method of `Instance` calling the function through its `Caller`.
+	pub fn generate_method(&self) -> TokenStream {
+		let module_name = syn::Ident::new(&self.module_name, Span::call_site());
+		let declarations = self.inputs.template_params.iter().map(|i| &i.declaration);
+		let definitions = self.inputs.template_params.iter().map(|i| &i.definition);
+		let names = &self.inputs.names;
+		let outputs_result = match self.output_struct {
+			true => quote! { functions::#module_name::Output },
+			false => self.outputs.result.clone(),
+		};
+		let declaration = format!("`{}`", self.declaration);
+
+		quote! {
+			/// Calls the function, resolving to its decoded output.
+			///
+			#[doc = #declaration]
+			pub fn #module_name<#(#declarations),*>(
+				&self,
+				#(#definitions),*
+			) -> impl std::future::Future<Output = ethabi::Result<#outputs_result>> {
+				let (data, decoder) = functions::#module_name::call(#(#names),*);
+				let output = self.caller.call(self.address, data);
+				async move { ethabi::FunctionOutputDecoder::decode(&decoder, &output.await?) }
+			}
+		}
+	}
+
+	/// Generates the interface for contract's function.
+	pub fn generate(&self) -> TokenStream {
+		let name = &self.name;
+		let module_name = syn::Ident::new(&self.module_name, Span::call_site());
+		let selector = &self.selector;
+		let canonical_signature = &self.canonical_signature;
+		let tokenize = &self.inputs.tokenize;
+		let declarations: &Vec<_> = &self.inputs.template_params.iter().map(|i| &i.declaration).collect();
+		let definitions: &Vec<_> = &self.inputs.template_params.iter().map(|i| &i.definition).collect();
+		let recreate_inputs = &self.inputs.recreate_quote;
+		let recreate_outputs = &self.outputs.recreate_quote;
+		let constant = &self.constant;
+		let state_mutability = &self.state_mutability;
+		let inputs_result = &self.inputs.result;
+		let inputs_implementation = &self.inputs.implementation;
+		let outputs_result = &self.outputs.result;
+		let outputs_implementation = &self.outputs.implementation;
+		let declaration = format!("`{}`", self.declaration);
+		let mutability_note = self.mutability_note.map(|note| quote! { #[doc = ""] #[doc = #note] });
+		let builder = match self.builder {
+			true => self.generate_builder(),
+			false => quote! {},
+		};
+		let output_struct = match self.output_struct {
+			true => self.generate_output_struct(),
+			false => quote! {},
+		};
+
+		quote! {
+			#[doc = #declaration]
+			#mutability_note
+			pub mod #module_name {
+				use super::{ethabi, INTERNAL_ERR};
+
+				/// Function selector, the first 4 bytes of the encoded calls.
+				pub const SELECTOR: [u8; 4] = [#(#selector),*];
+
+				/// Canonical signature of the function, the selector is the start of its hash.
+				pub const CANONICAL_SIGNATURE: &str = #canonical_signature;
+
+				fn function() -> ethabi::Function {
+					ethabi::Function {
+						name: #name.into(),
+						inputs: #recreate_inputs,
+						outputs: #recreate_outputs,
+						constant: #constant,
+						state_mutability: #state_mutability,
+					}
+				}
+
+				/// Encodes a call to the function with the precomputed selector.
+				fn encode_call(f: &ethabi::Function, tokens: &[ethabi::Token]) -> ethabi::Bytes {
+					f.validate_inputs(tokens).expect(INTERNAL_ERR);
+					SELECTOR.iter().copied().chain(ethabi::encode(tokens)).collect()
+				}
+
+				#output_struct
+
+				/// Generic function output decoder.
+				pub struct Decoder(ethabi::Function);
+
+				impl ethabi::FunctionOutputDecoder for Decoder {
+					type Output = #outputs_result;
+
+					fn decode(&self, output: &[u8]) -> ethabi::Result<Self::Output> {
+						#outputs_implementation
+					}
+				}
+
+				/// Encodes function input.
+				///
+				#[doc = #declaration]
+				pub fn encode_input<#(#declarations),*>(#(#definitions),*) -> ethabi::Bytes {
+					let f = function();
+					let tokens = vec![#(#tokenize),*];
+					encode_call(&f, &tokens)
+				}
+
+				/// Decodes function input from call data, failing if it is not a call to this function.
+				///
+				#[doc = #declaration]
+				pub fn decode_input(data: &[u8]) -> ethabi::Result<#inputs_result> {
+					if data.len() < 4 || data[..4] != SELECTOR {
+						return Err(ethabi::Error::InvalidData);
+					}
+					#inputs_implementation
+				}
+
+				/// Decodes function output.
+				///
+				#[doc = #declaration]
+				pub fn decode_output(output: &[u8]) -> ethabi::Result<#outputs_result> {
+					ethabi::FunctionOutputDecoder::decode(&Decoder(function()), output)
+				}
+
+				/// Encodes function output and creates a `Decoder` instance.
+				pub fn call<#(#declarations),*>(#(#definitions),*) -> (ethabi::Bytes, Decoder) {
+					let f = function();
+					let tokens = vec![#(#tokenize),*];
+					(encode_call(&f, &tokens), Decoder(f))
+				}
+
+				#builder
+			}
+		}
+	}
+}
+
+/// Reconstructs the Solidity declaration of a function,
+/// e.g. `function transfer(address to, uint256 value) returns (bool)`.
+fn declaration(f: &ethabi::Function) -> String {
+	let params = |params: &[ethabi::Param]| {
+		params
+			.iter()
+			.map(|param| match param.name.is_empty() {
+				true => param.kind.to_string(),
+				false => format!("{} {}", param.kind, param.name),
+			})
+			.collect::<Vec<_>>()
+			.join(", ")
+	};
+
+	let mut declaration = format!("function {}({})", f.name, params(&f.inputs));
+	match f.state_mutability {
+		ethabi::StateMutability::Pure => declaration.push_str(" pure"),
+		ethabi::StateMutability::View => declaration.push_str(" view"),
+		ethabi::StateMutability::Payable => declaration.push_str(" payable"),
+		ethabi::StateMutability::NonPayable => (),
+	}
+	if !f.outputs.is_empty() {
+		declaration.push_str(&format!(" returns ({})", params(&f.outputs)));
+	}
+	declaration
+}
+
+/// Converts the tokens `decoded` into the Rust values of at least one `params`.
+fn decode_implementation(params: &[ethabi::Param], decoded: TokenStream, integers: Integers) -> TokenStream {
+	match params.len() {
+		1 => {
+			let o = quote! { out };
+			let from_first = from_token(&params[0].kind, &o, integers);
+			quote! {
+				let out = #decoded.into_iter().next().expect(INTERNAL_ERR);
+				let value = #from_first;
+				Ok(value)
+			}
+		}
+		_ => {
+			let o = quote! { out.next().expect(INTERNAL_ERR) };
+			let outs: Vec<_> = params.iter().map(|param| from_token(&param.kind, &o, integers)).collect();
+
+			quote! {
+				let mut out = #decoded.into_iter();
+				Ok(( #(#outs),* ))
+			}
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::Function;
+	use quote::quote;
+
+	#[test]
+	fn test_no_params() {
+		let ethabi_function = ethabi::Function {
+			name: "empty".into(),
+			inputs: vec![],
+			outputs: vec![],
+			constant: false,
+			state_mutability: ethabi::StateMutability::NonPayable,
+		};
+
+		let f = Function::from(&ethabi_function);
+
+		let expected = quote! {
+			#[doc = "`function empty()`"]
+			pub mod empty {
+				use super::{ethabi, INTERNAL_ERR};
+
+				/// Function selector, the first 4 bytes of the encoded calls.
+				pub const SELECTOR: [u8; 4] = [242u8, 167u8, 95u8, 228u8];
+
+				/// Canonical signature of the function, the selector is the start of its hash.
+				pub const CANONICAL_SIGNATURE: &str = "empty()";
+
+				fn function() -> ethabi::Function {
+					ethabi::Function {
+						name: "empty".into(),
+						inputs: vec![],
+						outputs: vec![],
+						constant: false,
+						state_mutability: ethabi::StateMutability::NonPayable,
+					}
+				}
+
+				/// Encodes a call to the function with the precomputed selector.
+				fn encode_call(f: &ethabi::Function, tokens: &[ethabi::Token]) -> ethabi::Bytes {
+					f.validate_inputs(tokens).expect(INTERNAL_ERR);
+					SELECTOR.iter().copied().chain(ethabi::encode(tokens)).collect()
+				}
+
+				/// Generic function output decoder.
+				pub struct Decoder(ethabi::Function);
+
+				impl ethabi::FunctionOutputDecoder for Decoder {
+					type Output = ();
+
+					fn decode(&self, output: &[u8]) -> ethabi::Result<Self::Output> {
+						let _output = output;
+						Ok(())
+					}
+				}
+
+				/// Encodes function input.
+				///
+				#[doc = "`function empty()`"]
+				pub fn encode_input<>() -> ethabi::Bytes {
+					let f = function();
+					let tokens = vec![];
+					encode_call(&f, &tokens)
+				}
+
+				/// Decodes function input from call data, failing if it is not a call to this function.
+				///
+				#[doc = "`function empty()`"]
+				pub fn decode_input(data: &[u8]) -> ethabi::Result<()> {
+					if data.len() < 4 || data[..4] != SELECTOR {
+						return Err(ethabi::Error::InvalidData);
+					}
+					Ok(())
+				}
+
+				/// Decodes function output.
+				///
+				#[doc = "`function empty()`"]
+				pub fn decode_output(output: &[u8]) -> ethabi::Result<()> {
+					ethabi::FunctionOutputDecoder::decode(&Decoder(function()), output)
+				}
+
+				/// Encodes function output and creates a `Decoder` instance.
+				pub fn call<>() -> (ethabi::Bytes, Decoder) {
+					let f = function();
+					let tokens = vec![];
+					(encode_call(&f, &tokens), Decoder(f))
+				}
+			}
+		};
+
+		assert_eq!(expected.to_string(), f.generate().to_string());
+	}
+
+	#[test]
+	fn test_one_param() {
+		let ethabi_function = ethabi::Function {
+			name: "hello".into(),
+			inputs: vec![ethabi::Param {
+				name: "foo".into(),
+				kind: ethabi::ParamType::Address,
+				internal_type: None,
+				components: vec![],
+			}],
+			outputs: vec![ethabi::Param {
+				name: "bar".into(),
+				kind: ethabi::ParamType::Uint(256),
+				internal_type: None,
+				components: vec![],
+			}],
+			constant: false,
+			state_mutability: ethabi::StateMutability::NonPayable,
+		};
+
+		let f = Function::from(&ethabi_function);
+
+		let expected = quote! {
+			#[doc = "`function hello(address foo) returns (uint256 bar)`"]
+			pub mod hello {
+				use super::{ethabi, INTERNAL_ERR};
+
+				/// Function selector, the first 4 bytes of the encoded calls.
+				pub const SELECTOR: [u8; 4] = [132u8, 250u8, 231u8, 96u8];
+
+				/// Canonical signature of the function, the selector is the start of its hash.
+				pub const CANONICAL_SIGNATURE: &str = "hello(address)";
+
+				fn function() -> ethabi::Function {
+					ethabi::Function {
+						name: "hello".into(),
+						inputs: vec![ethabi::Param {
+							name: "foo".to_owned(),
+							kind: ethabi::ParamType::Address, internal_type: None, components: vec![]
+						}],
+						outputs: vec![ethabi::Param {
+							name: "bar".to_owned(),
+							kind: ethabi::ParamType::Uint(256usize), internal_type: None, components: vec![]
+						}],
+						constant: false,
+						state_mutability: ethabi::StateMutability::NonPayable,
+					}
+				}
+
+				/// Encodes a call to the function with the precomputed selector.
+				fn encode_call(f: &ethabi::Function, tokens: &[ethabi::Token]) -> ethabi::Bytes {
+					f.validate_inputs(tokens).expect(INTERNAL_ERR);
+					SELECTOR.iter().copied().chain(ethabi::encode(tokens)).collect()
+				}
+
+				/// Generic function output decoder.
+				pub struct Decoder(ethabi::Function);
+
+				impl ethabi::FunctionOutputDecoder for Decoder {
+					type Output = ethabi::Uint;
+
+					fn decode(&self, output: &[u8]) -> ethabi::Result<Self::Output> {
+						let out = self.0.decode_output(output)?.into_iter().next().expect(INTERNAL_ERR);
+						let value = out.into_uint().ok_or(ethabi::Error::InvalidData)?;
+					Ok(value)
+					}
+				}
+
+				/// Encodes function input.
+				///
+				#[doc = "`function hello(address foo) returns (uint256 bar)`"]
+				pub fn encode_input<T0: Into<ethabi::Address> >(foo: T0) -> ethabi::Bytes {
+					let f = function();
+					let tokens = vec![ethabi::Token::Address(foo.into())];
+					encode_call(&f, &tokens)
+				}
+
+				/// Decodes function input from call data, failing if it is not a call to this function.
+				///
+				#[doc = "`function hello(address foo) returns (uint256 bar)`"]
+				pub fn decode_input(data: &[u8]) -> ethabi::Result<ethabi::Address> {
+					if data.len() < 4 || data[..4] != SELECTOR {
+						return Err(ethabi::Error::InvalidData);
+					}
+					let out = function().decode_input(&data[4..])?.into_iter().next().expect(INTERNAL_ERR);
+					let value = out.into_address().ok_or(ethabi::Error::InvalidData)?;
+					Ok(value)
+				}
+
+				/// Decodes function output.
+				///
+				#[doc = "`function hello(address foo) returns (uint256 bar)`"]
+				pub fn decode_output(output: &[u8]) -> ethabi::Result<ethabi::Uint> {
+					ethabi::FunctionOutputDecoder::decode(&Decoder(function()), output)
+				}
+
+				/// Encodes function output and creates a `Decoder` instance.
+				pub fn call<T0: Into<ethabi::Address> >(foo: T0) -> (ethabi::Bytes, Decoder) {
+					let f = function();
+					let tokens = vec![ethabi::Token::Address(foo.into())];
+					(encode_call(&f, &tokens), Decoder(f))
+				}
+			}
+		};
+
+		assert_eq!(expected.to_string(), f.generate().to_string());
+	}
+
+	#[test]
+	fn test_multiple_params() {
+		let ethabi_function = ethabi::Function {
+			name: "multi".into(),
+			inputs: vec![
+				ethabi::Param {
+					name: "foo".into(),
+					kind: ethabi::ParamType::FixedArray(Box::new(ethabi::ParamType::Address), 2),
+					internal_type: None,
+					components: vec![],
+				},
+				ethabi::Param {
+					name: "bar".into(),
+					kind: ethabi::ParamType::Array(Box::new(ethabi::ParamType::Uint(256))),
+					internal_type: None,
+					components: vec![],
+				},
+			],
+			outputs: vec![
+				ethabi::Param {
+					name: "".into(),
+					kind: ethabi::ParamType::Uint(256),
+					internal_type: None,
+					components: vec![],
+				},
+				ethabi::Param {
+					name: "".into(),
+					kind: ethabi::ParamType::String,
+					internal_type: None,
+					components: vec![],
+				},
+			],
+			constant: false,
+			state_mutability: ethabi::StateMutability::NonPayable,
+		};
+
+		let f = Function::from(&ethabi_function);
+
+		let expected = quote! {
+			#[doc = "`function multi(address[2] foo, uint256[] bar) returns (uint256, string)`"]
+			pub mod multi {
+				use super::{ethabi, INTERNAL_ERR};
+
+				/// Function selector, the first 4 bytes of the encoded calls.
+				pub const SELECTOR: [u8; 4] = [229u8, 129u8, 114u8, 102u8];
+
+				/// Canonical signature of the function, the selector is the start of its hash.
+				pub const CANONICAL_SIGNATURE: &str = "multi(address[2],uint256[])";
+
+				fn function() -> ethabi::Function {
+					ethabi::Function {
+						name: "multi".into(),
+						inputs: vec![ethabi::Param {
+							name: "foo".to_owned(),
+							kind: ethabi::ParamType::FixedArray(Box::new(ethabi::ParamType::Address), 2usize), internal_type: None, components: vec![]
+						}, ethabi::Param {
+							name: "bar".to_owned(),
+							kind: ethabi::ParamType::Array(Box::new(ethabi::ParamType::Uint(256usize))), internal_type: None, components: vec![]
+						}],
+						outputs: vec![ethabi::Param {
+							name: "".to_owned(),
+							kind: ethabi::ParamType::Uint(256usize), internal_type: None, components: vec![]
+						}, ethabi::Param {
+							name: "".to_owned(),
+							kind: ethabi::ParamType::String, internal_type: None, components: vec![]
+						}],
+						constant: false,
+						state_mutability: ethabi::StateMutability::NonPayable,
+					}
+				}
+
+				/// Encodes a call to the function with the precomputed selector.
+				fn encode_call(f: &ethabi::Function, tokens: &[ethabi::Token]) -> ethabi::Bytes {
+					f.validate_inputs(tokens).expect(INTERNAL_ERR);
+					SELECTOR.iter().copied().chain(ethabi::encode(tokens)).collect()
+				}
+
+				/// Generic function output decoder.
+				pub struct Decoder(ethabi::Function);
+
+				impl ethabi::FunctionOutputDecoder for Decoder {
+					type Output = (ethabi::Uint, String);
+
+					fn decode(&self, output: &[u8]) -> ethabi::Result<Self::Output> {
+						let mut out = self.0.decode_output(output)?.into_iter();
+						Ok((out.next().expect(INTERNAL_ERR).into_uint().ok_or(ethabi::Error::InvalidData)?, out.next().expect(INTERNAL_ERR).into_string().ok_or(ethabi::Error::InvalidData)?))
+					}
+				}
+
+				/// Encodes function input.
+				///
+				#[doc = "`function multi(address[2] foo, uint256[] bar) returns (uint256, string)`"]
+				pub fn encode_input<T0: Into<[U0; 2usize]>, U0: Into<ethabi::Address>, T1: IntoIterator<Item = U1>, U1: Into<ethabi::Uint> >(foo: T0, bar: T1) -> ethabi::Bytes {
+					let f = function();
+					let tokens = vec![{
+						let v = IntoIterator::into_iter(Into::<[_; 2usize]>::into(foo)).map(Into::into).collect::<Vec<_>>().into_iter().map(|inner| ethabi::Token::Address(inner)).collect();
+						ethabi::Token::FixedArray(v)
+					}, {
+						let v = bar.into_iter().map(Into::into).collect::<Vec<_>>().into_iter().map(|inner| ethabi::Token::Uint(inner)).collect();
+						ethabi::Token::Array(v)
+					}];
+					encode_call(&f, &tokens)
+				}
+
+				/// Decodes function input from call data, failing if it is not a call to this function.
+				///
+				#[doc = "`function multi(address[2] foo, uint256[] bar) returns (uint256, string)`"]
+				pub fn decode_input(data: &[u8]) -> ethabi::Result<([ethabi::Address; 2usize], Vec<ethabi::Uint>)> {
+					if data.len() < 4 || data[..4] != SELECTOR {
+						return Err(ethabi::Error::InvalidData);
+					}
+					let mut out = function().decode_input(&data[4..])?.into_iter();
+					Ok((
+						{
+							let v = out.next().expect(INTERNAL_ERR).into_fixed_array().ok_or(ethabi::Error::InvalidData)?;
+							if v.len() != 2 {
+								return Err(ethabi::Error::InvalidData);
+							}
+							let mut values = Vec::with_capacity(2);
+							for inner in v {
+								values.push(inner.into_address().ok_or(ethabi::Error::InvalidData)?);
+							}
+							let mut iter = values.into_iter();
+							std::array::from_fn::<_, 2, _>(|_| iter.next().expect(INTERNAL_ERR))
+						},
+						{
+							let mut values = Vec::new();
+							for inner in out.next().expect(INTERNAL_ERR).into_array().ok_or(ethabi::Error::InvalidData)? {
+								values.push(inner.into_uint().ok_or(ethabi::Error::InvalidData)?);
+							}
+							values
+						}
+					))
+				}
+
+				/// Decodes function output.
+				///
+				#[doc = "`function multi(address[2] foo, uint256[] bar) returns (uint256, string)`"]
+				pub fn decode_output(output: &[u8]) -> ethabi::Result<(ethabi::Uint, String)> {
+					ethabi::FunctionOutputDecoder::decode(&Decoder(function()), output)
+				}
+
+				/// Encodes function output and creates a `Decoder` instance.
+				pub fn call<T0: Into<[U0; 2usize]>, U0: Into<ethabi::Address>, T1: IntoIterator<Item = U1>, U1: Into<ethabi::Uint> >(foo: T0, bar: T1) -> (ethabi::Bytes, Decoder) {
+					let f = function();
+					let tokens = vec![{
+						let v = IntoIterator::into_iter(Into::<[_; 2usize]>::into(foo)).map(Into::into).collect::<Vec<_>>().into_iter().map(|inner| ethabi::Token::Address(inner)).collect();
+						ethabi::Token::FixedArray(v)
+					}, {
+						let v = bar.into_iter().map(Into::into).collect::<Vec<_>>().into_iter().map(|inner| ethabi::Token::Uint(inner)).collect();
+						ethabi::Token::Array(v)
+					}];
+					(encode_call(&f, &tokens), Decoder(f))
+				}
+			}
+		};
+
+		assert_eq!(expected.to_string(), f.generate().to_string());
+	}
+
+	#[test]
+	fn test_doc_mentions_mutability() {
+		let ethabi_function = ethabi::Function::parse("deposit(uint256 amount) payable returns (bool)").unwrap();
+
+		let generated = Function::from(&ethabi_function).generate().to_string();
+
+		let declaration = quote! { #[doc = "`function deposit(uint256 amount) payable returns (bool)`"] };
+		assert!(generated.starts_with(&declaration.to_string()));
+		let note = quote! { #[doc = "The function is `payable`, calls may send ether."] };
+		assert!(generated.contains(&note.to_string()));
+	}
+
+	#[test]
+	fn test_builder_is_opt_in() {
+		let ethabi_function = ethabi::Function::parse("transfer(address to, uint256)").unwrap();
+
+		assert!(!Function::from(&ethabi_function).generate().to_string().contains("Builder"));
+
+		let generated = Function::from(&ethabi_function).with_builder().generate().to_string();
+		let setter = quote! { pub fn with_to<T0: Into<ethabi::Address> >(mut self, to: T0) -> Self };
+		assert!(generated.contains(&setter.to_string()));
+		// unnamed inputs are named after their position
+		let setter = quote! { pub fn with_param1<T1: Into<ethabi::Uint> >(mut self, param1: T1) -> Self };
+		assert!(generated.contains(&setter.to_string()));
+	}
+}
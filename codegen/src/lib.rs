@@ -0,0 +1,571 @@
+// Copyright 2015-2019 Parity Technologies
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Generation of the `use_contract!` bindings of contract ABIs, e.g. from build scripts.
+//!
+//! ```no_run
+//! // build.rs
+//! let out = std::path::Path::new(&std::env::var("OUT_DIR").unwrap()).join("eip20.rs");
+//! ethabi_codegen::generate_contract_module("res/eip20.abi", out).unwrap();
+//! ```
+//!
+//! The bindings are then included in a module with
+//! `pub mod eip20 { include!(concat!(env!("OUT_DIR"), "/eip20.rs")); }`. They are written
+//! on a single line, so the module better allow `clippy::all`.
+
+#![recursion_limit = "256"]
+
+mod constructor;
+mod contract;
+mod error;
+mod event;
+mod function;
+mod types;
+
+use anyhow::anyhow;
+use ethabi::{Contract, Param, ParamType, Result, TupleParam};
+use heck::SnakeCase;
+use proc_macro2::{Span, TokenStream};
+use quote::quote;
+use std::{fs, path::Path};
+
+/// How integer params are represented in the generated code.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Integers {
+	/// `ethabi::Uint` and `ethabi::Int` whatever their width.
+	#[default]
+	Ethabi,
+	/// Native Rust integers for the widths of `u8` to `u64`, `ethabi::Uint` and `ethabi::Int` for
+	/// the other widths, e.g. `uint24`, which a native integer would not bound.
+	Native,
+}
+
+/// What the generated bindings include, the defaults being the ones of `use_contract!` without
+/// options.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Options {
+	/// How integer params are represented.
+	pub integers: Integers,
+	/// Whether the functions get a builder of calls with a setter per input.
+	pub builders: bool,
+	/// Whether the functions with several outputs decode them to an `Output` struct with a
+	/// field per output, instead of a tuple.
+	pub structs: bool,
+	/// Whether the logs and output structs derive `serde::Serialize`, and the structs of the
+	/// params `serde::Serialize` and `serde::Deserialize`, which needs the crate including the
+	/// bindings to depend on serde.
+	pub serde: bool,
+	/// Whether the bindings get a `Caller` trait of transports and an `Instance` with an async
+	/// method per function, calling it through one.
+	pub futures: bool,
+	/// Creation bytecode of the contract, giving the bindings a `BYTECODE` constant and a
+	/// `deploy_data` encoding deployments with it.
+	pub bytecode: Option<ethabi::Bytes>,
+	/// Path of the ethabi crate in the generated code, e.g. `::my_crate::ethabi` when it is
+	/// re-exported, instead of `ethabi`. Generating the bindings fails if it is not a valid path.
+	pub crate_path: Option<String>,
+}
+
+/// Generates the bindings of `contract` with `options`.
+///
+/// Fails if the structs and enums of the contract cannot be given distinct names, or if the
+/// crate path of `options` is not a valid path.
+pub fn generate(contract: &Contract, options: Options) -> Result<TokenStream> {
+	Ok(contract::Contract::new(contract, options)?.generate())
+}
+
+/// Writes the bindings of the ABI at `abi_path` to `out_path`, the same as the ones of
+/// `use_contract!` without options.
+pub fn generate_contract_module(abi_path: impl AsRef<Path>, out_path: impl AsRef<Path>) -> Result<()> {
+	let (abi_path, out_path) = (abi_path.as_ref(), out_path.as_ref());
+	let source_file =
+		fs::File::open(abi_path).map_err(|_| anyhow!("Cannot load contract abi from `{}`", abi_path.display()))?;
+	let module = generate(&Contract::load(source_file)?, Options::default())?;
+	fs::write(out_path, module.to_string())
+		.map_err(|err| anyhow!("Cannot write contract bindings to `{}`: {}", out_path.display(), err))?;
+	Ok(())
+}
+
+fn to_syntax_string(param_type: &ethabi::ParamType) -> proc_macro2::TokenStream {
+	match *param_type {
+		ParamType::Address => quote! { ethabi::ParamType::Address },
+		ParamType::Bytes => quote! { ethabi::ParamType::Bytes },
+		ParamType::Int(x) => quote! { ethabi::ParamType::Int(#x) },
+		ParamType::Uint(x) => quote! { ethabi::ParamType::Uint(#x) },
+		ParamType::Fixed(x, y) => quote! { ethabi::ParamType::Fixed(#x, #y) },
+		ParamType::Ufixed(x, y) => quote! { ethabi::ParamType::Ufixed(#x, #y) },
+		ParamType::Bool => quote! { ethabi::ParamType::Bool },
+		ParamType::String => quote! { ethabi::ParamType::String },
+		ParamType::Array(ref param_type) => {
+			let param_type_quote = to_syntax_string(param_type);
+			quote! { ethabi::ParamType::Array(Box::new(#param_type_quote)) }
+		}
+		ParamType::FixedBytes(x) => quote! { ethabi::ParamType::FixedBytes(#x) },
+		ParamType::Function => quote! { ethabi::ParamType::Function },
+		ParamType::FixedArray(ref param_type, ref x) => {
+			let param_type_quote = to_syntax_string(param_type);
+			quote! { ethabi::ParamType::FixedArray(Box::new(#param_type_quote), #x) }
+		}
+		ParamType::Tuple(ref param_types) => {
+			let param_types_quote = param_types.iter().map(to_syntax_string);
+			quote! { ethabi::ParamType::Tuple(vec![#(#param_types_quote),*]) }
+		}
+	}
+}
+
+fn to_ethabi_param_vec<'a, P>(params: P) -> proc_macro2::TokenStream
+where
+	P: IntoIterator<Item = &'a Param> + 'a,
+{
+	let p = params
+		.into_iter()
+		.map(|x| {
+			let name = &x.name;
+			let kind = to_syntax_string(&x.kind);
+			let internal_type = to_internal_type(&x.internal_type);
+			let components = to_ethabi_components(&x.components);
+			quote! {
+				ethabi::Param {
+					name: #name.to_owned(),
+					kind: #kind,
+					internal_type: #internal_type,
+					components: #components
+				}
+			}
+		})
+		.collect::<Vec<_>>();
+
+	quote! { vec![ #(#p),* ] }
+}
+
+fn to_internal_type(internal_type: &Option<String>) -> proc_macro2::TokenStream {
+	match internal_type {
+		Some(internal_type) => quote! { Some(#internal_type.to_owned()) },
+		None => quote! { None },
+	}
+}
+
+/// Recreates the names and types of the components of a tuple param.
+fn to_ethabi_components(components: &[TupleParam]) -> proc_macro2::TokenStream {
+	let c = components.iter().map(|x| {
+		let name = match x.name {
+			Some(ref name) => quote! { Some(#name.to_owned()) },
+			None => quote! { None },
+		};
+		let kind = to_syntax_string(&x.kind);
+		let internal_type = to_internal_type(&x.internal_type);
+		let components = to_ethabi_components(&x.components);
+		quote! {
+			ethabi::TupleParam {
+				name: #name,
+				kind: #kind,
+				internal_type: #internal_type,
+				components: #components
+			}
+		}
+	});
+	quote! { vec![ #(#c),* ] }
+}
+
+/// Returns the native Rust integer a param is represented with, along with its width in bits.
+///
+/// Only integers as wide as a native one are, so that every value of the native integer encodes.
+fn native_integer(input: &ParamType, integers: Integers) -> Option<(proc_macro2::TokenStream, usize)> {
+	if integers == Integers::Ethabi {
+		return None;
+	}
+
+	let (signed, size) = match *input {
+		ParamType::Uint(size @ (8 | 16 | 32 | 64)) => (false, size),
+		ParamType::Int(size @ (8 | 16 | 32 | 64)) => (true, size),
+		_ => return None,
+	};
+	let prefix = if signed { "i" } else { "u" };
+	let ident = syn::Ident::new(&format!("{}{}", prefix, size), Span::call_site());
+	Some((quote! { #ident }, size))
+}
+
+fn rust_type(input: &ParamType, integers: Integers) -> proc_macro2::TokenStream {
+	if let Some((native, _)) = native_integer(input, integers) {
+		return native;
+	}
+
+	match *input {
+		ParamType::Address => quote! { ethabi::Address },
+		ParamType::Bytes => quote! { ethabi::Bytes },
+		ParamType::FixedBytes(32) => quote! { ethabi::Hash },
+		ParamType::FixedBytes(size) => quote! { [u8; #size] },
+		ParamType::Function => quote! { [u8; 24] },
+		ParamType::Int(_) | ParamType::Fixed(..) => quote! { ethabi::Int },
+		ParamType::Uint(_) | ParamType::Ufixed(..) => quote! { ethabi::Uint },
+		ParamType::Bool => quote! { bool },
+		ParamType::String => quote! { String },
+		ParamType::Array(ref kind) => {
+			let t = rust_type(kind, integers);
+			quote! { Vec<#t> }
+		}
+		ParamType::FixedArray(ref kind, size) => {
+			let t = rust_type(kind, integers);
+			quote! { [#t; #size] }
+		}
+		ParamType::Tuple(ref kinds) => {
+			let t = kinds.iter().map(|kind| rust_type(kind, integers));
+			quote! { (#(#t,)*) }
+		}
+	}
+}
+
+fn template_param_type(input: &ParamType, index: usize, integers: Integers) -> proc_macro2::TokenStream {
+	let t_ident = syn::Ident::new(&format!("T{}", index), Span::call_site());
+	let u_ident = syn::Ident::new(&format!("U{}", index), Span::call_site());
+	if let Some((native, _)) = native_integer(input, integers) {
+		return quote! { #t_ident: Into<#native> };
+	}
+
+	match *input {
+		ParamType::Address => quote! { #t_ident: Into<ethabi::Address> },
+		ParamType::Bytes => quote! { #t_ident: Into<ethabi::Bytes> },
+		ParamType::FixedBytes(32) => quote! { #t_ident: Into<ethabi::Hash> },
+		ParamType::FixedBytes(size) => quote! { #t_ident: Into<[u8; #size]> },
+		ParamType::Function => quote! { #t_ident: Into<[u8; 24]> },
+		ParamType::Int(_) | ParamType::Fixed(..) => quote! { #t_ident: Into<ethabi::Int> },
+		ParamType::Uint(_) | ParamType::Ufixed(..) => quote! { #t_ident: Into<ethabi::Uint> },
+		ParamType::Bool => quote! { #t_ident: Into<bool> },
+		ParamType::String => quote! { #t_ident: Into<String> },
+		ParamType::Array(ref kind) => {
+			let t = rust_type(kind, integers);
+			quote! {
+				#t_ident: IntoIterator<Item = #u_ident>, #u_ident: Into<#t>
+			}
+		}
+		ParamType::FixedArray(ref kind, size) => {
+			let t = rust_type(kind, integers);
+			quote! {
+				#t_ident: Into<[#u_ident; #size]>, #u_ident: Into<#t>
+			}
+		}
+		ParamType::Tuple(_) => {
+			let t = rust_type(input, integers);
+			quote! { #t_ident: Into<#t> }
+		}
+	}
+}
+
+fn from_template_param(input: &ParamType, name: &syn::Ident) -> proc_macro2::TokenStream {
+	match *input {
+		ParamType::Array(_) => quote! { #name.into_iter().map(Into::into).collect::<Vec<_>>() },
+		ParamType::FixedArray(_, size) => {
+			quote! {
+				IntoIterator::into_iter(Into::<[_; #size]>::into(#name)).map(Into::into).collect::<Vec<_>>()
+			}
+		}
+		_ => quote! {#name.into() },
+	}
+}
+
+fn to_token(name: &proc_macro2::TokenStream, kind: &ParamType, integers: Integers) -> proc_macro2::TokenStream {
+	match native_integer(kind, integers) {
+		Some((native, _)) if matches!(*kind, ParamType::Uint(_)) => {
+			return quote! {
+				{
+					let v: #native = #name;
+					ethabi::Token::Uint(ethabi::Uint::from(v))
+				}
+			};
+		}
+		Some((native, _)) => {
+			// sign extends negative values to 256 bits
+			return quote! {
+				{
+					let v: #native = #name;
+					let v = i64::from(v);
+					ethabi::Token::Int(if v < 0 { !ethabi::Int::from(!(v as u64)) } else { ethabi::Int::from(v as u64) })
+				}
+			};
+		}
+		None => {}
+	}
+
+	match *kind {
+		ParamType::Address => quote! { ethabi::Token::Address(#name) },
+		ParamType::Bytes => quote! { ethabi::Token::Bytes(#name) },
+		ParamType::FixedBytes(_) | ParamType::Function => quote! { ethabi::Token::FixedBytes(#name.as_ref().to_vec()) },
+		ParamType::Int(_) | ParamType::Fixed(..) => quote! { ethabi::Token::Int(#name) },
+		ParamType::Uint(_) | ParamType::Ufixed(..) => quote! { ethabi::Token::Uint(#name) },
+		ParamType::Bool => quote! { ethabi::Token::Bool(#name) },
+		ParamType::String => quote! { ethabi::Token::String(#name) },
+		ParamType::Array(ref kind) => {
+			let inner_name = quote! { inner };
+			let inner_loop = to_token(&inner_name, kind, integers);
+			quote! {
+				// note the double {{
+				{
+					let v = #name.into_iter().map(|#inner_name| #inner_loop).collect();
+					ethabi::Token::Array(v)
+				}
+			}
+		}
+		ParamType::FixedArray(ref kind, _) => {
+			let inner_name = quote! { inner };
+			let inner_loop = to_token(&inner_name, kind, integers);
+			quote! {
+				// note the double {{
+				{
+					let v = #name.into_iter().map(|#inner_name| #inner_loop).collect();
+					ethabi::Token::FixedArray(v)
+				}
+			}
+		}
+		ParamType::Tuple(ref kinds) => {
+			let names =
+				(0..kinds.len()).map(|i| syn::Ident::new(&format!("t{}", i), Span::call_site())).collect::<Vec<_>>();
+			let tokens = names.iter().zip(kinds).map(|(name, kind)| to_token(&quote! { #name }, kind, integers));
+			quote! {
+				{
+					let (#(#names,)*) = #name;
+					ethabi::Token::Tuple(vec![#(#tokens),*])
+				}
+			}
+		}
+	}
+}
+
+/// Converts a token into its Rust value.
+///
+/// Tokens not matching `kind`, and native integers which do not fit, return
+/// `Err(ethabi::Error::InvalidData)` from the enclosing function.
+fn from_token(kind: &ParamType, token: &proc_macro2::TokenStream, integers: Integers) -> proc_macro2::TokenStream {
+	if let Some((native, size)) = native_integer(kind, integers) {
+		let narrow = match size {
+			64 => quote! { v.low_u64() },
+			_ => quote! { v.low_u64() as #native },
+		};
+		if let ParamType::Uint(_) = *kind {
+			return quote! {
+				{
+					let v = #token.into_uint().ok_or(ethabi::Error::InvalidData)?;
+					if v.bits() > #size {
+						return Err(ethabi::Error::InvalidData);
+					}
+					#narrow
+				}
+			};
+		}
+
+		// the value must be the sign extension of its lowest `size` bits
+		let shift = size - 1;
+		let narrow = match size {
+			64 => quote! { v.low_u64() as i64 },
+			_ => quote! { v.low_u64() as i64 as #native },
+		};
+		return quote! {
+			{
+				let v = #token.into_int().ok_or(ethabi::Error::InvalidData)?;
+				let high = v >> #shift;
+				if !high.is_zero() && high != !ethabi::Int::zero() >> #shift {
+					return Err(ethabi::Error::InvalidData);
+				}
+				#narrow
+			}
+		};
+	}
+
+	match *kind {
+		ParamType::Address => quote! { #token.into_address().ok_or(ethabi::Error::InvalidData)? },
+		ParamType::Bytes => quote! { #token.into_bytes().ok_or(ethabi::Error::InvalidData)? },
+		ParamType::FixedBytes(32) => quote! {
+			{
+				let mut result = [0u8; 32];
+				let v = #token.into_fixed_bytes_exact(32).ok_or(ethabi::Error::InvalidData)?;
+				result.copy_from_slice(&v);
+				ethabi::Hash::from(result)
+			}
+		},
+		ParamType::FixedBytes(size) => {
+			let size: syn::Index = size.into();
+			quote! {
+				{
+					let mut result = [0u8; #size];
+					let v = #token.into_fixed_bytes_exact(#size).ok_or(ethabi::Error::InvalidData)?;
+					result.copy_from_slice(&v);
+					result
+				}
+			}
+		}
+		ParamType::Function => from_token(&ParamType::FixedBytes(24), token, integers),
+		ParamType::Int(_) | ParamType::Fixed(..) => quote! { #token.into_int().ok_or(ethabi::Error::InvalidData)? },
+		ParamType::Uint(_) | ParamType::Ufixed(..) => quote! { #token.into_uint().ok_or(ethabi::Error::InvalidData)? },
+		ParamType::Bool => quote! { #token.into_bool().ok_or(ethabi::Error::InvalidData)? },
+		ParamType::String => quote! { #token.into_string().ok_or(ethabi::Error::InvalidData)? },
+		ParamType::Array(ref kind) => {
+			let inner = quote! { inner };
+			let inner_loop = from_token(kind, &inner, integers);
+			quote! {
+				{
+					let mut values = Vec::new();
+					for #inner in #token.into_array().ok_or(ethabi::Error::InvalidData)? {
+						values.push(#inner_loop);
+					}
+					values
+				}
+			}
+		}
+		ParamType::FixedArray(ref kind, size) => {
+			let inner = quote! { inner };
+			let inner_loop = from_token(kind, &inner, integers);
+			let size: syn::Index = size.into();
+			quote! {
+				{
+					let v = #token.into_fixed_array().ok_or(ethabi::Error::InvalidData)?;
+					if v.len() != #size {
+						return Err(ethabi::Error::InvalidData);
+					}
+					let mut values = Vec::with_capacity(#size);
+					for #inner in v {
+						values.push(#inner_loop);
+					}
+					let mut iter = values.into_iter();
+					std::array::from_fn::<_, #size, _>(|_| iter.next().expect(INTERNAL_ERR))
+				}
+			}
+		}
+		ParamType::Tuple(ref kinds) => {
+			let inner = quote! { inner };
+			let members = kinds.iter().map(|kind| from_token(kind, &inner, integers));
+			let size = kinds.len();
+			quote! {
+				{
+					let v = #token.into_tuple().ok_or(ethabi::Error::InvalidData)?;
+					if v.len() != #size {
+						return Err(ethabi::Error::InvalidData);
+					}
+					let mut iter = v.into_iter();
+					(#({
+						let #inner = iter.next().expect(INTERNAL_ERR);
+						#members
+					},)*)
+				}
+			}
+		}
+	}
+}
+
+fn input_names(inputs: &[Param]) -> Vec<syn::Ident> {
+	inputs
+		.iter()
+		.enumerate()
+		.map(|(index, param)| {
+			if param.name.is_empty() {
+				syn::Ident::new(&format!("param{}", index), Span::call_site())
+			} else {
+				syn::Ident::new(&rust_variable(&param.name), Span::call_site())
+			}
+		})
+		.collect()
+}
+
+fn get_template_names(kinds: &[proc_macro2::TokenStream]) -> Vec<syn::Ident> {
+	kinds.iter().enumerate().map(|(index, _)| syn::Ident::new(&format!("T{}", index), Span::call_site())).collect()
+}
+
+fn get_output_kinds(outputs: &[Param], integers: Integers) -> proc_macro2::TokenStream {
+	match outputs.len() {
+		0 => quote! {()},
+		1 => {
+			let t = rust_type(&outputs[0].kind, integers);
+			quote! { #t }
+		}
+		_ => {
+			let outs: Vec<_> = outputs.iter().map(|param| rust_type(&param.kind, integers)).collect();
+			quote! { (#(#outs),*) }
+		}
+	}
+}
+
+/// Convert input into a rust variable name.
+///
+/// Avoid using keywords by escaping them.
+fn rust_variable(name: &str) -> String {
+	// avoid keyword parameters
+	match name {
+		"self" => "_self".to_string(),
+		other => other.to_snake_case(),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{from_token, Integers};
+	use ethabi::ParamType;
+	use quote::quote;
+
+	#[test]
+	fn test_from_token_large_fixed_array() {
+		let kind = ParamType::FixedArray(Box::new(ParamType::Bool), 1000);
+		let token = quote! { out };
+
+		let expected = quote! {
+			{
+				let v = out.into_fixed_array().ok_or(ethabi::Error::InvalidData)?;
+				if v.len() != 1000 {
+					return Err(ethabi::Error::InvalidData);
+				}
+				let mut values = Vec::with_capacity(1000);
+				for inner in v {
+					values.push(inner.into_bool().ok_or(ethabi::Error::InvalidData)?);
+				}
+				let mut iter = values.into_iter();
+				std::array::from_fn::<_, 1000, _>(|_| iter.next().expect(INTERNAL_ERR))
+			}
+		};
+
+		assert_eq!(expected.to_string(), from_token(&kind, &token, Integers::Ethabi).to_string());
+	}
+
+	#[test]
+	fn test_from_token_fixed_bytes_checks_length() {
+		let expected = quote! {
+			{
+				let mut result = [0u8; 3];
+				let v = out.into_fixed_bytes_exact(3).ok_or(ethabi::Error::InvalidData)?;
+				result.copy_from_slice(&v);
+				result
+			}
+		};
+
+		assert_eq!(
+			expected.to_string(),
+			from_token(&ParamType::FixedBytes(3), &quote! { out }, Integers::Ethabi).to_string()
+		);
+	}
+
+	#[test]
+	fn test_native_integers() {
+		use super::rust_type;
+
+		assert_eq!(rust_type(&ParamType::Uint(32), Integers::Native).to_string(), "u32");
+		assert_eq!(rust_type(&ParamType::Uint(24), Integers::Native).to_string(), quote! { ethabi::Uint }.to_string());
+		assert_eq!(rust_type(&ParamType::Int(64), Integers::Native).to_string(), "i64");
+		assert_eq!(rust_type(&ParamType::Uint(72), Integers::Native).to_string(), quote! { ethabi::Uint }.to_string());
+		assert_eq!(rust_type(&ParamType::Uint(8), Integers::Ethabi).to_string(), quote! { ethabi::Uint }.to_string());
+
+		let expected = quote! {
+			{
+				let v = out.into_uint().ok_or(ethabi::Error::InvalidData)?;
+				if v.bits() > 8usize {
+					return Err(ethabi::Error::InvalidData);
+				}
+				v.low_u64() as u8
+			}
+		};
+		assert_eq!(
+			expected.to_string(),
+			from_token(&ParamType::Uint(8), &quote! { out }, Integers::Native).to_string()
+		);
+	}
+}
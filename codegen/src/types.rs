@@ -0,0 +1,400 @@
+// Copyright 2015-2019 Parity Technologies
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use anyhow::anyhow;
+use ethabi::{InternalType, ParamType, Result, TupleParam};
+use proc_macro2::{Span, TokenStream};
+use quote::quote;
+use std::collections::BTreeMap;
+
+use super::{from_token, rust_type, rust_variable, to_token, Integers};
+
+/// Param of a function, event or error, or component of a tuple param.
+#[derive(Clone, Copy)]
+struct Node<'a> {
+	name: &'a str,
+	kind: &'a ParamType,
+	internal_type: Option<&'a str>,
+	components: &'a [TupleParam],
+}
+
+impl<'a> From<&'a ethabi::Param> for Node<'a> {
+	fn from(param: &'a ethabi::Param) -> Self {
+		Node {
+			name: &param.name,
+			kind: &param.kind,
+			internal_type: param.internal_type.as_deref(),
+			components: &param.components,
+		}
+	}
+}
+
+impl<'a> From<&'a ethabi::EventParam> for Node<'a> {
+	fn from(param: &'a ethabi::EventParam) -> Self {
+		Node {
+			name: &param.name,
+			kind: &param.kind,
+			internal_type: param.internal_type.as_deref(),
+			components: &param.components,
+		}
+	}
+}
+
+impl<'a> From<&'a TupleParam> for Node<'a> {
+	fn from(param: &'a TupleParam) -> Self {
+		Node {
+			name: param.name.as_deref().unwrap_or_default(),
+			kind: &param.kind,
+			internal_type: param.internal_type.as_deref(),
+			components: &param.components,
+		}
+	}
+}
+
+impl<'a> Node<'a> {
+	/// Type of the param without its array suffixes.
+	fn base(&self) -> &'a ParamType {
+		let mut kind = self.kind;
+		while let ParamType::Array(ref inner) | ParamType::FixedArray(ref inner, _) = *kind {
+			kind = inner;
+		}
+		kind
+	}
+
+	/// Struct or enum the param is of, possibly in arrays, as `(keyword, contract, name)`.
+	fn declared(&self) -> Option<Declared<'a>> {
+		let internal_type = self.internal_type.map(InternalType::parse)?;
+		match (internal_type, self.base()) {
+			(InternalType::Struct { contract, name }, ParamType::Tuple(kinds))
+				if kinds.len() == self.components.len() =>
+			{
+				Some(Declared { keyword: "struct", contract, name })
+			}
+			(InternalType::Enum { contract, name }, ParamType::Uint(8)) => {
+				Some(Declared { keyword: "enum", contract, name })
+			}
+			_ => None,
+		}
+	}
+}
+
+/// Struct or enum as declared in Solidity.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+struct Declared<'a> {
+	keyword: &'static str,
+	contract: Option<&'a str>,
+	name: &'a str,
+}
+
+impl Declared<'_> {
+	fn doc(&self) -> String {
+		match self.contract {
+			Some(contract) => format!("`{} {}.{}`", self.keyword, contract, self.name),
+			None => format!("`{} {}`", self.keyword, self.name),
+		}
+	}
+}
+
+/// Generates a struct per struct of the params, with a field per component, and a type alias per
+/// enum, as given by their `internalType`.
+///
+/// Structs convert from and into the tuples the functions take and return, and implement
+/// `ethabi::Tokenizable` and `ethabi::Detokenizable`. Enums are aliases of their `uint8`, ABIs not
+/// naming their variants. Of types of the same name declared in different contracts, each is
+/// prefixed with the name of its contract, e.g. `VaultOrder`, and the other clashes are errors.
+/// With `serde`, structs also derive `serde::Serialize` and `serde::Deserialize`, their fields
+/// named after their components.
+pub fn generate_types(c: &ethabi::Contract, integers: Integers, serde: bool) -> Result<Vec<TokenStream>> {
+	let params = c
+		.constructor
+		.iter()
+		.flat_map(|constructor| &constructor.inputs)
+		.chain(c.functions().flat_map(|function| function.inputs.iter().chain(&function.outputs)))
+		.chain(c.errors().flat_map(|error| &error.inputs))
+		.map(Node::from)
+		.chain(c.events().flat_map(|event| &event.inputs).map(Node::from));
+
+	let mut declarations = BTreeMap::new();
+	for param in params {
+		collect(param, &mut declarations)?;
+	}
+
+	// types are named after their declaration, prefixed by their contract when the name is taken
+	let mut by_name = BTreeMap::<&str, Vec<Declared>>::new();
+	for declared in declarations.keys() {
+		by_name.entry(declared.name).or_default().push(*declared);
+	}
+	let mut names = BTreeMap::new();
+	for (name, declared) in by_name {
+		for d in &declared {
+			let ident = match (declared.len(), d.contract) {
+				(1, _) => name.to_owned(),
+				(_, Some(contract)) => format!("{}{}", contract, name),
+				(_, None) => {
+					let docs = declared.iter().map(Declared::doc).collect::<Vec<_>>();
+					return Err(anyhow!("types {} have the same name", docs.join(" and ")).into());
+				}
+			};
+			names.insert(*d, ident);
+		}
+	}
+	let mut taken = BTreeMap::new();
+	for (declared, ident) in &names {
+		if let Some(other) = taken.insert(ident.as_str(), declared) {
+			return Err(anyhow!("types {} and {} are both named `{}`", other.doc(), declared.doc(), ident).into());
+		}
+	}
+
+	let idents = names
+		.iter()
+		.map(|(declared, ident)| (*declared, syn::Ident::new(ident, Span::call_site())))
+		.collect::<BTreeMap<_, _>>();
+	Ok(declarations
+		.into_iter()
+		.map(|(declared, node)| {
+			let ident = &idents[&declared];
+			let doc = declared.doc();
+			match declared.keyword {
+				"struct" => generate_struct(ident, &doc, node, &idents, integers, serde),
+				_ => {
+					let alias = rust_type(node.base(), integers);
+					quote! {
+						#[doc = #doc]
+						pub type #ident = #alias;
+					}
+				}
+			}
+		})
+		.collect())
+}
+
+/// Collects the structs and enums of `param` and of its components.
+fn collect<'a>(param: Node<'a>, declarations: &mut BTreeMap<Declared<'a>, Node<'a>>) -> Result<()> {
+	if let Some(declared) = param.declared() {
+		match declarations.get(&declared) {
+			Some(other) if shape(other) != shape(&param) => {
+				return Err(anyhow!("type {} is declared with different components", declared.doc()).into());
+			}
+			Some(_) => (),
+			None => {
+				declarations.insert(declared, param);
+			}
+		}
+	}
+	for component in param.components {
+		collect(component.into(), declarations)?;
+	}
+	Ok(())
+}
+
+/// Type and component names of a param, which tell two declarations apart.
+fn shape(param: &Node) -> (ParamType, Vec<String>) {
+	let names = param.components.iter().map(|component| component.name.clone().unwrap_or_default()).collect();
+	(param.base().clone(), names)
+}
+
+fn generate_struct(
+	ident: &syn::Ident,
+	doc: &str,
+	node: Node,
+	idents: &BTreeMap<Declared, syn::Ident>,
+	integers: Integers,
+	serde: bool,
+) -> TokenStream {
+	let kind = node.base();
+	let tuple = rust_type(kind, integers);
+	let fields = node.components.iter().map(Node::from).collect::<Vec<_>>();
+	let names = fields
+		.iter()
+		.enumerate()
+		.map(|(index, field)| match field.name.is_empty() {
+			true => syn::Ident::new(&format!("field{}", index), Span::call_site()),
+			false => {
+				let name = rust_variable(field.name);
+				match syn::parse_str::<syn::Ident>(&name) {
+					Ok(ident) => ident,
+					Err(_) => syn::Ident::new_raw(&name, Span::call_site()),
+				}
+			}
+		})
+		.collect::<Vec<_>>();
+	let types = fields.iter().map(|field| field_type(field.kind, *field, idents, integers));
+	let serde = match serde {
+		true => quote! { #[derive(serde::Serialize, serde::Deserialize)] },
+		false => quote! {},
+	};
+	// rust field names are snake case, serde keeps the names of the components
+	let renames = names.iter().zip(&fields).map(|(name, field)| {
+		match serde.is_empty() || field.name.is_empty() || syn::ext::IdentExt::unraw(name) == field.name {
+			true => quote! {},
+			false => {
+				let rename = field.name;
+				quote! { #[serde(rename = #rename)] }
+			}
+		}
+	});
+	let from_tuple = names.iter().zip(&fields).map(|(name, field)| convert(quote! { #name }, field.kind, *field));
+	let into_tuple = names.iter().zip(&fields).map(|(name, field)| convert(quote! { value.#name }, field.kind, *field));
+	let into_token = to_token(&quote! { value }, kind, integers);
+	let token = from_token(kind, &quote! { token }, integers);
+
+	quote! {
+		#[doc = #doc]
+		#[derive(Debug, Clone, PartialEq)]
+		#serde
+		pub struct #ident {
+			#(#renames pub #names: #types,)*
+		}
+
+		impl From<#tuple> for #ident {
+			fn from((#(#names,)*): #tuple) -> Self {
+				#ident { #(#names: #from_tuple,)* }
+			}
+		}
+
+		impl From<#ident> for #tuple {
+			fn from(value: #ident) -> Self {
+				(#(#into_tuple,)*)
+			}
+		}
+
+		impl ethabi::Tokenizable for #ident {
+			fn into_token(self) -> ethabi::Token {
+				let value: #tuple = self.into();
+				#into_token
+			}
+		}
+
+		impl ethabi::Detokenizable for #ident {
+			fn from_token(token: ethabi::Token) -> Result<Self, ethabi::Error> {
+				let value: #tuple = #token;
+				Ok(value.into())
+			}
+		}
+	}
+}
+
+/// Rust type of the field `kind` of `field`, its struct or enum if it has one.
+fn field_type(
+	kind: &ParamType,
+	field: Node,
+	idents: &BTreeMap<Declared, syn::Ident>,
+	integers: Integers,
+) -> TokenStream {
+	match *kind {
+		ParamType::Array(ref inner) => {
+			let t = field_type(inner, field, idents, integers);
+			quote! { Vec<#t> }
+		}
+		ParamType::FixedArray(ref inner, size) => {
+			let t = field_type(inner, field, idents, integers);
+			quote! { [#t; #size] }
+		}
+		_ => match field.declared() {
+			Some(declared) => {
+				let ident = &idents[&declared];
+				quote! { #ident }
+			}
+			None => rust_type(kind, integers),
+		},
+	}
+}
+
+/// Converts the `value` of the field `kind` of `field` between its struct and its tuple, in
+/// either direction.
+fn convert(value: TokenStream, kind: &ParamType, field: Node) -> TokenStream {
+	if !matches!(field.declared(), Some(Declared { keyword: "struct", .. })) {
+		return value;
+	}
+	match *kind {
+		ParamType::Array(ref inner) => {
+			let inner = convert(quote! { v }, inner, field);
+			quote! { #value.into_iter().map(|v| #inner).collect() }
+		}
+		ParamType::FixedArray(ref inner, _) => {
+			let inner = convert(quote! { v }, inner, field);
+			quote! { #value.map(|v| #inner) }
+		}
+		_ => quote! { #value.into() },
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use quote::quote;
+
+	use super::generate_types;
+	use crate::Integers;
+
+	const ABI: &str = r#"[
+		{"type":"function","name":"fill","inputs":[
+			{"name":"orders","type":"tuple[]","internalType":"struct Market.Order[]","components":[
+				{"name":"maker","type":"address"},
+				{"name":"side","type":"uint8","internalType":"enum Market.Side"}
+			]},
+			{"name":"token","type":"address","internalType":"contract IERC20"}
+		],"outputs":[
+			{"name":"order","type":"tuple","internalType":"struct Vault.Order","components":[
+				{"name":"owner","type":"address"}
+			]}
+		]}
+	]"#;
+
+	#[test]
+	fn test_generate_types() {
+		let contract = ethabi::Contract::load(ABI.as_bytes()).unwrap();
+		let types = generate_types(&contract, Integers::Native, false).unwrap();
+		let expected = quote! {
+			#[doc = "`enum Market.Side`"]
+			pub type Side = u8;
+			#[doc = "`struct Market.Order`"]
+			#[derive(Debug, Clone, PartialEq)]
+			pub struct MarketOrder {
+				pub maker: ethabi::Address,
+				pub side: Side,
+			}
+		};
+		let generated = quote! { #(#types)* }.to_string();
+		assert!(generated.starts_with(&expected.to_string()), "{}", generated);
+		assert!(generated.contains(&quote! { pub struct VaultOrder { pub owner: ethabi::Address, } }.to_string()));
+		assert!(generated.contains(&quote! { impl ethabi::Tokenizable for MarketOrder }.to_string()));
+	}
+
+	#[test]
+	fn test_generate_serde_types() {
+		let abi = ABI.replace(r#"{"name":"owner","type":"address"}"#, r#"{"name":"ownerAddress","type":"address"}"#);
+		let contract = ethabi::Contract::load(abi.as_bytes()).unwrap();
+		let types = generate_types(&contract, Integers::Native, true).unwrap();
+		let expected = quote! {
+			#[doc = "`struct Vault.Order`"]
+			#[derive(Debug, Clone, PartialEq)]
+			#[derive(serde::Serialize, serde::Deserialize)]
+			pub struct VaultOrder {
+				#[serde(rename = "ownerAddress")]
+				pub owner_address: ethabi::Address,
+			}
+		};
+		let generated = quote! { #(#types)* }.to_string();
+		assert!(generated.contains(&expected.to_string()), "{}", generated);
+		assert!(generated
+			.contains(&quote! { pub struct MarketOrder { pub maker: ethabi::Address, pub side: Side, } }.to_string()));
+	}
+
+	#[test]
+	fn test_type_clashes() {
+		let abi = ABI.replace("struct Vault.Order", "struct Order");
+		let contract = ethabi::Contract::load(abi.as_bytes()).unwrap();
+		let err = generate_types(&contract, Integers::Native, false).unwrap_err();
+		assert_eq!(err.to_string(), "types `struct Order` and `struct Market.Order` have the same name");
+
+		let abi = ABI.replace("struct Vault.Order", "struct Market.Order");
+		let contract = ethabi::Contract::load(abi.as_bytes()).unwrap();
+		let err = generate_types(&contract, Integers::Native, false).unwrap_err();
+		assert_eq!(err.to_string(), "type `struct Market.Order` is declared with different components");
+	}
+}
@@ -6,17 +6,93 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
+/// Generates bindings for the contract ABI at `$path` in a module named `$module`.
+///
+/// The bindings are stateless: every ABI function gets a module of free functions, so calldata
+/// is built with e.g. `$module::functions::transfer::encode_input(to, amount)` without
+/// instantiating any contract type. Events live in `$module::events` and `$module::logs`,
+/// custom errors in `$module::errors`.
+///
+/// Overloaded functions get a module per overload, suffixed with their position in the ABI,
+/// e.g. `balance_of_0` and `balance_of_1`. The same goes for overloaded events and for names
+/// which only clash once snake cased. Every function and error module has a `SELECTOR`
+/// constant, and non anonymous event modules their first topic as `SIGNATURE` and `TOPIC0`, the
+/// latter as bytes usable as a `match` pattern. These are computed by the macro, so encoding
+/// and decoding hash nothing at runtime. Function and event modules also have a
+/// `CANONICAL_SIGNATURE` such as `transfer(address,uint256)`.
+///
+/// Integer params are `ethabi::Uint` and `ethabi::Int` by default. With `integers = "native"`,
+/// those of 8, 16, 32 or 64 bits are native Rust integers instead, e.g. `u8` for `uint8` and
+/// `i32` for `int32`. Other widths, e.g. `uint24`, stay `ethabi::Uint` and `ethabi::Int`, which
+/// native integers would not bound. Decoding values which do not fit then fails with
+/// `ethabi::Error::InvalidData`.
+///
+/// With `builders = true`, every function module also gets a `Builder` of calls with a
+/// `with_<input>` setter per input, e.g. `transfer::builder().with_to(to).with_value(1).encode()`,
+/// which fails if an input was not set.
+///
+/// With `structs = true`, functions with several outputs decode them to an `Output` struct of
+/// their module, with a field per output named after it, instead of a tuple. With `serde = true`,
+/// the logs and output structs derive `serde::Serialize`, and the structs of `types` also
+/// `serde::Deserialize`, for which the crate using the macro must depend on serde with its
+/// `derive` feature.
+///
+/// With the `futures` feature of `ethabi-derive`, the module also gets a `Caller` trait of
+/// transports, which any client can implement, and an `Instance { caller, address }` of the
+/// contract with an async method per function, e.g. `instance.balance_of(owner).await`.
+///
+/// With `bytecode_path = "..."` naming a hex file, like the `.bin` output of solc, or a build
+/// artifact, the module also gets the `BYTECODE` of the contract and a `deploy_data` taking the
+/// constructor arguments, which encodes deployments of it.
+///
+/// The module is public unless another visibility precedes its name, e.g.
+/// `use_contract!(pub(crate) $module, ...)`. With `crate = "::my_crate::ethabi"`, the bindings
+/// reach ethabi through that path, e.g. when it is only a dependency of a crate re-exporting it.
+///
+/// Instead of a path, the ABI json can be given inline with `use_contract!($module, abi = "[...]")`,
+/// or its path can be read from an environment variable at build time with
+/// `use_contract!($module, path_env = "VAR")`, e.g. one set by a build script for generated ABIs.
 #[macro_export]
 macro_rules! use_contract {
-	($module: ident, $path: expr) => {
+	(pub $(($($restriction: tt)+))? $module: ident, $($rest: tt)+) => {
+		$crate::use_contract!(@module pub $(($($restriction)+))? $module, $($rest)+);
+	};
+	($module: ident, $($rest: tt)+) => {
+		$crate::use_contract!(@module pub $module, $($rest)+);
+	};
+	(@module $vis: vis $module: ident, path_env = $var: expr $(, $option: ident = $value: expr)*) => {
+		#[allow(dead_code)]
+		#[allow(missing_docs)]
+		#[allow(unused_imports)]
+		#[allow(unused_mut)]
+		#[allow(unused_variables)]
+		$vis mod $module {
+			#[derive(ethabi_derive::EthabiContract)]
+			#[ethabi_contract_options(path_env = $var $(, $option = $value)*)]
+			struct _Dummy;
+		}
+	};
+	(@module $vis: vis $module: ident, abi = $abi: expr $(, $option: ident = $value: expr)*) => {
+		#[allow(dead_code)]
+		#[allow(missing_docs)]
+		#[allow(unused_imports)]
+		#[allow(unused_mut)]
+		#[allow(unused_variables)]
+		$vis mod $module {
+			#[derive(ethabi_derive::EthabiContract)]
+			#[ethabi_contract_options(abi = $abi $(, $option = $value)*)]
+			struct _Dummy;
+		}
+	};
+	(@module $vis: vis $module: ident, $path: expr $(, $option: ident = $value: expr)*) => {
 		#[allow(dead_code)]
 		#[allow(missing_docs)]
 		#[allow(unused_imports)]
 		#[allow(unused_mut)]
 		#[allow(unused_variables)]
-		pub mod $module {
+		$vis mod $module {
 			#[derive(ethabi_derive::EthabiContract)]
-			#[ethabi_contract_options(path = $path)]
+			#[ethabi_contract_options(path = $path $(, $option = $value)*)]
 			struct _Dummy;
 		}
 	};
@@ -0,0 +1,57 @@
+// Copyright 2015-2019 Parity Technologies
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use anyhow::anyhow;
+use ethabi::Result;
+use proc_macro2::TokenStream;
+use quote::quote;
+
+/// Implements `ethabi::Tokenizable` and `ethabi::Detokenizable` for a struct, mapping it to a
+/// tuple of its fields in declaration order.
+pub fn impl_tokenize(ast: &syn::DeriveInput) -> Result<TokenStream> {
+	let fields = match ast.data {
+		syn::Data::Struct(ref data) => &data.fields,
+		_ => return Err(anyhow!("`derive(EthabiTokenize)` only supports structs").into()),
+	};
+
+	let name = &ast.ident;
+	let (impl_generics, ty_generics, where_clause) = ast.generics.split_for_impl();
+	let members: Vec<syn::Member> = fields
+		.iter()
+		.enumerate()
+		.map(|(index, field)| match field.ident {
+			Some(ref ident) => syn::Member::Named(ident.clone()),
+			None => syn::Member::Unnamed(index.into()),
+		})
+		.collect();
+	let from_tokens = members.iter().map(|member| {
+		quote! { #member: ethabi::Detokenizable::from_token(tokens.next().ok_or(ethabi::Error::InvalidData)?)? }
+	});
+
+	Ok(quote! {
+		impl #impl_generics ethabi::Tokenizable for #name #ty_generics #where_clause {
+			fn into_token(self) -> ethabi::Token {
+				ethabi::Token::Tuple(vec![#(ethabi::Tokenizable::into_token(self.#members)),*])
+			}
+		}
+
+		impl #impl_generics ethabi::Detokenizable for #name #ty_generics #where_clause {
+			fn from_token(token: ethabi::Token) -> Result<Self, ethabi::Error> {
+				let mut tokens = token.into_tuple().ok_or(ethabi::Error::InvalidData)?.into_iter();
+				let value = #name { #(#from_tokens),* };
+				match tokens.next() {
+					Some(_) => Err(ethabi::Error::InvalidData),
+					None => Ok(value),
+				}
+			}
+		}
+
+		impl #impl_generics ethabi::token::TokenizableItem for #name #ty_generics #where_clause {}
+		impl #impl_generics ethabi::token::DetokenizableItem for #name #ty_generics #where_clause {}
+	})
+}
@@ -0,0 +1,32 @@
+// Copyright 2015-2020 Parity Technologies
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Decoding benchmark of many small `(uint256,bytes[])` log datas.
+//!
+//! Run with `cargo bench -p ethabi --bench decode`.
+
+use std::time::Instant;
+
+use ethabi::{encode, Decoder, ParamType, Token};
+
+const ITERATIONS: u32 = 100_000;
+
+fn main() {
+	let types = [ParamType::Uint(256), ParamType::Array(Box::new(ParamType::Bytes))];
+	let data = encode(&[Token::Uint(1.into()), Token::Array((0..4).map(|i| Token::Bytes(vec![i; 40])).collect())]);
+
+	let start = Instant::now();
+	let mut tokens = Vec::new();
+	for _ in 0..ITERATIONS {
+		tokens.clear();
+		Decoder::new(&types, &data).unwrap().decode_into(&mut tokens).unwrap();
+	}
+	let elapsed = start.elapsed();
+
+	println!("decode (uint256,bytes[4]): {:?} per iteration", elapsed / ITERATIONS);
+}
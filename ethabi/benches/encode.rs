@@ -0,0 +1,40 @@
+// Copyright 2015-2020 Parity Technologies
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Encoding benchmark of a large `bytes[]`.
+//!
+//! Run with `cargo bench -p ethabi --bench encode`.
+
+use std::time::Instant;
+
+use ethabi::{encode, encode_into, Token};
+
+const ITERATIONS: u32 = 100;
+
+fn main() {
+	let tokens = [Token::Array((0..10_000).map(|i| Token::Bytes(vec![i as u8; 100])).collect())];
+
+	let start = Instant::now();
+	let mut len = 0;
+	for _ in 0..ITERATIONS {
+		len += encode(&tokens).len();
+	}
+	let elapsed = start.elapsed();
+
+	println!("encode bytes[10000]: {:?} per iteration ({} bytes)", elapsed / ITERATIONS, len / ITERATIONS as usize);
+
+	let start = Instant::now();
+	let mut buffer = Vec::new();
+	for _ in 0..ITERATIONS {
+		buffer.clear();
+		encode_into(&tokens, &mut buffer);
+	}
+	let elapsed = start.elapsed();
+
+	println!("encode_into bytes[10000]: {:?} per iteration, reusing the buffer", elapsed / ITERATIONS);
+}
@@ -0,0 +1,35 @@
+// Copyright 2015-2020 Parity Technologies
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Encodes a `balanceOf(address)` call and decodes its result from an ABI loaded at runtime,
+//! without generated bindings.
+//!
+//! Run with `cargo run -p ethabi --example balance_of`.
+
+use ethabi::{Address, Contract, Token};
+
+fn main() -> ethabi::Result<()> {
+	// the ABI could just as well come from a file or a database
+	let contract = Contract::load(&include_bytes!("../../res/eip20.abi")[..])?;
+	let balance_of = contract.function("balanceOf")?;
+
+	let owner = Address::from_low_u64_be(0x1234);
+	let calldata = balance_of.encode_input(&[Token::Address(owner)])?;
+	println!("calldata: 0x{}", hex::encode(&calldata));
+
+	// a token of the wrong type is rejected instead of being encoded
+	if let Err(err) = balance_of.encode_input(&[Token::Bool(true)]) {
+		println!("rejected: {}", err);
+	}
+
+	// what a node would return for the call
+	let output = ethabi::encode(&[Token::Uint(1_000.into())]);
+	let balance = balance_of.decode_output(&output)?;
+	println!("balance: {:#}", balance[0]);
+	Ok(())
+}
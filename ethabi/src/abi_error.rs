@@ -0,0 +1,126 @@
+// Copyright 2015-2020 Parity Technologies
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Contract custom error.
+
+#[cfg(not(feature = "std"))]
+use crate::no_std_prelude::*;
+use crate::{
+	decode, encode, human_readable, signature::short_signature, Bytes, Error, Param, ParamType, Result, Token,
+};
+use anyhow::anyhow;
+use serde::{ser::SerializeMap, Deserialize, Serialize, Serializer};
+
+/// Contract custom error specification, carried by reverts since Solidity 0.8.4.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct AbiError {
+	/// Error name.
+	pub name: String,
+	/// Error input.
+	pub inputs: Vec<Param>,
+}
+
+impl Serialize for AbiError {
+	fn serialize<S: Serializer>(&self, serializer: S) -> core::result::Result<S::Ok, S::Error> {
+		let mut map = serializer.serialize_map(Some(3))?;
+		map.serialize_entry("type", "error")?;
+		map.serialize_entry("name", &self.name)?;
+		map.serialize_entry("inputs", &self.inputs)?;
+		map.end()
+	}
+}
+
+impl AbiError {
+	/// The built-in `Error(string)`, reverted with by `require` and `revert` with a reason.
+	pub fn error_string() -> Self {
+		AbiError {
+			name: "Error".to_owned(),
+			inputs: vec![Param {
+				name: "reason".to_owned(),
+				kind: ParamType::String,
+				internal_type: None,
+				components: vec![],
+			}],
+		}
+	}
+
+	/// The built-in `Panic(uint256)`, reverted with on failed assertions, overflows, etc.
+	pub fn panic() -> Self {
+		AbiError {
+			name: "Panic".to_owned(),
+			inputs: vec![Param {
+				name: "code".to_owned(),
+				kind: ParamType::Uint(256),
+				internal_type: None,
+				components: vec![],
+			}],
+		}
+	}
+
+	/// Parses an error declaration, e.g. `error InsufficientBalance(uint256 available)`.
+	pub fn parse(sig: &str) -> Result<Self> {
+		human_readable::parse_error(sig)
+	}
+
+	/// Returns all input params of given error.
+	fn param_types(&self) -> Vec<ParamType> {
+		self.inputs.iter().map(|p| p.kind.clone()).collect()
+	}
+
+	/// Returns the 4 bytes selector the revert data starts with.
+	pub fn selector(&self) -> [u8; 4] {
+		short_signature(&self.name, &self.param_types())
+	}
+
+	/// Encodes the revert data of the error with given input params.
+	pub fn encode(&self, tokens: &[Token]) -> Result<Bytes> {
+		let params = self.param_types();
+
+		if !Token::types_check(tokens, &params) {
+			return Err(Error::InvalidData);
+		}
+
+		Ok(self.selector().iter().copied().chain(encode(tokens)).collect())
+	}
+
+	/// Parses the revert `data`, selector followed by the error params.
+	pub fn decode(&self, data: &[u8]) -> Result<Vec<Token>> {
+		match data.len() >= 4 && data[..4] == self.selector() {
+			true => decode(&self.param_types(), &data[4..]),
+			false => Err(anyhow!("revert data is not a `{}` error", self.name).into()),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::{AbiError, Token};
+	use hex_literal::hex;
+
+	#[test]
+	fn test_builtin_errors() {
+		let error = AbiError::error_string();
+		assert_eq!(error.selector(), hex!("08c379a0"));
+		let data = error.encode(&[Token::String("Not enough Ether provided.".to_owned())]).unwrap();
+		let expected = hex!(
+			"
+			08c379a0
+			0000000000000000000000000000000000000000000000000000000000000020
+			000000000000000000000000000000000000000000000000000000000000001a
+			4e6f7420656e6f7567682045746865722070726f76696465642e000000000000
+		"
+		);
+		assert_eq!(data, expected.to_vec());
+		assert_eq!(error.decode(&data).unwrap(), vec![Token::String("Not enough Ether provided.".to_owned())]);
+
+		let panic = AbiError::panic();
+		assert_eq!(panic.selector(), hex!("4e487b71"));
+		assert!(panic.decode(&data).is_err());
+		assert!(panic.decode(&hex!("4e48")).is_err());
+	}
+}
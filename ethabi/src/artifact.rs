@@ -0,0 +1,181 @@
+// Copyright 2015-2020 Parity Technologies
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Build artifacts bundling a contract ABI with its bytecode.
+
+#[cfg(not(feature = "std"))]
+use crate::no_std_prelude::*;
+use crate::{errors, Bytes, Contract};
+use alloc::collections::BTreeMap;
+use anyhow::anyhow;
+use serde::Deserialize;
+use serde_json::Value;
+#[cfg(feature = "std")]
+use std::io;
+
+/// Contract loaded from a build artifact.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Artifact {
+	/// Name of the contract, if the artifact has one.
+	pub contract_name: Option<String>,
+	/// Contract ABI.
+	pub contract: Contract,
+	/// Creation bytecode, if the artifact has one.
+	pub bytecode: Option<Bytes>,
+	/// Runtime bytecode, if the artifact has one.
+	pub deployed_bytecode: Option<Bytes>,
+}
+
+/// Truffle and Hardhat artifact, or Foundry one with the bytecodes as `{"object": ...}`.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RawArtifact {
+	contract_name: Option<String>,
+	abi: Contract,
+	bytecode: Option<RawBytecode>,
+	deployed_bytecode: Option<RawBytecode>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum RawBytecode {
+	Hex(String),
+	Object { object: String },
+}
+
+/// Output of `solc --combined-json abi,bin,bin-runtime`.
+#[derive(Deserialize)]
+struct RawCombined {
+	contracts: BTreeMap<String, RawCombinedContract>,
+}
+
+#[derive(Deserialize)]
+struct RawCombinedContract {
+	abi: Contract,
+	bin: Option<String>,
+	#[serde(rename = "bin-runtime")]
+	bin_runtime: Option<String>,
+}
+
+/// Decodes the optionally `0x` prefixed hex of a bytecode, empty meaning none.
+fn parse_bytecode(hex: Option<&str>) -> errors::Result<Option<Bytes>> {
+	let hex = match hex {
+		Some(hex) => hex.strip_prefix("0x").unwrap_or(hex),
+		None => return Ok(None),
+	};
+	if hex.is_empty() {
+		return Ok(None);
+	}
+	// unlinked libraries are `__$hash$__` placeholders, which can not be decoded
+	hex::decode(hex).map(Some).map_err(|err| anyhow!("bytecode is not valid hex, it may need linking: {}", err).into())
+}
+
+impl RawBytecode {
+	fn hex(&self) -> &str {
+		match self {
+			RawBytecode::Hex(hex) | RawBytecode::Object { object: hex } => hex,
+		}
+	}
+}
+
+impl Artifact {
+	/// Loads a Truffle, Hardhat or Foundry artifact, or a bare ABI which has no name nor bytecode.
+	#[cfg(feature = "std")]
+	pub fn load<T: io::Read>(reader: T) -> errors::Result<Self> {
+		Self::from_value(serde_json::from_reader(reader)?)
+	}
+
+	/// Loads an artifact from json bytes, see [`load`](Artifact::load).
+	pub fn load_from_slice(json: &[u8]) -> errors::Result<Self> {
+		Self::from_value(serde_json::from_slice(json)?)
+	}
+
+	/// Loads every contract of the output of `solc --combined-json`, keyed by `path:Name`.
+	///
+	/// The bytecodes are only there if `bin` and `bin-runtime` were requested.
+	pub fn load_combined(json: &[u8]) -> errors::Result<BTreeMap<String, Self>> {
+		let combined: RawCombined = serde_json::from_slice(json)?;
+		combined
+			.contracts
+			.into_iter()
+			.map(|(key, raw)| {
+				let artifact = Artifact {
+					contract_name: key.rsplit(':').next().map(ToOwned::to_owned),
+					contract: raw.abi,
+					bytecode: parse_bytecode(raw.bin.as_deref())?,
+					deployed_bytecode: parse_bytecode(raw.bin_runtime.as_deref())?,
+				};
+				Ok((key, artifact))
+			})
+			.collect()
+	}
+
+	fn from_value(value: Value) -> errors::Result<Self> {
+		if value.is_array() {
+			let contract = serde_json::from_value(value)?;
+			return Ok(Artifact { contract_name: None, contract, bytecode: None, deployed_bytecode: None });
+		}
+
+		let raw: RawArtifact = serde_json::from_value(value)?;
+		Ok(Artifact {
+			contract_name: raw.contract_name,
+			contract: raw.abi,
+			bytecode: parse_bytecode(raw.bytecode.as_ref().map(RawBytecode::hex))?,
+			deployed_bytecode: parse_bytecode(raw.deployed_bytecode.as_ref().map(RawBytecode::hex))?,
+		})
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::Artifact;
+	use crate::Contract;
+
+	const ABI: &str = r#"[{ "type": "function", "name": "foo", "inputs": [], "outputs": [] }]"#;
+
+	#[test]
+	fn test_load_artifacts() {
+		let hardhat = format!(
+			r#"{{ "_format": "hh-sol-artifact-1", "contractName": "Foo", "abi": {}, "bytecode": "0x6060", "deployedBytecode": "0x" }}"#,
+			ABI
+		);
+		let artifact = Artifact::load_from_slice(hardhat.as_bytes()).unwrap();
+		assert_eq!(artifact.contract_name.as_deref(), Some("Foo"));
+		assert!(artifact.contract.function("foo").is_ok());
+		assert_eq!(artifact.bytecode, Some(vec![0x60, 0x60]));
+		assert_eq!(artifact.deployed_bytecode, None);
+		assert_eq!(Contract::load(hardhat.as_bytes()).unwrap(), artifact.contract);
+
+		let foundry = format!(r#"{{ "abi": {}, "bytecode": {{ "object": "0x6060" }} }}"#, ABI);
+		let artifact = Artifact::load_from_slice(foundry.as_bytes()).unwrap();
+		assert_eq!(artifact.contract_name, None);
+		assert_eq!(artifact.bytecode, Some(vec![0x60, 0x60]));
+
+		let bare = Artifact::load_from_slice(ABI.as_bytes()).unwrap();
+		assert_eq!((bare.contract_name, bare.bytecode), (None, None));
+
+		let unlinked = format!(r#"{{ "abi": {}, "bytecode": "0x73__$1234$__" }}"#, ABI);
+		assert!(Artifact::load_from_slice(unlinked.as_bytes()).is_err());
+		assert!(Artifact::load_from_slice(br#"{ "bytecode": "0x" }"#).is_err());
+	}
+
+	#[test]
+	fn test_load_combined() {
+		// solc embeds the ABI as a JSON string
+		let combined = format!(
+			r#"{{ "contracts": {{ "src/Foo.sol:Foo": {{ "abi": {:?}, "bin": "6060", "bin-runtime": "" }} }}, "version": "0.8.0" }}"#,
+			ABI
+		);
+		let artifacts = Artifact::load_combined(combined.as_bytes()).unwrap();
+		let artifact = &artifacts["src/Foo.sol:Foo"];
+		assert_eq!(artifact.contract_name.as_deref(), Some("Foo"));
+		assert!(artifact.contract.function("foo").is_ok());
+		assert_eq!(artifact.bytecode, Some(vec![0x60, 0x60]));
+		assert_eq!(artifact.deployed_bytecode, None);
+	}
+}
@@ -0,0 +1,365 @@
+// Copyright 2015-2020 Parity Technologies
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Programmatic contract construction.
+
+#[cfg(not(feature = "std"))]
+use crate::no_std_prelude::*;
+use crate::{
+	human_readable::is_identifier, AbiError, Constructor, Contract, Error, Event, EventParam, Function, Param,
+	ParamType, Result, StateMutability,
+};
+use anyhow::anyhow;
+
+/// Builds a [`Contract`] item by item, e.g.
+///
+/// ```
+/// use ethabi::{ContractBuilder, ParamType};
+///
+/// let contract = ContractBuilder::new()
+///     .function("transfer")
+///     .input("to", ParamType::Address)
+///     .input("amount", ParamType::Uint(256))
+///     .output("", ParamType::Bool)
+///     .event("Transfer")
+///     .indexed_input("from", ParamType::Address)
+///     .indexed_input("to", ParamType::Address)
+///     .input("amount", ParamType::Uint(256))
+///     .build()
+///     .unwrap();
+/// assert_eq!(contract.function("transfer").unwrap().signature(), "transfer(address,uint256):(bool)");
+/// ```
+///
+/// Params are added to the last started item. Misuses, such as outputs of an event, and invalid
+/// names or types are reported by [`build`](ContractBuilder::build).
+#[derive(Debug)]
+pub struct ContractBuilder {
+	contract: Contract,
+	current: Option<Item>,
+	error: Option<Error>,
+}
+
+#[derive(Debug)]
+enum Item {
+	Constructor(Constructor),
+	Function(Function),
+	Event(Event),
+	Error(AbiError),
+}
+
+impl Default for ContractBuilder {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl ContractBuilder {
+	/// Starts an empty contract.
+	pub fn new() -> Self {
+		ContractBuilder {
+			contract: Contract {
+				constructor: None,
+				functions: Default::default(),
+				events: Default::default(),
+				errors: Default::default(),
+				fallback: None,
+				receive: false,
+			},
+			current: None,
+			error: None,
+		}
+	}
+
+	/// Starts the constructor.
+	pub fn constructor(self) -> Self {
+		self.start(Item::Constructor(Constructor { inputs: vec![] }))
+	}
+
+	/// Starts a non payable function named `name`.
+	pub fn function(self, name: &str) -> Self {
+		self.start(Item::Function(Function {
+			name: name.to_owned(),
+			inputs: vec![],
+			outputs: vec![],
+			constant: false,
+			state_mutability: StateMutability::NonPayable,
+		}))
+	}
+
+	/// Starts a non anonymous event named `name`.
+	pub fn event(self, name: &str) -> Self {
+		self.start(Item::Event(Event { name: name.to_owned(), inputs: vec![], anonymous: false }))
+	}
+
+	/// Starts a custom error named `name`.
+	pub fn error(self, name: &str) -> Self {
+		self.start(Item::Error(AbiError { name: name.to_owned(), inputs: vec![] }))
+	}
+
+	/// Adds an input to the current item, not indexed for events.
+	pub fn input(mut self, name: &str, kind: ParamType) -> Self {
+		let param = Param { name: name.to_owned(), kind, internal_type: None, components: vec![] };
+		match self.current {
+			Some(Item::Constructor(ref mut constructor)) => constructor.inputs.push(param),
+			Some(Item::Function(ref mut function)) => function.inputs.push(param),
+			Some(Item::Error(ref mut error)) => error.inputs.push(param),
+			Some(Item::Event(ref mut event)) => event.inputs.push(EventParam {
+				name: param.name,
+				kind: param.kind,
+				indexed: false,
+				internal_type: None,
+				components: vec![],
+			}),
+			None => return self.fail(format!("input `{}` is not part of a function, event or error", name)),
+		}
+		self
+	}
+
+	/// Adds an indexed input to the current event.
+	pub fn indexed_input(mut self, name: &str, kind: ParamType) -> Self {
+		match self.current {
+			Some(Item::Event(ref mut event)) => event.inputs.push(EventParam {
+				name: name.to_owned(),
+				kind,
+				indexed: true,
+				internal_type: None,
+				components: vec![],
+			}),
+			_ => return self.fail(format!("indexed input `{}` is not part of an event", name)),
+		}
+		self
+	}
+
+	/// Adds an output to the current function.
+	pub fn output(mut self, name: &str, kind: ParamType) -> Self {
+		match self.current {
+			Some(Item::Function(ref mut function)) => {
+				function.outputs.push(Param { name: name.to_owned(), kind, internal_type: None, components: vec![] })
+			}
+			_ => return self.fail(format!("output `{}` is not part of a function", name)),
+		}
+		self
+	}
+
+	/// Sets the state mutability of the current function.
+	pub fn state_mutability(mut self, state_mutability: StateMutability) -> Self {
+		match self.current {
+			Some(Item::Function(ref mut function)) => {
+				function.constant = state_mutability.is_constant();
+				function.state_mutability = state_mutability;
+			}
+			_ => return self.fail("state mutability is not set on a function".to_owned()),
+		}
+		self
+	}
+
+	/// Marks the current event as anonymous.
+	pub fn anonymous(mut self) -> Self {
+		match self.current {
+			Some(Item::Event(ref mut event)) => event.anonymous = true,
+			_ => return self.fail("anonymous is not set on an event".to_owned()),
+		}
+		self
+	}
+
+	/// Adds a fallback function with `state_mutability`.
+	pub fn fallback(mut self, state_mutability: StateMutability) -> Self {
+		self = self.finish();
+		self.contract.fallback = Some(state_mutability);
+		self
+	}
+
+	/// Adds a receive ether function.
+	pub fn receive(mut self) -> Self {
+		self = self.finish();
+		self.contract.receive = true;
+		self
+	}
+
+	/// Validates and returns the contract.
+	pub fn build(self) -> Result<Contract> {
+		let builder = self.finish();
+		match builder.error {
+			Some(error) => Err(error),
+			None => Ok(builder.contract),
+		}
+	}
+
+	fn start(self, item: Item) -> Self {
+		let mut builder = self.finish();
+		builder.current = Some(item);
+		builder
+	}
+
+	fn fail(mut self, message: String) -> Self {
+		self.error.get_or_insert_with(|| anyhow!(message).into());
+		self
+	}
+
+	/// Validates the current item and adds it to the contract.
+	fn finish(mut self) -> Self {
+		let item = match self.current.take() {
+			Some(item) => item,
+			None => return self,
+		};
+		if let Err(error) = validate(&item) {
+			self.error.get_or_insert(error);
+			return self;
+		}
+
+		// overloads must differ by their inputs
+		let contract = &mut self.contract;
+		let duplicate = match item {
+			Item::Constructor(constructor) => {
+				contract.constructor.replace(constructor).map(|_| "the constructor".to_owned())
+			}
+			Item::Function(function) => {
+				let overloads = contract.functions.entry(function.name.clone()).or_default();
+				let duplicate = overloads.iter().any(|other| other.short_signature() == function.short_signature());
+				let name = format!("function `{}`", function.name);
+				overloads.push(function);
+				Some(name).filter(|_| duplicate)
+			}
+			Item::Event(event) => {
+				let overloads = contract.events.entry(event.name.clone()).or_default();
+				let duplicate = overloads.iter().any(|other| other.signature() == event.signature());
+				let name = format!("event `{}`", event.name);
+				overloads.push(event);
+				Some(name).filter(|_| duplicate)
+			}
+			Item::Error(error) => {
+				let overloads = contract.errors.entry(error.name.clone()).or_default();
+				let duplicate = overloads.iter().any(|other| other.selector() == error.selector());
+				let name = format!("error `{}`", error.name);
+				overloads.push(error);
+				Some(name).filter(|_| duplicate)
+			}
+		};
+		match duplicate {
+			Some(name) => self.fail(format!("{} is declared twice", name)),
+			None => self,
+		}
+	}
+}
+
+fn validate(item: &Item) -> Result<()> {
+	let (name, params): (_, Vec<_>) = match item {
+		Item::Constructor(constructor) => (None, constructor.inputs.iter().map(|p| (&p.name, &p.kind)).collect()),
+		Item::Function(function) => {
+			let outputs = function.outputs.iter().map(|p| (&p.name, &p.kind));
+			(Some(&function.name), function.inputs.iter().map(|p| (&p.name, &p.kind)).chain(outputs).collect())
+		}
+		Item::Event(event) => (Some(&event.name), event.inputs.iter().map(|p| (&p.name, &p.kind)).collect()),
+		Item::Error(error) => (Some(&error.name), error.inputs.iter().map(|p| (&p.name, &p.kind)).collect()),
+	};
+	let item_name = name.map(String::as_str).unwrap_or("constructor");
+
+	if let Some(name) = name {
+		if !is_identifier(name) {
+			return Err(Error::InvalidName(name.clone()));
+		}
+	}
+	for (i, &(name, kind)) in params.iter().enumerate() {
+		if !name.is_empty() && !is_identifier(name) {
+			return Err(Error::InvalidName(name.clone()));
+		}
+		if !name.is_empty() && params[..i].iter().any(|&(other, _)| other == name) {
+			return Err(anyhow!("param `{}` of `{}` is declared twice", name, item_name).into());
+		}
+		if !is_valid_type(kind) {
+			return Err(anyhow!("param `{}` of `{}` has the invalid type `{}`", name, item_name, kind).into());
+		}
+	}
+
+	if let Item::Event(event) = item {
+		let indexed = event.inputs.iter().filter(|p| p.indexed).count();
+		let max_indexed = if event.anonymous { 4 } else { 3 };
+		if indexed > max_indexed {
+			return Err(anyhow!("event `{}` has more than {} indexed params", event.name, max_indexed).into());
+		}
+	}
+
+	Ok(())
+}
+
+fn is_valid_type(kind: &ParamType) -> bool {
+	match *kind {
+		ParamType::Uint(size) | ParamType::Int(size) => size % 8 == 0 && (8..=256).contains(&size),
+		ParamType::Ufixed(size, decimals) | ParamType::Fixed(size, decimals) => {
+			size % 8 == 0 && (8..=256).contains(&size) && decimals <= 80
+		}
+		ParamType::FixedBytes(size) => (1..=32).contains(&size),
+		ParamType::Array(ref inner) | ParamType::FixedArray(ref inner, _) => is_valid_type(inner),
+		ParamType::Tuple(ref components) => !components.is_empty() && components.iter().all(is_valid_type),
+		_ => true,
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::ContractBuilder;
+	use crate::{Contract, Error, ParamType, StateMutability};
+
+	#[test]
+	fn test_build() {
+		let contract = ContractBuilder::new()
+			.constructor()
+			.input("owner", ParamType::Address)
+			.function("balanceOf")
+			.input("owner", ParamType::Address)
+			.output("", ParamType::Uint(256))
+			.state_mutability(StateMutability::View)
+			.event("Approval")
+			.indexed_input("owner", ParamType::Address)
+			.input("amount", ParamType::Uint(256))
+			.anonymous()
+			.error("Unauthorized")
+			.receive()
+			.build()
+			.unwrap();
+
+		let expected = Contract::parse_signatures([
+			"constructor(address owner)",
+			"function balanceOf(address owner) view returns (uint256)",
+			"event Approval(address indexed owner, uint256 amount) anonymous",
+			"error Unauthorized()",
+			"receive() external payable",
+		])
+		.unwrap();
+		assert_eq!(contract, expected);
+	}
+
+	#[test]
+	fn test_invalid() {
+		let build = |builder: ContractBuilder| builder.build().unwrap_err().to_string();
+
+		assert!(matches!(ContractBuilder::new().function("1foo").build(), Err(Error::InvalidName(_))));
+		assert_eq!(
+			build(ContractBuilder::new().function("foo").input("a", ParamType::Uint(7))),
+			"param `a` of `foo` has the invalid type `uint7`"
+		);
+		assert_eq!(
+			build(ContractBuilder::new().error("Foo").input("a", ParamType::Bool).input("a", ParamType::Bool)),
+			"param `a` of `Foo` is declared twice"
+		);
+		assert_eq!(
+			build(ContractBuilder::new().event("Foo").output("a", ParamType::Bool)),
+			"output `a` is not part of a function"
+		);
+		assert_eq!(
+			build(ContractBuilder::new().input("a", ParamType::Bool)),
+			"input `a` is not part of a function, event or error"
+		);
+		assert_eq!(build(ContractBuilder::new().function("foo").function("foo")), "function `foo` is declared twice");
+
+		let event = (0..4).fold(ContractBuilder::new().event("Foo"), |event, i| {
+			event.indexed_input(&format!("a{}", i), ParamType::Bool)
+		});
+		assert_eq!(build(event), "event `Foo` has more than 3 indexed params");
+	}
+}
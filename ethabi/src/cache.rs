@@ -0,0 +1,218 @@
+// Copyright 2015-2020 Parity Technologies
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Versioned on-disk cache format of parsed contracts.
+
+#[cfg(not(feature = "std"))]
+use crate::no_std_prelude::*;
+use crate::{
+	errors, AbiError, Constructor, Contract, Event, EventParam, Function, Param, ParamType, StateMutability, TupleParam,
+};
+use alloc::collections::BTreeMap;
+use anyhow::anyhow;
+use serde::{Deserialize, Serialize};
+
+/// Version of the cache format, bumped on every change of its layout.
+const CACHE_VERSION: u32 = 6;
+
+/// Name, type, internal type and tuple components of a param.
+type CachedParam = (String, ParamType, Option<String>, Vec<TupleParam>);
+
+#[derive(Serialize, Deserialize)]
+struct CachedContract {
+	version: u32,
+	constructor: Option<Vec<CachedParam>>,
+	functions: Vec<CachedFunction>,
+	events: Vec<CachedEvent>,
+	errors: Vec<(String, Vec<CachedParam>)>,
+	fallback: Option<StateMutability>,
+	receive: bool,
+}
+
+#[derive(Serialize, Deserialize)]
+struct CachedFunction {
+	name: String,
+	inputs: Vec<CachedParam>,
+	outputs: Vec<CachedParam>,
+	state_mutability: StateMutability,
+}
+
+#[derive(Serialize, Deserialize)]
+struct CachedEvent {
+	name: String,
+	/// Params along with whether they are indexed.
+	inputs: Vec<(CachedParam, bool)>,
+	anonymous: bool,
+}
+
+fn to_cached_params(params: &[Param]) -> Vec<CachedParam> {
+	params.iter().map(|p| (p.name.clone(), p.kind.clone(), p.internal_type.clone(), p.components.clone())).collect()
+}
+
+fn from_cached_params(params: Vec<CachedParam>) -> Vec<Param> {
+	params
+		.into_iter()
+		.map(|(name, kind, internal_type, components)| Param { name, kind, internal_type, components })
+		.collect()
+}
+
+impl Contract {
+	/// Serializes the parsed contract to the cache format, which
+	/// [`from_cache_bytes`](Contract::from_cache_bytes) loads back without going through the ABI JSON.
+	pub fn to_cache_bytes(&self) -> Vec<u8> {
+		let cached = CachedContract {
+			version: CACHE_VERSION,
+			constructor: self.constructor.as_ref().map(|c| to_cached_params(&c.inputs)),
+			functions: self
+				.functions()
+				.map(|f| CachedFunction {
+					name: f.name.clone(),
+					inputs: to_cached_params(&f.inputs),
+					outputs: to_cached_params(&f.outputs),
+					state_mutability: f.state_mutability,
+				})
+				.collect(),
+			events: self
+				.events()
+				.map(|e| CachedEvent {
+					name: e.name.clone(),
+					inputs: e
+						.inputs
+						.iter()
+						.map(|p| {
+							((p.name.clone(), p.kind.clone(), p.internal_type.clone(), p.components.clone()), p.indexed)
+						})
+						.collect(),
+					anonymous: e.anonymous,
+				})
+				.collect(),
+			errors: self.errors().map(|e| (e.name.clone(), to_cached_params(&e.inputs))).collect(),
+			fallback: self.fallback,
+			receive: self.receive,
+		};
+		serde_json::to_vec(&cached).expect("cached contracts always serialize; qed")
+	}
+
+	/// Loads a contract serialized by [`to_cache_bytes`](Contract::to_cache_bytes).
+	///
+	/// Fails on caches written by another version of the format, which should then be rebuilt.
+	pub fn from_cache_bytes(bytes: &[u8]) -> errors::Result<Self> {
+		#[derive(Deserialize)]
+		struct Version {
+			version: u32,
+		}
+
+		let Version { version } = serde_json::from_slice(bytes)?;
+		if version != CACHE_VERSION {
+			return Err(anyhow!("cache version {} is not supported, expected {}", version, CACHE_VERSION).into());
+		}
+
+		let cached: CachedContract = serde_json::from_slice(bytes)?;
+		let mut functions = BTreeMap::<_, Vec<_>>::new();
+		for f in cached.functions {
+			functions.entry(f.name.clone()).or_default().push(Function {
+				name: f.name,
+				inputs: from_cached_params(f.inputs),
+				outputs: from_cached_params(f.outputs),
+				constant: f.state_mutability.is_constant(),
+				state_mutability: f.state_mutability,
+			});
+		}
+		let mut events = BTreeMap::<_, Vec<_>>::new();
+		for e in cached.events {
+			events.entry(e.name.clone()).or_default().push(Event {
+				name: e.name,
+				inputs: e
+					.inputs
+					.into_iter()
+					.map(|((name, kind, internal_type, components), indexed)| EventParam {
+						name,
+						kind,
+						indexed,
+						internal_type,
+						components,
+					})
+					.collect(),
+				anonymous: e.anonymous,
+			});
+		}
+
+		let mut errors = BTreeMap::<_, Vec<_>>::new();
+		for (name, inputs) in cached.errors {
+			errors.entry(name.clone()).or_default().push(AbiError { name, inputs: from_cached_params(inputs) });
+		}
+
+		Ok(Contract {
+			constructor: cached.constructor.map(|inputs| Constructor { inputs: from_cached_params(inputs) }),
+			functions,
+			events,
+			errors,
+			fallback: cached.fallback,
+			receive: cached.receive,
+		})
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::Contract;
+
+	#[test]
+	fn cache_roundtrip() {
+		let abi = r#"[{
+			"type": "constructor",
+			"inputs": [{ "name": "owner", "type": "address" }]
+		}, {
+			"type": "function",
+			"name": "foo",
+			"inputs": [{
+				"name": "orders",
+				"type": "tuple[]",
+				"internalType": "struct Foo.Order[]",
+				"components": [
+					{ "name": "id", "type": "uint256", "internalType": "uint256" },
+					{ "name": "tags", "type": "string[2]" }
+				]
+			}],
+			"outputs": [{ "name": "", "type": "bytes32" }],
+			"stateMutability": "view"
+		}, {
+			"type": "function",
+			"name": "foo",
+			"inputs": [],
+			"outputs": [],
+			"stateMutability": "payable"
+		}, {
+			"type": "event",
+			"name": "Bar",
+			"inputs": [
+				{ "name": "a", "type": "address", "indexed": true, "internalType": "contract IERC20" },
+				{ "name": "b", "type": "int8", "indexed": false }
+			],
+			"anonymous": false
+		}, {
+			"type": "error",
+			"name": "Unauthorized",
+			"inputs": [{ "name": "caller", "type": "address" }]
+		}, {
+			"type": "fallback"
+		}, {
+			"type": "receive",
+			"stateMutability": "payable"
+		}]"#;
+		let contract = Contract::load(abi.as_bytes()).unwrap();
+		let loaded = Contract::from_cache_bytes(&contract.to_cache_bytes()).unwrap();
+		assert_eq!(loaded, contract);
+	}
+
+	#[test]
+	fn cache_version_mismatch() {
+		let err = Contract::from_cache_bytes(br#"{"version":0}"#).unwrap_err();
+		assert_eq!(err.to_string(), "cache version 0 is not supported, expected 6");
+	}
+}
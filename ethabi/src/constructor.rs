@@ -7,8 +7,11 @@
 // except according to those terms.
 
 //! Contract constructor call builder.
-use crate::{encode, Bytes, Error, Param, ParamType, Result, Token};
-use serde::Deserialize;
+#[cfg(not(feature = "std"))]
+use crate::no_std_prelude::*;
+use crate::{decode, encode, Bytes, Error, Param, ParamType, Result, Token};
+use anyhow::anyhow;
+use serde::{ser::SerializeMap, Deserialize, Serialize, Serializer};
 
 /// Contract constructor specification.
 #[derive(Debug, Clone, PartialEq, Deserialize)]
@@ -17,6 +20,15 @@ pub struct Constructor {
 	pub inputs: Vec<Param>,
 }
 
+impl Serialize for Constructor {
+	fn serialize<S: Serializer>(&self, serializer: S) -> core::result::Result<S::Ok, S::Error> {
+		let mut map = serializer.serialize_map(Some(2))?;
+		map.serialize_entry("type", "constructor")?;
+		map.serialize_entry("inputs", &self.inputs)?;
+		map.end()
+	}
+}
+
 impl Constructor {
 	/// Returns all input params of given constructor.
 	fn param_types(&self) -> Vec<ParamType> {
@@ -25,12 +37,78 @@ impl Constructor {
 
 	/// Prepares ABI constructor call with given input params.
 	pub fn encode_input(&self, code: Bytes, tokens: &[Token]) -> Result<Bytes> {
+		Ok(code.into_iter().chain(self.encode_args(tokens)?).collect())
+	}
+
+	/// Encodes the constructor arguments only, without prepending the contract code.
+	pub fn encode_args(&self, tokens: &[Token]) -> Result<Bytes> {
 		let params = self.param_types();
 
 		if Token::types_check(tokens, &params) {
-			Ok(code.into_iter().chain(encode(tokens)).collect())
+			Ok(encode(tokens))
 		} else {
 			Err(Error::InvalidData)
 		}
 	}
+
+	/// Parses the constructor arguments, without the contract code, to a list of tokens.
+	pub fn decode_input(&self, data: &[u8]) -> Result<Vec<Token>> {
+		decode(&self.param_types(), data)
+	}
+
+	/// Parses deployment `data`, contract code followed by the constructor arguments, checking
+	/// that it starts with the contract `code`.
+	pub fn decode_deployment(&self, code: &[u8], data: &[u8]) -> Result<Vec<Token>> {
+		match data.strip_prefix(code) {
+			Some(args) => self.decode_input(args),
+			None => Err(anyhow!("deployment data does not start with the contract code").into()),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::{Constructor, Param, ParamType, Token};
+	use hex_literal::hex;
+
+	#[test]
+	fn test_constructor_encode_args() {
+		let constructor = Constructor {
+			inputs: vec![Param {
+				name: "a".to_owned(),
+				kind: ParamType::Uint(256),
+				internal_type: None,
+				components: vec![],
+			}],
+		};
+
+		let args = constructor.encode_args(&[Token::Uint(69.into())]).unwrap();
+		let expected = hex!("0000000000000000000000000000000000000000000000000000000000000045").to_vec();
+		assert_eq!(args, expected);
+
+		let input = constructor.encode_input(vec![0x60, 0x80], &[Token::Uint(69.into())]).unwrap();
+		assert_eq!(input[..2], [0x60, 0x80]);
+		assert_eq!(input[2..], expected[..]);
+
+		assert!(constructor.encode_args(&[Token::Bool(true)]).is_err());
+	}
+
+	#[test]
+	fn test_constructor_decode_input() {
+		let constructor = Constructor {
+			inputs: vec![Param {
+				name: "a".to_owned(),
+				kind: ParamType::Uint(256),
+				internal_type: None,
+				components: vec![],
+			}],
+		};
+		let tokens = vec![Token::Uint(69.into())];
+		let input = constructor.encode_input(vec![0x60, 0x80], &tokens).unwrap();
+
+		assert_eq!(constructor.decode_deployment(&[0x60, 0x80], &input).unwrap(), tokens);
+		assert_eq!(constructor.decode_input(&input[2..]).unwrap(), tokens);
+		assert!(constructor.decode_deployment(&[0x60, 0x40], &input).is_err());
+		assert!(constructor.decode_input(&input[..31]).is_err());
+	}
 }
@@ -6,15 +6,25 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
-use crate::{errors, operation::Operation, Constructor, Error, Event, Function};
+#[cfg(not(feature = "std"))]
+use crate::no_std_prelude::*;
+use crate::{
+	errors, human_readable, legacy, operation::Operation, AbiError, Address, Constructor, Error, Event, Function, Hash,
+	Log, ParamType, RawLog, StateMutability, Token, Topic, TopicFilter,
+};
+use alloc::collections::{btree_map::Values, BTreeMap};
+use anyhow::anyhow;
+use core::{fmt, iter::Flatten};
 use serde::{
-	de::{SeqAccess, Visitor},
-	Deserialize, Deserializer,
+	de::{Error as _, IgnoredAny, MapAccess, SeqAccess, Visitor},
+	ser::SerializeSeq,
+	Deserialize, Deserializer, Serialize, Serializer,
 };
+use serde_json::{json, Value};
+#[cfg(feature = "std")]
 use std::{
-	collections::{hash_map::Values, HashMap},
-	fmt, io,
-	iter::Flatten,
+	fs, io,
+	path::{Path, PathBuf},
 };
 
 /// API building calls to contracts ABI.
@@ -22,12 +32,16 @@ use std::{
 pub struct Contract {
 	/// Contract constructor.
 	pub constructor: Option<Constructor>,
-	/// Contract functions.
-	pub functions: HashMap<String, Vec<Function>>,
-	/// Contract events, maps signature to event.
-	pub events: HashMap<String, Vec<Event>>,
-	/// Contract has fallback function.
-	pub fallback: bool,
+	/// Contract functions, keyed and ordered by name.
+	pub functions: BTreeMap<String, Vec<Function>>,
+	/// Contract events, keyed and ordered by name.
+	pub events: BTreeMap<String, Vec<Event>>,
+	/// Contract custom errors, keyed and ordered by name.
+	pub errors: BTreeMap<String, Vec<AbiError>>,
+	/// State mutability of the fallback function, if the contract has one.
+	pub fallback: Option<StateMutability>,
+	/// Contract has a receive ether function.
+	pub receive: bool,
 }
 
 impl<'a> Deserialize<'a> for Contract {
@@ -39,6 +53,35 @@ impl<'a> Deserialize<'a> for Contract {
 	}
 }
 
+/// Serializes to the standard JSON ABI, a list of operations.
+impl Serialize for Contract {
+	fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+	where
+		S: Serializer,
+	{
+		let mut seq = serializer.serialize_seq(None)?;
+		if let Some(constructor) = &self.constructor {
+			seq.serialize_element(constructor)?;
+		}
+		for function in self.functions() {
+			seq.serialize_element(function)?;
+		}
+		for event in self.events() {
+			seq.serialize_element(event)?;
+		}
+		for error in self.errors() {
+			seq.serialize_element(error)?;
+		}
+		if let Some(state_mutability) = self.fallback {
+			seq.serialize_element(&Operation::Fallback(state_mutability))?;
+		}
+		if self.receive {
+			seq.serialize_element(&Operation::Receive)?;
+		}
+		seq.end()
+	}
+}
+
 struct ContractVisitor;
 
 impl<'a> Visitor<'a> for ContractVisitor {
@@ -48,12 +91,44 @@ impl<'a> Visitor<'a> for ContractVisitor {
 		formatter.write_str("valid abi spec file")
 	}
 
+	/// ABI embedded as a JSON string, as in solc outputs.
+	fn visit_str<E>(self, json: &str) -> Result<Self::Value, E>
+	where
+		E: serde::de::Error,
+	{
+		serde_json::from_str(json).map_err(E::custom)
+	}
+
+	/// Artifact of a build tool, e.g. Truffle or Hardhat, holding the ABI in its `abi` field.
+	fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+	where
+		A: MapAccess<'a>,
+	{
+		let mut abi = None;
+		while let Some(key) = map.next_key::<String>()? {
+			match key.as_str() {
+				"abi" if abi.is_some() => return Err(A::Error::duplicate_field("abi")),
+				"abi" => abi = Some(map.next_value()?),
+				_ => {
+					map.next_value::<IgnoredAny>()?;
+				}
+			}
+		}
+		abi.ok_or_else(|| A::Error::missing_field("abi"))
+	}
+
 	fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
 	where
 		A: SeqAccess<'a>,
 	{
-		let mut result =
-			Contract { constructor: None, functions: HashMap::default(), events: HashMap::default(), fallback: false };
+		let mut result = Contract {
+			constructor: None,
+			functions: BTreeMap::default(),
+			events: BTreeMap::default(),
+			errors: BTreeMap::default(),
+			fallback: None,
+			receive: false,
+		};
 
 		while let Some(operation) = seq.next_element()? {
 			match operation {
@@ -66,8 +141,14 @@ impl<'a> Visitor<'a> for ContractVisitor {
 				Operation::Event(event) => {
 					result.events.entry(event.name.clone()).or_default().push(event);
 				}
-				Operation::Fallback => {
-					result.fallback = true;
+				Operation::Error(error) => {
+					result.errors.entry(error.name.clone()).or_default().push(error);
+				}
+				Operation::Fallback(state_mutability) => {
+					result.fallback = Some(state_mutability);
+				}
+				Operation::Receive => {
+					result.receive = true;
 				}
 			}
 		}
@@ -77,50 +158,412 @@ impl<'a> Visitor<'a> for ContractVisitor {
 }
 
 impl Contract {
-	/// Loads contract from json.
+	/// Loads contract from json, either a bare ABI or an artifact holding it in its `abi` field.
+	///
+	/// See [`Artifact`](crate::Artifact) to also load the bytecode of artifacts.
+	#[cfg(feature = "std")]
 	pub fn load<T: io::Read>(reader: T) -> errors::Result<Self> {
 		serde_json::from_reader(reader).map_err(From::from)
 	}
 
+	/// Loads contract from json as leniently as [`load_lenient_from_slice`](Contract::load_lenient_from_slice).
+	#[cfg(feature = "std")]
+	pub fn load_lenient<T: io::Read>(reader: T) -> errors::Result<Self> {
+		let mut value: Value = serde_json::from_reader(reader)?;
+		legacy::normalize(&mut value);
+		serde_json::from_value(value).map_err(From::from)
+	}
+
+	/// Loads contract from json bytes.
+	pub fn load_from_slice(json: &[u8]) -> errors::Result<Self> {
+		serde_json::from_slice(json).map_err(From::from)
+	}
+
+	/// Loads contract from json bytes like [`load_from_slice`](Contract::load_from_slice), also
+	/// accepting the ABIs of Vyper and old solc versions: the type defaults to function and the
+	/// inputs and outputs to none, string `constant` and `payable` flags are parsed, and `gas`
+	/// fields and the units of params are dropped.
+	pub fn load_lenient_from_slice(json: &[u8]) -> errors::Result<Self> {
+		let mut value: Value = serde_json::from_slice(json)?;
+		legacy::normalize(&mut value);
+		serde_json::from_value(value).map_err(From::from)
+	}
+
+	/// Loads contract from a json string.
+	pub fn load_from_str(json: &str) -> errors::Result<Self> {
+		serde_json::from_str(json).map_err(From::from)
+	}
+
+	/// Builds a contract from human-readable declarations, e.g. `transfer(address,uint256)`
+	/// or `event Transfer(address indexed from, address indexed to, uint256 value)`.
+	///
+	/// Events and errors need their `event` and `error` keyword, other items are functions
+	/// unless named `constructor`, `fallback` or `receive`.
+	pub fn parse_signatures<I, S>(signatures: I) -> errors::Result<Self>
+	where
+		I: IntoIterator<Item = S>,
+		S: AsRef<str>,
+	{
+		human_readable::parse_contract(signatures)
+	}
+
+	/// Loads every `.json` and `.abi` file in the directory at `path`, keyed by file stem.
+	///
+	/// Files which fail to load do not abort the whole load, they are collected in
+	/// [`LoadedDir::errors`] instead.
+	#[cfg(feature = "std")]
+	pub fn load_dir<P: AsRef<Path>>(path: P) -> errors::Result<LoadedDir> {
+		let path = path.as_ref();
+		let entries = fs::read_dir(path).map_err(|err| anyhow!("failed to read `{}`: {}", path.display(), err))?;
+
+		let mut paths = Vec::new();
+		for entry in entries {
+			let entry = entry.map_err(|err| anyhow!("failed to read `{}`: {}", path.display(), err))?;
+			let path = entry.path();
+			let is_abi = matches!(path.extension().and_then(|ext| ext.to_str()), Some("json") | Some("abi"));
+			if is_abi && path.is_file() {
+				paths.push(path);
+			}
+		}
+		paths.sort();
+
+		let mut loaded = LoadedDir::default();
+		for path in paths {
+			let stem = path.file_stem().and_then(|stem| stem.to_str()).map(ToOwned::to_owned);
+			let result = match stem {
+				Some(stem) => fs::File::open(&path)
+					.map_err(|err| anyhow!("failed to open: {}", err).into())
+					.and_then(|file| Contract::load(io::BufReader::new(file)))
+					.map(|contract| (stem, contract)),
+				None => Err(anyhow!("file name is not valid UTF-8").into()),
+			};
+			match result {
+				Ok((stem, contract)) => {
+					loaded.contracts.insert(stem, contract);
+				}
+				Err(err) => loaded.errors.push((path, err)),
+			}
+		}
+		Ok(loaded)
+	}
+
 	/// Creates constructor call builder.
 	pub fn constructor(&self) -> Option<&Constructor> {
 		self.constructor.as_ref()
 	}
 
+	/// Get the custom error named `name`, the first if there are overloaded
+	/// versions of the same error.
+	pub fn error(&self, name: &str) -> errors::Result<&AbiError> {
+		self.errors.get(name).into_iter().flatten().next().ok_or_else(|| Error::MissingError(name.to_owned()))
+	}
+
+	/// Decodes revert `data` with the custom error of the contract having its selector, or with
+	/// the built-in `Error(string)` and `Panic(uint256)` even if they are not part of the ABI.
+	///
+	/// Returns the name of the error along with its params.
+	pub fn decode_error(&self, data: &[u8]) -> errors::Result<(&str, Vec<Token>)> {
+		if data.len() < 4 {
+			return Err(anyhow!("revert data of {} bytes is shorter than a selector", data.len()).into());
+		}
+
+		if let Some(error) = self.errors().find(|error| error.selector() == data[..4]) {
+			return Ok((&error.name, error.decode(data)?));
+		}
+
+		let builtins = [("Error", AbiError::error_string()), ("Panic", AbiError::panic())];
+		match builtins.iter().find(|(_, error)| error.selector() == data[..4]) {
+			Some((name, error)) => Ok((name, error.decode(data)?)),
+			None => Err(anyhow!("unknown error selector 0x{}", hex::encode(&data[..4])).into()),
+		}
+	}
+
 	/// Get the function named `name`, the first if there are overloaded
 	/// versions of the same function.
 	pub fn function(&self, name: &str) -> errors::Result<&Function> {
-		self.functions.get(name).into_iter().flatten().next().ok_or_else(|| Error::InvalidName(name.to_owned()))
+		self.functions.get(name).into_iter().flatten().next().ok_or_else(|| Error::MissingFunction(name.to_owned()))
 	}
 
 	/// Get the contract event named `name`, the first if there are multiple.
 	pub fn event(&self, name: &str) -> errors::Result<&Event> {
-		self.events.get(name).into_iter().flatten().next().ok_or_else(|| Error::InvalidName(name.to_owned()))
+		self.events.get(name).into_iter().flatten().next().ok_or_else(|| Error::MissingEvent(name.to_owned()))
 	}
 
 	/// Get all contract events named `name`.
 	pub fn events_by_name(&self, name: &str) -> errors::Result<&Vec<Event>> {
-		self.events.get(name).ok_or_else(|| Error::InvalidName(name.to_owned()))
+		self.events.get(name).ok_or_else(|| Error::MissingEvent(name.to_owned()))
 	}
 
 	/// Get all functions named `name`.
 	pub fn functions_by_name(&self, name: &str) -> errors::Result<&Vec<Function>> {
-		self.functions.get(name).ok_or_else(|| Error::InvalidName(name.to_owned()))
+		self.functions.get(name).ok_or_else(|| Error::MissingFunction(name.to_owned()))
+	}
+
+	/// Get the only function named `name` taking `arity` arguments.
+	///
+	/// Returns `None` if there is no such function or if several overloads have that arity.
+	pub fn function_by_arity(&self, name: &str, arity: usize) -> Option<&Function> {
+		let mut candidates = self.functions.get(name)?.iter().filter(|f| f.inputs.len() == arity);
+		match (candidates.next(), candidates.next()) {
+			(Some(function), None) => Some(function),
+			_ => None,
+		}
+	}
+
+	/// Get the function having `selector`.
+	///
+	/// Errors if there is no such function, or if several functions share the selector as
+	/// calls to it could not be told apart.
+	///
+	/// Every call scans the functions and hashes their signatures. To look up many selectors,
+	/// [`Interface`](crate::Interface) precomputes a selector map once.
+	pub fn function_by_selector(&self, selector: [u8; 4]) -> errors::Result<&Function> {
+		let mut candidates = self.functions().filter(|function| function.short_signature() == selector);
+		let function =
+			candidates.next().ok_or_else(|| anyhow!("no function has the selector 0x{}", hex::encode(selector)))?;
+		match candidates.find(|other| other.canonical_signature() != function.canonical_signature()) {
+			Some(other) => Err(selector_collision(function, other)),
+			None => Ok(function),
+		}
+	}
+
+	/// Get the non anonymous event having `topic` as signature.
+	///
+	/// Every call scans the events and hashes their signatures, which takes time linear in the
+	/// number of events. To look up many topics, [`Interface`](crate::Interface) indexes them once,
+	/// and so does [`parse_logs`](Self::parse_logs) for its batch.
+	pub fn event_by_topic(&self, topic: &Hash) -> errors::Result<&Event> {
+		self.events()
+			.find(|event| !event.anonymous && event.signature() == *topic)
+			.ok_or_else(|| anyhow!("no event has the topic {:?}", topic).into())
+	}
+
+	/// Parses a log emitted by a non anonymous event of the contract, found by its first topic
+	/// with [`event_by_topic`](Self::event_by_topic).
+	pub fn parse_log(&self, log: RawLog) -> errors::Result<(&Event, Log)> {
+		let topic = log.topics.first().ok_or_else(|| anyhow!("log has no topics"))?;
+		let event = self.event_by_topic(topic)?;
+		Ok((event, event.parse_log(log)?))
 	}
 
-	/// Iterate over all functions of the contract in arbitrary order.
-	pub fn functions(&self) -> Functions {
+	/// Parses a batch of logs emitted by non anonymous events of the contract, e.g. those of a
+	/// receipt, into one result per log.
+	///
+	/// Events are indexed by topic once for the whole batch, and logs with an unknown first topic
+	/// are errors without being decoded. Errors are [`Error::InvalidLog`](errors::Error::InvalidLog),
+	/// telling the position of the log.
+	pub fn parse_logs(&self, logs: &[RawLog]) -> Vec<errors::Result<(&Event, Log)>> {
+		let mut events = BTreeMap::new();
+		for event in self.events().filter(|event| !event.anonymous) {
+			events.entry(event.signature()).or_insert(event);
+		}
+
+		logs.iter()
+			.enumerate()
+			.map(|(index, log)| {
+				let parsed = match log.topics.first() {
+					Some(topic) => match events.get(topic) {
+						Some(event) => event.parse_log_params(log).map(|parsed| (*event, parsed)),
+						None => Err(anyhow!("no event has the topic {:?}", topic).into()),
+					},
+					None => Err(anyhow!("log has no topics").into()),
+				};
+				parsed.map_err(|error| errors::Error::InvalidLog { index, error: Box::new(error) })
+			})
+			.collect()
+	}
+
+	/// Checks that no two functions of the contract share a selector, which would make
+	/// dispatching calls by selector ambiguous.
+	pub fn validate_selectors(&self) -> errors::Result<()> {
+		let mut seen = BTreeMap::new();
+		for function in self.functions() {
+			match seen.insert(function.short_signature(), function) {
+				Some(other) if other.canonical_signature() != function.canonical_signature() => {
+					return Err(selector_collision(other, function))
+				}
+				_ => (),
+			}
+		}
+		Ok(())
+	}
+
+	/// Decodes call `data`, selector followed by the arguments, returning the called function
+	/// and its arguments.
+	///
+	/// The function is found with [`function_by_selector`](Self::function_by_selector), so this
+	/// errors on unknown and colliding selectors. [`Interface::decode_call`](crate::Interface::decode_call)
+	/// does the same with a precomputed selector map.
+	pub fn decode_input(&self, data: &[u8]) -> errors::Result<(&Function, Vec<Token>)> {
+		if data.len() < 4 {
+			return Err(anyhow!("calldata of {} bytes is shorter than a selector", data.len()).into());
+		}
+
+		let (selector, args) = data.split_at(4);
+		let mut array = [0u8; 4];
+		array.copy_from_slice(selector);
+		let function = self.function_by_selector(array)?;
+		Ok((function, function.decode_input(args)?))
+	}
+
+	/// Decodes the call `data` (selector followed by the arguments) with the first function
+	/// having its selector and whose inputs it decodes as.
+	///
+	/// Calls to unknown functions, e.g. on proxies whose implementation ABI differs, are returned
+	/// undecoded instead of erroring. Only `data` shorter than a selector is an error.
+	pub fn decode_input_best_effort(&self, data: &[u8]) -> errors::Result<DecodedInput<'_>> {
+		if data.len() < 4 {
+			return Err(anyhow!("calldata of {} bytes is shorter than a selector", data.len()).into());
+		}
+
+		let (selector, args) = data.split_at(4);
+		let decoded = self
+			.functions()
+			.filter(|function| function.short_signature() == selector)
+			.find_map(|function| function.decode_input(args).ok().map(|tokens| (function, tokens)));
+		Ok(match decoded {
+			Some((function, tokens)) => DecodedInput::Known { function, tokens },
+			None => {
+				let mut unknown = [0u8; 4];
+				unknown.copy_from_slice(selector);
+				DecodedInput::Unknown { selector: unknown, args: args.to_vec() }
+			}
+		})
+	}
+
+	/// Iterate over all functions of the contract ordered by name, overloads in ABI order.
+	pub fn functions(&self) -> Functions<'_> {
 		Functions(self.functions.values().flatten())
 	}
 
-	/// Iterate over all events of the contract in arbitrary order.
-	pub fn events(&self) -> Events {
+	/// Iterate over all events of the contract ordered by name, overloads in ABI order.
+	pub fn events(&self) -> Events<'_> {
 		Events(self.events.values().flatten())
 	}
 
+	/// Iterate over all custom errors of the contract ordered by name, overloads in ABI order.
+	pub fn errors(&self) -> AbiErrors<'_> {
+		AbiErrors(self.errors.values().flatten())
+	}
+
 	/// Returns true if contract has fallback
 	pub fn fallback(&self) -> bool {
-		self.fallback
+		self.fallback.is_some()
+	}
+
+	/// Returns true if contract has a receive ether function.
+	pub fn has_receive(&self) -> bool {
+		self.receive
+	}
+
+	/// Returns true if the contract accepts ether sent without calldata, which goes to the
+	/// receive function or, without one, to a payable fallback.
+	pub fn accepts_plain_transfers(&self) -> bool {
+		self.receive || self.fallback == Some(StateMutability::Payable)
+	}
+
+	/// Builds `eth_getLogs` params matching logs of any of the events named `events` emitted by
+	/// `address`. All overloads of a name are included. The topic0 slot of the filter is the
+	/// list of the events' signatures, which is why anonymous events are rejected.
+	pub fn logs_filter(
+		&self,
+		events: &[&str],
+		address: Address,
+		from_block: Option<u64>,
+		to_block: Option<u64>,
+	) -> errors::Result<Value> {
+		let mut signatures = vec![];
+		for name in events {
+			for event in self.events_by_name(name)? {
+				if event.anonymous {
+					return Err(anyhow!("anonymous event `{}` cannot be matched by topic0", name).into());
+				}
+				signatures.push(event.signature());
+			}
+		}
+
+		let topics = TopicFilter { topic0: Topic::OneOf(signatures), ..Default::default() };
+		let mut filter = json!({ "address": format!("{:?}", address), "topics": topics });
+		if let Some(from_block) = from_block {
+			filter["fromBlock"] = format!("0x{:x}", from_block).into();
+		}
+		if let Some(to_block) = to_block {
+			filter["toBlock"] = format!("0x{:x}", to_block).into();
+		}
+
+		Ok(filter)
+	}
+
+	/// Returns true if any param of the contract, custom errors included, requires ABI encoder
+	/// v2, that is if it uses tuples (structs) or arrays of dynamic types.
+	pub fn uses_abi_v2(&self) -> bool {
+		let constructor = self.constructor.iter().flat_map(|c| &c.inputs).map(|p| &p.kind);
+		let functions = self.functions().flat_map(|f| f.inputs.iter().chain(&f.outputs)).map(|p| &p.kind);
+		let events = self.events().flat_map(|e| &e.inputs).map(|p| &p.kind);
+		let errors = self.errors().flat_map(|e| &e.inputs).map(|p| &p.kind);
+
+		constructor.chain(functions).chain(events).chain(errors).any(requires_abi_v2)
+	}
+}
+
+/// Calldata decoded by [`Contract::decode_input_best_effort`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum DecodedInput<'a> {
+	/// Call to a function of the contract.
+	Known {
+		/// Called function.
+		function: &'a Function,
+		/// Decoded arguments.
+		tokens: Vec<Token>,
+	},
+	/// Call to a function unknown to the contract.
+	Unknown {
+		/// Selector of the call.
+		selector: [u8; 4],
+		/// Undecoded arguments.
+		args: Vec<u8>,
+	},
+}
+
+/// Contracts loaded by [`Contract::load_dir`].
+#[cfg(feature = "std")]
+#[derive(Debug, Default)]
+pub struct LoadedDir {
+	/// Successfully loaded contracts, keyed and ordered by file stem.
+	pub contracts: BTreeMap<String, Contract>,
+	/// Files which could not be loaded, ordered by path.
+	pub errors: Vec<(PathBuf, Error)>,
+}
+
+/// Maps the selector of every function of `contracts` to the distinct signatures having it,
+/// e.g. `transfer(address,uint256)`. Selectors shared by several signatures list all of them.
+pub fn build_selector_db(contracts: &[Contract]) -> BTreeMap<[u8; 4], Vec<String>> {
+	let mut db = BTreeMap::<_, Vec<String>>::new();
+	for function in contracts.iter().flat_map(Contract::functions) {
+		let signature = function.canonical_signature();
+		let signatures = db.entry(function.short_signature()).or_default();
+		if let Err(pos) = signatures.binary_search(&signature) {
+			signatures.insert(pos, signature);
+		}
+	}
+	db
+}
+
+fn selector_collision(function: &Function, other: &Function) -> Error {
+	anyhow!(
+		"`{}` and `{}` share the selector 0x{}",
+		function.canonical_signature(),
+		other.canonical_signature(),
+		hex::encode(function.short_signature())
+	)
+	.into()
+}
+
+fn requires_abi_v2(kind: &ParamType) -> bool {
+	match kind {
+		ParamType::Tuple(_) => true,
+		ParamType::Array(inner) | ParamType::FixedArray(inner, _) => inner.is_dynamic() || requires_abi_v2(inner),
+		_ => false,
 	}
 }
 
@@ -145,3 +588,475 @@ impl<'a> Iterator for Events<'a> {
 		self.0.next()
 	}
 }
+
+/// Contract custom errors iterator.
+pub struct AbiErrors<'a>(Flatten<Values<'a, String, Vec<AbiError>>>);
+
+impl<'a> Iterator for AbiErrors<'a> {
+	type Item = &'a AbiError;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		self.0.next()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::build_selector_db;
+	use crate::{encode, Contract, DecodedInput, Error, Function, Hash, ParamType, RawLog, StateMutability, Token};
+	use hex_literal::hex;
+
+	#[test]
+	fn test_uses_abi_v2() {
+		let v1 = r#"[{
+			"type": "function",
+			"name": "foo",
+			"inputs": [{ "name": "a", "type": "uint256[2][]" }, { "name": "b", "type": "string" }],
+			"outputs": [{ "name": "", "type": "bytes" }]
+		}, {
+			"type": "event",
+			"name": "Bar",
+			"inputs": [{ "name": "a", "type": "address", "indexed": true }],
+			"anonymous": false
+		}]"#;
+		assert!(!Contract::load(v1.as_bytes()).unwrap().uses_abi_v2());
+
+		let nested_dynamic = r#"[{
+			"type": "function",
+			"name": "foo",
+			"inputs": [],
+			"outputs": [{ "name": "", "type": "string[]" }]
+		}]"#;
+		assert!(Contract::load(nested_dynamic.as_bytes()).unwrap().uses_abi_v2());
+
+		let tuple = r#"[{
+			"type": "constructor",
+			"inputs": [{
+				"name": "a",
+				"type": "tuple",
+				"components": [{ "name": "b", "type": "uint256" }]
+			}]
+		}]"#;
+		assert!(Contract::load(tuple.as_bytes()).unwrap().uses_abi_v2());
+
+		let error = r#"[{
+			"type": "error",
+			"name": "Rejected",
+			"inputs": [{ "name": "reasons", "type": "string[]" }]
+		}]"#;
+		assert!(Contract::load(error.as_bytes()).unwrap().uses_abi_v2());
+	}
+
+	#[test]
+	fn test_logs_filter() {
+		let abi = r#"[{
+			"type": "event",
+			"name": "Transfer",
+			"inputs": [
+				{ "name": "from", "type": "address", "indexed": true },
+				{ "name": "to", "type": "address", "indexed": true },
+				{ "name": "value", "type": "uint256", "indexed": false }
+			],
+			"anonymous": false
+		}, {
+			"type": "event",
+			"name": "Approval",
+			"inputs": [
+				{ "name": "owner", "type": "address", "indexed": true },
+				{ "name": "spender", "type": "address", "indexed": true },
+				{ "name": "value", "type": "uint256", "indexed": false }
+			],
+			"anonymous": false
+		}, {
+			"type": "event",
+			"name": "Secret",
+			"inputs": [],
+			"anonymous": true
+		}]"#;
+		let contract = Contract::load(abi.as_bytes()).unwrap();
+
+		let filter = contract.logs_filter(&["Transfer", "Approval"], [0x11u8; 20].into(), Some(16), None).unwrap();
+		assert_eq!(
+			filter.to_string(),
+			concat!(
+				r#"{"address":"0x1111111111111111111111111111111111111111","fromBlock":"0x10","topics":[["#,
+				r#""0xddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef","#,
+				r#""0x8c5be1e5ebec7d5bd14f71427d1e84f3dd0314c0f7b2291e5b200ac8c7c3b925"],null,null,null]}"#,
+			)
+		);
+
+		assert!(contract.logs_filter(&["Secret"], [0x11u8; 20].into(), None, None).is_err());
+		assert!(contract.logs_filter(&["Missing"], [0x11u8; 20].into(), None, None).is_err());
+
+		let approval = contract.event("Approval").unwrap();
+		let log = RawLog {
+			topics: vec![approval.signature(), Hash::from_low_u64_be(1), Hash::from_low_u64_be(2)],
+			data: encode(&[Token::Uint(7.into())]),
+		};
+		let (event, parsed) = contract.parse_log(log).unwrap();
+		assert_eq!(event.name, "Approval");
+		assert_eq!(parsed.params[2].value, Token::Uint(7.into()));
+
+		assert!(contract.parse_log(RawLog { topics: vec![], data: vec![] }).is_err());
+		let secret = contract.event("Secret").unwrap().signature();
+		assert!(contract.event_by_topic(&secret).is_err());
+	}
+
+	#[test]
+	fn test_parse_logs() {
+		let contract = Contract::parse_signatures([
+			"event Transfer(address indexed from, address indexed to, uint256 value)",
+			"event Paused(address account)",
+		])
+		.unwrap();
+		let transfer = contract.event("Transfer").unwrap().signature();
+		let logs = [
+			RawLog {
+				topics: vec![transfer, Hash::from_low_u64_be(1), Hash::from_low_u64_be(2)],
+				data: encode(&[Token::Uint(7.into())]),
+			},
+			RawLog { topics: vec![Hash::from_low_u64_be(3)], data: vec![] },
+			RawLog { topics: vec![transfer], data: vec![] },
+			RawLog { topics: vec![], data: vec![] },
+		];
+
+		let parsed = contract.parse_logs(&logs);
+		assert_eq!(parsed.len(), 4);
+		let (event, log) = parsed[0].as_ref().unwrap();
+		assert_eq!(event.name, "Transfer");
+		assert_eq!(log.params[2].value, Token::Uint(7.into()));
+		for (index, result) in parsed.iter().enumerate().skip(1) {
+			assert!(matches!(result, Err(Error::InvalidLog { index: i, .. }) if *i == index));
+		}
+		assert_eq!(
+			parsed[1].as_ref().unwrap_err().to_string(),
+			format!("Invalid log 1: no event has the topic {:?}", Hash::from_low_u64_be(3))
+		);
+	}
+
+	#[test]
+	fn test_load_from_memory() {
+		let abi = r#"[
+			{ "type": "function", "name": "foo", "inputs": [], "outputs": [] },
+			{ "type": "function", "name": "foo", "inputs": [{ "name": "x", "type": "bool" }], "outputs": [] },
+			{ "type": "function", "name": "bar", "inputs": [], "outputs": [] },
+			{ "type": "event", "name": "Baz", "inputs": [], "anonymous": false }
+		]"#;
+		let contract = Contract::load_from_str(abi).unwrap();
+		assert_eq!(contract.functions().count(), 3);
+		assert_eq!(contract.events().count(), 1);
+		assert_eq!(Contract::load_from_slice(abi.as_bytes()).unwrap(), contract);
+		assert!(Contract::load_from_str("[{").is_err());
+	}
+
+	#[test]
+	fn test_functions_are_ordered_by_name() {
+		let abi = r#"[
+			{ "type": "function", "name": "c", "inputs": [], "outputs": [] },
+			{ "type": "function", "name": "a", "inputs": [{ "name": "x", "type": "bool" }], "outputs": [] },
+			{ "type": "function", "name": "b", "inputs": [], "outputs": [] },
+			{ "type": "function", "name": "a", "inputs": [], "outputs": [] }
+		]"#;
+		let contract = Contract::load(abi.as_bytes()).unwrap();
+		let signatures = contract.functions().map(|f| f.signature()).collect::<Vec<_>>();
+		assert_eq!(signatures, vec!["a(bool)", "a()", "b()", "c()"]);
+	}
+
+	#[test]
+	fn test_function_by_arity() {
+		let abi = r#"[
+			{ "type": "function", "name": "foo", "inputs": [], "outputs": [] },
+			{ "type": "function", "name": "foo", "inputs": [{ "name": "a", "type": "bool" }], "outputs": [] },
+			{ "type": "function", "name": "foo", "inputs": [{ "name": "a", "type": "bool" }, { "name": "b", "type": "bool" }], "outputs": [] },
+			{ "type": "function", "name": "foo", "inputs": [{ "name": "a", "type": "bool" }, { "name": "b", "type": "string" }], "outputs": [] }
+		]"#;
+		let contract = Contract::load(abi.as_bytes()).unwrap();
+
+		assert_eq!(contract.function_by_arity("foo", 0).unwrap().signature(), "foo()");
+		assert_eq!(contract.function_by_arity("foo", 1).unwrap().signature(), "foo(bool)");
+		assert!(contract.function_by_arity("foo", 2).is_none());
+		assert!(contract.function_by_arity("foo", 3).is_none());
+		assert!(contract.function_by_arity("bar", 0).is_none());
+		assert!(matches!(contract.function("bar"), Err(Error::MissingFunction(name)) if name == "bar"));
+		assert!(matches!(contract.event("foo"), Err(Error::MissingEvent(_))));
+		assert!(matches!(contract.error("foo"), Err(Error::MissingError(_))));
+	}
+
+	#[test]
+	fn test_selector_collisions() {
+		let abi = r#"[
+			{ "type": "function", "name": "transfer", "inputs": [{ "name": "to", "type": "address" }, { "name": "value", "type": "uint256" }], "outputs": [] },
+			{ "type": "function", "name": "f8491", "inputs": [], "outputs": [] },
+			{ "type": "function", "name": "f130736", "inputs": [], "outputs": [] }
+		]"#;
+		let contract = Contract::load(abi.as_bytes()).unwrap();
+
+		let error = contract.validate_selectors().unwrap_err().to_string();
+		assert_eq!(error, "`f130736()` and `f8491()` share the selector 0x62018627");
+		let error = contract.function_by_selector([0x62, 0x01, 0x86, 0x27]).unwrap_err().to_string();
+		assert_eq!(error, "`f130736()` and `f8491()` share the selector 0x62018627");
+		assert_eq!(contract.function_by_selector([0xa9, 0x05, 0x9c, 0xbb]).unwrap().name, "transfer");
+		assert!(contract.function_by_selector([0; 4]).is_err());
+
+		let abi = r#"[
+			{ "type": "function", "name": "transfer", "inputs": [{ "name": "to", "type": "address" }, { "name": "value", "type": "uint256" }], "outputs": [] },
+			{ "type": "function", "name": "f8491", "inputs": [], "outputs": [] }
+		]"#;
+		assert!(Contract::load(abi.as_bytes()).unwrap().validate_selectors().is_ok());
+	}
+
+	#[test]
+	fn test_build_selector_db() {
+		let token = r#"[
+			{ "type": "function", "name": "transfer", "inputs": [{ "name": "to", "type": "address" }, { "name": "value", "type": "uint256" }], "outputs": [] },
+			{ "type": "function", "name": "totalSupply", "inputs": [], "outputs": [] }
+		]"#;
+		let other = r#"[
+			{ "type": "function", "name": "transfer", "inputs": [{ "name": "", "type": "address" }, { "name": "", "type": "uint256" }], "outputs": [{ "name": "", "type": "bool" }] },
+			{ "type": "function", "name": "f8491", "inputs": [], "outputs": [] },
+			{ "type": "function", "name": "f130736", "inputs": [], "outputs": [] }
+		]"#;
+		let contracts = [Contract::load(token.as_bytes()).unwrap(), Contract::load(other.as_bytes()).unwrap()];
+
+		let db = build_selector_db(&contracts);
+		assert_eq!(db[&[0xa9, 0x05, 0x9c, 0xbb]], vec!["transfer(address,uint256)"]);
+		assert_eq!(db[&[0x18, 0x16, 0x0d, 0xdd]], vec!["totalSupply()"]);
+		// a selector collision
+		assert_eq!(db[&[0x62, 0x01, 0x86, 0x27]], vec!["f130736()", "f8491()"]);
+		assert_eq!(db.len(), 3);
+	}
+
+	#[test]
+	fn test_tuple_without_components() {
+		let abi = r#"[{
+			"type": "function",
+			"name": "foo",
+			"inputs": [{ "name": "config", "type": "tuple" }],
+			"outputs": []
+		}]"#;
+		let err = Contract::load(abi.as_bytes()).unwrap_err().to_string();
+		assert!(err.contains("tuple param `config` has no components"), "{}", err);
+
+		let abi = r#"[{
+			"type": "event",
+			"name": "Foo",
+			"inputs": [{ "name": "orders", "type": "tuple[]", "components": null, "indexed": false }],
+			"anonymous": false
+		}]"#;
+		let err = Contract::load(abi.as_bytes()).unwrap_err().to_string();
+		assert!(err.contains("tuple param `orders` has no components"), "{}", err);
+
+		let abi = r#"[{
+			"type": "function",
+			"name": "foo",
+			"inputs": [{ "name": "a", "type": "tuple", "components": [{ "name": "inner", "type": "tuple[2]", "components": [] }] }],
+			"outputs": []
+		}]"#;
+		let err = Contract::load(abi.as_bytes()).unwrap_err().to_string();
+		assert!(err.contains("tuple param `inner` has no components"), "{}", err);
+	}
+
+	#[test]
+	fn test_event_tuple_array_components() {
+		let abi = r#"[{
+			"type": "event",
+			"name": "Foo",
+			"inputs": [{
+				"name": "orders",
+				"type": "tuple[]",
+				"components": [{ "name": "id", "type": "uint256" }, { "name": "maker", "type": "address" }],
+				"indexed": false
+			}],
+			"anonymous": false
+		}]"#;
+		let contract = Contract::load(abi.as_bytes()).unwrap();
+		assert_eq!(
+			contract.event("Foo").unwrap().inputs[0].kind,
+			ParamType::Array(Box::new(ParamType::Tuple(vec![ParamType::Uint(256), ParamType::Address])))
+		);
+	}
+
+	#[test]
+	fn test_load_dir() {
+		let dir = std::env::temp_dir().join(format!("ethabi-load-dir-{}", std::process::id()));
+		std::fs::create_dir_all(&dir).unwrap();
+		std::fs::write(dir.join("Foo.abi"), r#"[{ "type": "function", "name": "foo", "inputs": [], "outputs": [] }]"#)
+			.unwrap();
+		std::fs::write(dir.join("Bar.json"), "[]").unwrap();
+		std::fs::write(dir.join("package.json"), r#"{ "name": "not-an-abi" }"#).unwrap();
+		std::fs::write(dir.join("README.md"), "not an abi either").unwrap();
+		std::fs::write(dir.join("Zed.abi"), "not json").unwrap();
+
+		let loaded = Contract::load_dir(&dir).unwrap();
+		std::fs::remove_dir_all(&dir).unwrap();
+
+		// in a stable order, whatever the order of the directory entries
+		assert_eq!(loaded.contracts.keys().collect::<Vec<_>>(), ["Bar", "Foo"]);
+		assert!(loaded.contracts["Foo"].function("foo").is_ok());
+		let failed = loaded.errors.iter().map(|(path, _)| path.clone()).collect::<Vec<_>>();
+		assert_eq!(failed, [dir.join("Zed.abi"), dir.join("package.json")]);
+	}
+
+	#[test]
+	fn test_receive_and_fallback() {
+		let abi = r#"[
+			{ "type": "fallback", "stateMutability": "nonpayable" },
+			{ "type": "receive", "stateMutability": "payable" }
+		]"#;
+		let contract = Contract::load(abi.as_bytes()).unwrap();
+		assert!(contract.fallback());
+		assert_eq!(contract.fallback, Some(StateMutability::NonPayable));
+		assert!(contract.has_receive());
+		assert!(contract.accepts_plain_transfers());
+
+		let contract = Contract::load(r#"[{ "type": "fallback", "payable": true }]"#.as_bytes()).unwrap();
+		assert!(!contract.has_receive());
+		assert!(contract.accepts_plain_transfers());
+
+		let contract = Contract::load(&b"[]"[..]).unwrap();
+		assert!(!contract.fallback());
+		assert!(!contract.accepts_plain_transfers());
+	}
+
+	#[test]
+	fn test_load_dir_missing() {
+		assert!(Contract::load_dir("/this/path/does/not/exist").is_err());
+	}
+
+	#[test]
+	fn test_decode_input() {
+		let abi = r#"[
+			{ "type": "function", "name": "transfer", "inputs": [{ "name": "to", "type": "address" }, { "name": "value", "type": "uint256" }], "outputs": [] },
+			{ "type": "function", "name": "approve", "inputs": [{ "name": "spender", "type": "address" }, { "name": "value", "type": "uint256" }], "outputs": [] }
+		]"#;
+		let contract = Contract::load(abi.as_bytes()).unwrap();
+		let approve = contract.function("approve").unwrap();
+
+		let tokens = vec![Token::Address([0x11; 20].into()), Token::Uint(7.into())];
+		let data = approve.encode_input(&tokens).unwrap();
+		assert_eq!(contract.decode_input(&data).unwrap(), (approve, tokens));
+
+		// balanceOf(address) is unknown to the contract
+		assert!(contract.decode_input(&hex!("70a08231")).is_err());
+		assert!(contract.decode_input(&data[..36]).is_err());
+		assert!(contract.decode_input(&data[..3]).is_err());
+	}
+
+	#[test]
+	fn test_decode_input_best_effort() {
+		let abi = r#"[{
+			"type": "function",
+			"name": "transfer",
+			"inputs": [{ "name": "to", "type": "address" }, { "name": "value", "type": "uint256" }],
+			"outputs": []
+		}]"#;
+		let contract = Contract::load(abi.as_bytes()).unwrap();
+		let transfer = contract.function("transfer").unwrap();
+
+		let tokens = vec![Token::Address([0x11; 20].into()), Token::Uint(7.into())];
+		let data = transfer.encode_input(&tokens).unwrap();
+		assert_eq!(
+			contract.decode_input_best_effort(&data).unwrap(),
+			DecodedInput::Known { function: transfer, tokens }
+		);
+
+		// approve(address,uint256) is unknown to the contract
+		let data = hex!("095ea7b3 00000000000000000000000000000000000000000000000000000000000000ff");
+		assert_eq!(
+			contract.decode_input_best_effort(&data).unwrap(),
+			DecodedInput::Unknown { selector: hex!("095ea7b3"), args: data[4..].to_vec() }
+		);
+
+		// a known selector with arguments it can not decode is treated as unknown
+		let data = hex!("a9059cbb 00");
+		assert_eq!(
+			contract.decode_input_best_effort(&data).unwrap(),
+			DecodedInput::Unknown { selector: hex!("a9059cbb"), args: vec![0] }
+		);
+
+		assert!(contract.decode_input_best_effort(&hex!("a905")).is_err());
+	}
+
+	#[test]
+	fn test_decode_error() {
+		let abi = r#"[{
+			"type": "error",
+			"name": "InsufficientBalance",
+			"inputs": [{ "name": "available", "type": "uint256" }, { "name": "required", "type": "uint256" }]
+		}]"#;
+		let contract = Contract::load(abi.as_bytes()).unwrap();
+		assert_eq!(contract.errors().count(), 1);
+
+		let error = contract.error("InsufficientBalance").unwrap();
+		let tokens = vec![Token::Uint(1.into()), Token::Uint(2.into())];
+		let data = error.encode(&tokens).unwrap();
+		assert_eq!(contract.decode_error(&data).unwrap(), ("InsufficientBalance", tokens));
+
+		// built-in errors decode without being part of the ABI
+		let data = hex!("4e487b71 0000000000000000000000000000000000000000000000000000000000000011");
+		assert_eq!(contract.decode_error(&data).unwrap(), ("Panic", vec![Token::Uint(0x11.into())]));
+		let data = crate::AbiError::error_string().encode(&[Token::String("nope".to_owned())]).unwrap();
+		assert_eq!(contract.decode_error(&data).unwrap(), ("Error", vec![Token::String("nope".to_owned())]));
+
+		assert!(contract.decode_error(&hex!("deadbeef")).is_err());
+		assert!(contract.decode_error(&hex!("4e48")).is_err());
+	}
+
+	#[test]
+	fn test_serialize() {
+		let abi = r#"[{
+			"type": "constructor",
+			"inputs": [{ "name": "owner", "type": "address" }]
+		}, {
+			"type": "function",
+			"name": "foo",
+			"inputs": [{
+				"name": "a",
+				"type": "tuple[2][]",
+				"components": [{ "name": "id", "type": "uint8" }, { "name": "data", "type": "bytes" }]
+			}, {
+				"name": "b",
+				"type": "tuple",
+				"components": [{
+					"name": "inner",
+					"type": "tuple[]",
+					"components": [{ "name": "flag", "type": "bool" }]
+				}]
+			}],
+			"outputs": [{ "name": "", "type": "bool" }],
+			"stateMutability": "view"
+		}, {
+			"type": "event",
+			"name": "Bar",
+			"inputs": [{ "name": "a", "type": "string", "indexed": true }],
+			"anonymous": false
+		}, {
+			"type": "error",
+			"name": "Baz",
+			"inputs": []
+		}, {
+			"type": "fallback",
+			"stateMutability": "nonpayable"
+		}, {
+			"type": "receive",
+			"stateMutability": "payable"
+		}]"#;
+		let contract = Contract::load(abi.as_bytes()).unwrap();
+		let serialized = serde_json::to_value(&contract).unwrap();
+		assert_eq!(serialized, serde_json::from_str::<serde_json::Value>(abi).unwrap());
+		assert_eq!(serde_json::from_value::<Contract>(serialized).unwrap(), contract);
+	}
+
+	#[test]
+	fn test_serialize_unnamed_components() {
+		// params built by hand have no component names
+		let function = Function::parse("foo((uint8,bytes) a)").unwrap();
+		let serialized = serde_json::to_value(&function.inputs[0]).unwrap();
+		let expected = serde_json::json!({
+			"name": "a",
+			"type": "tuple",
+			"components": [{ "name": "", "type": "uint8" }, { "name": "", "type": "bytes" }]
+		});
+		assert_eq!(serialized, expected);
+	}
+}
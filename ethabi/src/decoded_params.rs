@@ -0,0 +1,105 @@
+// Copyright 2015-2020 Parity Technologies
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Decoded params addressable by name.
+
+#[cfg(not(feature = "std"))]
+use crate::no_std_prelude::*;
+use crate::Token;
+use core::ops::Index;
+
+/// Decoded params in declaration order, accessible by name or by position.
+///
+/// Unnamed params have an empty name, so they are only accessible by position.
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct DecodedParams {
+	params: Vec<(String, Token)>,
+}
+
+impl DecodedParams {
+	/// Pairs the `names` of params with their values.
+	pub fn new(names: impl IntoIterator<Item = String>, tokens: Vec<Token>) -> Self {
+		DecodedParams { params: names.into_iter().zip(tokens).collect() }
+	}
+
+	/// Returns the value of the first param named `name`.
+	pub fn get(&self, name: &str) -> Option<&Token> {
+		self.params.iter().find(|(param, _)| !name.is_empty() && param == name).map(|(_, token)| token)
+	}
+
+	/// Returns the value of the param at `index`.
+	pub fn get_index(&self, index: usize) -> Option<&Token> {
+		self.params.get(index).map(|(_, token)| token)
+	}
+
+	/// Returns the number of params.
+	pub fn len(&self) -> usize {
+		self.params.len()
+	}
+
+	/// Returns true if there are no params.
+	pub fn is_empty(&self) -> bool {
+		self.params.is_empty()
+	}
+
+	/// Iterate over the names and values of the params, in declaration order.
+	pub fn iter(&self) -> impl Iterator<Item = (&str, &Token)> {
+		self.params.iter().map(|(name, token)| (name.as_str(), token))
+	}
+
+	/// Returns the values of the params, dropping their names.
+	pub fn into_tokens(self) -> Vec<Token> {
+		self.params.into_iter().map(|(_, token)| token).collect()
+	}
+}
+
+impl Index<usize> for DecodedParams {
+	type Output = Token;
+
+	fn index(&self, index: usize) -> &Token {
+		&self.params[index].1
+	}
+}
+
+impl Index<&str> for DecodedParams {
+	type Output = Token;
+
+	fn index(&self, name: &str) -> &Token {
+		self.get(name).unwrap_or_else(|| panic!("no param is named `{}`", name))
+	}
+}
+
+impl IntoIterator for DecodedParams {
+	type Item = (String, Token);
+	type IntoIter = alloc::vec::IntoIter<(String, Token)>;
+
+	fn into_iter(self) -> Self::IntoIter {
+		self.params.into_iter()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::{DecodedParams, Token};
+
+	#[test]
+	fn test_access() {
+		let params = DecodedParams::new(
+			vec!["amount".to_owned(), String::new()],
+			vec![Token::Uint(1.into()), Token::Bool(true)],
+		);
+		assert_eq!(params.get("amount"), Some(&Token::Uint(1.into())));
+		assert_eq!(params["amount"], Token::Uint(1.into()));
+		assert_eq!(params.get(""), None);
+		assert_eq!(params.get("missing"), None);
+		assert_eq!(params[1], Token::Bool(true));
+		assert_eq!(params.get_index(2), None);
+		assert_eq!(params.iter().map(|(name, _)| name).collect::<Vec<_>>(), vec!["amount", ""]);
+		assert_eq!(params.into_tokens(), vec![Token::Uint(1.into()), Token::Bool(true)]);
+	}
+}
@@ -8,17 +8,11 @@
 
 //! ABI decoder.
 
-use crate::{util::slice_data, Error, ParamType, Token, Word};
-
-struct DecodeResult {
-	token: Token,
-	new_offset: usize,
-}
-
-struct BytesTaken {
-	bytes: Vec<u8>,
-	new_offset: usize,
-}
+#[cfg(not(feature = "std"))]
+use crate::no_std_prelude::*;
+use crate::{encode, function::in_range, token::TokenRef, Address, Error, ParamType, Token, Word};
+use anyhow::anyhow;
+use core::{convert::TryInto, iter, slice};
 
 fn as_u32(slice: &Word) -> Result<u32, Error> {
 	if !slice[..28].iter().all(|x| *x == 0) {
@@ -41,190 +35,685 @@ fn as_bool(slice: &Word) -> Result<bool, Error> {
 
 /// Decodes ABI compliant vector of bytes into vector of tokens described by types param.
 pub fn decode(types: &[ParamType], data: &[u8]) -> Result<Vec<Token>, Error> {
-	let is_empty_bytes_valid_encoding = types.iter().all(|t| t.is_empty_bytes_valid_encoding());
-	if !is_empty_bytes_valid_encoding && data.is_empty() {
-		return Err(Error::InvalidName(
-			"please ensure the contract and method you're calling exist! \
-			 failed to decode empty bytes. if you're using jsonrpc this is \
-			 likely due to jsonrpc returning `0x` in case contract or method \
-			 don't exist"
-				.into(),
-		));
-	}
-	let slices = slice_data(data)?;
 	let mut tokens = Vec::with_capacity(types.len());
-	let mut offset = 0;
-	for param in types {
-		let res = decode_param(param, &slices, offset)?;
-		offset = res.new_offset;
-		tokens.push(res.token);
-	}
+	Decoder::new(types, data)?.decode_into(&mut tokens)?;
 	Ok(tokens)
 }
 
-fn peek(slices: &[Word], position: usize) -> Result<&Word, Error> {
-	slices.get(position).ok_or(Error::InvalidData)
+/// Decodes ABI compliant vector of bytes like [`decode`], but only accepts the canonical
+/// encoding of the tokens, the one of [`encode`].
+///
+/// Offsets must point right after the previous tail, padding and the high bits of integers
+/// narrower than 256 bits must be zero, and no data may follow the params. Errors tell the
+/// position of the first non canonical byte.
+pub fn decode_strict(types: &[ParamType], data: &[u8]) -> Result<Vec<Token>, Error> {
+	let tokens = decode(types, data)?;
+	if let Some(index) = types.iter().zip(&tokens).position(|(kind, token)| !in_range(kind, token)) {
+		return Err(anyhow!("param {} is out of range for `{}`", index, types[index]).into());
+	}
+
+	let canonical = encode(&tokens);
+	match canonical.iter().zip(data).position(|(expected, byte)| expected != byte) {
+		Some(position) => Err(anyhow!("non canonical encoding at byte {}", position).into()),
+		None if data.len() != canonical.len() => {
+			Err(anyhow!("unexpected data after the params at byte {}", canonical.len()).into())
+		}
+		None => Ok(tokens),
+	}
+}
+
+/// Decodes ABI compliant vector of bytes like [`decode`], but into tokens borrowing their
+/// bytes and strings from `data` instead of copying them.
+pub fn decode_borrowed<'a>(types: &'a [ParamType], data: &'a [u8]) -> Result<Vec<TokenRef<'a>>, Error> {
+	let mut decoder = Decoder::new(types, data)?;
+	iter::from_fn(|| decoder.decode_next()).collect()
 }
 
-fn take_bytes(slices: &[Word], position: usize, len: usize) -> Result<BytesTaken, Error> {
-	let slices_len = (len + 31) / 32;
+/// Decodes `data` holding a single dynamic array of type `kind`, e.g. `uint256[]`, lazily
+/// yielding its elements one at a time instead of collecting them.
+pub fn decode_iter<'a>(kind: &'a ParamType, data: &'a [u8]) -> Result<ArrayDecoder<'a>, Error> {
+	let element = match kind {
+		ParamType::Array(element) => element,
+		_ => return Err(anyhow!("`{}` is not a dynamic array", kind).into()),
+	};
 
-	let mut bytes_slices = Vec::with_capacity(slices_len);
-	for i in 0..slices_len {
-		let slice = peek(slices, position + i)?;
-		bytes_slices.push(slice);
+	let decoder = Decoder::new(&[], data)?;
+	let len_offset = (as_u32(decoder.word(0, 0)?)? / 32) as usize;
+	let len = as_u32(decoder.word(0, len_offset)?)? as usize;
+	Ok(ArrayDecoder { decoder, element, base: len_offset + 1, offset: 0, remaining: len })
+}
+
+/// Decodes ABI compliant vector of bytes like [`decode`], but tolerates a final word truncated by
+/// encoders dropping its trailing zero bytes. The final word is zero-extended to 32 bytes before
+/// decoding; [`decode`] rejects such data.
+pub fn decode_lenient(types: &[ParamType], data: &[u8]) -> Result<Vec<Token>, Error> {
+	match data.len() % 32 {
+		0 => decode(types, data),
+		rem => {
+			let mut padded = data.to_vec();
+			padded.resize(data.len() + 32 - rem, 0);
+			decode(types, &padded)
+		}
 	}
+}
 
-	let bytes = bytes_slices.into_iter().flat_map(|slice| slice.to_vec()).take(len).collect();
+/// Tokens the [`Decoder`] builds, owning their values or borrowing them from the data.
+trait Decoded<'a>: Sized {
+	fn address(word: &'a Word) -> Self;
+	fn int(word: &'a Word) -> Self;
+	fn uint(word: &'a Word) -> Self;
+	fn bool(b: bool) -> Self;
+	fn fixed_bytes(bytes: &'a [u8]) -> Self;
+	fn bytes(bytes: &'a [u8]) -> Self;
+	fn string(bytes: &'a [u8]) -> Result<Self, Error>;
+	fn fixed_array(tokens: Vec<Self>) -> Self;
+	fn array(tokens: Vec<Self>) -> Self;
+	fn tuple(tokens: Vec<Self>) -> Self;
+}
 
-	let taken = BytesTaken { bytes, new_offset: position + slices_len };
+impl<'a> Decoded<'a> for Token {
+	fn address(word: &'a Word) -> Self {
+		Token::Address(Address::from_slice(&word[12..]))
+	}
+
+	fn int(word: &'a Word) -> Self {
+		Token::Int((*word).into())
+	}
+
+	fn uint(word: &'a Word) -> Self {
+		Token::Uint((*word).into())
+	}
+
+	fn bool(b: bool) -> Self {
+		Token::Bool(b)
+	}
+
+	fn fixed_bytes(bytes: &'a [u8]) -> Self {
+		Token::FixedBytes(bytes.to_vec())
+	}
+
+	fn bytes(bytes: &'a [u8]) -> Self {
+		Token::Bytes(bytes.to_vec())
+	}
+
+	fn string(bytes: &'a [u8]) -> Result<Self, Error> {
+		Ok(Token::String(String::from_utf8(bytes.to_vec())?))
+	}
+
+	fn fixed_array(tokens: Vec<Self>) -> Self {
+		Token::FixedArray(tokens)
+	}
+
+	fn array(tokens: Vec<Self>) -> Self {
+		Token::Array(tokens)
+	}
 
-	Ok(taken)
+	fn tuple(tokens: Vec<Self>) -> Self {
+		Token::Tuple(tokens)
+	}
 }
 
-fn decode_param(param: &ParamType, slices: &[Word], offset: usize) -> Result<DecodeResult, Error> {
-	match *param {
-		ParamType::Address => {
-			let slice = peek(slices, offset)?;
-			let mut address = [0u8; 20];
-			address.copy_from_slice(&slice[12..]);
+impl<'a> Decoded<'a> for TokenRef<'a> {
+	fn address(word: &'a Word) -> Self {
+		TokenRef::Address(Address::from_slice(&word[12..]))
+	}
 
-			let result = DecodeResult { token: Token::Address(address.into()), new_offset: offset + 1 };
+	fn int(word: &'a Word) -> Self {
+		TokenRef::Int((*word).into())
+	}
 
-			Ok(result)
+	fn uint(word: &'a Word) -> Self {
+		TokenRef::Uint((*word).into())
+	}
+
+	fn bool(b: bool) -> Self {
+		TokenRef::Bool(b)
+	}
+
+	fn fixed_bytes(bytes: &'a [u8]) -> Self {
+		TokenRef::FixedBytes(bytes)
+	}
+
+	fn bytes(bytes: &'a [u8]) -> Self {
+		TokenRef::Bytes(bytes)
+	}
+
+	fn string(bytes: &'a [u8]) -> Result<Self, Error> {
+		match core::str::from_utf8(bytes) {
+			Ok(string) => Ok(TokenRef::String(string)),
+			// only the owned error converts into `Error::Utf8`
+			Err(_) => Err(String::from_utf8(bytes.to_vec()).expect_err("invalid utf8; qed").into()),
 		}
-		ParamType::Int(_) => {
-			let slice = peek(slices, offset)?;
+	}
 
-			let result = DecodeResult { token: Token::Int(slice.clone().into()), new_offset: offset + 1 };
+	fn fixed_array(tokens: Vec<Self>) -> Self {
+		TokenRef::FixedArray(tokens)
+	}
 
-			Ok(result)
+	fn array(tokens: Vec<Self>) -> Self {
+		TokenRef::Array(tokens)
+	}
+
+	fn tuple(tokens: Vec<Self>) -> Self {
+		TokenRef::Tuple(tokens)
+	}
+}
+
+/// Decoder yielding the params of ABI encoded data one at a time.
+///
+/// Heads and tails are read in place from the encoded bytes, and the bookkeeping of the tails
+/// of nested params shares a single buffer, so the only allocations are the ones of the tokens
+/// themselves. [`Decoder::decode_into`] appends the tokens to a buffer which can be reused
+/// across calls. The decoder stops at the first error.
+pub struct Decoder<'a> {
+	types: slice::Iter<'a, ParamType>,
+	data: &'a [u8],
+	/// Word offset of the head of the next param.
+	offset: usize,
+	/// Word ranges of the tails decoded so far, those of each nesting level sorted and
+	/// stacked on the ones of the enclosing levels.
+	tails: Vec<(usize, usize)>,
+}
+
+impl<'a> Decoder<'a> {
+	/// Creates a decoder of `data` as params of `types`.
+	pub fn new(types: &'a [ParamType], data: &'a [u8]) -> Result<Self, Error> {
+		let is_empty_bytes_valid_encoding = types.iter().all(|t| t.is_empty_bytes_valid_encoding());
+		if !is_empty_bytes_valid_encoding && data.is_empty() {
+			// jsonrpc returns `0x` for calls to missing contracts or methods
+			return Err(Error::EmptyData);
+		}
+		if !data.len().is_multiple_of(32) {
+			return Err(Error::InvalidDataLength(data.len()));
 		}
-		ParamType::Uint(_) => {
-			let slice = peek(slices, offset)?;
 
-			let result = DecodeResult { token: Token::Uint(slice.clone().into()), new_offset: offset + 1 };
+		Ok(Decoder { types: types.iter(), data, offset: 0, tails: Vec::new() })
+	}
 
-			Ok(result)
+	/// Decodes the remaining params, appending their tokens to `tokens`.
+	pub fn decode_into(self, tokens: &mut Vec<Token>) -> Result<(), Error> {
+		tokens.reserve(self.types.len());
+		for token in self {
+			tokens.push(token?);
 		}
-		ParamType::Bool => {
-			let slice = peek(slices, offset)?;
+		Ok(())
+	}
 
-			let b = as_bool(slice)?;
+	/// Decodes the next param, if any, stopping at the first error.
+	fn decode_next<T: Decoded<'a>>(&mut self) -> Option<Result<T, Error>> {
+		let param = self.types.next()?;
+		let decoded = self.decode_tracked(param, 0, self.offset, 0);
+		Some(match decoded {
+			Ok((token, new_offset, _)) => {
+				self.offset = new_offset;
+				Ok(token)
+			}
+			Err(err) => {
+				self.types = [].iter();
+				Err(err)
+			}
+		})
+	}
 
-			let result = DecodeResult { token: Token::Bool(b), new_offset: offset + 1 };
-			Ok(result)
+	fn word(&self, base: usize, position: usize) -> Result<&'a Word, Error> {
+		// offsets overflowing are past the end of any data
+		let start = base.saturating_add(position).saturating_mul(32);
+		let word = self
+			.data
+			.get(start..start.saturating_add(32))
+			.ok_or(Error::UnexpectedEnd { offset: start, len: self.data.len() })?;
+		Ok(word.try_into().expect("slice of 32 bytes; qed"))
+	}
+
+	/// Takes `len` bytes from the words starting at `position`, returning them along with the
+	/// number of those words.
+	fn take_bytes(&self, base: usize, position: usize, len: usize) -> Result<(&'a [u8], usize), Error> {
+		let words = len.div_ceil(32);
+		if words > 0 {
+			self.word(base, position + words - 1)?;
 		}
-		ParamType::FixedBytes(len) => {
-			// FixedBytes is anything from bytes1 to bytes32. These values
-			// are padded with trailing zeros to fill 32 bytes.
-			let taken = take_bytes(slices, offset, len)?;
-			let result = DecodeResult { token: Token::FixedBytes(taken.bytes), new_offset: taken.new_offset };
-			Ok(result)
+
+		let start = (base + position) * 32;
+		Ok((&self.data[start..start + len], words))
+	}
+
+	/// Decodes the consecutive params of `types` whose heads start at `offset` of the region
+	/// starting at word `base`.
+	///
+	/// Returns the tokens, the offset following the last head and the size in words of the
+	/// params, heads and tails.
+	fn decode_params<'t, T, I>(
+		&mut self,
+		types: I,
+		base: usize,
+		mut offset: usize,
+	) -> Result<(Vec<T>, usize, usize), Error>
+	where
+		T: Decoded<'a>,
+		I: ExactSizeIterator<Item = &'t ParamType>,
+	{
+		let level = self.tails.len();
+		let start = offset;
+		let mut tails_size = 0;
+		// lengths of arrays come from the data, they only bound the capacity as far as it goes
+		let mut tokens = Vec::with_capacity(types.len().min(self.data.len() / 32));
+		for param in types {
+			let (token, new_offset, tail_size) = self.decode_tracked(param, base, offset, level)?;
+			offset = new_offset;
+			tails_size += tail_size;
+			tokens.push(token);
 		}
-		ParamType::Bytes => {
-			let offset_slice = peek(slices, offset)?;
-			let len_offset = (as_u32(offset_slice)? / 32) as usize;
+		self.tails.truncate(level);
+		Ok((tokens, offset, offset - start + tails_size))
+	}
 
-			let len_slice = peek(slices, len_offset)?;
-			let len = as_u32(len_slice)? as usize;
+	/// Decodes a param like [`Decoder::decode_param`], recording its tail among the tails of
+	/// its nesting level, which starts at index `level` of the stack.
+	///
+	/// Tails of dynamic params must not overlap, otherwise the same bytes would be decoded
+	/// as several different values.
+	fn decode_tracked<T: Decoded<'a>>(
+		&mut self,
+		param: &ParamType,
+		base: usize,
+		offset: usize,
+		level: usize,
+	) -> Result<(T, usize, usize), Error> {
+		let tail_start = match param.is_dynamic() {
+			true => Some(as_u32(self.word(base, offset)?)? as usize / 32),
+			false => None,
+		};
 
-			let taken = take_bytes(slices, len_offset + 1, len)?;
+		let (token, new_offset, tail_size) = self.decode_param(param, base, offset)?;
+		if let Some(start) = tail_start {
+			if tail_size > 0 {
+				let tail = (start, start + tail_size);
+				let tails = &self.tails[level..];
+				let position = tails.partition_point(|other| *other <= tail);
+				let overlaps_previous = position > 0 && tail.0 < tails[position - 1].1;
+				let overlaps_next = position < tails.len() && tails[position].0 < tail.1;
+				if overlaps_previous || overlaps_next {
+					return Err(Error::InvalidData);
+				}
+				self.tails.insert(level + position, tail);
+			}
+		}
 
-			let result = DecodeResult { token: Token::Bytes(taken.bytes), new_offset: offset + 1 };
-			Ok(result)
+		Ok((token, new_offset, tail_size))
+	}
+
+	/// Decodes the param whose head is at `offset` of the region starting at word `base`.
+	///
+	/// Returns its token, the offset following its head and the size in words of its tail,
+	/// which is zero for static params.
+	fn decode_param<T: Decoded<'a>>(
+		&mut self,
+		param: &ParamType,
+		base: usize,
+		offset: usize,
+	) -> Result<(T, usize, usize), Error> {
+		match *param {
+			ParamType::Address => Ok((T::address(self.word(base, offset)?), offset + 1, 0)),
+			ParamType::Int(_) | ParamType::Fixed(..) => Ok((T::int(self.word(base, offset)?), offset + 1, 0)),
+			ParamType::Uint(_) | ParamType::Ufixed(..) => Ok((T::uint(self.word(base, offset)?), offset + 1, 0)),
+			ParamType::Bool => Ok((T::bool(as_bool(self.word(base, offset)?)?), offset + 1, 0)),
+			ParamType::FixedBytes(len) => {
+				// FixedBytes is anything from bytes1 to bytes32. These values
+				// are padded with trailing zeros to fill 32 bytes.
+				let (bytes, words) = self.take_bytes(base, offset, len)?;
+				Ok((T::fixed_bytes(bytes), offset + words, 0))
+			}
+			ParamType::Function => {
+				let (bytes, words) = self.take_bytes(base, offset, 24)?;
+				Ok((T::fixed_bytes(bytes), offset + words, 0))
+			}
+			ParamType::Bytes => {
+				let len_offset = (as_u32(self.word(base, offset)?)? / 32) as usize;
+				let len = as_u32(self.word(base, len_offset)?)? as usize;
+				let (bytes, words) = self.take_bytes(base, len_offset + 1, len)?;
+				Ok((T::bytes(bytes), offset + 1, 1 + words))
+			}
+			ParamType::String => {
+				let len_offset = (as_u32(self.word(base, offset)?)? / 32) as usize;
+				let len = as_u32(self.word(base, len_offset)?)? as usize;
+				let (bytes, words) = self.take_bytes(base, len_offset + 1, len)?;
+				Ok((T::string(bytes)?, offset + 1, 1 + words))
+			}
+			ParamType::Array(ref t) => {
+				let len_offset = (as_u32(self.word(base, offset)?)? / 32) as usize;
+				let len = as_u32(self.word(base, len_offset)?)? as usize;
+				let (tokens, _, size) = self.decode_params(iter::repeat_n(&**t, len), base + len_offset + 1, 0)?;
+				Ok((T::array(tokens), offset + 1, 1 + size))
+			}
+			ParamType::FixedArray(ref t, len) => match param.is_dynamic() {
+				true => {
+					let tail = base + as_u32(self.word(base, offset)?)? as usize / 32;
+					let (tokens, _, size) = self.decode_params(iter::repeat_n(&**t, len), tail, 0)?;
+					Ok((T::fixed_array(tokens), offset + 1, size))
+				}
+				false => {
+					let (tokens, new_offset, _) = self.decode_params(iter::repeat_n(&**t, len), base, offset)?;
+					Ok((T::fixed_array(tokens), new_offset, 0))
+				}
+			},
+			// The head of a dynamic tuple is an offset to the tuple's data, which follows
+			// the head right away for a static tuple.
+			ParamType::Tuple(ref t) => match param.is_dynamic() {
+				true => {
+					let tail = base + as_u32(self.word(base, offset)?)? as usize / 32;
+					let (tokens, _, size) = self.decode_params(t.iter(), tail, 0)?;
+					Ok((T::tuple(tokens), offset + 1, size))
+				}
+				false => {
+					let (tokens, new_offset, _) = self.decode_params(t.iter(), base, offset)?;
+					Ok((T::tuple(tokens), new_offset, 0))
+				}
+			},
 		}
-		ParamType::String => {
-			let offset_slice = peek(slices, offset)?;
-			let len_offset = (as_u32(offset_slice)? / 32) as usize;
+	}
+}
 
-			let len_slice = peek(slices, len_offset)?;
-			let len = as_u32(len_slice)? as usize;
+impl Iterator for Decoder<'_> {
+	type Item = Result<Token, Error>;
 
-			let taken = take_bytes(slices, len_offset + 1, len)?;
+	fn next(&mut self) -> Option<Self::Item> {
+		self.decode_next()
+	}
 
-			let result = DecodeResult { token: Token::String(String::from_utf8(taken.bytes)?), new_offset: offset + 1 };
-			Ok(result)
+	fn size_hint(&self) -> (usize, Option<usize>) {
+		(0, Some(self.types.len()))
+	}
+}
+
+/// Iterator over the elements of an encoded dynamic array, see [`decode_iter`].
+///
+/// Static elements are decoded in constant memory, dynamic ones still record the bounds of
+/// their tails to reject overlapping ones. The iterator stops at the first error.
+pub struct ArrayDecoder<'a> {
+	decoder: Decoder<'a>,
+	element: &'a ParamType,
+	/// Word offset of the elements.
+	base: usize,
+	/// Word offset of the head of the next element, from `base`.
+	offset: usize,
+	remaining: usize,
+}
+
+impl Iterator for ArrayDecoder<'_> {
+	type Item = Result<Token, Error>;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		if self.remaining == 0 {
+			return None;
 		}
-		ParamType::Array(ref t) => {
-			let offset_slice = peek(slices, offset)?;
-			let len_offset = (as_u32(offset_slice)? / 32) as usize;
-			let len_slice = peek(slices, len_offset)?;
-			let len = as_u32(len_slice)? as usize;
-
-			let tail = &slices[len_offset + 1..];
-			let mut tokens = Vec::with_capacity(len);
-			let mut new_offset = 0;
-
-			for _ in 0..len {
-				let res = decode_param(t, &tail, new_offset)?;
-				new_offset = res.new_offset;
-				tokens.push(res.token);
+
+		self.remaining -= 1;
+		Some(match self.decoder.decode_tracked(self.element, self.base, self.offset, 0) {
+			Ok((token, new_offset, _)) => {
+				self.offset = new_offset;
+				Ok(token)
 			}
+			Err(err) => {
+				self.remaining = 0;
+				Err(err)
+			}
+		})
+	}
 
-			let result = DecodeResult { token: Token::Array(tokens), new_offset: offset + 1 };
+	fn size_hint(&self) -> (usize, Option<usize>) {
+		(0, Some(self.remaining))
+	}
+}
 
-			Ok(result)
+#[cfg(test)]
+mod tests {
+	use crate::{
+		decode, decode_borrowed, decode_iter, decode_lenient, decode_strict, encode, Address, Decoder, Error,
+		ParamType, Token, TokenRef, Uint,
+	};
+	use hex_literal::hex;
+
+	/// The decoder as it was before [`crate::decoder::Decoder`], working on a copy of the data
+	/// split into words, to check the decoder against. Offsets of tuples and fixed arrays past
+	/// the end of the data are errors instead of panics.
+	mod reference {
+		use crate::{encoder::tail_size, Error, ParamType, Token, Word};
+		use std::convert::TryInto;
+
+		struct DecodeResult {
+			token: Token,
+			new_offset: usize,
 		}
-		ParamType::FixedArray(ref t, len) => {
-			let mut tokens = Vec::with_capacity(len);
-			let is_dynamic = param.is_dynamic();
-
-			let (tail, mut new_offset) = if is_dynamic {
-				(&slices[(as_u32(peek(slices, offset)?)? as usize / 32)..], 0)
-			} else {
-				(slices, offset)
-			};
 
-			for _ in 0..len {
-				let res = decode_param(t, &tail, new_offset)?;
-				new_offset = res.new_offset;
-				tokens.push(res.token);
+		struct BytesTaken {
+			bytes: Vec<u8>,
+			new_offset: usize,
+		}
+
+		fn as_u32(slice: &Word) -> Result<u32, Error> {
+			if !slice[..28].iter().all(|x| *x == 0) {
+				return Err(Error::InvalidData);
 			}
 
-			let result = DecodeResult {
-				token: Token::FixedArray(tokens),
-				new_offset: if is_dynamic { offset + 1 } else { new_offset },
-			};
+			let result = ((slice[28] as u32) << 24)
+				+ ((slice[29] as u32) << 16)
+				+ ((slice[30] as u32) << 8)
+				+ (slice[31] as u32);
 
 			Ok(result)
 		}
-		ParamType::Tuple(ref t) => {
-			let is_dynamic = param.is_dynamic();
-
-			// The first element in a dynamic Tuple is an offset to the Tuple's data
-			// For a static Tuple the data begins right away
-			let (tail, mut new_offset) = if is_dynamic {
-				(&slices[(as_u32(peek(slices, offset)?)? as usize / 32)..], 0)
-			} else {
-				(slices, offset)
-			};
 
-			let len = t.len();
-			let mut tokens = Vec::with_capacity(len);
-			for param in t {
-				let res = decode_param(param, &tail, new_offset)?;
-				new_offset = res.new_offset;
+		fn as_bool(slice: &Word) -> Result<bool, Error> {
+			if !slice[..31].iter().all(|x| *x == 0) {
+				return Err(Error::InvalidData);
+			}
+
+			Ok(slice[31] == 1)
+		}
+
+		pub(super) fn decode(types: &[ParamType], data: &[u8]) -> Result<Vec<Token>, Error> {
+			let is_empty_bytes_valid_encoding = types.iter().all(|t| t.is_empty_bytes_valid_encoding());
+			if !is_empty_bytes_valid_encoding && data.is_empty() {
+				return Err(Error::InvalidName(
+					"please ensure the contract and method you're calling exist! \
+					 failed to decode empty bytes. if you're using jsonrpc this is \
+					 likely due to jsonrpc returning `0x` in case contract or method \
+					 don't exist"
+						.into(),
+				));
+			}
+			if !data.len().is_multiple_of(32) {
+				return Err(Error::InvalidData);
+			}
+			let slices: Vec<Word> = data.chunks(32).map(|chunk| chunk.try_into().unwrap()).collect();
+			let (tokens, _) = decode_params(types, &slices, 0)?;
+			Ok(tokens)
+		}
+
+		/// Decodes consecutive params whose heads start at `offset`, returning the tokens and the
+		/// offset following the last head.
+		///
+		/// Tails of dynamic params must not overlap, otherwise the same bytes would be decoded
+		/// as several different values.
+		fn decode_params<'a, I>(types: I, slices: &[Word], mut offset: usize) -> Result<(Vec<Token>, usize), Error>
+		where
+			I: IntoIterator<Item = &'a ParamType>,
+		{
+			let mut tokens = vec![];
+			let mut tails = vec![];
+			for param in types {
+				let tail_start = match param.is_dynamic() {
+					true => Some(as_u32(peek(slices, offset)?)? as usize / 32),
+					false => None,
+				};
+
+				let res = decode_param(param, slices, offset)?;
+				if let Some(start) = tail_start {
+					let len = tail_size(&res.token) / 32;
+					if len > 0 {
+						tails.push((start, start + len));
+					}
+				}
+
+				offset = res.new_offset;
 				tokens.push(res.token);
 			}
 
-			// The returned new_offset depends on whether the Tuple is dynamic
-			// dynamic Tuple -> follows the prefixed Tuple data offset element
-			// static Tuple  -> follows the last data element
-			let result = DecodeResult {
-				token: Token::Tuple(tokens),
-				new_offset: if is_dynamic { offset + 1 } else { new_offset },
-			};
+			tails.sort_unstable();
+			if tails.windows(2).any(|pair| pair[1].0 < pair[0].1) {
+				return Err(Error::InvalidData);
+			}
 
-			Ok(result)
+			Ok((tokens, offset))
 		}
-	}
-}
 
-#[cfg(test)]
-mod tests {
-	use crate::{decode, ParamType, Token};
-	use hex_literal::hex;
+		fn peek(slices: &[Word], position: usize) -> Result<&Word, Error> {
+			slices.get(position).ok_or(Error::InvalidData)
+		}
+
+		fn take_bytes(slices: &[Word], position: usize, len: usize) -> Result<BytesTaken, Error> {
+			let slices_len = len.div_ceil(32);
+
+			let mut bytes_slices = Vec::with_capacity(slices_len);
+			for i in 0..slices_len {
+				let slice = peek(slices, position + i)?;
+				bytes_slices.push(slice);
+			}
+
+			let bytes = bytes_slices.into_iter().flat_map(|slice| slice.to_vec()).take(len).collect();
+
+			let taken = BytesTaken { bytes, new_offset: position + slices_len };
+
+			Ok(taken)
+		}
+
+		fn decode_param(param: &ParamType, slices: &[Word], offset: usize) -> Result<DecodeResult, Error> {
+			match *param {
+				ParamType::Address => {
+					let slice = peek(slices, offset)?;
+					let mut address = [0u8; 20];
+					address.copy_from_slice(&slice[12..]);
+
+					let result = DecodeResult { token: Token::Address(address.into()), new_offset: offset + 1 };
+
+					Ok(result)
+				}
+				ParamType::Int(_) | ParamType::Fixed(..) => {
+					let slice = peek(slices, offset)?;
+
+					let result = DecodeResult { token: Token::Int((*slice).into()), new_offset: offset + 1 };
+
+					Ok(result)
+				}
+				ParamType::Uint(_) | ParamType::Ufixed(..) => {
+					let slice = peek(slices, offset)?;
+
+					let result = DecodeResult { token: Token::Uint((*slice).into()), new_offset: offset + 1 };
+
+					Ok(result)
+				}
+				ParamType::Bool => {
+					let slice = peek(slices, offset)?;
+
+					let b = as_bool(slice)?;
+
+					let result = DecodeResult { token: Token::Bool(b), new_offset: offset + 1 };
+					Ok(result)
+				}
+				ParamType::FixedBytes(len) => {
+					// FixedBytes is anything from bytes1 to bytes32. These values
+					// are padded with trailing zeros to fill 32 bytes.
+					let taken = take_bytes(slices, offset, len)?;
+					let result = DecodeResult { token: Token::FixedBytes(taken.bytes), new_offset: taken.new_offset };
+					Ok(result)
+				}
+				ParamType::Function => {
+					let taken = take_bytes(slices, offset, 24)?;
+					let result = DecodeResult { token: Token::FixedBytes(taken.bytes), new_offset: taken.new_offset };
+					Ok(result)
+				}
+				ParamType::Bytes => {
+					let offset_slice = peek(slices, offset)?;
+					let len_offset = (as_u32(offset_slice)? / 32) as usize;
+
+					let len_slice = peek(slices, len_offset)?;
+					let len = as_u32(len_slice)? as usize;
+
+					let taken = take_bytes(slices, len_offset + 1, len)?;
+
+					let result = DecodeResult { token: Token::Bytes(taken.bytes), new_offset: offset + 1 };
+					Ok(result)
+				}
+				ParamType::String => {
+					let offset_slice = peek(slices, offset)?;
+					let len_offset = (as_u32(offset_slice)? / 32) as usize;
+
+					let len_slice = peek(slices, len_offset)?;
+					let len = as_u32(len_slice)? as usize;
+
+					let taken = take_bytes(slices, len_offset + 1, len)?;
+
+					let result =
+						DecodeResult { token: Token::String(String::from_utf8(taken.bytes)?), new_offset: offset + 1 };
+					Ok(result)
+				}
+				ParamType::Array(ref t) => {
+					let offset_slice = peek(slices, offset)?;
+					let len_offset = (as_u32(offset_slice)? / 32) as usize;
+					let len_slice = peek(slices, len_offset)?;
+					let len = as_u32(len_slice)? as usize;
+
+					let tail = &slices[len_offset + 1..];
+					let (tokens, _) = decode_params((0..len).map(|_| &**t), tail, 0)?;
+
+					let result = DecodeResult { token: Token::Array(tokens), new_offset: offset + 1 };
+
+					Ok(result)
+				}
+				ParamType::FixedArray(ref t, len) => {
+					let is_dynamic = param.is_dynamic();
+
+					let (tail, new_offset) = if is_dynamic {
+						(slices.get(as_u32(peek(slices, offset)?)? as usize / 32..).ok_or(Error::InvalidData)?, 0)
+					} else {
+						(slices, offset)
+					};
+
+					let (tokens, new_offset) = decode_params((0..len).map(|_| &**t), tail, new_offset)?;
+
+					let result = DecodeResult {
+						token: Token::FixedArray(tokens),
+						new_offset: if is_dynamic { offset + 1 } else { new_offset },
+					};
+
+					Ok(result)
+				}
+				ParamType::Tuple(ref t) => {
+					let is_dynamic = param.is_dynamic();
+
+					// The first element in a dynamic Tuple is an offset to the Tuple's data
+					// For a static Tuple the data begins right away
+					let (tail, new_offset) = if is_dynamic {
+						(slices.get(as_u32(peek(slices, offset)?)? as usize / 32..).ok_or(Error::InvalidData)?, 0)
+					} else {
+						(slices, offset)
+					};
+
+					let (tokens, new_offset) = decode_params(t, tail, new_offset)?;
+
+					// The returned new_offset depends on whether the Tuple is dynamic
+					// dynamic Tuple -> follows the prefixed Tuple data offset element
+					// static Tuple  -> follows the last data element
+					let result = DecodeResult {
+						token: Token::Tuple(tokens),
+						new_offset: if is_dynamic { offset + 1 } else { new_offset },
+					};
+
+					Ok(result)
+				}
+			}
+		}
+	}
 
 	#[test]
 	fn decode_from_empty_byte_slice() {
@@ -246,6 +735,21 @@ mod tests {
 		assert!(decode(&[ParamType::FixedArray(Box::new(ParamType::Bool), 0)], &[]).is_ok());
 	}
 
+	#[test]
+	fn decode_errors_have_positions() {
+		assert!(matches!(decode(&[ParamType::Bool], &[]), Err(Error::EmptyData)));
+		assert!(matches!(decode(&[ParamType::Bool], &[0; 33]), Err(Error::InvalidDataLength(33))));
+		assert!(matches!(
+			decode(&[ParamType::Bool, ParamType::Bool], &[0; 32]),
+			Err(Error::UnexpectedEnd { offset: 32, len: 32 })
+		));
+
+		// the length of the string is read past the end through its offset
+		let mut encoded = [0u8; 32];
+		encoded[31] = 0x40;
+		assert!(matches!(decode(&[ParamType::String], &encoded), Err(Error::UnexpectedEnd { offset: 64, len: 32 })));
+	}
+
 	#[test]
 	fn decode_static_tuple_of_addresses_and_uints() {
 		let encoded = hex!(
@@ -503,4 +1007,292 @@ mod tests {
 			]
 		);
 	}
+
+	#[test]
+	fn decode_overlapping_tails() {
+		// both `bytes` point to the same tail
+		let encoded = hex!(
+			"
+			0000000000000000000000000000000000000000000000000000000000000040
+			0000000000000000000000000000000000000000000000000000000000000040
+			0000000000000000000000000000000000000000000000000000000000000002
+			1234000000000000000000000000000000000000000000000000000000000000
+		"
+		);
+		assert!(decode(&[ParamType::Bytes, ParamType::Bytes], &encoded).is_err());
+
+		// the second element of `string[]` starts inside the first one
+		let encoded = hex!(
+			"
+			0000000000000000000000000000000000000000000000000000000000000020
+			0000000000000000000000000000000000000000000000000000000000000002
+			0000000000000000000000000000000000000000000000000000000000000040
+			0000000000000000000000000000000000000000000000000000000000000060
+			0000000000000000000000000000000000000000000000000000000000000020
+			0000000000000000000000000000000000000000000000000000000000000001
+			6100000000000000000000000000000000000000000000000000000000000000
+		"
+		);
+		assert!(decode(&[ParamType::Array(Box::new(ParamType::String))], &encoded).is_err());
+	}
+
+	#[test]
+	fn decode_arrays_of_bools() {
+		let encoded = hex!(
+			"
+			0000000000000000000000000000000000000000000000000000000000000000
+			0000000000000000000000000000000000000000000000000000000000000001
+			0000000000000000000000000000000000000000000000000000000000000080
+			0000000000000000000000000000000000000000000000000000000000000001
+			0000000000000000000000000000000000000000000000000000000000000002
+			0000000000000000000000000000000000000000000000000000000000000001
+			0000000000000000000000000000000000000000000000000000000000000000
+		"
+		);
+		let decoded = decode(
+			&[
+				ParamType::FixedArray(Box::new(ParamType::Bool), 2),
+				ParamType::Array(Box::new(ParamType::Bool)),
+				ParamType::Bool,
+			],
+			&encoded,
+		)
+		.unwrap();
+		assert_eq!(
+			decoded,
+			vec![
+				Token::FixedArray(vec![Token::Bool(false), Token::Bool(true)]),
+				Token::Array(vec![Token::Bool(true), Token::Bool(false)]),
+				Token::Bool(true),
+			]
+		);
+	}
+
+	#[test]
+	fn decode_truncated_final_word() {
+		// the final word is `0x10000` with its two trailing zero bytes dropped
+		let encoded = hex!(
+			"
+			0000000000000000000000001111111111111111111111111111111111111111
+			000000000000000000000000000000000000000000000000000000000001
+		"
+		);
+		let types = [ParamType::Address, ParamType::Uint(256)];
+
+		assert!(decode(&types, &encoded).is_err());
+		assert_eq!(
+			decode_lenient(&types, &encoded).unwrap(),
+			vec![Token::Address([0x11u8; 20].into()), Token::Uint(0x10000.into())]
+		);
+	}
+
+	#[test]
+	fn decoder_yields_tokens_one_at_a_time() {
+		let types = [ParamType::Uint(256), ParamType::String, ParamType::Bool];
+		let tokens = vec![Token::Uint(7.into()), Token::String("gavofyork".into()), Token::Bool(true)];
+		let encoded = encode(&tokens);
+
+		let mut decoder = Decoder::new(&types, &encoded).unwrap();
+		assert_eq!(decoder.next().unwrap().unwrap(), tokens[0]);
+		assert_eq!(decoder.next().unwrap().unwrap(), tokens[1]);
+		assert_eq!(decoder.next().unwrap().unwrap(), tokens[2]);
+		assert!(decoder.next().is_none());
+
+		let mut buffer = vec![Token::Bool(false)];
+		Decoder::new(&types, &encoded).unwrap().decode_into(&mut buffer).unwrap();
+		assert_eq!(buffer[1..], tokens[..]);
+
+		// the first word of the string tail is its length, not a bool
+		let mut decoder = Decoder::new(&types[..2], &encoded[..96]).unwrap();
+		assert!(decoder.next().unwrap().is_ok());
+		assert!(decoder.next().unwrap().is_err());
+		assert!(decoder.next().is_none());
+	}
+
+	#[test]
+	fn decode_strict_rejects_non_canonical_data() {
+		let types = [ParamType::Uint(8), ParamType::Bytes, ParamType::Address];
+		let tokens = vec![Token::Uint(7.into()), Token::Bytes(vec![0x11; 3]), Token::Address([0x22; 20].into())];
+		let encoded = encode(&tokens);
+		assert_eq!(decode_strict(&types, &encoded).unwrap(), tokens);
+
+		let error = |data: &[u8]| decode_strict(&types, data).unwrap_err().to_string();
+
+		let mut trailing = encoded.clone();
+		trailing.extend_from_slice(&[0u8; 32]);
+		assert_eq!(decode(&types, &trailing).unwrap(), tokens);
+		assert_eq!(error(&trailing), "unexpected data after the params at byte 160");
+
+		let mut out_of_range = encoded.clone();
+		out_of_range[30] = 1;
+		assert_eq!(error(&out_of_range), "param 0 is out of range for `uint8`");
+
+		let mut dirty_padding = encoded.clone();
+		dirty_padding[159] = 1;
+		assert_eq!(error(&dirty_padding), "non canonical encoding at byte 159");
+
+		let mut dirty_address = encoded.clone();
+		dirty_address[64] = 1;
+		assert_eq!(error(&dirty_address), "non canonical encoding at byte 64");
+
+		// the bytes moved one word further, after a gap
+		let mut gap = encoded[..96].to_vec();
+		gap[63] = 0x80;
+		gap.extend_from_slice(&[0u8; 32]);
+		gap.extend_from_slice(&encoded[96..]);
+		assert_eq!(decode(&types, &gap).unwrap(), tokens);
+		assert_eq!(error(&gap), "non canonical encoding at byte 63");
+	}
+
+	#[test]
+	fn decode_iter_yields_elements() {
+		let elements = (0..100u64).map(|i| Token::Uint(i.into())).collect::<Vec<_>>();
+		let encoded = encode(&[Token::Array(elements.clone())]);
+		let kind = ParamType::Array(Box::new(ParamType::Uint(256)));
+		assert_eq!(decode_iter(&kind, &encoded).unwrap().collect::<Result<Vec<_>, _>>().unwrap(), elements);
+
+		let strings = vec![Token::String("a".into()), Token::String("gavofyork".into())];
+		let kind = ParamType::Array(Box::new(ParamType::String));
+		let encoded = encode(&[Token::Array(strings.clone())]);
+		assert_eq!(decode_iter(&kind, &encoded).unwrap().collect::<Result<Vec<_>, _>>().unwrap(), strings);
+
+		// truncated after the first element, which is still yielded
+		let mut decoded = decode_iter(&kind, &encoded[..encoded.len() - 64]).unwrap();
+		assert_eq!(decoded.next().unwrap().unwrap(), strings[0]);
+		assert!(decoded.next().unwrap().is_err());
+		assert!(decoded.next().is_none());
+
+		assert!(decode_iter(&ParamType::Uint(256), &encoded).is_err());
+	}
+
+	#[test]
+	fn decode_borrowed_points_into_data() {
+		let types = [ParamType::Array(Box::new(ParamType::Bytes)), ParamType::String];
+		let tokens = vec![
+			Token::Array(vec![Token::Bytes(vec![0x11; 40]), Token::Bytes(vec![])]),
+			Token::String("gavofyork".into()),
+		];
+		let encoded = encode(&tokens);
+
+		let decoded = decode_borrowed(&types, &encoded).unwrap();
+		let data = encoded.as_ptr_range();
+		match decoded[..] {
+			[TokenRef::Array(ref elements), TokenRef::String(string)] => {
+				assert!(data.contains(&string.as_ptr()));
+				match elements[..] {
+					[TokenRef::Bytes(first), TokenRef::Bytes(second)] => {
+						assert!(data.contains(&first.as_ptr()));
+						assert_eq!((first, second), (&[0x11u8; 40][..], &[][..]));
+					}
+					_ => panic!("unexpected elements {:?}", elements),
+				}
+			}
+			_ => panic!("unexpected tokens {:?}", decoded),
+		}
+		assert_eq!(decoded.iter().map(TokenRef::to_token).collect::<Vec<_>>(), tokens);
+
+		let mut invalid = encoded;
+		let string_start = invalid.len() - 32;
+		invalid[string_start] = 0xff;
+		assert!(matches!(decode_borrowed(&types, &invalid), Err(crate::Error::Utf8(_))));
+	}
+
+	/// Xorshift generator, so that the differential test is reproducible.
+	struct Rng(u64);
+
+	impl Rng {
+		fn below(&mut self, n: usize) -> usize {
+			self.0 ^= self.0 << 13;
+			self.0 ^= self.0 >> 7;
+			self.0 ^= self.0 << 17;
+			(self.0 % n as u64) as usize
+		}
+
+		fn bytes(&mut self, len: usize) -> Vec<u8> {
+			(0..len).map(|_| self.below(256) as u8).collect()
+		}
+
+		fn param_type(&mut self, depth: usize) -> ParamType {
+			let kinds = if depth == 0 { 6 } else { 9 };
+			match self.below(kinds) {
+				0 => ParamType::Address,
+				1 => ParamType::Uint(256),
+				2 => ParamType::Bool,
+				3 => ParamType::FixedBytes(1 + self.below(32)),
+				4 => ParamType::Bytes,
+				5 => ParamType::String,
+				6 => ParamType::Array(Box::new(self.param_type(depth - 1))),
+				7 => ParamType::FixedArray(Box::new(self.param_type(depth - 1)), 1 + self.below(3)),
+				_ => ParamType::Tuple((0..1 + self.below(3)).map(|_| self.param_type(depth - 1)).collect()),
+			}
+		}
+
+		fn token(&mut self, kind: &ParamType) -> Token {
+			match kind {
+				ParamType::Address => Token::Address(Address::from_slice(&self.bytes(20))),
+				ParamType::Uint(_) => Token::Uint(Uint::from_big_endian(&self.bytes(32))),
+				ParamType::Bool => Token::Bool(self.below(2) == 0),
+				ParamType::FixedBytes(len) => Token::FixedBytes(self.bytes(*len)),
+				ParamType::Bytes => {
+					let len = self.below(70);
+					Token::Bytes(self.bytes(len))
+				}
+				ParamType::String => Token::String((0..self.below(70)).map(|_| 'a').collect()),
+				ParamType::Array(kind) => Token::Array((0..self.below(4)).map(|_| self.token(kind)).collect()),
+				ParamType::FixedArray(kind, len) => Token::FixedArray((0..*len).map(|_| self.token(kind)).collect()),
+				ParamType::Tuple(kinds) => Token::Tuple(kinds.iter().map(|kind| self.token(kind)).collect()),
+				_ => unreachable!(),
+			}
+		}
+	}
+
+	fn assert_same_as_reference(types: &[ParamType], data: &[u8]) {
+		match (decode(types, data), reference::decode(types, data)) {
+			(Ok(tokens), Ok(expected)) => assert_eq!(tokens, expected),
+			(Err(_), Err(_)) => (),
+			(decoded, expected) => panic!("{:?} decoded as {:?}, expected {:?}", types, decoded, expected),
+		}
+
+		let borrowed =
+			decode_borrowed(types, data).map(|tokens| tokens.iter().map(TokenRef::to_token).collect::<Vec<_>>());
+		match (borrowed, decode(types, data)) {
+			(Ok(tokens), Ok(expected)) => assert_eq!(tokens, expected),
+			(Err(_), Err(_)) => (),
+			(borrowed, expected) => panic!("{:?} decoded as {:?}, expected {:?}", types, borrowed, expected),
+		}
+	}
+
+	#[test]
+	fn decode_same_as_reference() {
+		let nested = [
+			ParamType::Array(Box::new(ParamType::Array(Box::new(ParamType::Bytes)))),
+			ParamType::Tuple(vec![
+				ParamType::String,
+				ParamType::Uint(256),
+				ParamType::Array(Box::new(ParamType::Bool)),
+			]),
+		];
+		let mut rng = Rng(0x2545_f491_4f6c_dd1d);
+		for _ in 0..2000 {
+			let types: Vec<_> = match rng.below(4) {
+				0 => nested.to_vec(),
+				_ => (0..1 + rng.below(3)).map(|_| rng.param_type(3)).collect(),
+			};
+			let tokens: Vec<_> = types.iter().map(|kind| rng.token(kind)).collect();
+			let encoded = encode(&tokens);
+			assert_eq!(decode(&types, &encoded).unwrap(), tokens);
+			assert_same_as_reference(&types, &encoded);
+
+			// corrupted offsets and lengths
+			for _ in 0..10 {
+				let mut corrupted = encoded.clone();
+				let word = rng.below(corrupted.len() / 32);
+				corrupted[word * 32..word * 32 + 32].copy_from_slice(&[0u8; 32]);
+				corrupted[word * 32 + 31] = rng.below(encoded.len() + 64) as u8;
+				assert_same_as_reference(&types, &corrupted);
+				let truncated = &encoded[..rng.below(encoded.len() / 32) * 32];
+				assert_same_as_reference(&types, truncated);
+			}
+		}
+	}
 }
@@ -0,0 +1,192 @@
+// Copyright 2015-2020 Parity Technologies
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Listing of the entries of a contract and comparison of two versions of its ABI.
+
+#[cfg(not(feature = "std"))]
+use crate::no_std_prelude::*;
+use crate::{signature::canonical_signature, AbiError, Constructor, Contract, Event, Function, StateMutability};
+use alloc::collections::BTreeMap;
+
+/// Entry of a contract ABI, borrowed from a [`Contract`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AbiEntry<'a> {
+	/// Contract constructor.
+	Constructor(&'a Constructor),
+	/// Contract function.
+	Function(&'a Function),
+	/// Contract event.
+	Event(&'a Event),
+	/// Contract custom error.
+	Error(&'a AbiError),
+	/// Fallback function, with its state mutability.
+	Fallback(StateMutability),
+	/// Receive ether function.
+	Receive,
+}
+
+impl AbiEntry<'_> {
+	/// Type of the entry in the JSON ABI, e.g. `function`.
+	pub fn kind(&self) -> &'static str {
+		match *self {
+			AbiEntry::Constructor(_) => "constructor",
+			AbiEntry::Function(_) => "function",
+			AbiEntry::Event(_) => "event",
+			AbiEntry::Error(_) => "error",
+			AbiEntry::Fallback(_) => "fallback",
+			AbiEntry::Receive => "receive",
+		}
+	}
+
+	/// Canonical signature of the entry, e.g. `transfer(address,uint256)`, which identifies it
+	/// among the entries of its kind.
+	pub fn signature(&self) -> String {
+		match *self {
+			AbiEntry::Constructor(constructor) => {
+				let types = constructor.inputs.iter().map(|param| param.kind.clone()).collect::<Vec<_>>();
+				canonical_signature("constructor", &types)
+			}
+			AbiEntry::Function(function) => function.canonical_signature(),
+			AbiEntry::Event(event) => event.canonical_signature(),
+			AbiEntry::Error(error) => {
+				let types = error.inputs.iter().map(|param| param.kind.clone()).collect::<Vec<_>>();
+				canonical_signature(&error.name, &types)
+			}
+			AbiEntry::Fallback(_) => "fallback()".to_owned(),
+			AbiEntry::Receive => "receive()".to_owned(),
+		}
+	}
+}
+
+/// Differences between two versions of a contract ABI, see [`Contract::diff`].
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ContractDiff<'a> {
+	/// Entries only in the new version.
+	pub added: Vec<AbiEntry<'a>>,
+	/// Entries only in the old version.
+	pub removed: Vec<AbiEntry<'a>>,
+	/// Entries with the same kind and signature in both versions which differ otherwise, e.g.
+	/// by their outputs, state mutability, param names or indexed params, as `(old, new)`.
+	pub changed: Vec<(AbiEntry<'a>, AbiEntry<'a>)>,
+}
+
+impl ContractDiff<'_> {
+	/// Whether both versions have the same entries.
+	pub fn is_empty(&self) -> bool {
+		self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+	}
+}
+
+impl Contract {
+	/// Iterates over all the entries of the contract: the constructor, the functions, events
+	/// and custom errors ordered by name, overloads in ABI order, then the fallback and receive
+	/// functions. This is the order of the serialized ABI.
+	pub fn entries(&self) -> impl Iterator<Item = AbiEntry<'_>> {
+		self.constructor
+			.iter()
+			.map(AbiEntry::Constructor)
+			.chain(self.functions().map(AbiEntry::Function))
+			.chain(self.events().map(AbiEntry::Event))
+			.chain(self.errors().map(AbiEntry::Error))
+			.chain(self.fallback.map(AbiEntry::Fallback))
+			.chain(self.receive.then_some(AbiEntry::Receive))
+	}
+
+	/// Compares the contract to its `new` version, matching their entries by kind and
+	/// canonical signature.
+	pub fn diff<'a>(&'a self, new: &'a Contract) -> ContractDiff<'a> {
+		let by_signature = |contract: &'a Contract| {
+			contract.entries().map(|entry| ((entry.kind(), entry.signature()), entry)).collect::<BTreeMap<_, _>>()
+		};
+		let (old_entries, new_entries) = (by_signature(self), by_signature(new));
+
+		let mut diff = ContractDiff::default();
+		for old in self.entries() {
+			match new_entries.get(&(old.kind(), old.signature())) {
+				None => diff.removed.push(old),
+				Some(new) if *new != old => diff.changed.push((old, *new)),
+				Some(_) => (),
+			}
+		}
+		diff.added =
+			new.entries().filter(|entry| !old_entries.contains_key(&(entry.kind(), entry.signature()))).collect();
+		diff
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::AbiEntry;
+	use crate::{Contract, StateMutability};
+
+	#[test]
+	fn test_entries() {
+		let contract = Contract::parse_signatures([
+			"constructor(address owner)",
+			"function transfer(address to, uint256 amount) returns (bool)",
+			"function approve(address spender, uint256 amount) returns (bool)",
+			"event Transfer(address indexed from, address indexed to, uint256 value)",
+			"error Unauthorized(address caller)",
+		])
+		.unwrap();
+
+		let entries = contract.entries().map(|entry| (entry.kind(), entry.signature())).collect::<Vec<_>>();
+		assert_eq!(
+			entries,
+			vec![
+				("constructor", "constructor(address)".to_owned()),
+				("function", "approve(address,uint256)".to_owned()),
+				("function", "transfer(address,uint256)".to_owned()),
+				("event", "Transfer(address,address,uint256)".to_owned()),
+				("error", "Unauthorized(address)".to_owned()),
+			]
+		);
+	}
+
+	#[test]
+	fn test_diff() {
+		let old = Contract::parse_signatures([
+			"function transfer(address to, uint256 amount) returns (bool)",
+			"function approve(address spender, uint256 amount) returns (bool)",
+			"event Transfer(address indexed from, address indexed to, uint256 value)",
+		])
+		.unwrap();
+		let mut new = Contract::parse_signatures([
+			"function transfer(address to, uint256 amount)",
+			"function approve(address spender, uint256 amount) returns (bool)",
+			"function permit(address owner, address spender, uint256 value)",
+			"event Transfer(address indexed from, address to, uint256 value)",
+		])
+		.unwrap();
+		new.fallback = Some(StateMutability::Payable);
+
+		let diff = old.diff(&new);
+		assert_eq!(
+			diff.added,
+			vec![AbiEntry::Function(new.function("permit").unwrap()), AbiEntry::Fallback(StateMutability::Payable)]
+		);
+		assert!(diff.removed.is_empty());
+		assert_eq!(
+			diff.changed,
+			vec![
+				(
+					AbiEntry::Function(old.function("transfer").unwrap()),
+					AbiEntry::Function(new.function("transfer").unwrap())
+				),
+				(AbiEntry::Event(old.event("Transfer").unwrap()), AbiEntry::Event(new.event("Transfer").unwrap())),
+			]
+		);
+
+		let removed = new.diff(&old).removed;
+		assert_eq!(
+			removed,
+			vec![AbiEntry::Function(new.function("permit").unwrap()), AbiEntry::Fallback(StateMutability::Payable)]
+		);
+		assert!(old.diff(&old).is_empty());
+	}
+}
@@ -0,0 +1,412 @@
+// Copyright 2015-2020 Parity Technologies
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! EIP-712 typed structured data hashing.
+//!
+//! Struct values are [`Token::Tuple`]s holding the values of the fields in declaration order,
+//! array values are [`Token::Array`]s or [`Token::FixedArray`]s.
+//!
+//! [`TypedData`] hashes whole typed data JSON documents, converting their values with
+//! [`parse_value`].
+
+#[cfg(not(feature = "std"))]
+use crate::no_std_prelude::*;
+use crate::{encode, function::json_to_token, param_type::Reader, Hash, ParamType, Result, Token};
+use alloc::collections::{BTreeMap, BTreeSet};
+use anyhow::anyhow;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha3::{Digest, Keccak256};
+
+/// Field of an EIP-712 struct type.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Field {
+	/// Field name.
+	pub name: String,
+	/// Field type, e.g. `uint256`, `Person` or `Person[]`.
+	#[serde(rename = "type")]
+	pub kind: String,
+}
+
+/// EIP-712 struct types keyed by name, as in the `types` of typed data JSON.
+pub type Types = BTreeMap<String, Vec<Field>>;
+
+/// Typed data JSON document, as signed by `eth_signTypedData_v4`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TypedData {
+	/// Struct types, `EIP712Domain` included.
+	pub types: Types,
+	/// Struct type of the message.
+	pub primary_type: String,
+	/// Values of the `EIP712Domain` fields.
+	pub domain: Value,
+	/// Values of the message fields.
+	pub message: Value,
+}
+
+impl TypedData {
+	/// Returns the [`hash_struct`] of the domain.
+	pub fn domain_separator(&self) -> Result<Hash> {
+		let values = parse_struct(&self.types, "EIP712Domain", &self.domain)?;
+		hash_struct(&self.types, "EIP712Domain", &values)
+	}
+
+	/// Returns the [`hash_struct`] of the message.
+	pub fn message_hash(&self) -> Result<Hash> {
+		let values = parse_struct(&self.types, &self.primary_type, &self.message)?;
+		hash_struct(&self.types, &self.primary_type, &values)
+	}
+
+	/// Returns the [`digest`] to sign.
+	pub fn digest(&self) -> Result<Hash> {
+		Ok(digest(&self.domain_separator()?, &self.message_hash()?))
+	}
+}
+
+fn keccak(data: &[u8]) -> Hash {
+	Hash::from_slice(&Keccak256::digest(data))
+}
+
+fn fields<'a>(types: &'a Types, name: &str) -> Result<&'a [Field]> {
+	types.get(name).map(Vec::as_slice).ok_or_else(|| anyhow!("unknown struct type `{}`", name).into())
+}
+
+/// Returns the name of a type with its array suffixes stripped.
+fn base_type(kind: &str) -> &str {
+	&kind[..kind.find('[').unwrap_or(kind.len())]
+}
+
+fn collect_dependencies<'a>(types: &'a Types, name: &str, deps: &mut BTreeSet<&'a str>) -> Result<()> {
+	let (name, fields) = types.get_key_value(name).ok_or_else(|| anyhow!("unknown struct type `{}`", name))?;
+	if deps.insert(name) {
+		for field in fields {
+			if types.contains_key(base_type(&field.kind)) {
+				collect_dependencies(types, base_type(&field.kind), deps)?;
+			}
+		}
+	}
+	Ok(())
+}
+
+/// Encodes the struct type `primary`, e.g. `Mail(Person from,Person to,string contents)`, followed by
+/// the struct types it references, sorted by name.
+pub fn encode_type(types: &Types, primary: &str) -> Result<String> {
+	let mut deps = BTreeSet::new();
+	collect_dependencies(types, primary, &mut deps)?;
+	deps.remove(primary);
+
+	let mut result = String::new();
+	for name in core::iter::once(primary).chain(deps) {
+		let fields = fields(types, name)?.iter().map(|f| format!("{} {}", f.kind, f.name)).collect::<Vec<_>>();
+		result.push_str(&format!("{}({})", name, fields.join(",")));
+	}
+	Ok(result)
+}
+
+/// Returns the keccak256 of [`encode_type`].
+pub fn type_hash(types: &Types, primary: &str) -> Result<Hash> {
+	encode_type(types, primary).map(|encoded| keccak(encoded.as_bytes()))
+}
+
+/// Hashes the struct of type `primary` whose fields have the given `values`.
+pub fn hash_struct(types: &Types, primary: &str, values: &[Token]) -> Result<Hash> {
+	let fields = fields(types, primary)?;
+	if fields.len() != values.len() {
+		return Err(anyhow!("struct `{}` has {} fields, got {} values", primary, fields.len(), values.len()).into());
+	}
+
+	let mut encoded = Vec::with_capacity(32 * (fields.len() + 1));
+	encoded.extend_from_slice(type_hash(types, primary)?.as_bytes());
+	for (field, value) in fields.iter().zip(values) {
+		encoded.extend_from_slice(encode_value(types, &field.kind, value)?.as_bytes());
+	}
+	Ok(keccak(&encoded))
+}
+
+/// Returns the digest to sign for a message, `keccak256("\x19\x01" ‖ domainSeparator ‖ hashStruct(message))`.
+///
+/// The domain separator is the [`hash_struct`] of the `EIP712Domain` of the message.
+pub fn digest(domain_separator: &Hash, message_hash: &Hash) -> Hash {
+	let mut encoded = Vec::with_capacity(66);
+	encoded.extend_from_slice(b"\x19\x01");
+	encoded.extend_from_slice(domain_separator.as_bytes());
+	encoded.extend_from_slice(message_hash.as_bytes());
+	keccak(&encoded)
+}
+
+/// Encodes a field value into a single word: structs, arrays and dynamic values are hashed,
+/// atomic values are ABI encoded.
+fn encode_value(types: &Types, kind: &str, value: &Token) -> Result<Hash> {
+	let invalid = || anyhow!("value {:?} is not a valid `{}`", value, kind).into();
+
+	if let Some(array) = kind.strip_suffix(']') {
+		let (element, size) = array.rfind('[').map(|pos| (&array[..pos], &array[pos + 1..])).ok_or_else(invalid)?;
+		let elements = match value {
+			Token::Array(elements) if size.is_empty() => elements,
+			Token::FixedArray(elements) if size.parse() == Ok(elements.len()) => elements,
+			_ => return Err(invalid()),
+		};
+
+		let mut encoded = Vec::with_capacity(32 * elements.len());
+		for element_value in elements {
+			encoded.extend_from_slice(encode_value(types, element, element_value)?.as_bytes());
+		}
+		return Ok(keccak(&encoded));
+	}
+
+	match value {
+		Token::Tuple(values) if types.contains_key(kind) => hash_struct(types, kind, values),
+		Token::String(s) if kind == "string" => Ok(keccak(s.as_bytes())),
+		Token::Bytes(bytes) if kind == "bytes" => Ok(keccak(bytes)),
+		_ if types.contains_key(kind) => Err(invalid()),
+		_ => match Reader::read(kind)? {
+			ParamType::Tuple(_) | ParamType::Array(_) | ParamType::FixedArray(..) => Err(invalid()),
+			param if !param.is_dynamic() && value.type_check(&param) => {
+				Ok(Hash::from_slice(&encode(core::slice::from_ref(value))))
+			}
+			_ => Err(invalid()),
+		},
+	}
+}
+
+/// Converts the JSON value of a field of type `kind` into a token.
+///
+/// Structs are objects, arrays are arrays, integers are numbers or decimal or `0x` prefixed
+/// hex strings, and bytes and addresses are `0x` prefixed hex strings.
+pub fn parse_value(types: &Types, kind: &str, value: &Value) -> Result<Token> {
+	let invalid = || anyhow!("value {} is not a valid `{}`", value, kind).into();
+
+	if let Some(array) = kind.strip_suffix(']') {
+		let (element, size) = array.rfind('[').map(|pos| (&array[..pos], &array[pos + 1..])).ok_or_else(invalid)?;
+		let elements = value.as_array().ok_or_else(invalid)?;
+		let tokens =
+			elements.iter().map(|element_value| parse_value(types, element, element_value)).collect::<Result<_>>()?;
+		return match size.is_empty() {
+			true => Ok(Token::Array(tokens)),
+			false if size.parse() == Ok(elements.len()) => Ok(Token::FixedArray(tokens)),
+			false => Err(invalid()),
+		};
+	}
+
+	if types.contains_key(kind) {
+		return parse_struct(types, kind, value).map(Token::Tuple);
+	}
+
+	match Reader::read(kind)? {
+		ParamType::Tuple(_) | ParamType::Array(_) | ParamType::FixedArray(..) => Err(invalid()),
+		param => json_to_token(&param, value),
+	}
+}
+
+/// Converts a JSON object into the values of the fields of the struct `name`.
+fn parse_struct(types: &Types, name: &str, value: &Value) -> Result<Vec<Token>> {
+	let object = value.as_object().ok_or_else(|| anyhow!("value {} is not a valid `{}`", value, name))?;
+	fields(types, name)?
+		.iter()
+		.map(|field| match object.get(&field.name) {
+			Some(field_value) => parse_value(types, &field.kind, field_value),
+			None => Err(anyhow!("field `{}` of `{}` is missing", field.name, name).into()),
+		})
+		.collect()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{digest, encode_type, hash_struct, parse_value, type_hash, Field, TypedData, Types};
+	use crate::{Hash, Token};
+	use hex_literal::hex;
+	use serde_json::json;
+	use sha3::{Digest, Keccak256};
+
+	fn types() -> Types {
+		serde_json::from_str(
+			r#"{
+				"EIP712Domain": [
+					{ "name": "name", "type": "string" },
+					{ "name": "version", "type": "string" },
+					{ "name": "chainId", "type": "uint256" },
+					{ "name": "verifyingContract", "type": "address" }
+				],
+				"Person": [
+					{ "name": "name", "type": "string" },
+					{ "name": "wallet", "type": "address" }
+				],
+				"Mail": [
+					{ "name": "from", "type": "Person" },
+					{ "name": "to", "type": "Person" },
+					{ "name": "contents", "type": "string" }
+				]
+			}"#,
+		)
+		.unwrap()
+	}
+
+	fn person(name: &str, wallet: [u8; 20]) -> Token {
+		Token::Tuple(vec![Token::String(name.to_owned()), Token::Address(wallet.into())])
+	}
+
+	fn cow() -> Token {
+		person("Cow", hex!("CD2a3d9F938E13CD947Ec05AbC7FE734Df8DD826"))
+	}
+
+	fn bob() -> Token {
+		person("Bob", hex!("bBbBBBBbbBBBbbbBbbBbbbbBBbBbbbbBbBbbBBbB"))
+	}
+
+	#[test]
+	fn mail_example() {
+		let types = types();
+		assert_eq!(
+			encode_type(&types, "Mail").unwrap(),
+			"Mail(Person from,Person to,string contents)Person(string name,address wallet)"
+		);
+		assert_eq!(
+			type_hash(&types, "Mail").unwrap(),
+			Hash::from(hex!("a0cedeb2dc280ba39b857546d74f5549c3a1d7bdc2dd96bf881f76108e23dac2"))
+		);
+
+		let domain = [
+			Token::String("Ether Mail".to_owned()),
+			Token::String("1".to_owned()),
+			Token::Uint(1.into()),
+			Token::Address(hex!("CcCCccccCCCCcCCCCCCcCcCccCcCCCcCcccccccC").into()),
+		];
+		let domain_separator = hash_struct(&types, "EIP712Domain", &domain).unwrap();
+		assert_eq!(
+			domain_separator,
+			Hash::from(hex!("f2cee375fa42b42143804025fc449deafd50cc031ca257e0b194a650a912090f"))
+		);
+
+		let mail = [cow(), bob(), Token::String("Hello, Bob!".to_owned())];
+		let message_hash = hash_struct(&types, "Mail", &mail).unwrap();
+		assert_eq!(message_hash, Hash::from(hex!("c52c0ee5d84264471806290a3f2c4cecfc5490626bf912d01f240d7a274b371e")));
+
+		assert_eq!(
+			digest(&domain_separator, &message_hash),
+			Hash::from(hex!("be609aee343fb3c4b28e1df9e632fca64fcfaede20f02e86244efddf30957bd2"))
+		);
+	}
+
+	#[test]
+	fn struct_arrays() {
+		let mut types = types();
+		types.insert(
+			"Group".to_owned(),
+			vec![
+				Field { name: "members".to_owned(), kind: "Person[]".to_owned() },
+				Field { name: "leaders".to_owned(), kind: "Person[1]".to_owned() },
+				Field { name: "tags".to_owned(), kind: "bytes[]".to_owned() },
+			],
+		);
+		assert_eq!(
+			encode_type(&types, "Group").unwrap(),
+			"Group(Person[] members,Person[1] leaders,bytes[] tags)Person(string name,address wallet)"
+		);
+
+		let group = [
+			Token::Array(vec![cow(), bob()]),
+			Token::FixedArray(vec![bob()]),
+			Token::Array(vec![Token::Bytes(vec![1, 2, 3])]),
+		];
+		let person_hash = |person: Token| match person {
+			Token::Tuple(values) => hash_struct(&types, "Person", &values).unwrap(),
+			_ => unreachable!(),
+		};
+		let keccak = |data: &[u8]| Hash::from_slice(&Keccak256::digest(data));
+
+		let mut expected = type_hash(&types, "Group").unwrap().as_bytes().to_vec();
+		expected.extend_from_slice(keccak(&[person_hash(cow()).0, person_hash(bob()).0].concat()).as_bytes());
+		expected.extend_from_slice(keccak(person_hash(bob()).as_bytes()).as_bytes());
+		expected.extend_from_slice(keccak(keccak(&[1, 2, 3]).as_bytes()).as_bytes());
+		assert_eq!(hash_struct(&types, "Group", &group).unwrap(), keccak(&expected));
+
+		let wrong_size = [Token::Array(vec![]), Token::FixedArray(vec![]), Token::Array(vec![])];
+		let err = hash_struct(&types, "Group", &wrong_size).unwrap_err();
+		assert_eq!(err.to_string(), "value FixedArray([]) is not a valid `Person[1]`");
+	}
+
+	#[test]
+	fn invalid_values() {
+		let types = types();
+		let err = hash_struct(&types, "Person", &[Token::Bool(true), Token::String("Cow".to_owned())]).unwrap_err();
+		assert_eq!(err.to_string(), "value Bool(true) is not a valid `string`");
+		assert!(hash_struct(&types, "Mail", &[cow()]).is_err());
+		assert!(hash_struct(&types, "Unknown", &[]).is_err());
+	}
+
+	fn mail_typed_data() -> serde_json::Value {
+		json!({
+			"types": types(),
+			"primaryType": "Mail",
+			"domain": {
+				"name": "Ether Mail",
+				"version": "1",
+				"chainId": 1,
+				"verifyingContract": "0xCcCCccccCCCCcCCCCCCcCcCccCcCCCcCcccccccC"
+			},
+			"message": {
+				"from": { "name": "Cow", "wallet": "0xCD2a3d9F938E13CD947Ec05AbC7FE734Df8DD826" },
+				"to": { "name": "Bob", "wallet": "0xbBbBBBBbbBBBbbbBbbBbbbbBBbBbbbbBbBbbBBbB" },
+				"contents": "Hello, Bob!"
+			}
+		})
+	}
+
+	#[test]
+	fn typed_data_document() {
+		let typed_data: TypedData = serde_json::from_value(mail_typed_data()).unwrap();
+		assert_eq!(
+			typed_data.domain_separator().unwrap(),
+			Hash::from(hex!("f2cee375fa42b42143804025fc449deafd50cc031ca257e0b194a650a912090f"))
+		);
+		assert_eq!(
+			typed_data.message_hash().unwrap(),
+			Hash::from(hex!("c52c0ee5d84264471806290a3f2c4cecfc5490626bf912d01f240d7a274b371e"))
+		);
+		assert_eq!(
+			typed_data.digest().unwrap(),
+			Hash::from(hex!("be609aee343fb3c4b28e1df9e632fca64fcfaede20f02e86244efddf30957bd2"))
+		);
+
+		let mut document = mail_typed_data();
+		document["message"]["to"].as_object_mut().unwrap().remove("wallet");
+		let typed_data: TypedData = serde_json::from_value(document).unwrap();
+		assert_eq!(typed_data.message_hash().unwrap_err().to_string(), "field `wallet` of `Person` is missing");
+	}
+
+	#[test]
+	fn parse_values() {
+		let types = types();
+		assert_eq!(parse_value(&types, "uint8", &json!(7)).unwrap(), Token::Uint(7.into()));
+		assert_eq!(parse_value(&types, "uint256", &json!("0x10")).unwrap(), Token::Uint(16.into()));
+		assert_eq!(parse_value(&types, "int256", &json!(-1)).unwrap(), Token::Int(crate::Uint::MAX));
+		assert_eq!(parse_value(&types, "bytes", &json!("0x0102")).unwrap(), Token::Bytes(vec![1, 2]));
+		assert_eq!(
+			parse_value(&types, "bytes2[1]", &json!(["0x0102"])).unwrap(),
+			Token::FixedArray(vec![Token::FixedBytes(vec![1, 2])])
+		);
+		assert_eq!(parse_value(&types, "bool[]", &json!([true])).unwrap(), Token::Array(vec![Token::Bool(true)]));
+		assert_eq!(
+			parse_value(
+				&types,
+				"Person",
+				&json!({"name": "Cow", "wallet": "0xCD2a3d9F938E13CD947Ec05AbC7FE734Df8DD826"})
+			)
+			.unwrap(),
+			cow()
+		);
+
+		assert_eq!(
+			parse_value(&types, "string", &json!(1)).unwrap_err().to_string(),
+			"value 1 is not a valid `string`"
+		);
+		assert!(parse_value(&types, "bool[2]", &json!([true])).is_err());
+		assert!(parse_value(&types, "Person", &json!("Cow")).is_err());
+	}
+}
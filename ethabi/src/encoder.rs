@@ -8,176 +8,259 @@
 
 //! ABI encoder.
 
-use crate::{util::pad_u32, Bytes, Token, Word};
-
-fn pad_bytes(bytes: &[u8]) -> Vec<Word> {
-	let mut result = vec![pad_u32(bytes.len() as u32)];
-	result.extend(pad_fixed_bytes(bytes));
-	result
-}
-
-fn pad_fixed_bytes(bytes: &[u8]) -> Vec<Word> {
-	let len = (bytes.len() + 31) / 32;
-	let mut result = Vec::with_capacity(len);
-	for i in 0..len {
-		let mut padded = [0u8; 32];
-
-		let to_copy = match i == len - 1 {
-			false => 32,
-			true => match bytes.len() % 32 {
-				0 => 32,
-				x => x,
-			},
-		};
-
-		let offset = 32 * i;
-		padded[..to_copy].copy_from_slice(&bytes[offset..offset + to_copy]);
-		result.push(padded);
-	}
+#[cfg(not(feature = "std"))]
+use crate::no_std_prelude::*;
+use crate::{function::in_range, util::pad_u32, Bytes, ParamType, Result, Token, Word};
+use anyhow::anyhow;
 
+/// Encodes vector of tokens into ABI compliant vector of bytes.
+pub fn encode(tokens: &[Token]) -> Bytes {
+	let mut result = Vec::new();
+	encode_into(tokens, &mut result);
 	result
 }
 
-#[derive(Debug)]
-enum Mediate {
-	Raw(Vec<Word>),
-	Prefixed(Vec<Word>),
-	PrefixedArray(Vec<Mediate>),
-	PrefixedArrayWithLength(Vec<Mediate>),
-	RawTuple(Vec<Mediate>),
-	PrefixedTuple(Vec<Mediate>),
+/// Encodes `tokens` like [`encode`], appending them to `out` instead of allocating a new buffer.
+///
+/// `out` grows once by the [`encoded_size`] of `tokens`.
+pub fn encode_into(tokens: &[Token], out: &mut Vec<u8>) {
+	out.reserve(encoded_size(tokens));
+	write_head_tail(tokens, &mut |bytes| out.extend_from_slice(bytes));
 }
 
-impl Mediate {
-	fn head_len(&self) -> u32 {
-		match *self {
-			Mediate::Raw(ref raw) => 32 * raw.len() as u32,
-			Mediate::RawTuple(ref mediates) => 32 * mediates.len() as u32,
-			Mediate::Prefixed(_)
-			| Mediate::PrefixedArray(_)
-			| Mediate::PrefixedArrayWithLength(_)
-			| Mediate::PrefixedTuple(_) => 32,
+/// Encodes `tokens` like [`encode`], streaming them to `writer` without buffering the encoding.
+#[cfg(feature = "std")]
+pub fn encode_to_writer<W: std::io::Write>(tokens: &[Token], mut writer: W) -> std::io::Result<()> {
+	let mut result = Ok(());
+	write_head_tail(tokens, &mut |bytes| {
+		if result.is_ok() {
+			result = writer.write_all(bytes);
 		}
-	}
+	});
+	result
+}
 
-	fn tail_len(&self) -> u32 {
-		match *self {
-			Mediate::Raw(_) | Mediate::RawTuple(_) => 0,
-			Mediate::Prefixed(ref pre) => pre.len() as u32 * 32,
-			Mediate::PrefixedArray(ref mediates) => mediates.iter().fold(0, |acc, m| acc + m.head_len() + m.tail_len()),
-			Mediate::PrefixedArrayWithLength(ref mediates) => {
-				mediates.iter().fold(32, |acc, m| acc + m.head_len() + m.tail_len())
+/// Writes the heads of `tokens`, pointing dynamic ones at their tails, followed by the tails.
+fn write_head_tail<W: FnMut(&[u8])>(tokens: &[Token], write: &mut W) {
+	let mut offset = tokens.iter().map(head_size).sum::<usize>();
+	for token in tokens {
+		match token.is_dynamic() {
+			true => {
+				write(&pad_u32(offset as u32));
+				offset += tail_size(token);
 			}
-			Mediate::PrefixedTuple(ref mediates) => mediates.iter().fold(0, |acc, m| acc + m.head_len() + m.tail_len()),
+			false => write_static(token, write),
 		}
 	}
+	for token in tokens.iter().filter(|token| token.is_dynamic()) {
+		write_tail(token, write);
+	}
+}
 
-	fn head(&self, suffix_offset: u32) -> Vec<Word> {
-		match *self {
-			Mediate::Raw(ref raw) => raw.clone(),
-			Mediate::RawTuple(ref raw) => raw.iter().map(|mediate| mediate.head(0)).flatten().collect(),
-			Mediate::Prefixed(_)
-			| Mediate::PrefixedArray(_)
-			| Mediate::PrefixedArrayWithLength(_)
-			| Mediate::PrefixedTuple(_) => vec![pad_u32(suffix_offset)],
+/// Writes the whole encoding of a static `token`, which is its head.
+fn write_static<W: FnMut(&[u8])>(token: &Token, write: &mut W) {
+	match *token {
+		Token::Address(ref address) => {
+			write(&[0u8; 12]);
+			write(address.as_bytes());
 		}
+		Token::FixedBytes(ref bytes) => write_padded(bytes, write),
+		Token::Int(int) | Token::Uint(int) => write(&Word::from(int)),
+		Token::Bool(b) => write(&pad_u32(b as u32)),
+		Token::FixedArray(ref tokens) | Token::Tuple(ref tokens) => write_head_tail(tokens, write),
+		Token::Bytes(_) | Token::String(_) | Token::Array(_) => unreachable!("dynamic tokens have tails; qed"),
 	}
+}
 
-	fn tail(&self) -> Vec<Word> {
-		match *self {
-			Mediate::Raw(_) | Mediate::RawTuple(_) => vec![],
-			Mediate::PrefixedTuple(ref mediates) => encode_head_tail(mediates),
-			Mediate::Prefixed(ref raw) => raw.clone(),
-			Mediate::PrefixedArray(ref mediates) => encode_head_tail(mediates),
-			Mediate::PrefixedArrayWithLength(ref mediates) => {
-				// + 32 added to offset represents len of the array prepanded to tail
-				let mut result = vec![pad_u32(mediates.len() as u32)];
-
-				let head_tail = encode_head_tail(mediates);
-
-				result.extend(head_tail);
-				result
-			}
+/// Writes the tail of a dynamic `token`, the data its head points at.
+fn write_tail<W: FnMut(&[u8])>(token: &Token, write: &mut W) {
+	match *token {
+		Token::Bytes(ref bytes) => {
+			write(&pad_u32(bytes.len() as u32));
+			write_padded(bytes, write);
+		}
+		Token::String(ref s) => {
+			write(&pad_u32(s.len() as u32));
+			write_padded(s.as_bytes(), write);
 		}
+		Token::Array(ref tokens) => {
+			write(&pad_u32(tokens.len() as u32));
+			write_head_tail(tokens, write);
+		}
+		Token::FixedArray(ref tokens) | Token::Tuple(ref tokens) => write_head_tail(tokens, write),
+		_ => unreachable!("static tokens have no tail; qed"),
 	}
 }
 
-fn encode_head_tail(mediates: &[Mediate]) -> Vec<Word> {
-	let heads_len = mediates.iter().fold(0, |acc, m| acc + m.head_len());
-
-	let (mut result, len) =
-		mediates.iter().fold((Vec::with_capacity(heads_len as usize), heads_len), |(mut acc, offset), m| {
-			acc.extend(m.head(offset));
-			(acc, offset + m.tail_len())
-		});
-
-	let tails = mediates.iter().fold(Vec::with_capacity((len - heads_len) as usize), |mut acc, m| {
-		acc.extend(m.tail());
-		acc
-	});
+/// Writes `bytes` right padded with zeros to a multiple of 32 bytes.
+fn write_padded<W: FnMut(&[u8])>(bytes: &[u8], write: &mut W) {
+	write(bytes);
+	match bytes.len() % 32 {
+		0 => (),
+		len => write(&[0u8; 32][len..]),
+	}
+}
 
-	result.extend(tails);
-	result
+/// Encodes `tokens` like [`encode`], checking the invariants of the encoding when the
+/// `checked-encode` feature is enabled.
+///
+/// The output must be made of whole words, have the [`encoded_size`] of `tokens` and decode
+/// back to `tokens`, which ensures the offsets of the heads point at their tails. Without the
+/// feature this is just [`encode`].
+pub fn encode_checked(tokens: &[Token]) -> Result<Bytes> {
+	let encoded = encode(tokens);
+	#[cfg(feature = "checked-encode")]
+	check_encoding(tokens, &encoded)?;
+	Ok(encoded)
 }
 
-/// Encodes vector of tokens into ABI compliant vector of bytes.
-pub fn encode(tokens: &[Token]) -> Bytes {
-	let mediates = &tokens.iter().map(encode_token).collect::<Vec<_>>();
+#[cfg(feature = "checked-encode")]
+fn check_encoding(tokens: &[Token], encoded: &[u8]) -> Result<()> {
+	if !encoded.len().is_multiple_of(32) {
+		return Err(anyhow!("encoding is {} bytes long, which is not a multiple of 32", encoded.len()).into());
+	}
+
+	let expected_len = encoded_size(tokens);
+	if encoded.len() != expected_len {
+		return Err(anyhow!("encoding is {} bytes long, expected {}", encoded.len(), expected_len).into());
+	}
 
-	encode_head_tail(mediates).iter().flat_map(|word| word.to_vec()).collect()
+	let kinds = tokens.iter().map(kind_of).collect::<Vec<_>>();
+	let decoded = crate::decode(&kinds, encoded).map_err(|err| anyhow!("encoding does not decode: {}", err))?;
+	match decoded.iter().zip(tokens).position(|(decoded, token)| decoded != token) {
+		Some(i) => Err(anyhow!("token {} decodes to {:?}, expected {:?}", i, decoded[i], tokens[i]).into()),
+		None => Ok(()),
+	}
 }
 
-fn encode_token(token: &Token) -> Mediate {
+/// Returns the param type a `token` is encoded as. Empty arrays are arbitrarily `uint256[]`.
+#[cfg(feature = "checked-encode")]
+fn kind_of(token: &Token) -> ParamType {
 	match *token {
-		Token::Address(ref address) => {
-			let mut padded = [0u8; 32];
-			padded[12..].copy_from_slice(address.as_ref());
-			Mediate::Raw(vec![padded])
-		}
-		Token::Bytes(ref bytes) => Mediate::Prefixed(pad_bytes(bytes)),
-		Token::String(ref s) => Mediate::Prefixed(pad_bytes(s.as_bytes())),
-		Token::FixedBytes(ref bytes) => Mediate::Raw(pad_fixed_bytes(bytes)),
-		Token::Int(int) => Mediate::Raw(vec![int.into()]),
-		Token::Uint(uint) => Mediate::Raw(vec![uint.into()]),
-		Token::Bool(b) => {
-			let mut value = [0u8; 32];
-			if b {
-				value[31] = 1;
-			}
-			Mediate::Raw(vec![value])
+		Token::Address(_) => ParamType::Address,
+		Token::FixedBytes(ref bytes) => ParamType::FixedBytes(bytes.len()),
+		Token::Bytes(_) => ParamType::Bytes,
+		Token::Int(_) => ParamType::Int(256),
+		Token::Uint(_) => ParamType::Uint(256),
+		Token::Bool(_) => ParamType::Bool,
+		Token::String(_) => ParamType::String,
+		Token::Array(ref tokens) => ParamType::Array(Box::new(tokens.first().map_or(ParamType::Uint(256), kind_of))),
+		Token::FixedArray(ref tokens) => {
+			ParamType::FixedArray(Box::new(tokens.first().map_or(ParamType::Uint(256), kind_of)), tokens.len())
 		}
-		Token::Array(ref tokens) => {
-			let mediates = tokens.iter().map(encode_token).collect();
+		Token::Tuple(ref tokens) => ParamType::Tuple(tokens.iter().map(kind_of).collect()),
+	}
+}
+
+/// Encodes `tokens` of types `params` like Solidity's `abi.encodePacked`.
+///
+/// Values are concatenated without padding, `uintN` and `intN` taking N / 8 bytes, and `bytes`
+/// and `string` have no length prefix. Array elements are still padded to 32 bytes. Tuples and
+/// arrays of dynamic or nested types, which Solidity cannot pack, are rejected.
+pub fn encode_packed(params: &[ParamType], tokens: &[Token]) -> Result<Bytes> {
+	if params.len() != tokens.len() {
+		return Err(anyhow!("expected {} tokens to pack, got {}", params.len(), tokens.len()).into());
+	}
 
-			Mediate::PrefixedArrayWithLength(mediates)
+	let mut result = Vec::new();
+	for (param, token) in params.iter().zip(tokens) {
+		if !token.type_check(param) || !in_range(param, token) {
+			return Err(anyhow!("`{}` is not a valid `{}`", token, param).into());
 		}
-		Token::FixedArray(ref tokens) => {
-			let mediates = tokens.iter().map(encode_token).collect();
 
-			if token.is_dynamic() {
-				Mediate::PrefixedArray(mediates)
-			} else {
-				Mediate::Raw(encode_head_tail(&mediates))
+		match (param, token) {
+			(ParamType::Tuple(_), _) => return Err(anyhow!("tuple `{}` cannot be packed", param).into()),
+			(ParamType::Array(kind), Token::Array(tokens))
+			| (ParamType::FixedArray(kind, _), Token::FixedArray(tokens)) => {
+				if kind.is_dynamic() || matches!(**kind, ParamType::FixedArray(..) | ParamType::Tuple(_)) {
+					return Err(anyhow!("array `{}` cannot be packed", param).into());
+				}
+				result.extend(encode(tokens));
 			}
+			(_, Token::Bytes(bytes)) | (_, Token::FixedBytes(bytes)) => result.extend_from_slice(bytes),
+			(_, Token::String(string)) => result.extend_from_slice(string.as_bytes()),
+			(_, Token::Address(address)) => result.extend_from_slice(address.as_bytes()),
+			(_, Token::Bool(b)) => result.push(*b as u8),
+			(ParamType::Uint(size), _)
+			| (ParamType::Int(size), _)
+			| (ParamType::Ufixed(size, _), _)
+			| (ParamType::Fixed(size, _), _) => result.extend_from_slice(&encode(core::slice::from_ref(token))[32 - size / 8..]),
+			_ => unreachable!("tokens are type checked; qed"),
 		}
-		Token::Tuple(ref tokens) if token.is_dynamic() => {
-			let mediates = tokens.iter().map(encode_token).collect();
+	}
+	Ok(result)
+}
 
-			Mediate::PrefixedTuple(mediates)
-		}
-		Token::Tuple(ref tokens) => {
-			let mediates = tokens.iter().map(encode_token).collect();
+/// Returns the length in bytes of the ABI encoding of `tokens`, without encoding them.
+pub fn encoded_size(tokens: &[Token]) -> usize {
+	tokens.iter().map(|token| head_size(token) + tail_size(token)).sum()
+}
 
-			Mediate::RawTuple(mediates)
-		}
+/// Returns the length in bytes of the ABI encoding of a single `token` of type `param`,
+/// head and tail included.
+///
+/// Summed over a list of params it gives the [`encoded_size`] of the whole list.
+pub fn token_encoded_size(param: &ParamType, token: &Token) -> usize {
+	match param.is_dynamic() {
+		true => 32 + tail_size(token),
+		false => param_head_size(param),
+	}
+}
+
+/// Returns the size of the head of a param, which is the whole encoding of static params.
+pub(crate) fn param_head_size(kind: &ParamType) -> usize {
+	match *kind {
+		_ if kind.is_dynamic() => 32,
+		ParamType::FixedBytes(len) => 32 * len.div_ceil(32),
+		ParamType::FixedArray(ref kind, len) => len * param_head_size(kind),
+		ParamType::Tuple(ref kinds) => kinds.iter().map(param_head_size).sum(),
+		_ => 32,
+	}
+}
+
+fn head_size(token: &Token) -> usize {
+	match *token {
+		_ if token.is_dynamic() => 32,
+		Token::FixedBytes(ref bytes) => 32 * bytes.len().div_ceil(32),
+		Token::FixedArray(ref tokens) | Token::Tuple(ref tokens) => tokens.iter().map(head_size).sum(),
+		_ => 32,
+	}
+}
+
+pub(crate) fn tail_size(token: &Token) -> usize {
+	match *token {
+		_ if !token.is_dynamic() => 0,
+		Token::Bytes(ref bytes) => 32 + 32 * bytes.len().div_ceil(32),
+		Token::String(ref s) => 32 + 32 * s.len().div_ceil(32),
+		Token::Array(ref tokens) => 32 + encoded_size(tokens),
+		Token::FixedArray(ref tokens) | Token::Tuple(ref tokens) => encoded_size(tokens),
+		_ => 0,
 	}
 }
 
 #[cfg(test)]
 mod tests {
-	use crate::{encode, util::pad_u32, Token};
+	use crate::{
+		encode, encode_into, encode_packed, encoded_size, token_encoded_size, util::pad_u32, ParamType, Token, Uint,
+	};
 	use hex_literal::hex;
 
+	#[test]
+	fn encode_into_buffer() {
+		let tokens =
+			[Token::String("gavofyork".to_owned()), Token::Array(vec![Token::Bool(true)]), Token::Uint(1.into())];
+		let expected = encode(&tokens);
+
+		let mut buffer = vec![0xaa];
+		encode_into(&tokens, &mut buffer);
+		assert_eq!(buffer[0], 0xaa);
+		assert_eq!(buffer[1..], expected[..]);
+
+		let mut written = Vec::new();
+		crate::encode_to_writer(&tokens, &mut written).unwrap();
+		assert_eq!(written, expected);
+	}
+
 	#[test]
 	fn encode_address() {
 		let address = Token::Address([0x11u8; 20].into());
@@ -204,6 +287,39 @@ mod tests {
 		assert_eq!(encoded, expected);
 	}
 
+	#[test]
+	fn encode_dynamic_array_of_bools() {
+		let bools = Token::Array(vec![Token::Bool(true), Token::Bool(false), Token::Bool(true)]);
+		let encoded = encode(&[bools]);
+		let expected = hex!(
+			"
+			0000000000000000000000000000000000000000000000000000000000000020
+			0000000000000000000000000000000000000000000000000000000000000003
+			0000000000000000000000000000000000000000000000000000000000000001
+			0000000000000000000000000000000000000000000000000000000000000000
+			0000000000000000000000000000000000000000000000000000000000000001
+		"
+		)
+		.to_vec();
+		assert_eq!(encoded, expected);
+	}
+
+	#[test]
+	fn encode_fixed_array_of_bools() {
+		let bools = Token::FixedArray(vec![Token::Bool(false), Token::Bool(true), Token::Bool(false)]);
+		let encoded = encode(&[bools, Token::Uint(7.into())]);
+		let expected = hex!(
+			"
+			0000000000000000000000000000000000000000000000000000000000000000
+			0000000000000000000000000000000000000000000000000000000000000001
+			0000000000000000000000000000000000000000000000000000000000000000
+			0000000000000000000000000000000000000000000000000000000000000007
+		"
+		)
+		.to_vec();
+		assert_eq!(encoded, expected);
+	}
+
 	#[test]
 	fn encode_fixed_array_of_addresses() {
 		let address1 = Token::Address([0x11u8; 20].into());
@@ -560,7 +676,7 @@ mod tests {
 
 	#[test]
 	fn comprehensive_test2() {
-		let encoded = encode(&vec![
+		let encoded = encode(&[
 			Token::Int(1.into()),
 			Token::String("gavofyork".to_owned()),
 			Token::Int(2.into()),
@@ -812,4 +928,144 @@ mod tests {
 		.to_vec();
 		assert_eq!(encoded, expected);
 	}
+
+	#[test]
+	fn encoded_size_matches_encoding() {
+		let address = Token::Address([0x11u8; 20].into());
+		let bytes = Token::Bytes(vec![0x22; 33]);
+		let tokens = vec![
+			address.clone(),
+			Token::FixedBytes(vec![0x33; 40]),
+			Token::String("gavofyork".to_owned()),
+			Token::Array(vec![bytes.clone(), Token::Bytes(vec![])]),
+			Token::FixedArray(vec![address.clone(), address.clone()]),
+			Token::FixedArray(vec![bytes.clone(), bytes.clone()]),
+			Token::Tuple(vec![address.clone(), Token::Bool(true)]),
+			Token::Tuple(vec![address, bytes, Token::Array(vec![])]),
+		];
+
+		for token in tokens.chunks(1) {
+			assert_eq!(encoded_size(token), encode(token).len(), "{:?}", token);
+		}
+		assert_eq!(encoded_size(&tokens), encode(&tokens).len());
+	}
+
+	#[test]
+	fn token_encoded_size_sums_to_encoded_size() {
+		let types = [
+			ParamType::Uint(256),
+			ParamType::String,
+			ParamType::FixedArray(Box::new(ParamType::Tuple(vec![ParamType::Bool, ParamType::Address])), 2),
+			ParamType::Array(Box::new(ParamType::Bytes)),
+		];
+		let pair = Token::Tuple(vec![Token::Bool(true), Token::Address([0x11u8; 20].into())]);
+		let tokens = [
+			Token::Uint(1.into()),
+			Token::String("gavofyork".to_owned()),
+			Token::FixedArray(vec![pair.clone(), pair]),
+			Token::Array(vec![Token::Bytes(vec![0x22; 33]), Token::Bytes(vec![])]),
+		];
+
+		let sizes =
+			types.iter().zip(&tokens).map(|(param, token)| token_encoded_size(param, token)).collect::<Vec<_>>();
+		assert_eq!(sizes, vec![32, 96, 128, 32 + 32 + 64 + 96 + 32]);
+		assert_eq!(sizes.iter().sum::<usize>(), encoded_size(&tokens));
+		assert_eq!(sizes.iter().sum::<usize>(), encode(&tokens).len());
+	}
+
+	#[test]
+	#[cfg(feature = "checked-encode")]
+	fn encode_checked_accepts_valid_encodings() {
+		use crate::encode_checked;
+
+		let tokens = [
+			Token::Array(vec![Token::String("gavofyork".to_owned()), Token::String(String::new())]),
+			Token::Tuple(vec![Token::Bool(true), Token::Bytes(vec![0x12; 33])]),
+			Token::FixedArray(vec![Token::Array(vec![]), Token::Array(vec![Token::Uint(1.into())])]),
+			Token::FixedBytes(vec![0xab; 4]),
+		];
+		assert_eq!(encode_checked(&tokens).unwrap(), encode(&tokens));
+	}
+
+	#[test]
+	#[cfg(feature = "checked-encode")]
+	fn encode_checked_rejects_inconsistent_tokens() {
+		use crate::encode_checked;
+
+		// array elements of different shapes can not be decoded back
+		let tokens = [Token::Array(vec![Token::Uint(1.into()), Token::String("foo".to_owned())])];
+		assert!(encode_checked(&tokens).is_err());
+	}
+
+	#[test]
+	fn encode_nested_static_tuple_before_dynamic_param() {
+		let tokens = [
+			Token::Tuple(vec![Token::Uint(7.into()), Token::Tuple(vec![Token::Uint(3.into()), Token::Bool(true)])]),
+			Token::Bytes(vec![0x12]),
+		];
+		let encoded = encode(&tokens);
+		let expected = hex!(
+			"
+			0000000000000000000000000000000000000000000000000000000000000007
+			0000000000000000000000000000000000000000000000000000000000000003
+			0000000000000000000000000000000000000000000000000000000000000001
+			0000000000000000000000000000000000000000000000000000000000000080
+			0000000000000000000000000000000000000000000000000000000000000001
+			1200000000000000000000000000000000000000000000000000000000000000
+		"
+		)
+		.to_vec();
+		assert_eq!(encoded, expected);
+	}
+
+	#[test]
+	fn encode_packed_values() {
+		// abi.encodePacked(int16(-1), bytes1(0x42), uint16(0x03), string("Hello, world!"))
+		let params = [ParamType::Int(16), ParamType::FixedBytes(1), ParamType::Uint(16), ParamType::String];
+		let tokens = [
+			Token::Int(Uint::MAX),
+			Token::FixedBytes(vec![0x42]),
+			Token::Uint(3.into()),
+			Token::String("Hello, world!".to_owned()),
+		];
+		assert_eq!(encode_packed(&params, &tokens).unwrap(), hex!("ffff42000348656c6c6f2c20776f726c6421").to_vec());
+
+		let params = [ParamType::Address, ParamType::Bool, ParamType::Bytes];
+		let tokens = [Token::Address([0x11u8; 20].into()), Token::Bool(true), Token::Bytes(vec![0xab, 0xcd])];
+		assert_eq!(
+			encode_packed(&params, &tokens).unwrap(),
+			hex!("111111111111111111111111111111111111111101abcd").to_vec()
+		);
+	}
+
+	#[test]
+	fn encode_packed_array() {
+		// abi.encodePacked(uint8[] [1, 2])
+		let params = [ParamType::Array(Box::new(ParamType::Uint(8)))];
+		let tokens = [Token::Array(vec![Token::Uint(1.into()), Token::Uint(2.into())])];
+		let expected = hex!(
+			"
+			0000000000000000000000000000000000000000000000000000000000000001
+			0000000000000000000000000000000000000000000000000000000000000002
+		"
+		);
+		assert_eq!(encode_packed(&params, &tokens).unwrap(), expected.to_vec());
+	}
+
+	#[test]
+	fn encode_packed_rejects_unpackable() {
+		let tuple = ParamType::Tuple(vec![ParamType::Bool]);
+		assert!(encode_packed(&[tuple], &[Token::Tuple(vec![Token::Bool(true)])]).is_err());
+
+		let strings = ParamType::Array(Box::new(ParamType::String));
+		assert!(encode_packed(&[strings], &[Token::Array(vec![Token::String("a".to_owned())])]).is_err());
+
+		let nested = ParamType::Array(Box::new(ParamType::FixedArray(Box::new(ParamType::Bool), 1)));
+		assert!(encode_packed(&[nested], &[Token::Array(vec![])]).is_err());
+
+		// out of range for the width and wrong types
+		assert!(encode_packed(&[ParamType::Uint(8)], &[Token::Uint(256.into())]).is_err());
+		assert!(encode_packed(&[ParamType::Bool], &[Token::Uint(1.into())]).is_err());
+		assert!(encode_packed(&[ParamType::Bool], &[]).is_err());
+	}
 }
@@ -6,37 +6,144 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
+#[cfg(not(feature = "std"))]
+use crate::no_std_prelude::*;
+use crate::{ParamType, Token};
+use alloc::string;
 use anyhow::anyhow;
-use std::{num, string};
-use thiserror::Error;
+use core::num;
 
 /// Ethabi result type
-pub type Result<T> = std::result::Result<T, Error>;
+pub type Result<T> = core::result::Result<T, Error>;
 
 /// Ethabi errors
-#[derive(Debug, Error)]
+#[derive(Debug)]
+#[cfg_attr(feature = "std", derive(thiserror::Error))]
 pub enum Error {
 	/// Invalid entity such as a bad function name.
-	#[error("Invalid name: {0}")]
+	#[cfg_attr(feature = "std", error("Invalid name: {0}"))]
 	InvalidName(String),
 	/// Invalid data.
-	#[error("Invalid data")]
+	#[cfg_attr(feature = "std", error("Invalid data"))]
 	InvalidData,
+	/// Encoded data whose length is not a multiple of 32 bytes.
+	#[cfg_attr(feature = "std", error("Invalid data length {0}, expected a multiple of 32 bytes"))]
+	InvalidDataLength(usize),
+	/// Encoded data ending before a word the params need.
+	#[cfg_attr(feature = "std", error("Unexpected end of data: a word at byte {offset} is read from {len} bytes"))]
+	UnexpectedEnd {
+		/// Byte offset of the word.
+		offset: usize,
+		/// Length of the data.
+		len: usize,
+	},
+	/// Empty data decoded as params which cannot be empty, usually returned for a call to a
+	/// missing contract or function.
+	#[cfg_attr(feature = "std", error("Empty data: please ensure the contract and method you're calling exist"))]
+	EmptyData,
+	/// A token given as argument does not match the type of its param.
+	#[cfg_attr(feature = "std", error("Invalid argument {index} `{name}`: expected `{expected}`, got `{found}`"))]
+	InvalidArgument {
+		/// Position of the argument.
+		index: usize,
+		/// Name of the param, empty if it has none.
+		name: String,
+		/// Type of the param.
+		expected: ParamType,
+		/// Token given for the param.
+		found: Token,
+	},
+	/// No function of the contract has the name.
+	#[cfg_attr(feature = "std", error("Missing function `{0}`"))]
+	MissingFunction(String),
+	/// No event of the contract has the name.
+	#[cfg_attr(feature = "std", error("Missing event `{0}`"))]
+	MissingEvent(String),
+	/// No custom error of the contract has the name.
+	#[cfg_attr(feature = "std", error("Missing error `{0}`"))]
+	MissingError(String),
+	/// Log of a batch which could not be parsed.
+	#[cfg_attr(feature = "std", error("Invalid log {index}: {error}"))]
+	InvalidLog {
+		/// Position of the log in the batch.
+		index: usize,
+		/// Why the log could not be parsed.
+		error: Box<Error>,
+	},
+	/// An argument required to encode a call was not given.
+	#[cfg_attr(feature = "std", error("Missing argument `{0}`"))]
+	MissingArgument(String),
 	/// Serialization error.
-	#[error("Serialization error: {0}")]
-	SerdeJson(#[from] serde_json::Error),
+	#[cfg_attr(feature = "std", error("Serialization error: {0}"))]
+	SerdeJson(#[cfg_attr(feature = "std", from)] serde_json::Error),
 	/// Integer parsing error.
-	#[error("Integer parsing error: {0}")]
-	ParseInt(#[from] num::ParseIntError),
+	#[cfg_attr(feature = "std", error("Integer parsing error: {0}"))]
+	ParseInt(#[cfg_attr(feature = "std", from)] num::ParseIntError),
 	/// UTF-8 parsing error.
-	#[error("UTF-8 parsing error: {0}")]
-	Utf8(#[from] string::FromUtf8Error),
+	#[cfg_attr(feature = "std", error("UTF-8 parsing error: {0}"))]
+	Utf8(#[cfg_attr(feature = "std", from)] string::FromUtf8Error),
 	/// Hex string parsing error.
-	#[error("Hex parsing error: {0}")]
-	Hex(#[from] hex::FromHexError),
+	#[cfg_attr(feature = "std", error("Hex parsing error: {0}"))]
+	Hex(#[cfg_attr(feature = "std", from)] hex::FromHexError),
 	/// Other errors.
-	#[error("{0}")]
-	Other(#[from] anyhow::Error),
+	#[cfg_attr(feature = "std", error("{0}"))]
+	Other(#[cfg_attr(feature = "std", from)] anyhow::Error),
+}
+
+// `thiserror` needs `std`, so without it the conversions and messages are written out.
+#[cfg(not(feature = "std"))]
+mod no_std_impls {
+	use super::Error;
+	use core::fmt;
+
+	macro_rules! impl_from {
+		($($source: ty => $variant: ident,)*) => {
+			$(
+				impl From<$source> for Error {
+					fn from(err: $source) -> Self {
+						Error::$variant(err)
+					}
+				}
+			)*
+		};
+	}
+
+	impl_from! {
+		serde_json::Error => SerdeJson,
+		core::num::ParseIntError => ParseInt,
+		alloc::string::FromUtf8Error => Utf8,
+		hex::FromHexError => Hex,
+		anyhow::Error => Other,
+	}
+
+	impl fmt::Display for Error {
+		fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+			match self {
+				Error::InvalidName(name) => write!(f, "Invalid name: {}", name),
+				Error::InvalidData => write!(f, "Invalid data"),
+				Error::InvalidDataLength(len) => {
+					write!(f, "Invalid data length {}, expected a multiple of 32 bytes", len)
+				}
+				Error::UnexpectedEnd { offset, len } => {
+					write!(f, "Unexpected end of data: a word at byte {} is read from {} bytes", offset, len)
+				}
+				Error::EmptyData => write!(f, "Empty data: please ensure the contract and method you're calling exist"),
+				Error::InvalidArgument { index, name, expected, found } => {
+					write!(f, "Invalid argument {} `{}`: expected `{}`, got `{}`", index, name, expected, found)
+				}
+				Error::MissingFunction(name) => write!(f, "Missing function `{}`", name),
+				Error::MissingEvent(name) => write!(f, "Missing event `{}`", name),
+				Error::MissingError(name) => write!(f, "Missing error `{}`", name),
+				Error::InvalidLog { index, error } => write!(f, "Invalid log {}: {}", index, error),
+				Error::MissingArgument(name) => write!(f, "Missing argument `{}`", name),
+				Error::SerdeJson(err) => write!(f, "Serialization error: {}", err),
+				Error::ParseInt(err) => write!(f, "Integer parsing error: {}", err),
+				Error::Utf8(err) => write!(f, "UTF-8 parsing error: {}", err),
+				Error::Hex(err) => write!(f, "Hex parsing error: {}", err),
+				Error::Other(err) => write!(f, "{}", err),
+			}
+		}
+	}
 }
 
 impl From<uint::FromDecStrErr> for Error {
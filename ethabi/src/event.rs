@@ -8,13 +8,18 @@
 
 //! Contract event.
 
-use serde::Deserialize;
-use sha3::{Digest, Keccak256};
-use std::collections::HashMap;
+#[cfg(not(feature = "std"))]
+use crate::no_std_prelude::*;
+use alloc::collections::BTreeMap;
+use anyhow::anyhow;
+use ethereum_types::BloomInput;
+use serde::{ser::SerializeMap, Deserialize, Serialize, Serializer};
 
 use crate::{
-	decode, encode, signature::long_signature, Error, EventParam, Hash, Log, LogParam, ParamType, RawLog,
-	RawTopicFilter, Result, Token, Topic, TopicFilter,
+	decode, human_readable,
+	signature::{canonical_signature, long_signature},
+	Bloom, Error, EventParam, Hash, Log, LogParam, ParamType, RawLog, RawTopicFilter, Result, Token, Topic,
+	TopicFilter, TopicFilterBuilder,
 };
 
 /// Contract event.
@@ -25,10 +30,32 @@ pub struct Event {
 	/// Event input.
 	pub inputs: Vec<EventParam>,
 	/// If anonymous, event cannot be found using `from` filter.
+	///
+	/// Anonymous events have no signature topic, leaving room for a fourth indexed param.
+	#[serde(default)]
 	pub anonymous: bool,
 }
 
+impl Serialize for Event {
+	fn serialize<S: Serializer>(&self, serializer: S) -> core::result::Result<S::Ok, S::Error> {
+		let mut map = serializer.serialize_map(Some(4))?;
+		map.serialize_entry("type", "event")?;
+		map.serialize_entry("name", &self.name)?;
+		map.serialize_entry("inputs", &self.inputs)?;
+		map.serialize_entry("anonymous", &self.anonymous)?;
+		map.end()
+	}
+}
+
 impl Event {
+	/// Parses a Solidity event declaration, e.g.
+	/// `Transfer(address indexed from, address indexed to, uint256 value)`.
+	///
+	/// Parameter names are optional and a trailing `anonymous` marks the event as anonymous.
+	pub fn parse(sig: &str) -> Result<Event> {
+		human_readable::parse_event(sig)
+	}
+
 	/// Returns names of all params.
 	fn params_names(&self) -> Vec<String> {
 		self.inputs.iter().map(|p| p.name.clone()).collect()
@@ -39,64 +66,99 @@ impl Event {
 		self.inputs.iter().map(|p| p.kind.clone()).collect()
 	}
 
-	/// Returns all params of the event.
-	fn indexed_params(&self, indexed: bool) -> Vec<EventParam> {
-		self.inputs.iter().filter(|p| p.indexed == indexed).cloned().collect()
+	/// Returns the indexed params of the event, which are stored in the log topics.
+	pub fn indexed_params(&self) -> Vec<&EventParam> {
+		self.inputs.iter().filter(|p| p.indexed).collect()
+	}
+
+	/// Returns the non-indexed params of the event, which are stored in the log data.
+	pub fn data_params(&self) -> Vec<&EventParam> {
+		self.inputs.iter().filter(|p| !p.indexed).collect()
 	}
 
-	/// Event signature
+	/// Returns the canonical signature the event signature is the hash of,
+	/// e.g. `Transfer(address,address,uint256)`.
+	pub fn canonical_signature(&self) -> String {
+		canonical_signature(&self.name, &self.param_types())
+	}
+
+	/// Event signature.
+	///
+	/// The signature is hashed on every call, the fields being public, and so is it by
+	/// [`parse_log`](Self::parse_log). Hot paths should keep it, as
+	/// [`Interface::parse_log`](crate::Interface::parse_log) and the `TOPIC0` constants of the
+	/// generated code do.
 	pub fn signature(&self) -> Hash {
 		long_signature(&self.name, &self.param_types())
 	}
 
-	/// Creates topic filter
-	pub fn filter(&self, raw: RawTopicFilter) -> Result<TopicFilter> {
-		fn convert_token(token: Token, kind: &ParamType) -> Result<Hash> {
-			if !token.type_check(kind) {
-				return Err(Error::InvalidData);
-			}
-			let encoded = encode(&[token]);
-			if encoded.len() == 32 {
-				let mut data = [0u8; 32];
-				data.copy_from_slice(&encoded);
-				Ok(data.into())
-			} else {
-				Ok(Hash::from_slice(Keccak256::digest(&encoded).as_slice()))
-			}
+	/// Bloom of the first topic of the logs of the event, its signature, which the logs bloom of
+	/// a block contains if any of its logs is of the event. Empty for anonymous events.
+	///
+	/// Use [`TopicFilter::bloom`] to also account for the values of indexed params.
+	pub fn bloom(&self) -> Bloom {
+		match self.anonymous {
+			true => Bloom::default(),
+			false => Bloom::from(BloomInput::Raw(self.signature().as_bytes())),
 		}
+	}
 
-		fn convert_topic(topic: Topic<Token>, kind: Option<&ParamType>) -> Result<Topic<Hash>> {
+	/// Starts a topic filter given by the names of the indexed params, e.g.
+	/// `event.filter_builder().param_any_of("from", [alice, bob]).build()`.
+	pub fn filter_builder(&self) -> TopicFilterBuilder<'_> {
+		TopicFilterBuilder::new(self)
+	}
+
+	/// Computes the topic matching `value` for the indexed param `name`, as a filter on it needs.
+	///
+	/// Strings, bytes, arrays and tuples are hashed into their topic, the other types are their
+	/// word, see [`EventParam::encode_topic`].
+	pub fn topic_for(&self, name: &str, value: &Token) -> Result<Hash> {
+		let param = self
+			.inputs
+			.iter()
+			.find(|param| param.indexed && param.name == name)
+			.ok_or_else(|| anyhow!("`{}` is not an indexed param of `{}`", name, self.name))?;
+		param.encode_topic(value)
+	}
+
+	/// Creates topic filter
+	pub fn filter(&self, raw: RawTopicFilter) -> Result<TopicFilter> {
+		fn convert_topic(topic: Topic<Token>, param: Option<&EventParam>) -> Result<Topic<Hash>> {
 			match topic {
 				Topic::Any => Ok(Topic::Any),
-				Topic::OneOf(tokens) => match kind {
+				Topic::OneOf(tokens) => match param {
 					None => Err(Error::InvalidData),
-					Some(kind) => {
+					Some(param) => {
 						let topics =
-							tokens.into_iter().map(|token| convert_token(token, kind)).collect::<Result<Vec<_>>>()?;
+							tokens.iter().map(|token| param.encode_topic(token)).collect::<Result<Vec<_>>>()?;
 						Ok(Topic::OneOf(topics))
 					}
 				},
-				Topic::This(token) => match kind {
+				Topic::This(token) => match param {
 					None => Err(Error::InvalidData),
-					Some(kind) => Ok(Topic::This(convert_token(token, kind)?)),
+					Some(param) => Ok(Topic::This(param.encode_topic(&token)?)),
 				},
 			}
 		}
 
-		let kinds: Vec<_> = self.indexed_params(true).into_iter().map(|param| param.kind).collect();
+		let params = self.indexed_params();
 		let result = if self.anonymous {
 			TopicFilter {
-				topic0: convert_topic(raw.topic0, kinds.get(0))?,
-				topic1: convert_topic(raw.topic1, kinds.get(1))?,
-				topic2: convert_topic(raw.topic2, kinds.get(2))?,
-				topic3: Topic::Any,
+				topic0: convert_topic(raw.topic0, params.first().copied())?,
+				topic1: convert_topic(raw.topic1, params.get(1).copied())?,
+				topic2: convert_topic(raw.topic2, params.get(2).copied())?,
+				topic3: convert_topic(raw.topic3, params.get(3).copied())?,
 			}
 		} else {
+			if !raw.topic3.is_any() {
+				return Err(Error::InvalidData);
+			}
 			TopicFilter {
 				topic0: Topic::This(self.signature()),
-				topic1: convert_topic(raw.topic0, kinds.get(0))?,
-				topic2: convert_topic(raw.topic1, kinds.get(1))?,
-				topic3: convert_topic(raw.topic2, kinds.get(2))?,
+				topic1: convert_topic(raw.topic0, params.first().copied())?,
+				topic2: convert_topic(raw.topic1, params.get(1).copied())?,
+				topic3: convert_topic(raw.topic2, params.get(2).copied())?,
 			}
 		};
 
@@ -108,57 +170,83 @@ impl Event {
 	// these indexed param types according to
 	// https://solidity.readthedocs.io/en/develop/abi-spec.html#encoding-of-indexed-event-parameters
 	fn convert_topic_param_type(&self, kind: &ParamType) -> ParamType {
-		match kind {
-			ParamType::String
-			| ParamType::Bytes
-			| ParamType::Array(_)
-			| ParamType::FixedArray(_, _)
-			| ParamType::Tuple(_) => ParamType::FixedBytes(32),
-			_ => kind.clone(),
+		match kind.is_hashed_in_topic() {
+			true => ParamType::FixedBytes(32),
+			false => kind.clone(),
 		}
 	}
 
+	/// Decodes the non-indexed params of the event from the data portion of a log.
+	pub fn decode_data(&self, data: &[u8]) -> Result<Vec<Token>> {
+		let data_types = self.data_params().into_iter().map(|p| p.kind.clone()).collect::<Vec<ParamType>>();
+
+		decode(&data_types, data)
+	}
+
 	/// Parses `RawLog` and retrieves all log params from it.
+	///
+	/// Indexed params whose topic is the hash of their value, see
+	/// [`ParamType::is_hashed_in_topic`], are the hash as a 32 bytes `Token::FixedBytes`.
 	pub fn parse_log(&self, log: RawLog) -> Result<Log> {
-		let topics = log.topics;
-		let data = log.data;
-		let topics_len = topics.len();
-		// obtains all params info
-		let topic_params = self.indexed_params(true);
-		let data_params = self.indexed_params(false);
-		// then take first topic if event is not anonymous
-		let to_skip = if self.anonymous {
-			0
-		} else {
+		if !self.anonymous {
 			// verify
-			let event_signature = topics.get(0).ok_or(Error::InvalidData)?;
+			let event_signature = log.topics.first().ok_or(Error::InvalidData)?;
 			if event_signature != &self.signature() {
 				return Err(Error::InvalidData);
 			}
-			1
-		};
+		}
+		self.parse_log_params(&log)
+	}
+
+	/// Parses a batch of logs, e.g. those of a receipt, into one result per log.
+	///
+	/// Logs with another first topic than the signature of a non anonymous event are errors
+	/// without being decoded. Errors are [`Error::InvalidLog`], telling the position of the log.
+	pub fn parse_logs(&self, logs: &[RawLog]) -> Vec<Result<Log>> {
+		let signature = self.signature();
+		logs.iter()
+			.enumerate()
+			.map(|(index, log)| {
+				let parsed = match self.anonymous || log.topics.first() == Some(&signature) {
+					true => self.parse_log_params(log),
+					false => Err(anyhow!("log was not emitted by `{}`", self.name).into()),
+				};
+				parsed.map_err(|error| Error::InvalidLog { index, error: Box::new(error) })
+			})
+			.collect()
+	}
+
+	/// Parses the params of `log`, whose first topic was checked to be the signature of the
+	/// event unless it is anonymous.
+	pub(crate) fn parse_log_params(&self, log: &RawLog) -> Result<Log> {
+		let topics = &log.topics;
+		let data = &log.data;
+		let topics_len = topics.len();
+		// obtains all params info
+		let topic_params = self.indexed_params();
+		let data_params = self.data_params();
+		// then skip the signature if event is not anonymous
+		let to_skip = if self.anonymous { 0 } else { 1 };
 
 		let topic_types =
 			topic_params.iter().map(|p| self.convert_topic_param_type(&p.kind)).collect::<Vec<ParamType>>();
 
-		let flat_topics = topics.into_iter().skip(to_skip).flat_map(|t| t.as_ref().to_vec()).collect::<Vec<u8>>();
+		let flat_topics = topics.iter().skip(to_skip).flat_map(|t| t.as_ref().to_vec()).collect::<Vec<u8>>();
 
 		let topic_tokens = decode(&topic_types, &flat_topics)?;
 
 		// topic may be only a 32 bytes encoded token
-		if topic_tokens.len() != topics_len - to_skip {
+		if topic_tokens.len() != topics_len.saturating_sub(to_skip) {
 			return Err(Error::InvalidData);
 		}
 
-		let topics_named_tokens = topic_params.into_iter().map(|p| p.name).zip(topic_tokens.into_iter());
-
-		let data_types = data_params.iter().map(|p| p.kind.clone()).collect::<Vec<ParamType>>();
+		let topics_named_tokens = topic_params.into_iter().map(|p| p.name.clone()).zip(topic_tokens);
 
-		let data_tokens = decode(&data_types, &data)?;
+		let data_tokens = self.decode_data(data)?;
 
-		let data_named_tokens = data_params.into_iter().map(|p| p.name).zip(data_tokens.into_iter());
+		let data_named_tokens = data_params.into_iter().map(|p| p.name.clone()).zip(data_tokens);
 
-		let named_tokens = topics_named_tokens.chain(data_named_tokens).collect::<HashMap<String, Token>>();
+		let named_tokens = topics_named_tokens.chain(data_named_tokens).collect::<BTreeMap<String, Token>>();
 
 		let decoded_params = self
 			.params_names()
@@ -178,29 +266,123 @@ mod tests {
 		log::{Log, RawLog},
 		signature::long_signature,
 		token::Token,
-		Event, EventParam, LogParam, ParamType,
+		Bloom, Event, EventParam, Hash, LogParam, ParamType, RawTopicFilter, Topic,
 	};
+	use ethereum_types::BloomInput;
 	use hex_literal::hex;
 
+	#[test]
+	fn test_topic_for() {
+		let event = Event::parse("event Registered(string indexed name, address indexed owner, uint256 fee)").unwrap();
+		let name = Token::String("alice".to_owned());
+		let owner = Token::Address([0x11; 20].into());
+
+		// keccak256("alice")
+		let hash = hex!("9c0257114eb9399a2985f8e75dad7600c5d89fe3824ffa99ec1c3eb8bf3b0501");
+		assert_eq!(event.topic_for("name", &name).unwrap(), Hash::from(hash));
+		let mut word = [0u8; 32];
+		word[12..].copy_from_slice(&[0x11; 20]);
+		assert_eq!(event.topic_for("owner", &owner).unwrap(), Hash::from(word));
+
+		let filter = event.filter_builder().param("name", "alice".to_owned()).build().unwrap();
+		assert_eq!(filter.topic1, Topic::This(event.topic_for("name", &name).unwrap()));
+
+		assert_eq!(
+			event.topic_for("fee", &Token::Uint(1.into())).unwrap_err().to_string(),
+			"`fee` is not an indexed param of `Registered`"
+		);
+		assert!(event.topic_for("name", &owner).is_err());
+	}
+
+	#[test]
+	fn test_parse_logs() {
+		let event = Event::parse("event Paused(address account)").unwrap();
+		let data = crate::encode(&[Token::Address([0x11; 20].into())]);
+		let logs = [
+			RawLog { topics: vec![event.signature()], data: data.clone() },
+			RawLog { topics: vec![Hash::from_low_u64_be(1)], data },
+			RawLog { topics: vec![event.signature()], data: vec![0; 31] },
+		];
+
+		let parsed = event.parse_logs(&logs);
+		assert_eq!(parsed[0].as_ref().unwrap().params[0].value, Token::Address([0x11; 20].into()));
+		assert_eq!(parsed[1].as_ref().unwrap_err().to_string(), "Invalid log 1: log was not emitted by `Paused`");
+		assert!(matches!(
+			parsed[2],
+			Err(crate::Error::InvalidLog { index: 2, ref error }) if matches!(**error, crate::Error::InvalidDataLength(31))
+		));
+	}
+
+	#[test]
+	fn test_event_bloom() {
+		let mut event =
+			Event::parse("event Transfer(address indexed from, address indexed to, uint256 value)").unwrap();
+		let filter = event.filter_builder().param("to", crate::Address::from([0x11; 20])).build().unwrap();
+		assert_eq!(event.bloom(), Bloom::from(BloomInput::Raw(event.signature().as_bytes())));
+
+		let mut logs_bloom = event.bloom();
+		assert!(logs_bloom.contains_bloom(&event.bloom()));
+		assert!(!filter.may_match_bloom(&logs_bloom));
+		logs_bloom.accrue_bloom(&filter.bloom());
+		assert!(filter.may_match_bloom(&logs_bloom));
+
+		event.anonymous = true;
+		assert!(event.bloom().is_empty());
+	}
+
 	#[test]
 	fn test_decoding_event() {
 		let event = Event {
 			name: "foo".to_owned(),
 			inputs: vec![
-				EventParam { name: "a".to_owned(), kind: ParamType::Int(256), indexed: false },
-				EventParam { name: "b".to_owned(), kind: ParamType::Int(256), indexed: true },
-				EventParam { name: "c".to_owned(), kind: ParamType::Address, indexed: false },
-				EventParam { name: "d".to_owned(), kind: ParamType::Address, indexed: true },
-				EventParam { name: "e".to_owned(), kind: ParamType::String, indexed: true },
+				EventParam {
+					name: "a".to_owned(),
+					kind: ParamType::Int(256),
+					indexed: false,
+					internal_type: None,
+					components: vec![],
+				},
+				EventParam {
+					name: "b".to_owned(),
+					kind: ParamType::Int(256),
+					indexed: true,
+					internal_type: None,
+					components: vec![],
+				},
+				EventParam {
+					name: "c".to_owned(),
+					kind: ParamType::Address,
+					indexed: false,
+					internal_type: None,
+					components: vec![],
+				},
+				EventParam {
+					name: "d".to_owned(),
+					kind: ParamType::Address,
+					indexed: true,
+					internal_type: None,
+					components: vec![],
+				},
+				EventParam {
+					name: "e".to_owned(),
+					kind: ParamType::String,
+					indexed: true,
+					internal_type: None,
+					components: vec![],
+				},
 				EventParam {
 					name: "f".to_owned(),
 					kind: ParamType::Array(Box::new(ParamType::Int(256))),
 					indexed: true,
+					internal_type: None,
+					components: vec![],
 				},
 				EventParam {
 					name: "g".to_owned(),
 					kind: ParamType::FixedArray(Box::new(ParamType::Address), 5),
 					indexed: true,
+					internal_type: None,
+					components: vec![],
 				},
 			],
 			anonymous: false,
@@ -270,4 +452,163 @@ mod tests {
 			}
 		);
 	}
+
+	#[test]
+	fn test_parse_event_declaration() {
+		let event = Event::parse("Transfer(address indexed from, address indexed to, uint256 value)").unwrap();
+
+		assert_eq!(
+			event,
+			Event {
+				name: "Transfer".to_owned(),
+				inputs: vec![
+					EventParam {
+						name: "from".to_owned(),
+						kind: ParamType::Address,
+						indexed: true,
+						internal_type: None,
+						components: vec![]
+					},
+					EventParam {
+						name: "to".to_owned(),
+						kind: ParamType::Address,
+						indexed: true,
+						internal_type: None,
+						components: vec![]
+					},
+					EventParam {
+						name: "value".to_owned(),
+						kind: ParamType::Uint(256),
+						indexed: false,
+						internal_type: None,
+						components: vec![],
+					},
+				],
+				anonymous: false,
+			}
+		);
+
+		let filter = event.filter(Default::default()).unwrap();
+		let transfer: Hash = hex!("ddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef").into();
+		assert_eq!(filter.topic0, Topic::This(transfer));
+	}
+
+	#[test]
+	fn test_decode_data() {
+		let event = Event::parse("Transfer(address indexed from, address indexed to, uint256 value)").unwrap();
+		let data = hex!("00000000000000000000000000000000000000000000000000000000000003e8");
+
+		assert_eq!(event.decode_data(&data).unwrap(), vec![Token::Uint(1000.into())]);
+		assert!(event.decode_data(&data[..31]).is_err());
+	}
+
+	#[test]
+	fn test_anonymous_event() {
+		let event: Event =
+			serde_json::from_str(r#"{"name": "Foo", "inputs": [{"name": "a", "type": "uint256", "indexed": true}]}"#)
+				.unwrap();
+		assert!(!event.anonymous);
+
+		let event = Event::parse(
+			"Moved(address indexed a, address indexed b, address indexed c, uint256 indexed d, bool e) anonymous",
+		)
+		.unwrap();
+		let address = |byte: u8| Token::Address([byte; 20].into());
+		let topics = [address(1), address(2), address(3), Token::Uint(4.into())];
+		let log = RawLog {
+			topics: topics.iter().map(|token| Hash::from_slice(&crate::encode(core::slice::from_ref(token)))).collect(),
+			data: crate::encode(&[Token::Bool(true)]),
+		};
+		let parsed = event.parse_log(log.clone()).unwrap();
+		assert_eq!(parsed.param("a"), Some(&address(1)));
+		assert_eq!(parsed.param("d"), Some(&Token::Uint(4.into())));
+		assert_eq!(parsed.param("e"), Some(&Token::Bool(true)));
+
+		let filter = event
+			.filter(RawTopicFilter {
+				topic0: Topic::Any,
+				topic3: Topic::This(Token::Uint(4.into())),
+				..Default::default()
+			})
+			.unwrap();
+		assert_eq!(filter.topic0, Topic::Any);
+		assert_eq!(filter.topic3, Topic::This(log.topics[3]));
+
+		let named = Event::parse("Moved(address indexed a)").unwrap();
+		let fourth = RawTopicFilter { topic3: Topic::This(address(1)), ..Default::default() };
+		assert!(named.filter(fourth).is_err());
+	}
+
+	#[test]
+	fn test_filter_builder() {
+		let event = Event::parse("Transfer(address indexed from, address indexed to, uint256 value)").unwrap();
+		let alice = crate::Address::from([1u8; 20]);
+		let bob = crate::Address::from([2u8; 20]);
+		let topic = |address: crate::Address| format!("{:?}", Hash::from(address));
+
+		let filter = event.filter_builder().param("to", alice).param_any_of("from", [alice, bob]).build().unwrap();
+		let expected =
+			serde_json::json!([format!("{:?}", event.signature()), [topic(alice), topic(bob)], topic(alice), null]);
+		assert_eq!(serde_json::to_value(&filter).unwrap(), expected);
+
+		let err = event.filter_builder().param("value", 1u64).build().unwrap_err();
+		assert_eq!(err.to_string(), "`value` is not an indexed param of `Transfer`");
+	}
+
+	#[test]
+	fn test_indexed_and_data_params() {
+		let event = Event::parse("Transfer(address indexed from, address indexed to, uint256 value)").unwrap();
+
+		let indexed = event.indexed_params().into_iter().map(|p| p.name.as_str()).collect::<Vec<_>>();
+		let data = event.data_params().into_iter().map(|p| p.name.as_str()).collect::<Vec<_>>();
+		assert_eq!(indexed, vec!["from", "to"]);
+		assert_eq!(data, vec!["value"]);
+	}
+
+	#[test]
+	fn test_event_signature_ignores_names_and_indexed() {
+		let event = Event {
+			name: "Transfer".to_owned(),
+			inputs: vec![
+				EventParam {
+					name: "from".to_owned(),
+					kind: ParamType::Address,
+					indexed: true,
+					internal_type: None,
+					components: vec![],
+				},
+				EventParam {
+					name: "to".to_owned(),
+					kind: ParamType::Address,
+					indexed: true,
+					internal_type: None,
+					components: vec![],
+				},
+				EventParam {
+					name: "value".to_owned(),
+					kind: ParamType::Uint(256),
+					indexed: false,
+					internal_type: None,
+					components: vec![],
+				},
+			],
+			anonymous: false,
+		};
+		let unnamed = Event {
+			inputs: event
+				.inputs
+				.iter()
+				.map(|p| EventParam { name: "".to_owned(), indexed: false, ..p.clone() })
+				.collect(),
+			..event.clone()
+		};
+
+		let expected: Hash = hex!("ddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef").into();
+		assert_eq!(event.signature(), expected);
+		assert_eq!(unnamed.signature(), expected);
+		assert_eq!(
+			event.signature(),
+			long_signature("Transfer", &[ParamType::Address, ParamType::Address, ParamType::Uint(256)])
+		);
+	}
 }
@@ -8,12 +8,20 @@
 
 //! Event param specification.
 
-use crate::{ParamType, TupleParam};
+#[cfg(not(feature = "std"))]
+use crate::no_std_prelude::*;
+use crate::{
+	encode,
+	tuple_param::{serialize_param, with_components},
+	Error as AbiError, Hash, InternalType, ParamType, Result as AbiResult, Token, TupleParam,
+};
+use core::fmt;
 use serde::{
-	de::{Error, MapAccess, Visitor},
-	Deserialize, Deserializer,
+	de::{Error, IgnoredAny, MapAccess, Visitor},
+	ser::SerializeMap,
+	Deserialize, Deserializer, Serialize, Serializer,
 };
-use std::fmt;
+use sha3::{Digest, Keccak256};
 
 /// Event param specification.
 #[derive(Debug, Clone, PartialEq)]
@@ -24,6 +32,57 @@ pub struct EventParam {
 	pub kind: ParamType,
 	/// Indexed flag. If true, param is used to build block bloom.
 	pub indexed: bool,
+	/// Solidity type of the param as given by the `internalType` of the JSON ABI, e.g.
+	/// `struct Market.Order`, if the ABI has it.
+	pub internal_type: Option<String>,
+	/// Names and types of the components of a tuple param, possibly nested in arrays, empty for
+	/// other types.
+	pub components: Vec<TupleParam>,
+}
+
+impl EventParam {
+	/// Solidity type of the param, see [`InternalType`].
+	pub fn internal_type(&self) -> Option<InternalType<'_>> {
+		self.internal_type.as_deref().map(InternalType::parse)
+	}
+
+	/// Computes the log topic matching `value` for this param, as used by filters.
+	///
+	/// Value types are used directly as their word. Strings, bytes, arrays and tuples, see
+	/// [`ParamType::is_hashed_in_topic`], are stored as the keccak256 of their value: the contents of
+	/// strings and bytes, and the elements of arrays and tuples encoded in place, each padded
+	/// to 32 bytes and without lengths nor offsets.
+	pub fn encode_topic(&self, value: &Token) -> AbiResult<Hash> {
+		if !value.type_check(&self.kind) {
+			return Err(AbiError::InvalidData);
+		}
+
+		match self.kind.is_hashed_in_topic() {
+			true => Ok(Hash::from_slice(&Keccak256::digest(&topic_encoding(value, false)))),
+			false => Ok(Hash::from_slice(&encode(core::slice::from_ref(value)))),
+		}
+	}
+}
+
+/// Encodes `value` the way it is hashed into a topic, strings and bytes being padded to 32 bytes
+/// when `padded`, as they are within arrays and tuples.
+fn topic_encoding(value: &Token, padded: bool) -> Vec<u8> {
+	match value {
+		Token::String(string) => pad(string.as_bytes(), padded),
+		Token::Bytes(bytes) => pad(bytes, padded),
+		Token::Array(tokens) | Token::FixedArray(tokens) | Token::Tuple(tokens) => {
+			tokens.iter().flat_map(|token| topic_encoding(token, true)).collect()
+		}
+		_ => encode(core::slice::from_ref(value)),
+	}
+}
+
+fn pad(bytes: &[u8], padded: bool) -> Vec<u8> {
+	let mut result = bytes.to_vec();
+	if padded {
+		result.resize(bytes.len().div_ceil(32) * 32, 0);
+	}
+	result
 }
 
 impl<'a> Deserialize<'a> for EventParam {
@@ -35,6 +94,18 @@ impl<'a> Deserialize<'a> for EventParam {
 	}
 }
 
+impl Serialize for EventParam {
+	fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		let mut map = serializer.serialize_map(None)?;
+		serialize_param(&mut map, &self.name, &self.kind, &self.components)?;
+		map.serialize_entry("indexed", &self.indexed)?;
+		if let Some(ref internal_type) = self.internal_type {
+			map.serialize_entry("internalType", internal_type)?;
+		}
+		map.end()
+	}
+}
+
 struct EventParamVisitor;
 
 impl<'a> Visitor<'a> for EventParamVisitor {
@@ -52,6 +123,7 @@ impl<'a> Visitor<'a> for EventParamVisitor {
 		let mut kind = None;
 		let mut indexed = None;
 		let mut components = None;
+		let mut internal_type = None;
 
 		while let Some(ref key) = map.next_key::<String>()? {
 			match key.as_ref() {
@@ -71,8 +143,7 @@ impl<'a> Visitor<'a> for EventParamVisitor {
 					if components.is_some() {
 						return Err(Error::duplicate_field("components"));
 					}
-					let component: Vec<TupleParam> = map.next_value()?;
-					components = Some(component)
+					components = map.next_value::<Option<Vec<TupleParam>>>()?;
 				}
 				"indexed" => {
 					if indexed.is_some() {
@@ -80,26 +151,30 @@ impl<'a> Visitor<'a> for EventParamVisitor {
 					}
 					indexed = Some(map.next_value()?);
 				}
-				_ => {}
+				"internalType" => {
+					if internal_type.is_some() {
+						return Err(Error::duplicate_field("internalType"));
+					}
+					internal_type = map.next_value()?;
+				}
+				_ => {
+					map.next_value::<IgnoredAny>()?;
+				}
 			}
 		}
-		let name = name.ok_or_else(|| Error::missing_field("name"))?;
-		let kind = kind.ok_or_else(|| Error::missing_field("kind")).and_then(|param_type| {
-			if let ParamType::Tuple(_) = param_type {
-				let tuple_params = components.ok_or_else(|| Error::missing_field("components"))?;
-				Ok(ParamType::Tuple(tuple_params.into_iter().map(|param| param.kind).collect()))
-			} else {
-				Ok(param_type)
-			}
-		})?;
+		let name: String = name.ok_or_else(|| Error::missing_field("name"))?;
+		let kind = kind.ok_or_else(|| Error::missing_field("kind"))?;
+		let (kind, components) = with_components(kind, components, &name)?;
 		let indexed = indexed.unwrap_or(false);
-		Ok(EventParam { name, kind, indexed })
+		Ok(EventParam { name, kind, indexed, internal_type, components })
 	}
 }
 
 #[cfg(test)]
 mod tests {
-	use crate::{EventParam, ParamType};
+	use crate::{EventParam, Hash, InternalType, ParamType, Token, TupleParam};
+	use hex_literal::hex;
+	use sha3::{Digest, Keccak256};
 
 	#[test]
 	fn event_param_deserialization() {
@@ -111,8 +186,41 @@ mod tests {
 
 		let deserialized: EventParam = serde_json::from_str(s).unwrap();
 
-		assert_eq!(deserialized, EventParam { name: "foo".to_owned(), kind: ParamType::Address, indexed: true });
+		assert_eq!(
+			deserialized,
+			EventParam {
+				name: "foo".to_owned(),
+				kind: ParamType::Address,
+				indexed: true,
+				internal_type: None,
+				components: vec![]
+			}
+		);
 	}
+
+	#[test]
+	fn event_param_internal_type() {
+		let s = r#"{
+			"name": "order",
+			"type": "tuple",
+			"indexed": false,
+			"internalType": "struct Market.Order",
+			"components": [{ "name": "side", "type": "uint8", "internalType": "enum Market.Side" }]
+		}"#;
+
+		let deserialized: EventParam = serde_json::from_str(s).unwrap();
+		assert_eq!(
+			deserialized.internal_type(),
+			Some(InternalType::Struct { contract: Some("Market"), name: "Order" })
+		);
+		assert_eq!(
+			deserialized.components[0].internal_type(),
+			Some(InternalType::Enum { contract: Some("Market"), name: "Side" })
+		);
+		let serialized = serde_json::to_value(&deserialized).unwrap();
+		assert_eq!(serialized, serde_json::from_str::<serde_json::Value>(s).unwrap());
+	}
+
 	#[test]
 	fn event_param_tuple_deserialization() {
 		let s = r#"{
@@ -145,7 +253,101 @@ mod tests {
 				name: "foo".to_owned(),
 				kind: ParamType::Tuple(vec![ParamType::Uint(48), ParamType::Tuple(vec![ParamType::Address])]),
 				indexed: true,
+				components: vec![
+					TupleParam {
+						name: Some("amount".to_owned()),
+						kind: ParamType::Uint(48),
+						internal_type: None,
+						components: vec![]
+					},
+					TupleParam {
+						name: Some("things".to_owned()),
+						kind: ParamType::Tuple(vec![ParamType::Address]),
+						components: vec![TupleParam {
+							name: Some("baseTupleParam".to_owned()),
+							kind: ParamType::Address,
+							internal_type: None,
+							components: vec![],
+						}],
+						internal_type: None,
+					},
+				],
+				internal_type: None,
 			}
 		);
 	}
+
+	#[test]
+	fn event_param_encode_topic() {
+		let param = EventParam {
+			name: "from".to_owned(),
+			kind: ParamType::Address,
+			indexed: true,
+			internal_type: None,
+			components: vec![],
+		};
+		assert_eq!(
+			param.encode_topic(&Token::Address([0x11u8; 20].into())).unwrap(),
+			Hash::from(hex!("0000000000000000000000001111111111111111111111111111111111111111"))
+		);
+		assert!(param.encode_topic(&Token::Bool(true)).is_err());
+
+		let param = EventParam {
+			name: "ids".to_owned(),
+			kind: ParamType::Array(Box::new(ParamType::Bool)),
+			indexed: true,
+			internal_type: None,
+			components: vec![],
+		};
+		let value = Token::Array(vec![Token::Bool(true)]);
+		assert_eq!(
+			param.encode_topic(&value).unwrap(),
+			Hash::from_slice(&Keccak256::digest(&hex!(
+				"0000000000000000000000000000000000000000000000000000000000000001"
+			)))
+		);
+
+		// static arrays are hashed too, even when they fit in a word
+		let param = EventParam {
+			name: "id".to_owned(),
+			kind: ParamType::FixedArray(Box::new(ParamType::Uint(256)), 1),
+			indexed: true,
+			internal_type: None,
+			components: vec![],
+		};
+		let value = Token::FixedArray(vec![Token::Uint(1.into())]);
+		assert_eq!(
+			param.encode_topic(&value).unwrap(),
+			Hash::from_slice(&Keccak256::digest(&[0u8; 31].iter().chain(&[1]).copied().collect::<Vec<_>>()))
+		);
+	}
+
+	#[test]
+	fn event_param_encode_dynamic_topics() {
+		let param = EventParam {
+			name: "memo".to_owned(),
+			kind: ParamType::String,
+			indexed: true,
+			internal_type: None,
+			components: vec![],
+		};
+		assert_eq!(
+			param.encode_topic(&Token::String("abc".to_owned())).unwrap(),
+			Hash::from_slice(&Keccak256::digest(b"abc"))
+		);
+
+		// strings and bytes within arrays and tuples are padded, without their length
+		let param = EventParam {
+			name: "pairs".to_owned(),
+			kind: ParamType::Array(Box::new(ParamType::Tuple(vec![ParamType::Bytes, ParamType::Uint(8)]))),
+			indexed: true,
+			internal_type: None,
+			components: vec![],
+		};
+		let value = Token::Array(vec![Token::Tuple(vec![Token::Bytes(vec![0xab; 33]), Token::Uint(2.into())])]);
+		let mut expected = vec![0xab; 33];
+		expected.resize(64, 0);
+		expected.extend_from_slice(&hex!("0000000000000000000000000000000000000000000000000000000000000002"));
+		assert_eq!(param.encode_topic(&value).unwrap(), Hash::from_slice(&Keccak256::digest(&expected)));
+	}
 }
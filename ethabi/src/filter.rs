@@ -6,10 +6,14 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
-use crate::{Hash, Token};
+#[cfg(not(feature = "std"))]
+use crate::no_std_prelude::*;
+use crate::{Bloom, Error, Event, Hash, Result, Token, Tokenizable};
+use anyhow::anyhow;
+use core::ops;
+use ethereum_types::BloomInput;
 use serde::{Serialize, Serializer};
 use serde_json::Value;
-use std::ops;
 
 /// Raw topic filter.
 #[derive(Debug, PartialEq, Default)]
@@ -20,6 +24,8 @@ pub struct RawTopicFilter {
 	pub topic1: Topic<Token>,
 	/// Topic.
 	pub topic2: Topic<Token>,
+	/// Fourth indexed param, only anonymous events have room for it.
+	pub topic3: Topic<Token>,
 }
 
 /// Topic filter.
@@ -35,19 +41,52 @@ pub struct TopicFilter {
 	pub topic3: Topic<Hash>,
 }
 
+impl TopicFilter {
+	fn topics(&self) -> [&Topic<Hash>; 4] {
+		[&self.topic0, &self.topic1, &self.topic2, &self.topic3]
+	}
+
+	/// Bloom of the topics matching a single hash, which the logs bloom of a block contains if
+	/// any of its logs matches the filter.
+	pub fn bloom(&self) -> Bloom {
+		let mut bloom = Bloom::default();
+		for topic in self.topics() {
+			if let Topic::This(ref hash) = *topic {
+				bloom.accrue(BloomInput::Raw(hash.as_bytes()));
+			}
+		}
+		bloom
+	}
+
+	/// Whether a block whose logs bloom is `logs_bloom` may contain a log matching the filter,
+	/// which must be fetched to tell for sure.
+	///
+	/// Unlike [`TopicFilter::bloom`], the topics matching any of several hashes are checked too.
+	pub fn may_match_bloom(&self, logs_bloom: &Bloom) -> bool {
+		logs_bloom.contains_bloom(&self.bloom())
+			&& self.topics().iter().all(|topic| match **topic {
+				Topic::OneOf(ref hashes) if !hashes.is_empty() => {
+					hashes.iter().any(|hash| logs_bloom.contains_input(BloomInput::Raw(hash.as_bytes())))
+				}
+				_ => true,
+			})
+	}
+}
+
 impl Serialize for TopicFilter {
-	fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+	fn serialize<S>(&self, serializer: S) -> core::result::Result<S::Ok, S::Error>
 	where
 		S: Serializer,
 	{
-		vec![&self.topic0, &self.topic1, &self.topic2, &self.topic3].serialize(serializer)
+		self.topics().serialize(serializer)
 	}
 }
 
 /// Acceptable topic possibilities.
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Default)]
 pub enum Topic<T> {
 	/// Match any.
+	#[default]
 	Any,
 	/// Match any of the hashes.
 	OneOf(Vec<T>),
@@ -77,12 +116,6 @@ impl<T> Topic<T> {
 	}
 }
 
-impl<T> Default for Topic<T> {
-	fn default() -> Self {
-		Topic::Any
-	}
-}
-
 impl<T> From<Option<T>> for Topic<T> {
 	fn from(o: Option<T>) -> Self {
 		match o {
@@ -104,9 +137,9 @@ impl<T> From<Vec<T>> for Topic<T> {
 	}
 }
 
-impl<T> Into<Vec<T>> for Topic<T> {
-	fn into(self) -> Vec<T> {
-		match self {
+impl<T> From<Topic<T>> for Vec<T> {
+	fn from(topic: Topic<T>) -> Self {
+		match topic {
 			Topic::Any => vec![],
 			Topic::This(topic) => vec![topic],
 			Topic::OneOf(topics) => topics,
@@ -115,7 +148,7 @@ impl<T> Into<Vec<T>> for Topic<T> {
 }
 
 impl Serialize for Topic<Hash> {
-	fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+	fn serialize<S>(&self, serializer: S) -> core::result::Result<S::Ok, S::Error>
 	where
 		S: Serializer,
 	{
@@ -131,6 +164,52 @@ impl Serialize for Topic<Hash> {
 	}
 }
 
+/// Builds the topic filter of an event by the names of its indexed params, see
+/// [`Event::filter_builder`].
+#[derive(Debug)]
+pub struct TopicFilterBuilder<'a> {
+	event: &'a Event,
+	topics: [Topic<Token>; 4],
+	error: Option<Error>,
+}
+
+impl<'a> TopicFilterBuilder<'a> {
+	pub(crate) fn new(event: &'a Event) -> Self {
+		TopicFilterBuilder { event, topics: Default::default(), error: None }
+	}
+
+	/// Matches logs whose indexed param `name` is `value`.
+	pub fn param<T: Tokenizable>(self, name: &str, value: T) -> Self {
+		self.set(name, Topic::This(value.into_token()))
+	}
+
+	/// Matches logs whose indexed param `name` is any of `values`.
+	pub fn param_any_of<T: Tokenizable, I: IntoIterator<Item = T>>(self, name: &str, values: I) -> Self {
+		self.set(name, Topic::OneOf(values.into_iter().map(Tokenizable::into_token).collect()))
+	}
+
+	/// Encodes the values into the topics, matching any value for the params not given.
+	pub fn build(self) -> Result<TopicFilter> {
+		if let Some(error) = self.error {
+			return Err(error);
+		}
+		let [topic0, topic1, topic2, topic3] = self.topics;
+		self.event.filter(RawTopicFilter { topic0, topic1, topic2, topic3 })
+	}
+
+	fn set(mut self, name: &str, topic: Topic<Token>) -> Self {
+		let position = self.event.inputs.iter().filter(|param| param.indexed).position(|param| param.name == name);
+		match position {
+			Some(position) => self.topics[position] = topic,
+			None => {
+				let error = anyhow!("`{}` is not an indexed param of `{}`", name, self.event.name);
+				self.error.get_or_insert(error.into());
+			}
+		}
+		self
+	}
+}
+
 impl<T> ops::Index<usize> for Topic<T> {
 	type Output = T;
 
@@ -151,9 +230,10 @@ impl<T> ops::Index<usize> for Topic<T> {
 #[cfg(test)]
 mod tests {
 	use super::{Topic, TopicFilter};
-	use crate::Hash;
+	use crate::{Bloom, Hash};
+	use ethereum_types::BloomInput;
 
-	fn hash(s: &'static str) -> Hash {
+	fn hash(s: &str) -> Hash {
 		s.parse().unwrap()
 	}
 
@@ -175,6 +255,26 @@ mod tests {
 		assert_eq!(expected, &topic_str);
 	}
 
+	#[test]
+	fn test_topic_filter_bloom() {
+		let (signature, alice, bob) = (hash(&"11".repeat(32)), hash(&"22".repeat(32)), hash(&"33".repeat(32)));
+		let filter = TopicFilter {
+			topic0: Topic::This(signature),
+			topic1: Topic::Any,
+			topic2: Topic::OneOf(vec![alice, bob]),
+			topic3: Topic::Any,
+		};
+		assert_eq!(filter.bloom(), Bloom::from(BloomInput::Raw(signature.as_bytes())));
+
+		let mut logs_bloom = Bloom::default();
+		assert!(!filter.may_match_bloom(&logs_bloom));
+		logs_bloom.accrue(BloomInput::Raw(signature.as_bytes()));
+		assert!(!filter.may_match_bloom(&logs_bloom));
+		logs_bloom.accrue(BloomInput::Raw(bob.as_bytes()));
+		assert!(filter.may_match_bloom(&logs_bloom));
+		assert!(TopicFilter::default().may_match_bloom(&Bloom::default()));
+	}
+
 	#[test]
 	fn test_topic_from() {
 		assert_eq!(Topic::Any as Topic<u64>, None.into());
@@ -8,13 +8,27 @@
 
 //! Contract function call builder.
 
-use std::string::ToString;
+#[cfg(not(feature = "std"))]
+use crate::no_std_prelude::*;
+use alloc::string::ToString;
+use core::convert::TryFrom;
 
-use crate::{decode, encode, signature::short_signature, Bytes, Error, Param, ParamType, Result, Token};
-use serde::Deserialize;
+use crate::{
+	decode, encode,
+	encoder::param_head_size,
+	human_readable,
+	signature::{canonical_signature, short_signature},
+	token::{LenientTokenizer, Tokenizer},
+	util::{decode_prefixed_hex, encode_prefixed_hex},
+	Bytes, DecodedParams, Decoder, Error, Param, ParamType, Result, StateMutability, Token, Uint,
+};
+use anyhow::anyhow;
+use serde::{de::DeserializeOwned, ser::SerializeMap, Deserialize, Serialize, Serializer};
+use serde_json::{Map, Value};
 
 /// Contract function specification.
 #[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(try_from = "RawFunction")]
 pub struct Function {
 	/// Function name.
 	pub name: String,
@@ -22,12 +36,92 @@ pub struct Function {
 	pub inputs: Vec<Param>,
 	/// Function output.
 	pub outputs: Vec<Param>,
-	/// Constant function.
-	#[serde(default)]
+	/// Constant function, true for `pure` and `view` functions.
 	pub constant: bool,
+	/// Function state mutability.
+	pub state_mutability: StateMutability,
+}
+
+/// Function as found in the JSON ABI, with either the legacy `constant` and `payable` flags,
+/// the `stateMutability` or both.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RawFunction {
+	name: String,
+	inputs: Vec<Param>,
+	outputs: Vec<Param>,
+	constant: Option<bool>,
+	payable: Option<bool>,
+	state_mutability: Option<StateMutability>,
+}
+
+impl TryFrom<RawFunction> for Function {
+	type Error = String;
+
+	fn try_from(raw: RawFunction) -> core::result::Result<Self, Self::Error> {
+		// `stateMutability` supersedes the legacy flags when both are present
+		let state_mutability = match raw.state_mutability {
+			Some(state_mutability) => state_mutability,
+			None => StateMutability::from_legacy(raw.constant.unwrap_or(false), raw.payable.unwrap_or(false))
+				.ok_or_else(|| format!("function `{}` can not be both constant and payable", raw.name))?,
+		};
+
+		Ok(Function {
+			name: raw.name,
+			inputs: raw.inputs,
+			outputs: raw.outputs,
+			constant: state_mutability.is_constant(),
+			state_mutability,
+		})
+	}
+}
+
+impl Serialize for Function {
+	fn serialize<S: Serializer>(&self, serializer: S) -> core::result::Result<S::Ok, S::Error> {
+		let mut map = serializer.serialize_map(Some(5))?;
+		map.serialize_entry("type", "function")?;
+		map.serialize_entry("name", &self.name)?;
+		map.serialize_entry("inputs", &self.inputs)?;
+		map.serialize_entry("outputs", &self.outputs)?;
+		map.serialize_entry("stateMutability", &self.state_mutability)?;
+		map.end()
+	}
 }
 
 impl Function {
+	/// Parses a function signature, e.g. `transfer(address to, uint256 amount) returns (bool)`.
+	///
+	/// Parameter names, visibility and mutability keywords are optional. Functions declared
+	/// `view`, `pure` or `constant` are marked as constant.
+	pub fn parse(sig: &str) -> Result<Function> {
+		human_readable::parse_function(sig)
+	}
+
+	/// Returns the 4 bytes selector of the function.
+	///
+	/// The selector is hashed on every call, the fields being public, and so is it by
+	/// [`encode_input`](Self::encode_input). Hot paths should keep it, as
+	/// [`Interface::encode_call`](crate::Interface::encode_call) and the `SELECTOR` constants of the
+	/// generated code do.
+	pub fn short_signature(&self) -> [u8; 4] {
+		short_signature(&self.name, &self.input_param_types())
+	}
+
+	/// Returns the 4 bytes selector of the function, same as [`short_signature`](Function::short_signature).
+	pub fn selector(&self) -> [u8; 4] {
+		self.short_signature()
+	}
+
+	/// Returns the canonical signature the selector is the hash of, e.g. `transfer(address,uint256)`.
+	pub fn canonical_signature(&self) -> String {
+		canonical_signature(&self.name, &self.input_param_types())
+	}
+
+	/// Returns true if the selector of the function is one of `allowed`.
+	pub fn selector_in(&self, allowed: &[[u8; 4]]) -> bool {
+		allowed.contains(&self.short_signature())
+	}
+
 	/// Returns all input params of given function.
 	fn input_param_types(&self) -> Vec<ParamType> {
 		self.inputs.iter().map(|p| p.kind.clone()).collect()
@@ -39,26 +133,189 @@ impl Function {
 	}
 
 	/// Prepares ABI function call with given input params.
+	///
+	/// Fails with [`Error::InvalidArgument`] for the first token not matching its param type.
+	/// Integers must also fit the bit size of their type and fixed bytes have its exact size.
 	pub fn encode_input(&self, tokens: &[Token]) -> Result<Bytes> {
+		self.encode_input_with_selector(self.short_signature(), tokens)
+	}
+
+	/// Prepares ABI function call with given input params, prefixed with the already computed
+	/// `selector` of the function.
+	pub(crate) fn encode_input_with_selector(&self, selector: [u8; 4], tokens: &[Token]) -> Result<Bytes> {
 		let params = self.input_param_types();
 
-		if !Token::types_check(tokens, &params) {
-			return Err(Error::InvalidData);
+		if tokens.len() != params.len() {
+			return Err(anyhow!("`{}` expects {} arguments, got {}", self.name, params.len(), tokens.len()).into());
+		}
+		if let Some(index) = tokens.iter().zip(&params).position(|(token, param)| !token.type_check(param)) {
+			return Err(Error::InvalidArgument {
+				index,
+				name: self.inputs[index].name.clone(),
+				expected: params[index].clone(),
+				found: tokens[index].clone(),
+			});
+		}
+		if let Some(index) = tokens.iter().zip(&params).position(|(token, param)| !in_range(param, token)) {
+			let param = &self.inputs[index];
+			return Err(anyhow!("argument {} `{}` is out of range for `{}`", index, param.name, param.kind).into());
 		}
 
-		let signed = short_signature(&self.name, &params).to_vec();
 		let encoded = encode(tokens);
-		Ok(signed.into_iter().chain(encoded.into_iter()).collect())
+		Ok(selector.iter().copied().chain(encoded).collect())
+	}
+
+	/// Prepares ABI function call with given input params, as `0x` prefixed hex.
+	pub fn encode_input_hex(&self, tokens: &[Token]) -> Result<String> {
+		self.encode_input(tokens).map(|data| encode_prefixed_hex(&data))
+	}
+
+	/// Checks that `tokens` would encode cleanly as the inputs of this function, without
+	/// encoding them.
+	///
+	/// Every argument is type checked and integers are checked to fit the bit size of their
+	/// param type. All errors are returned, not just the first one.
+	pub fn validate_inputs(&self, tokens: &[Token]) -> core::result::Result<(), Vec<Error>> {
+		let mut errors = Vec::new();
+		if tokens.len() != self.inputs.len() {
+			errors
+				.push(anyhow!("`{}` expects {} arguments, got {}", self.name, self.inputs.len(), tokens.len()).into());
+		}
+
+		for (i, (param, token)) in self.inputs.iter().zip(tokens).enumerate() {
+			if !token.type_check(&param.kind) {
+				errors.push(anyhow!("argument {} `{}` is not a valid `{}`", i, param.name, param.kind).into());
+			} else if !in_range(&param.kind, token) {
+				errors.push(anyhow!("argument {} `{}` is out of range for `{}`", i, param.name, param.kind).into());
+			}
+		}
+
+		match errors.is_empty() {
+			true => Ok(()),
+			false => Err(errors),
+		}
 	}
 
 	/// Parses the ABI function output to list of tokens.
 	pub fn decode_output(&self, data: &[u8]) -> Result<Vec<Token>> {
-		decode(&self.output_param_types(), &data)
+		decode(&self.output_param_types(), data)
+	}
+
+	/// Parses the ABI function output like [`decode_output`](Self::decode_output), but accepts the
+	/// non conforming output of some proxies and older contracts.
+	///
+	/// This is lossy: missing trailing static outputs are filled with zero tokens, which cannot be
+	/// told apart from actual zeros, and data after the outputs is ignored. A truncated final word is
+	/// zero-extended. Missing dynamic outputs and malformed outputs are still errors.
+	pub fn decode_output_lenient(&self, data: &[u8]) -> Result<Vec<Token>> {
+		let types = self.output_param_types();
+		let mut padded = data.to_vec();
+		padded.resize(data.len().next_multiple_of(32), 0);
+
+		let mut tokens = Vec::with_capacity(types.len());
+		let mut missing = Error::EmptyData;
+		if !padded.is_empty() {
+			for token in Decoder::new(&types, &padded)? {
+				match token {
+					Ok(token) => tokens.push(token),
+					Err(err @ Error::UnexpectedEnd { .. }) => {
+						missing = err;
+						break;
+					}
+					Err(err) => return Err(err),
+				}
+			}
+		}
+		for kind in &types[tokens.len()..] {
+			match zero_token(kind) {
+				Some(token) => tokens.push(token),
+				None => return Err(missing),
+			}
+		}
+		Ok(tokens)
+	}
+
+	/// Parses the ABI function output to tokens accessible by output name.
+	pub fn decode_output_named(&self, data: &[u8]) -> Result<DecodedParams> {
+		let tokens = self.decode_output(data)?;
+		Ok(DecodedParams::new(self.outputs.iter().map(|param| param.name.clone()), tokens))
+	}
+
+	/// Parses the `0x` prefixed hex ABI function output, e.g. the result of an `eth_call`.
+	pub fn decode_output_hex(&self, data: &str) -> Result<Vec<Token>> {
+		self.decode_output(&decode_prefixed_hex(data)?)
+	}
+
+	/// Parses the ABI function output and deserializes it into `T`.
+	///
+	/// The outputs are first converted into a JSON object keyed by output name (or position
+	/// for unnamed outputs) using the following mapping:
+	/// - `address`, `bytes` and `bytesN` become `0x`-prefixed hex strings,
+	/// - `intN` and `uintN` become decimal strings, since they often exceed JSON number precision,
+	/// - `bool` and `string` map to the JSON equivalents,
+	/// - arrays and tuples become JSON arrays.
+	pub fn decode_output_as<T: DeserializeOwned>(&self, data: &[u8]) -> Result<T> {
+		let tokens = self.decode_output(data)?;
+		let object = self
+			.outputs
+			.iter()
+			.zip(tokens)
+			.enumerate()
+			.map(|(index, (param, token))| {
+				let name = if param.name.is_empty() { index.to_string() } else { param.name.clone() };
+				(name, token_to_json(token))
+			})
+			.collect::<Map<_, _>>();
+
+		serde_json::from_value(Value::Object(object)).map_err(From::from)
 	}
 
 	/// Parses the ABI function input to a list of tokens.
 	pub fn decode_input(&self, data: &[u8]) -> Result<Vec<Token>> {
-		decode(&self.input_param_types(), &data)
+		decode(&self.input_param_types(), data)
+	}
+
+	/// Parses call `data`, selector followed by the arguments, checking that it is a call to
+	/// this function.
+	pub fn decode_call(&self, data: &[u8]) -> Result<Vec<Token>> {
+		if data.len() < 4 {
+			return Err(anyhow!("calldata of {} bytes is shorter than a selector", data.len()).into());
+		}
+
+		let (selector, args) = data.split_at(4);
+		if selector != self.short_signature() {
+			return Err(anyhow!(
+				"selector 0x{} is not the one of `{}`",
+				hex::encode(selector),
+				self.canonical_signature()
+			)
+			.into());
+		}
+		self.decode_input(args)
+	}
+
+	/// Cheaply checks that the length of the call `data` (selector followed by the arguments)
+	/// is consistent with the function inputs, without decoding it.
+	///
+	/// Calldata of functions with only static inputs must have exactly the size of the
+	/// encoded inputs. With dynamic inputs only the size of the heads is a lower bound.
+	pub fn validate_calldata_len(&self, data: &[u8]) -> Result<()> {
+		let heads_len = 4 + self.inputs.iter().map(|p| param_head_size(&p.kind)).sum::<usize>();
+		let is_dynamic = self.inputs.iter().any(|p| p.kind.is_dynamic());
+
+		match (data.len(), is_dynamic) {
+			(len, false) if len != heads_len => {
+				Err(anyhow!("calldata of `{}` must be {} bytes long, got {}", self.name, heads_len, len).into())
+			}
+			(len, true) if len < heads_len || (len - 4) % 32 != 0 => Err(anyhow!(
+				"calldata of `{}` must be at least {} bytes long and word aligned, got {}",
+				self.name,
+				heads_len,
+				len
+			)
+			.into()),
+			_ => Ok(()),
+		}
 	}
 
 	/// Returns a signature that uniquely identifies this function.
@@ -80,21 +337,189 @@ impl Function {
 	}
 }
 
+/// Decodes `data`, without selector, as the types of a signature like `(uint256,address)`.
+///
+/// A function name before the type list is ignored, e.g. `transfer(address,uint256)`.
+pub fn decode_signature(signature: &str, data: &[u8]) -> Result<Vec<Token>> {
+	decode(&human_readable::parse_types(signature)?, data)
+}
+
+/// Encodes a call to the function with the given signature, e.g. `transfer(address,uint256)`,
+/// tokenizing the arguments leniently.
+pub fn encode_call(signature: &str, args: &[&str]) -> Result<Bytes> {
+	let function = Function::parse(signature)?;
+	if function.inputs.len() != args.len() {
+		return Err(anyhow!(
+			"function `{}` expects {} arguments, got {}",
+			function.name,
+			function.inputs.len(),
+			args.len()
+		)
+		.into());
+	}
+
+	let tokens = function
+		.inputs
+		.iter()
+		.zip(args)
+		.enumerate()
+		.map(|(index, (param, arg))| {
+			LenientTokenizer::tokenize(&param.kind, arg)
+				.map_err(|e| anyhow!("failed to parse argument {} `{}` as `{}`: {}", index, arg, param.kind, e).into())
+		})
+		.collect::<Result<Vec<_>>>()?;
+
+	function.encode_input(&tokens)
+}
+
+/// Decodes the call `data` (selector followed by the arguments) of `function` into a
+/// human-readable string like `transfer(to: 0x1111…, amount: 1000)`.
+///
+/// Integers are rendered in decimal, addresses and bytes as `0x`-prefixed hex, strings
+/// quoted, arrays in brackets and tuples inline in parentheses.
+pub fn format_call(function: &Function, data: &[u8]) -> Result<String> {
+	let params = function.input_param_types();
+	if data.len() < 4 || data[..4] != short_signature(&function.name, &params) {
+		return Err(anyhow!("call data does not start with the selector of `{}`", function.name).into());
+	}
+
+	let args = function
+		.inputs
+		.iter()
+		.zip(decode(&params, &data[4..])?)
+		.map(|(param, token)| match param.name.as_str() {
+			"" => format_token(&token),
+			name => format!("{}: {}", name, format_token(&token)),
+		})
+		.collect::<Vec<_>>();
+
+	Ok(format!("{}({})", function.name, args.join(", ")))
+}
+
+pub(crate) fn format_token(token: &Token) -> String {
+	match *token {
+		Token::Address(ref address) => format!("{:?}", address),
+		Token::Bytes(ref bytes) | Token::FixedBytes(ref bytes) => format!("0x{}", hex::encode(bytes)),
+		Token::Uint(ref uint) => uint.to_string(),
+		Token::Int(ref int) if int.bit(255) => format!("-{}", !*int + 1),
+		Token::Int(ref int) => int.to_string(),
+		Token::Bool(b) => b.to_string(),
+		Token::String(ref s) => format!("{:?}", s),
+		Token::Array(ref tokens) | Token::FixedArray(ref tokens) => {
+			format!("[{}]", tokens.iter().map(format_token).collect::<Vec<_>>().join(", "))
+		}
+		Token::Tuple(ref tokens) => format!("({})", tokens.iter().map(format_token).collect::<Vec<_>>().join(", ")),
+	}
+}
+
+/// Returns whether the integers of a type checked `token` fit the bit size of `kind`, and its
+/// fixed bytes have the exact size of their type.
+pub(crate) fn in_range(kind: &ParamType, token: &Token) -> bool {
+	match (kind, token) {
+		(ParamType::FixedBytes(size), Token::FixedBytes(bytes)) => bytes.len() == *size,
+		(ParamType::Uint(size), Token::Uint(uint)) | (ParamType::Ufixed(size, _), Token::Uint(uint)) => {
+			*size >= 256 || uint.bits() <= *size
+		}
+		(ParamType::Int(size), Token::Int(int)) | (ParamType::Fixed(size, _), Token::Int(int)) => {
+			// the value must be the sign extension of its lowest `size` bits
+			match *size {
+				0 => int.is_zero(),
+				size if size >= 256 => true,
+				size => {
+					let high = *int >> (size - 1);
+					high.is_zero() || high == Uint::MAX >> (size - 1)
+				}
+			}
+		}
+		(ParamType::Array(kind), Token::Array(tokens))
+		| (ParamType::FixedArray(kind, _), Token::FixedArray(tokens)) => tokens.iter().all(|token| in_range(kind, token)),
+		(ParamType::Tuple(kinds), Token::Tuple(tokens)) => {
+			kinds.iter().zip(tokens).all(|(kind, token)| in_range(kind, token))
+		}
+		_ => true,
+	}
+}
+
+pub(crate) fn token_to_json(token: Token) -> Value {
+	match token {
+		Token::Address(address) => format!("{:?}", address).into(),
+		Token::Bytes(bytes) | Token::FixedBytes(bytes) => format!("0x{}", hex::encode(bytes)).into(),
+		Token::Uint(uint) => uint.to_string().into(),
+		Token::Int(int) if int.bit(255) => format!("-{}", !int + 1).into(),
+		Token::Int(int) => int.to_string().into(),
+		Token::Bool(b) => b.into(),
+		Token::String(s) => s.into(),
+		Token::Array(tokens) | Token::FixedArray(tokens) | Token::Tuple(tokens) => {
+			tokens.into_iter().map(token_to_json).collect()
+		}
+	}
+}
+
+/// Converts a JSON `value` into a token of `kind`, the other way around of [`token_to_json`].
+///
+/// Arrays and tuples are JSON arrays, integers are numbers or decimal or `0x` prefixed hex
+/// strings, and the other types are strings as [`LenientTokenizer`] takes them.
+pub(crate) fn json_to_token(kind: &ParamType, value: &Value) -> Result<Token> {
+	let invalid = || anyhow!("value {} is not a valid `{}`", value, kind).into();
+	match (kind, value) {
+		(ParamType::Array(kind), Value::Array(values)) => {
+			values.iter().map(|value| json_to_token(kind, value)).collect::<Result<_>>().map(Token::Array)
+		}
+		(ParamType::FixedArray(kind, len), Value::Array(values)) if values.len() == *len => {
+			values.iter().map(|value| json_to_token(kind, value)).collect::<Result<_>>().map(Token::FixedArray)
+		}
+		(ParamType::Tuple(kinds), Value::Array(values)) if values.len() == kinds.len() => kinds
+			.iter()
+			.zip(values)
+			.map(|(kind, value)| json_to_token(kind, value))
+			.collect::<Result<_>>()
+			.map(Token::Tuple),
+		(ParamType::Array(_), _) | (ParamType::FixedArray(..), _) | (ParamType::Tuple(_), _) => Err(invalid()),
+		(ParamType::Bool, Value::Bool(b)) => Ok(Token::Bool(*b)),
+		(ParamType::String, Value::String(s)) => Ok(Token::String(s.clone())),
+		(ParamType::Int(_) | ParamType::Uint(_) | ParamType::Fixed(..) | ParamType::Ufixed(..), Value::Number(n)) => {
+			LenientTokenizer::tokenize(kind, &n.to_string())
+		}
+		(_, Value::String(s)) => LenientTokenizer::tokenize(kind, s),
+		_ => Err(invalid()),
+	}
+}
+
+/// Token of zeros of a static `kind`, the one of the zero bytes.
+fn zero_token(kind: &ParamType) -> Option<Token> {
+	match *kind {
+		ParamType::Address => Some(Token::Address(Default::default())),
+		ParamType::Bool => Some(Token::Bool(false)),
+		ParamType::Int(_) | ParamType::Fixed(..) => Some(Token::Int(Uint::zero())),
+		ParamType::Uint(_) | ParamType::Ufixed(..) => Some(Token::Uint(Uint::zero())),
+		ParamType::FixedBytes(len) => Some(Token::FixedBytes(vec![0; len])),
+		ParamType::Function => Some(Token::FixedBytes(vec![0; 24])),
+		ParamType::FixedArray(ref kind, len) => {
+			(0..len).map(|_| zero_token(kind)).collect::<Option<_>>().map(Token::FixedArray)
+		}
+		ParamType::Tuple(ref kinds) => kinds.iter().map(zero_token).collect::<Option<_>>().map(Token::Tuple),
+		ParamType::Bytes | ParamType::String | ParamType::Array(_) => None,
+	}
+}
+
 #[cfg(test)]
 mod tests {
-	use crate::{Function, Param, ParamType, Token};
+	use super::{decode_signature, encode_call, format_call, in_range};
+	use crate::{encode, Error, Function, Param, ParamType, StateMutability, Token, Uint};
 	use hex_literal::hex;
+	use serde::Deserialize;
 
 	#[test]
 	fn test_function_encode_call() {
 		let func = Function {
 			name: "baz".to_owned(),
 			inputs: vec![
-				Param { name: "a".to_owned(), kind: ParamType::Uint(32) },
-				Param { name: "b".to_owned(), kind: ParamType::Bool },
+				Param { name: "a".to_owned(), kind: ParamType::Uint(32), internal_type: None, components: vec![] },
+				Param { name: "b".to_owned(), kind: ParamType::Bool, internal_type: None, components: vec![] },
 			],
 			outputs: vec![],
 			constant: false,
+			state_mutability: StateMutability::NonPayable,
 		};
 
 		let mut uint = [0u8; 32];
@@ -103,4 +528,391 @@ mod tests {
 		let expected = hex!("cdcd77c000000000000000000000000000000000000000000000000000000000000000450000000000000000000000000000000000000000000000000000000000000001").to_vec();
 		assert_eq!(encoded, expected);
 	}
+
+	#[test]
+	fn test_function_decode_call() {
+		let func = Function::parse("baz(uint32 a, bool b)").unwrap();
+		let tokens = vec![Token::Uint(69.into()), Token::Bool(true)];
+		let data = func.encode_input(&tokens).unwrap();
+
+		assert_eq!(func.decode_call(&data).unwrap(), tokens);
+
+		let other = Function::parse("bar(uint32 a, bool b)").unwrap();
+		let err = other.decode_call(&data).unwrap_err();
+		assert_eq!(err.to_string(), "selector 0xcdcd77c0 is not the one of `bar(uint32,bool)`");
+		assert_eq!(
+			func.decode_call(&data[..3]).unwrap_err().to_string(),
+			"calldata of 3 bytes is shorter than a selector"
+		);
+		assert!(func.decode_call(&data[..36]).is_err());
+	}
+
+	#[test]
+	fn test_function_hex_round_trip() {
+		let func = Function::parse("baz(uint32 a, bool b) returns (bool)").unwrap();
+
+		let encoded = func.encode_input_hex(&[Token::Uint(69.into()), Token::Bool(true)]).unwrap();
+		assert_eq!(encoded, "0xcdcd77c000000000000000000000000000000000000000000000000000000000000000450000000000000000000000000000000000000000000000000000000000000001");
+
+		let output = "0x0000000000000000000000000000000000000000000000000000000000000001";
+		assert_eq!(func.decode_output_hex(output).unwrap(), vec![Token::Bool(true)]);
+		assert_eq!(
+			func.decode_output_hex(&output[2..]).unwrap_err().to_string(),
+			"hex value is missing the `0x` prefix"
+		);
+		assert!(func.decode_output_hex(&output[..output.len() - 1]).is_err());
+	}
+
+	#[test]
+	fn test_decode_output_named() {
+		let func = Function::parse("quote() returns (uint256 price, bool)").unwrap();
+		let output = encode(&[Token::Uint(7.into()), Token::Bool(true)]);
+		let decoded = func.decode_output_named(&output).unwrap();
+		assert_eq!(decoded["price"], Token::Uint(7.into()));
+		assert_eq!(decoded[1], Token::Bool(true));
+		assert_eq!(decoded.len(), 2);
+	}
+
+	#[test]
+	fn test_decode_output_lenient() {
+		let func = Function::parse("info() returns (uint256 supply, bool paused, (address,uint8) owner)").unwrap();
+		let tokens = vec![
+			Token::Uint(7.into()),
+			Token::Bool(true),
+			Token::Tuple(vec![Token::Address([0x11; 20].into()), Token::Uint(3.into())]),
+		];
+		let mut output = encode(&tokens);
+		assert_eq!(func.decode_output_lenient(&output).unwrap(), tokens);
+
+		// extra data is ignored and missing trailing outputs are zeros
+		output.extend_from_slice(&[0xff; 40]);
+		assert_eq!(func.decode_output_lenient(&output).unwrap(), tokens);
+		let zeros = vec![
+			Token::Uint(7.into()),
+			Token::Bool(false),
+			Token::Tuple(vec![Token::Address(Default::default()), Token::Uint(0.into())]),
+		];
+		assert!(func.decode_output(&output[..32]).is_err());
+		assert_eq!(func.decode_output_lenient(&output[..32]).unwrap(), zeros);
+		assert_eq!(func.decode_output_lenient(&[]).unwrap()[0], Token::Uint(0.into()));
+
+		// malformed and missing dynamic outputs are errors
+		let func = Function::parse("name() returns (uint256, string)").unwrap();
+		let mut malformed = encode(&[Token::Uint(1.into()), Token::String("ab".to_owned())]);
+		malformed[96] = 0xff;
+		assert!(matches!(func.decode_output_lenient(&malformed), Err(Error::Utf8(_))));
+		assert!(matches!(func.decode_output_lenient(&[0; 32]), Err(Error::UnexpectedEnd { .. })));
+		assert!(matches!(func.decode_output_lenient(&[]), Err(Error::EmptyData)));
+	}
+
+	#[test]
+	fn test_function_encode_call_mismatch() {
+		let func = Function::parse("baz(uint32 a, bool b)").unwrap();
+
+		match func.encode_input(&[Token::Uint(69.into()), Token::Uint(1.into())]).unwrap_err() {
+			Error::InvalidArgument { index, name, expected, found } => {
+				assert_eq!(index, 1);
+				assert_eq!(name, "b");
+				assert_eq!(expected, ParamType::Bool);
+				assert_eq!(found, Token::Uint(1.into()));
+			}
+			err => panic!("unexpected error {}", err),
+		}
+
+		let err = func.encode_input(&[Token::Uint(69.into())]).unwrap_err();
+		assert_eq!(err.to_string(), "`baz` expects 2 arguments, got 1");
+	}
+
+	#[test]
+	fn test_function_encode_out_of_range() {
+		let func = Function::parse("baz(uint8 a, bytes2 b)").unwrap();
+
+		let err = func.encode_input(&[Token::Uint(256.into()), Token::FixedBytes(vec![1, 2])]).unwrap_err();
+		assert_eq!(err.to_string(), "argument 0 `a` is out of range for `uint8`");
+		let err = func.encode_input(&[Token::Uint(255.into()), Token::FixedBytes(vec![1])]).unwrap_err();
+		assert_eq!(err.to_string(), "argument 1 `b` is out of range for `bytes2`");
+		assert!(func.encode_input(&[Token::Uint(255.into()), Token::FixedBytes(vec![1, 2])]).is_ok());
+	}
+
+	#[test]
+	fn test_zero_bit_integers() {
+		assert!(Function::parse("f(int0 a)").is_err());
+
+		// types built by hand do not go through the reader
+		let kinds = [ParamType::Int(0), ParamType::Fixed(0, 0)];
+		assert!(!in_range(&kinds[0], &Token::Int(1.into())));
+		assert!(in_range(&kinds[1], &Token::Int(0.into())));
+		assert!(crate::decode_strict(&kinds, &[0xff; 64]).is_err());
+	}
+
+	#[test]
+	fn test_parse_function_signature() {
+		let func = Function::parse("transfer(address to, uint256 amount) returns (bool)").unwrap();
+
+		assert_eq!(
+			func,
+			Function {
+				name: "transfer".to_owned(),
+				inputs: vec![
+					Param { name: "to".to_owned(), kind: ParamType::Address, internal_type: None, components: vec![] },
+					Param {
+						name: "amount".to_owned(),
+						kind: ParamType::Uint(256),
+						internal_type: None,
+						components: vec![]
+					},
+				],
+				outputs: vec![Param {
+					name: "".to_owned(),
+					kind: ParamType::Bool,
+					internal_type: None,
+					components: vec![]
+				}],
+				constant: false,
+				state_mutability: StateMutability::NonPayable,
+			}
+		);
+
+		let encoded = func.encode_input(&[Token::Address([0x11u8; 20].into()), Token::Uint(69.into())]).unwrap();
+		let expected = hex!("a9059cbb00000000000000000000000011111111111111111111111111111111111111110000000000000000000000000000000000000000000000000000000000000045").to_vec();
+		assert_eq!(encoded, expected);
+
+		let output = hex!("0000000000000000000000000000000000000000000000000000000000000001");
+		assert_eq!(func.decode_output(&output).unwrap(), vec![Token::Bool(true)]);
+	}
+
+	#[test]
+	fn test_parse_function_signature_without_returns() {
+		let func = Function::parse("baz(uint32,bool)").unwrap();
+
+		assert_eq!(
+			func,
+			Function {
+				name: "baz".to_owned(),
+				inputs: vec![
+					Param { name: "".to_owned(), kind: ParamType::Uint(32), internal_type: None, components: vec![] },
+					Param { name: "".to_owned(), kind: ParamType::Bool, internal_type: None, components: vec![] },
+				],
+				outputs: vec![],
+				constant: false,
+				state_mutability: StateMutability::NonPayable,
+			}
+		);
+	}
+
+	#[test]
+	fn test_decode_output_as() {
+		#[derive(Debug, PartialEq, Deserialize)]
+		struct Output {
+			owner: String,
+			balance: String,
+			delta: String,
+			#[serde(rename = "3")]
+			flags: Vec<bool>,
+		}
+
+		let func = Function::parse("info() returns (address owner, uint256 balance, int256 delta, bool[])").unwrap();
+
+		let output = hex!(
+			"
+			0000000000000000000000001111111111111111111111111111111111111111
+			00000000000000000000000000000000000000000000000000000000000003e8
+			fffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffe
+			0000000000000000000000000000000000000000000000000000000000000080
+			0000000000000000000000000000000000000000000000000000000000000002
+			0000000000000000000000000000000000000000000000000000000000000001
+			0000000000000000000000000000000000000000000000000000000000000000
+		"
+		);
+
+		assert_eq!(
+			func.decode_output_as::<Output>(&output).unwrap(),
+			Output {
+				owner: "0x1111111111111111111111111111111111111111".to_owned(),
+				balance: "1000".to_owned(),
+				delta: "-2".to_owned(),
+				flags: vec![true, false],
+			}
+		);
+	}
+
+	#[test]
+	fn test_encode_call() {
+		let encoded =
+			encode_call("transfer(address,uint256)", &["1111111111111111111111111111111111111111", "1000"]).unwrap();
+		let expected = hex!(
+			"
+			a9059cbb
+			0000000000000000000000001111111111111111111111111111111111111111
+			00000000000000000000000000000000000000000000000000000000000003e8
+		"
+		);
+		assert_eq!(encoded, expected.to_vec());
+	}
+
+	#[test]
+	fn test_encode_call_errors() {
+		assert!(encode_call("transfer(address,uint256)", &["1111111111111111111111111111111111111111"]).is_err());
+
+		let err = encode_call("transfer(address,uint256)", &["1111111111111111111111111111111111111111", "foo"])
+			.unwrap_err()
+			.to_string();
+		assert!(err.contains("argument 1 `foo`"), "{}", err);
+	}
+
+	#[test]
+	fn test_format_call() {
+		let func = Function::parse("transfer(address to, uint256 amount)").unwrap();
+		let data =
+			encode_call("transfer(address,uint256)", &["1111111111111111111111111111111111111111", "1000"]).unwrap();
+		assert_eq!(
+			format_call(&func, &data).unwrap(),
+			"transfer(to: 0x1111111111111111111111111111111111111111, amount: 1000)"
+		);
+
+		let func = Function::parse("foo((int8,string) a, bytes, string[] b)").unwrap();
+		let data = encode_call("foo((int8,string),bytes,string[])", &["(-3,x)", "1234", "[a,b]"]).unwrap();
+		assert_eq!(format_call(&func, &data).unwrap(), r#"foo(a: (-3, "x"), 0x1234, b: ["a", "b"])"#);
+
+		assert!(format_call(&func, &data[1..]).is_err());
+	}
+
+	#[test]
+	fn test_validate_calldata_len() {
+		let func = Function::parse("foo(uint256,(bool,address),bytes32[2])").unwrap();
+		let data = func
+			.encode_input(&[
+				Token::Uint(1.into()),
+				Token::Tuple(vec![Token::Bool(true), Token::Address([0x11u8; 20].into())]),
+				Token::FixedArray(vec![Token::FixedBytes(vec![0; 32]), Token::FixedBytes(vec![1; 32])]),
+			])
+			.unwrap();
+		assert_eq!(data.len(), 4 + 5 * 32);
+		assert!(func.validate_calldata_len(&data).is_ok());
+		assert!(func.validate_calldata_len(&data[..data.len() - 32]).is_err());
+		assert!(func.validate_calldata_len(&[data.clone(), vec![0; 32]].concat()).is_err());
+		assert!(func.validate_calldata_len(&data[..3]).is_err());
+
+		let func = Function::parse("bar(uint256,string)").unwrap();
+		let data = func.encode_input(&[Token::Uint(1.into()), Token::String("hello".to_owned())]).unwrap();
+		assert!(func.validate_calldata_len(&data).is_ok());
+		assert!(func.validate_calldata_len(&data[..4 + 64]).is_ok());
+		assert!(func.validate_calldata_len(&data[..4 + 32]).is_err());
+		assert!(func.validate_calldata_len(&data[..data.len() - 1]).is_err());
+	}
+
+	#[test]
+	fn test_decode_signature() {
+		let data = hex!(
+			"
+			00000000000000000000000000000000000000000000000000000000000003e8
+			0000000000000000000000001111111111111111111111111111111111111111
+		"
+		);
+		let expected = vec![Token::Uint(1000.into()), Token::Address([0x11u8; 20].into())];
+
+		assert_eq!(decode_signature("(uint256,address)", &data).unwrap(), expected);
+		assert_eq!(decode_signature("foo(uint256,address)", &data).unwrap(), expected);
+		assert!(decode_signature("(uint256,address,bool)", &data).is_err());
+		assert!(decode_signature("uint256,address", &data).is_err());
+	}
+
+	#[test]
+	fn test_selector_in() {
+		let func = Function::parse("transfer(address,uint256)").unwrap();
+		assert_eq!(func.short_signature(), hex!("a9059cbb"));
+		assert!(func.selector_in(&[hex!("095ea7b3"), hex!("a9059cbb")]));
+		assert!(!func.selector_in(&[hex!("095ea7b3")]));
+	}
+
+	#[test]
+	fn test_state_mutability_resolution() {
+		use StateMutability::*;
+
+		let cases = [
+			// legacy flags only
+			(r#""constant": false, "payable": false"#, Some(NonPayable)),
+			(r#""constant": false, "payable": true"#, Some(Payable)),
+			(r#""constant": true, "payable": false"#, Some(View)),
+			(r#""constant": true, "payable": true"#, None),
+			(r#""constant": true"#, Some(View)),
+			(r#""payable": true"#, Some(Payable)),
+			// modern only
+			(r#""stateMutability": "pure""#, Some(Pure)),
+			(r#""stateMutability": "view""#, Some(View)),
+			(r#""stateMutability": "nonpayable""#, Some(NonPayable)),
+			(r#""stateMutability": "payable""#, Some(Payable)),
+			// both, as emitted by solc 0.4 and 0.5
+			(r#""constant": true, "payable": false, "stateMutability": "pure""#, Some(Pure)),
+			(r#""constant": false, "payable": true, "stateMutability": "payable""#, Some(Payable)),
+			// neither
+			("", Some(NonPayable)),
+		];
+
+		for (fields, expected) in &cases {
+			let separator = if fields.is_empty() { "" } else { "," };
+			let abi = format!(r#"{{ "name": "foo", "inputs": [], "outputs": []{} {} }}"#, separator, fields);
+			let function = serde_json::from_str::<Function>(&abi);
+			match expected {
+				Some(expected) => {
+					let function = function.unwrap();
+					assert_eq!(function.state_mutability, *expected, "{}", fields);
+					assert_eq!(function.constant, expected.is_constant(), "{}", fields);
+				}
+				None => assert!(function.is_err(), "{}", fields),
+			}
+		}
+	}
+
+	#[test]
+	fn test_validate_inputs() {
+		let func = Function {
+			name: "foo".to_owned(),
+			inputs: vec![
+				Param { name: "a".to_owned(), kind: ParamType::Uint(8), internal_type: None, components: vec![] },
+				Param { name: "b".to_owned(), kind: ParamType::Int(8), internal_type: None, components: vec![] },
+				Param { name: "c".to_owned(), kind: ParamType::Address, internal_type: None, components: vec![] },
+				Param {
+					name: "d".to_owned(),
+					kind: ParamType::Array(Box::new(ParamType::Int(16))),
+					internal_type: None,
+					components: vec![],
+				},
+			],
+			outputs: vec![],
+			constant: false,
+			state_mutability: StateMutability::NonPayable,
+		};
+		let minus = |x: u64| !Uint::from(x) + 1;
+
+		let valid = [
+			Token::Uint(255.into()),
+			Token::Int(minus(128)),
+			Token::Address([0u8; 20].into()),
+			Token::Array(vec![Token::Int(32767.into()), Token::Int(minus(32768))]),
+		];
+		assert!(func.validate_inputs(&valid).is_ok());
+
+		let invalid = [
+			Token::Uint(256.into()),
+			Token::Int(minus(129)),
+			Token::Bool(true),
+			Token::Array(vec![Token::Int(32768.into())]),
+		];
+		let errors = func.validate_inputs(&invalid).unwrap_err();
+		let errors = errors.iter().map(ToString::to_string).collect::<Vec<_>>();
+		assert_eq!(
+			errors,
+			vec![
+				"argument 0 `a` is out of range for `uint8`",
+				"argument 1 `b` is out of range for `int8`",
+				"argument 2 `c` is not a valid `address`",
+				"argument 3 `d` is out of range for `int16[]`",
+			]
+		);
+
+		let errors = func.validate_inputs(&valid[..2]).unwrap_err();
+		assert_eq!(errors.len(), 1);
+		assert_eq!(errors[0].to_string(), "`foo` expects 4 arguments, got 2");
+	}
 }
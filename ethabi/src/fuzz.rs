@@ -0,0 +1,199 @@
+// Copyright 2015-2020 Parity Technologies
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Generation of random but well-formed types and tokens from the input of a fuzzer.
+//!
+//! Every choice is taken from the bytes of a [`Source`], so the same input always generates
+//! the same values and a fuzzer mutating it explores the types and tokens. Once the input is
+//! exhausted every choice is the first one, which keeps the generated values small.
+//!
+//! ```
+//! use ethabi::{decode, encode, fuzz::Source};
+//!
+//! let mut source = Source::new(b"any fuzzer input");
+//! let (kind, token) = source.param_and_token();
+//! assert!(token.type_check(&kind));
+//! assert_eq!(decode(&[kind], &encode(&[token.clone()])).unwrap(), vec![token]);
+//! ```
+
+#[cfg(not(feature = "std"))]
+use crate::no_std_prelude::*;
+use crate::{Address, ParamType, Token, Uint};
+
+/// How deep arrays and tuples are nested at most.
+const MAX_DEPTH: usize = 3;
+
+/// Random choices read from the bytes of a fuzzer input.
+#[derive(Debug, Clone)]
+pub struct Source<'a> {
+	data: &'a [u8],
+}
+
+impl<'a> Source<'a> {
+	/// Source of the choices taken from `data`.
+	pub fn new(data: &'a [u8]) -> Self {
+		Source { data }
+	}
+
+	/// Whether all the bytes of the input were consumed.
+	pub fn is_empty(&self) -> bool {
+		self.data.is_empty()
+	}
+
+	fn byte(&mut self) -> u8 {
+		match self.data.split_first() {
+			Some((byte, rest)) => {
+				self.data = rest;
+				*byte
+			}
+			None => 0,
+		}
+	}
+
+	fn bytes(&mut self, len: usize) -> Vec<u8> {
+		(0..len).map(|_| self.byte()).collect()
+	}
+
+	/// Chooses one of `count` alternatives.
+	fn choose(&mut self, count: usize) -> usize {
+		self.byte() as usize % count
+	}
+
+	/// Generates a type, along with a token of it.
+	pub fn param_and_token(&mut self) -> (ParamType, Token) {
+		let kind = self.param_type();
+		let token = self.token(&kind);
+		(kind, token)
+	}
+
+	/// Generates a valid type, with arrays and tuples nested a few levels at most.
+	pub fn param_type(&mut self) -> ParamType {
+		self.nested_param_type(0)
+	}
+
+	fn nested_param_type(&mut self, depth: usize) -> ParamType {
+		let kinds = if depth < MAX_DEPTH { 14 } else { 11 };
+		match self.choose(kinds) {
+			0 => ParamType::Address,
+			1 => ParamType::Bool,
+			2 => ParamType::Bytes,
+			3 => ParamType::String,
+			4 => ParamType::Function,
+			5 => ParamType::FixedBytes(1 + self.choose(32)),
+			6 => ParamType::Uint(8 * (1 + self.choose(32))),
+			7 => ParamType::Int(8 * (1 + self.choose(32))),
+			8 => ParamType::Ufixed(8 * (1 + self.choose(32)), self.choose(81)),
+			9 => ParamType::Fixed(8 * (1 + self.choose(32)), self.choose(81)),
+			// `uint256` gets a better chance, being the most common type
+			10 => ParamType::Uint(256),
+			11 => ParamType::Array(Box::new(self.nested_param_type(depth + 1))),
+			12 => {
+				let len = 1 + self.choose(3);
+				ParamType::FixedArray(Box::new(self.nested_param_type(depth + 1)), len)
+			}
+			_ => {
+				let len = 1 + self.choose(3);
+				ParamType::Tuple((0..len).map(|_| self.nested_param_type(depth + 1)).collect())
+			}
+		}
+	}
+
+	/// Generates a token of `kind`, whose integers are in the range of its bit size.
+	pub fn token(&mut self, kind: &ParamType) -> Token {
+		match *kind {
+			ParamType::Address => Token::Address(Address::from_slice(&self.bytes(20))),
+			ParamType::Bool => Token::Bool(self.byte() & 1 == 1),
+			ParamType::Bytes => {
+				let len = self.choose(72);
+				Token::Bytes(self.bytes(len))
+			}
+			ParamType::String => {
+				let len = self.choose(48);
+				Token::String(String::from_utf8_lossy(&self.bytes(len)).into_owned())
+			}
+			ParamType::Function => Token::FixedBytes(self.bytes(24)),
+			ParamType::FixedBytes(len) => Token::FixedBytes(self.bytes(len)),
+			ParamType::Uint(size) | ParamType::Ufixed(size, _) => Token::Uint(self.uint(size)),
+			ParamType::Int(size) | ParamType::Fixed(size, _) => {
+				// sign extension of the lowest `size` bits
+				let uint = self.uint(size);
+				match size < 256 && uint.bit(size - 1) {
+					true => Token::Int(uint | (Uint::MAX << size)),
+					false => Token::Int(uint),
+				}
+			}
+			ParamType::Array(ref kind) => {
+				let len = self.choose(4);
+				Token::Array((0..len).map(|_| self.token(kind)).collect())
+			}
+			ParamType::FixedArray(ref kind, len) => Token::FixedArray((0..len).map(|_| self.token(kind)).collect()),
+			ParamType::Tuple(ref kinds) => Token::Tuple(kinds.iter().map(|kind| self.token(kind)).collect()),
+		}
+	}
+
+	/// Unsigned integer of `size` bits, biased towards small values and bounds.
+	fn uint(&mut self, size: usize) -> Uint {
+		let max = match size < 256 {
+			true => (Uint::one() << size) - 1,
+			false => Uint::MAX,
+		};
+		match self.choose(4) {
+			0 => Uint::from(self.byte()),
+			1 => max,
+			_ => Uint::from_big_endian(&self.bytes(32)) & max,
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::Source;
+	use crate::{
+		decode, decode_strict, encode,
+		token::{LenientTokenizer, Tokenizer},
+	};
+	use core::slice;
+
+	/// Pseudo random fuzzer inputs, the same on every run.
+	fn inputs() -> impl Iterator<Item = Vec<u8>> {
+		let mut state = 0x2545_f491_4f6c_dd1d_u64;
+		(0..500).map(move |i| {
+			(0..i % 200)
+				.map(|_| {
+					state ^= state << 13;
+					state ^= state >> 7;
+					state ^= state << 17;
+					state as u8
+				})
+				.collect()
+		})
+	}
+
+	#[test]
+	fn test_round_trip() {
+		for input in inputs() {
+			let (kind, token) = Source::new(&input).param_and_token();
+			assert!(token.type_check(&kind), "`{}` does not type check as `{}`", token.to_literal(), kind);
+
+			let (kinds, tokens) = (slice::from_ref(&kind), slice::from_ref(&token));
+			let encoded = encode(tokens);
+			assert_eq!(decode(kinds, &encoded).unwrap(), tokens, "{}", kind);
+			assert_eq!(decode_strict(kinds, &encoded).unwrap(), tokens, "{}", kind);
+			assert_eq!(LenientTokenizer::tokenize_literal(&kind, &token.to_literal()).unwrap(), token, "{}", kind);
+		}
+	}
+
+	#[test]
+	fn test_exhausted_source() {
+		let mut source = Source::new(&[]);
+		assert!(source.is_empty());
+		let (kind, token) = source.param_and_token();
+		assert_eq!(kind.to_string(), "address");
+		assert!(token.type_check(&kind));
+	}
+}
@@ -0,0 +1,505 @@
+// Copyright 2015-2020 Parity Technologies
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Human-readable (Solidity-like) signature parsing.
+
+#[cfg(not(feature = "std"))]
+use crate::no_std_prelude::*;
+use crate::{
+	param_type::Reader, AbiError, Constructor, Contract, Event, EventParam, Function, Param, ParamType, Result,
+	StateMutability,
+};
+use anyhow::anyhow;
+
+/// Parses an event declaration like
+/// `Transfer(address indexed from, address indexed to, uint256 value)`.
+///
+/// A leading `event` keyword and a trailing `anonymous` modifier are accepted.
+pub fn parse_event(sig: &str) -> Result<Event> {
+	let (name, params, rest) = split_signature(strip_keyword(sig.trim(), "event"))?;
+	let anonymous = match rest {
+		"" => false,
+		"anonymous" => true,
+		_ => return Err(anyhow!("unexpected `{}` after parameters of event `{}`", rest, name).into()),
+	};
+
+	let inputs = params.into_iter().map(parse_event_param).collect::<Result<_>>()?;
+
+	Ok(Event { name: name.to_owned(), inputs, anonymous })
+}
+
+/// Parses a function signature like `transfer(address to, uint256 amount) returns (bool)`.
+///
+/// A leading `function` keyword, visibility and mutability modifiers are accepted.
+/// Functions declared `view`, `pure` or `constant` are marked as constant, the legacy
+/// `constant` meaning `view`.
+pub fn parse_function(sig: &str) -> Result<Function> {
+	let (name, params, mut rest) = split_signature(strip_keyword(sig.trim(), "function"))?;
+	let inputs = params.into_iter().map(parse_param).collect::<Result<_>>()?;
+
+	let mut state_mutability = StateMutability::NonPayable;
+	let mut outputs = vec![];
+	while !rest.is_empty() {
+		let word_len = rest.find(|c: char| c.is_whitespace() || c == '(').unwrap_or(rest.len());
+		match &rest[..word_len] {
+			"returns" => {
+				let returns = rest[word_len..].trim_start();
+				if !returns.starts_with('(') {
+					return Err(anyhow!("expected `(` after `returns` in signature `{}`", sig).into());
+				}
+				let close = matching_paren(returns, 0)?;
+				outputs = split_params(&returns[1..close])?.into_iter().map(parse_param).collect::<Result<_>>()?;
+				rest = &returns[close + 1..];
+			}
+			"view" | "constant" => {
+				state_mutability = StateMutability::View;
+				rest = &rest[word_len..];
+			}
+			"pure" => {
+				state_mutability = StateMutability::Pure;
+				rest = &rest[word_len..];
+			}
+			"payable" => {
+				state_mutability = StateMutability::Payable;
+				rest = &rest[word_len..];
+			}
+			"nonpayable" | "external" | "public" => rest = &rest[word_len..],
+			word => return Err(anyhow!("unexpected `{}` in signature of function `{}`", word, name).into()),
+		}
+		rest = rest.trim_start();
+	}
+
+	Ok(Function { name: name.to_owned(), inputs, outputs, constant: state_mutability.is_constant(), state_mutability })
+}
+
+/// Parses a custom error declaration like `error InsufficientBalance(uint256 available)`.
+///
+/// The leading `error` keyword is optional.
+pub fn parse_error(sig: &str) -> Result<AbiError> {
+	let (name, params, rest) = split_signature(strip_keyword(sig.trim(), "error"))?;
+	if !rest.is_empty() {
+		return Err(anyhow!("unexpected `{}` after parameters of error `{}`", rest, name).into());
+	}
+
+	let inputs = params.into_iter().map(parse_param).collect::<Result<_>>()?;
+
+	Ok(AbiError { name: name.to_owned(), inputs })
+}
+
+/// Builds a contract from a list of declarations, one per item.
+///
+/// Events and errors need their `event` and `error` keyword, `constructor(...)`, `fallback()`
+/// and `receive()` are recognized by name and everything else is parsed as a function.
+pub fn parse_contract<I, S>(signatures: I) -> Result<Contract>
+where
+	I: IntoIterator<Item = S>,
+	S: AsRef<str>,
+{
+	let mut contract = Contract {
+		constructor: None,
+		functions: Default::default(),
+		events: Default::default(),
+		errors: Default::default(),
+		fallback: None,
+		receive: false,
+	};
+
+	for sig in signatures {
+		let sig = sig.as_ref().trim();
+		let keyword_len = sig.find(|c: char| c.is_whitespace() || c == '(').unwrap_or(sig.len());
+		match &sig[..keyword_len] {
+			"event" => {
+				let event = parse_event(sig)?;
+				contract.events.entry(event.name.clone()).or_default().push(event);
+			}
+			"error" => {
+				let error = parse_error(sig)?;
+				contract.errors.entry(error.name.clone()).or_default().push(error);
+			}
+			"constructor" => {
+				let (_, params, rest) = split_signature(sig)?;
+				if !matches!(rest, "" | "payable" | "public") {
+					return Err(anyhow!("unexpected `{}` after parameters of the constructor", rest).into());
+				}
+				let inputs = params.into_iter().map(parse_param).collect::<Result<_>>()?;
+				contract.constructor = Some(Constructor { inputs });
+			}
+			"fallback" | "receive" => {
+				let (name, params, rest) = split_signature(sig)?;
+				if !params.is_empty() {
+					return Err(anyhow!("`{}` takes no parameters", name).into());
+				}
+				match name {
+					"receive" => contract.receive = true,
+					_ if rest.split_whitespace().any(|word| word == "payable") => {
+						contract.fallback = Some(StateMutability::Payable)
+					}
+					_ => contract.fallback = Some(StateMutability::NonPayable),
+				}
+			}
+			_ => {
+				let function = parse_function(sig)?;
+				contract.functions.entry(function.name.clone()).or_default().push(function);
+			}
+		}
+	}
+
+	Ok(contract)
+}
+
+/// Parses a parenthesized type list like `(uint256,address)`, optionally preceded by a name
+/// as in `transfer(address,uint256)`. Parameter names are accepted and ignored.
+pub fn parse_types(sig: &str) -> Result<Vec<ParamType>> {
+	let sig = sig.trim();
+	let open = sig.find('(').ok_or_else(|| anyhow!("missing `(` in `{}`", sig))?;
+	let name = sig[..open].trim();
+	if !name.is_empty() && !is_identifier(name) {
+		return Err(anyhow!("invalid name `{}` in `{}`", name, sig).into());
+	}
+
+	let close = matching_paren(sig, open)?;
+	if !sig[close + 1..].trim().is_empty() {
+		return Err(anyhow!("unexpected `{}` after the types of `{}`", sig[close + 1..].trim(), sig).into());
+	}
+
+	split_params(&sig[open + 1..close])?.into_iter().map(|param| parse_param(param).map(|p| p.kind)).collect()
+}
+
+fn parse_param(param: &str) -> Result<Param> {
+	let (kind, modifiers) = split_type(param)?;
+	let mut name = None;
+
+	for word in modifiers.split_whitespace() {
+		match word {
+			"memory" | "calldata" | "storage" if name.is_none() => (),
+			_ if name.is_none() && is_identifier(word) => name = Some(word),
+			_ => return Err(anyhow!("unexpected `{}` in parameter `{}`", word, param).into()),
+		}
+	}
+
+	Ok(Param { name: name.unwrap_or_default().to_owned(), kind, internal_type: None, components: vec![] })
+}
+
+fn parse_event_param(param: &str) -> Result<EventParam> {
+	let (kind, modifiers) = split_type(param)?;
+	let mut indexed = false;
+	let mut name = None;
+
+	for word in modifiers.split_whitespace() {
+		match word {
+			"indexed" if !indexed && name.is_none() => indexed = true,
+			_ if name.is_none() && is_identifier(word) => name = Some(word),
+			_ => return Err(anyhow!("unexpected `{}` in event parameter `{}`", word, param).into()),
+		}
+	}
+
+	Ok(EventParam { name: name.unwrap_or_default().to_owned(), kind, indexed, internal_type: None, components: vec![] })
+}
+
+/// Strips a leading keyword (e.g. `event`) followed by whitespace.
+fn strip_keyword<'a>(sig: &'a str, keyword: &str) -> &'a str {
+	match sig.strip_prefix(keyword) {
+		Some(rest) if rest.starts_with(char::is_whitespace) => rest.trim_start(),
+		_ => sig,
+	}
+}
+
+/// Splits `name(params) rest` into the name, the top-level params and whatever follows
+/// the closing parenthesis.
+fn split_signature(sig: &str) -> Result<(&str, Vec<&str>, &str)> {
+	let open = sig.find('(').ok_or_else(|| anyhow!("missing `(` in signature `{}`", sig))?;
+	let name = sig[..open].trim();
+	if !is_identifier(name) {
+		return Err(anyhow!("invalid name `{}` in signature `{}`", name, sig).into());
+	}
+
+	let close = matching_paren(sig, open)?;
+	let params = split_params(&sig[open + 1..close])?;
+
+	Ok((name, params, sig[close + 1..].trim()))
+}
+
+/// Returns the position of the parenthesis closing the one at `open`.
+fn matching_paren(s: &str, open: usize) -> Result<usize> {
+	let mut nested = 0usize;
+	for (pos, c) in s.char_indices().skip_while(|&(pos, _)| pos < open) {
+		match c {
+			'(' => nested += 1,
+			')' => {
+				nested -= 1;
+				if nested == 0 {
+					return Ok(pos);
+				}
+			}
+			_ => (),
+		}
+	}
+
+	Err(anyhow!("unbalanced parentheses in `{}`", s).into())
+}
+
+/// Splits a comma separated parameter list, ignoring commas nested in tuples.
+fn split_params(s: &str) -> Result<Vec<&str>> {
+	if s.trim().is_empty() {
+		return Ok(vec![]);
+	}
+
+	let mut params = vec![];
+	let mut nested = 0isize;
+	let mut last_item = 0;
+	for (pos, c) in s.char_indices() {
+		match c {
+			'(' => nested += 1,
+			')' => nested -= 1,
+			',' if nested == 0 => {
+				params.push(s[last_item..pos].trim());
+				last_item = pos + 1;
+			}
+			_ => (),
+		}
+		if nested < 0 {
+			return Err(anyhow!("unbalanced parentheses in `{}`", s).into());
+		}
+	}
+	if nested != 0 {
+		return Err(anyhow!("unbalanced parentheses in `{}`", s).into());
+	}
+	params.push(s[last_item..].trim());
+
+	match params.iter().find(|param| param.is_empty()) {
+		Some(_) => Err(anyhow!("empty parameter in `{}`", s).into()),
+		None => Ok(params),
+	}
+}
+
+/// Splits a single parameter declaration into its type and the words following it.
+fn split_type(param: &str) -> Result<(ParamType, &str)> {
+	let param = param.trim();
+	let stripped = param.strip_prefix("tuple").filter(|rest| rest.starts_with('(')).unwrap_or(param);
+
+	if stripped.starts_with('(') {
+		let close = matching_paren(stripped, 0)?;
+		let components = split_params(&stripped[1..close])?
+			.into_iter()
+			.map(|component| split_type(component).map(|(kind, _)| kind))
+			.collect::<Result<_>>()?;
+		let rest = &stripped[close + 1..];
+		let suffix_len = rest.find(char::is_whitespace).unwrap_or(rest.len());
+		let kind = read_array_suffix(ParamType::Tuple(components), &rest[..suffix_len])?;
+		Ok((kind, &rest[suffix_len..]))
+	} else {
+		let type_len = param.find(char::is_whitespace).unwrap_or(param.len());
+		Ok((Reader::read(&param[..type_len])?, &param[type_len..]))
+	}
+}
+
+/// Wraps `kind` into the arrays described by a suffix like `[2][]`.
+fn read_array_suffix(mut kind: ParamType, mut suffix: &str) -> Result<ParamType> {
+	while !suffix.is_empty() {
+		let close = match (suffix.starts_with('['), suffix.find(']')) {
+			(true, Some(close)) => close,
+			_ => return Err(anyhow!("invalid array suffix `{}`", suffix).into()),
+		};
+		kind = match &suffix[1..close] {
+			"" => ParamType::Array(Box::new(kind)),
+			len => ParamType::FixedArray(Box::new(kind), len.parse()?),
+		};
+		suffix = &suffix[close + 1..];
+	}
+
+	Ok(kind)
+}
+
+pub(crate) fn is_identifier(s: &str) -> bool {
+	!s.is_empty()
+		&& !s.starts_with(|c: char| c.is_ascii_digit())
+		&& s.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$')
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{parse_contract, parse_error, parse_event, parse_function, parse_types, split_type};
+	use crate::{EventParam, Param, ParamType, StateMutability};
+	use hex_literal::hex;
+
+	#[test]
+	fn test_parse_type() {
+		assert_eq!(split_type("uint256").unwrap().0, ParamType::Uint(256));
+		assert_eq!(split_type("address[] foo").unwrap().0, ParamType::Array(Box::new(ParamType::Address)));
+		assert_eq!(
+			split_type("(uint256 a, (bool, string) b)[2]").unwrap().0,
+			ParamType::FixedArray(
+				Box::new(ParamType::Tuple(vec![
+					ParamType::Uint(256),
+					ParamType::Tuple(vec![ParamType::Bool, ParamType::String])
+				])),
+				2
+			)
+		);
+		assert_eq!(
+			split_type("tuple(address,bytes)[]").unwrap().0,
+			ParamType::Array(Box::new(ParamType::Tuple(vec![ParamType::Address, ParamType::Bytes])))
+		);
+	}
+
+	#[test]
+	fn test_parse_event_unnamed_params() {
+		let event = parse_event("event Foo(uint, bool indexed)").unwrap();
+		assert_eq!(
+			event.inputs,
+			vec![
+				EventParam {
+					name: "".to_owned(),
+					kind: ParamType::Uint(256),
+					indexed: false,
+					internal_type: None,
+					components: vec![]
+				},
+				EventParam {
+					name: "".to_owned(),
+					kind: ParamType::Bool,
+					indexed: true,
+					internal_type: None,
+					components: vec![]
+				},
+			]
+		);
+		assert!(!event.anonymous);
+	}
+
+	#[test]
+	fn test_parse_event_anonymous() {
+		let event = parse_event("Foo(address indexed sender) anonymous").unwrap();
+		assert_eq!(event.name, "Foo");
+		assert!(event.anonymous);
+	}
+
+	#[test]
+	fn test_parse_function_modifiers() {
+		let function =
+			parse_function("function balanceOf(address owner) external view returns (uint256 balance)").unwrap();
+		assert_eq!(function.name, "balanceOf");
+		assert_eq!(
+			function.inputs,
+			vec![Param { name: "owner".to_owned(), kind: ParamType::Address, internal_type: None, components: vec![] }]
+		);
+		assert_eq!(
+			function.outputs,
+			vec![Param {
+				name: "balance".to_owned(),
+				kind: ParamType::Uint(256),
+				internal_type: None,
+				components: vec![]
+			}]
+		);
+		assert!(function.constant);
+		assert_eq!(function.state_mutability, StateMutability::View);
+
+		let function = parse_function("setData(bytes calldata, string memory name) payable").unwrap();
+		assert_eq!(
+			function.inputs,
+			vec![
+				Param { name: "".to_owned(), kind: ParamType::Bytes, internal_type: None, components: vec![] },
+				Param { name: "name".to_owned(), kind: ParamType::String, internal_type: None, components: vec![] },
+			]
+		);
+		assert!(function.outputs.is_empty());
+		assert!(!function.constant);
+		assert_eq!(function.state_mutability, StateMutability::Payable);
+	}
+
+	#[test]
+	fn test_parse_function_errors() {
+		assert!(parse_function("foo(uint256) returns").is_err());
+		assert!(parse_function("foo(uint256) returns (bool").is_err());
+		assert!(parse_function("foo(uint256) internal").is_err());
+		assert!(parse_function("foo(uint256 indexed a)").is_err());
+	}
+
+	#[test]
+	fn test_parse_event_errors() {
+		assert!(parse_event("Foo(uint256").is_err());
+		assert!(parse_event("Foo(uint256))").is_err());
+		assert!(parse_event("Foo(uint256,)").is_err());
+		assert!(parse_event("Foo(uint256 a b)").is_err());
+		assert!(parse_event("Foo(uint256) payable").is_err());
+		assert!(parse_event("(uint256)").is_err());
+	}
+
+	#[test]
+	fn test_parse_types() {
+		let expected = vec![ParamType::Uint(256), ParamType::Tuple(vec![ParamType::Address, ParamType::Bool])];
+		assert_eq!(parse_types("(uint256,(address,bool))").unwrap(), expected);
+		assert_eq!(parse_types("foo(uint256 a, (address, bool) b)").unwrap(), expected);
+		assert_eq!(parse_types("()").unwrap(), vec![]);
+
+		assert!(parse_types("uint256").is_err());
+		assert!(parse_types("(uint256) returns (bool)").is_err());
+		assert!(parse_types("1foo(uint256)").is_err());
+	}
+
+	#[test]
+	fn test_parse_error() {
+		let error = parse_error("error InsufficientBalance(uint256 available, uint256 required)").unwrap();
+		assert_eq!(error.name, "InsufficientBalance");
+		assert_eq!(error.inputs.len(), 2);
+		assert_eq!(parse_error("Unauthorized()").unwrap().selector(), hex!("82b42900"));
+
+		assert!(parse_error("error Foo(uint256) view").is_err());
+	}
+
+	#[test]
+	fn test_canonical_signature_round_trip() {
+		for sig in &["transfer(address,uint256)", "settle((address,uint256)[],bytes32[2],(bool,(string,int8)))"] {
+			assert_eq!(&parse_function(sig).unwrap().signature(), sig);
+		}
+		assert_eq!(parse_function("transfer(address,uint256)").unwrap().short_signature(), hex!("a9059cbb"));
+
+		let event = parse_event("event Transfer(address indexed from, address indexed to, uint256 value)").unwrap();
+		assert_eq!(event.signature(), parse_event("Transfer(address,address,uint256)").unwrap().signature());
+	}
+
+	#[test]
+	fn test_parse_contract() {
+		let contract = parse_contract([
+			"constructor(string name) payable",
+			"function transfer(address to, uint256 amount) returns (bool)",
+			"balanceOf(address) view returns (uint256)",
+			"balanceOf(address,uint256) view returns (uint256)",
+			"event Transfer(address indexed from, address indexed to, uint256 value)",
+			"error Unauthorized()",
+			"receive() external payable",
+		])
+		.unwrap();
+
+		assert_eq!(
+			contract.constructor.as_ref().unwrap().inputs,
+			vec![Param { name: "name".to_owned(), kind: ParamType::String, internal_type: None, components: vec![] }]
+		);
+		assert_eq!(contract.functions["transfer"][0].outputs[0].kind, ParamType::Bool);
+		assert_eq!(contract.functions["balanceOf"].len(), 2);
+		assert_eq!(contract.events["Transfer"][0].inputs.len(), 3);
+		assert!(contract.errors.contains_key("Unauthorized"));
+		assert!(contract.receive);
+		assert_eq!(contract.fallback, None);
+		assert!(contract.accepts_plain_transfers());
+
+		let contract = parse_contract(["fallback() external"]).unwrap();
+		assert_eq!(contract.fallback, Some(StateMutability::NonPayable));
+		assert!(!contract.accepts_plain_transfers());
+	}
+
+	#[test]
+	fn test_parse_contract_errors() {
+		let err = parse_contract(["transfer(address,uint256"]).unwrap_err();
+		assert_eq!(err.to_string(), "unbalanced parentheses in `transfer(address,uint256`");
+		assert!(parse_contract(["Transfer(address indexed from)"]).is_err());
+		assert!(parse_contract(["constructor(uint256) view"]).is_err());
+		assert!(parse_contract(["fallback(bytes)"]).is_err());
+	}
+}
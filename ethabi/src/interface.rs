@@ -0,0 +1,250 @@
+// Copyright 2015-2020 Parity Technologies
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! High level contract interface.
+
+#[cfg(not(feature = "std"))]
+use crate::no_std_prelude::*;
+use crate::{errors, Bytes, Contract, Error, Event, Function, Hash, Log, RawLog, Token};
+use alloc::collections::BTreeMap;
+use anyhow::anyhow;
+#[cfg(feature = "std")]
+use std::io;
+
+/// Contract wrapper precomputing the lookups needed to encode calls and decode calls and logs.
+///
+/// Selectors and topics are hashed once, when the interface is created, and encoding calls or
+/// parsing logs reuses them. The wrapped contract cannot be changed afterwards, so they never go
+/// stale. [`Contract`] remains the lower level primitive, which [`Interface::contract`] gives
+/// access to.
+#[derive(Debug, Clone)]
+pub struct Interface {
+	contract: Contract,
+	/// Name and overload index of functions keyed by selector.
+	selectors: BTreeMap<[u8; 4], (String, usize)>,
+	/// Name and overload index of functions keyed by signature, e.g. `transfer(address,uint256)`.
+	signatures: BTreeMap<String, (String, usize)>,
+	/// Selectors of the functions keyed by name, in overload order.
+	function_selectors: BTreeMap<String, Vec<[u8; 4]>>,
+	/// Name and overload index of non anonymous events keyed by topic.
+	topics: BTreeMap<Hash, (String, usize)>,
+}
+
+impl From<Contract> for Interface {
+	fn from(contract: Contract) -> Self {
+		let mut selectors = BTreeMap::new();
+		let mut signatures = BTreeMap::new();
+		let mut function_selectors = BTreeMap::new();
+		for (name, functions) in &contract.functions {
+			let mut overloads = Vec::with_capacity(functions.len());
+			for (i, function) in functions.iter().enumerate() {
+				let selector = function.short_signature();
+				selectors.entry(selector).or_insert_with(|| (name.clone(), i));
+				signatures.insert(function.canonical_signature(), (name.clone(), i));
+				overloads.push(selector);
+			}
+			function_selectors.insert(name.clone(), overloads);
+		}
+
+		let mut topics = BTreeMap::new();
+		for (name, events) in &contract.events {
+			for (i, event) in events.iter().enumerate().filter(|(_, event)| !event.anonymous) {
+				topics.entry(event.signature()).or_insert_with(|| (name.clone(), i));
+			}
+		}
+
+		Interface { contract, selectors, signatures, function_selectors, topics }
+	}
+}
+
+impl Interface {
+	/// Loads the interface from the ABI JSON.
+	#[cfg(feature = "std")]
+	pub fn load<T: io::Read>(reader: T) -> errors::Result<Self> {
+		Contract::load(reader).map(Into::into)
+	}
+
+	/// Returns the wrapped contract.
+	pub fn contract(&self) -> &Contract {
+		&self.contract
+	}
+
+	/// Get a function by name, which must not be overloaded, or by signature such as
+	/// `transfer(address,uint256)`.
+	pub fn function(&self, name_or_signature: &str) -> errors::Result<&Function> {
+		let (name, i) = self.function_position(name_or_signature)?;
+		Ok(&self.contract.functions[name][i])
+	}
+
+	/// Name and overload index of the function with the given name or signature.
+	fn function_position(&self, name_or_signature: &str) -> errors::Result<(&str, usize)> {
+		if name_or_signature.contains('(') {
+			return self
+				.signatures
+				.get(name_or_signature)
+				.map(|(name, i)| (name.as_str(), *i))
+				.ok_or_else(|| anyhow!("unknown function signature `{}`", name_or_signature).into());
+		}
+
+		match self.contract.functions.get_key_value(name_or_signature) {
+			None => Err(Error::MissingFunction(name_or_signature.to_owned())),
+			Some((name, functions)) if functions.len() == 1 => Ok((name, 0)),
+			Some(_) => Err(anyhow!("function `{}` is overloaded, use its full signature", name_or_signature).into()),
+		}
+	}
+
+	/// Get the function having `selector`.
+	pub fn function_by_selector(&self, selector: [u8; 4]) -> Option<&Function> {
+		self.selectors.get(&selector).map(|(name, i)| &self.contract.functions[name][*i])
+	}
+
+	/// Get the non anonymous event having `topic` as signature.
+	pub fn event_by_topic(&self, topic: &Hash) -> Option<&Event> {
+		self.topics.get(topic).map(|(name, i)| &self.contract.events[name][*i])
+	}
+
+	/// Iterate over the functions keyed by selector, in no particular order.
+	pub fn selectors(&self) -> impl Iterator<Item = ([u8; 4], &Function)> {
+		self.selectors.iter().map(move |(selector, (name, i))| (*selector, &self.contract.functions[name][*i]))
+	}
+
+	/// Iterate over the non anonymous events keyed by topic, in no particular order.
+	pub fn topics(&self) -> impl Iterator<Item = (&Hash, &Event)> {
+		self.topics.iter().map(move |(topic, (name, i))| (topic, &self.contract.events[name][*i]))
+	}
+
+	/// Encodes a call to the function with the given name or signature, see [`Interface::function`],
+	/// with its precomputed selector.
+	pub fn encode_call(&self, name_or_signature: &str, tokens: &[Token]) -> errors::Result<Bytes> {
+		let (name, i) = self.function_position(name_or_signature)?;
+		self.contract.functions[name][i].encode_input_with_selector(self.function_selectors[name][i], tokens)
+	}
+
+	/// Decodes call `data`, selector followed by the arguments, returning the called function
+	/// and its arguments.
+	pub fn decode_call(&self, data: &[u8]) -> errors::Result<(&Function, Vec<Token>)> {
+		if data.len() < 4 {
+			return Err(anyhow!("calldata of {} bytes is shorter than a selector", data.len()).into());
+		}
+
+		let mut selector = [0u8; 4];
+		selector.copy_from_slice(&data[..4]);
+		let function = self
+			.function_by_selector(selector)
+			.ok_or_else(|| anyhow!("unknown function selector 0x{}", hex::encode(selector)))?;
+		Ok((function, function.decode_input(&data[4..])?))
+	}
+
+	/// Decodes the output of the function with the given name or signature.
+	pub fn decode_output(&self, name_or_signature: &str, data: &[u8]) -> errors::Result<Vec<Token>> {
+		self.function(name_or_signature)?.decode_output(data)
+	}
+
+	/// Decodes revert `data` with a custom error of the contract or a built-in error, see
+	/// [`Contract::decode_error`].
+	pub fn decode_error(&self, data: &[u8]) -> errors::Result<(&str, Vec<Token>)> {
+		self.contract.decode_error(data)
+	}
+
+	/// Parses a log emitted by a non anonymous event of the contract, found by its first topic.
+	///
+	/// The precomputed topic stands for the signature check of [`Event::parse_log`].
+	pub fn parse_log(&self, log: RawLog) -> errors::Result<(&Event, Log)> {
+		let topic = log.topics.first().ok_or_else(|| anyhow!("log has no topics"))?;
+		let event = self.event_by_topic(topic).ok_or_else(|| anyhow!("unknown event topic {:?}", topic))?;
+		Ok((event, event.parse_log_params(&log)?))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::{Interface, RawLog, Token};
+	use hex_literal::hex;
+
+	fn interface() -> Interface {
+		let abi = r#"[{
+			"type": "function",
+			"name": "transfer",
+			"inputs": [{ "name": "to", "type": "address" }, { "name": "value", "type": "uint256" }],
+			"outputs": [{ "name": "", "type": "bool" }]
+		}, {
+			"type": "function",
+			"name": "balanceOf",
+			"inputs": [{ "name": "owner", "type": "address" }],
+			"outputs": [{ "name": "", "type": "uint256" }]
+		}, {
+			"type": "function",
+			"name": "balanceOf",
+			"inputs": [{ "name": "owner", "type": "address" }, { "name": "id", "type": "uint256" }],
+			"outputs": [{ "name": "", "type": "uint256" }]
+		}, {
+			"type": "event",
+			"name": "Transfer",
+			"inputs": [
+				{ "name": "from", "type": "address", "indexed": true },
+				{ "name": "to", "type": "address", "indexed": true },
+				{ "name": "value", "type": "uint256", "indexed": false }
+			],
+			"anonymous": false
+		}]"#;
+		Interface::load(abi.as_bytes()).unwrap()
+	}
+
+	#[test]
+	fn test_calls() {
+		let interface = interface();
+		let tokens = vec![Token::Address([0x11; 20].into()), Token::Uint(7.into())];
+
+		let data = interface.encode_call("transfer", &tokens).unwrap();
+		assert_eq!(data[..4], hex!("a9059cbb"));
+		let (function, decoded) = interface.decode_call(&data).unwrap();
+		assert_eq!(function.name, "transfer");
+		assert_eq!(decoded, tokens);
+
+		let data = interface.encode_call("balanceOf(address,uint256)", &tokens).unwrap();
+		assert_eq!(interface.decode_call(&data).unwrap().0.inputs.len(), 2);
+		// the precomputed selector is the one of the function
+		let function = interface.function("balanceOf(address,uint256)").unwrap();
+		assert_eq!(data, function.encode_input(&tokens).unwrap());
+		assert!(interface.encode_call("transfer", &tokens[..1]).is_err());
+
+		assert!(interface.function("balanceOf").is_err());
+		assert!(interface.function("balanceOf(address)").is_ok());
+		assert!(interface.function("balanceOf(bool)").is_err());
+		assert!(interface.decode_call(&hex!("095ea7b3")).is_err());
+		assert_eq!(interface.selectors().count(), 3);
+
+		let output = interface
+			.decode_output("transfer", &hex!("0000000000000000000000000000000000000000000000000000000000000001"));
+		assert_eq!(output.unwrap(), vec![Token::Bool(true)]);
+	}
+
+	#[test]
+	fn test_parse_log() {
+		let interface = interface();
+		let log = RawLog {
+			topics: vec![
+				hex!("ddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef").into(),
+				hex!("0000000000000000000000001111111111111111111111111111111111111111").into(),
+				hex!("0000000000000000000000002222222222222222222222222222222222222222").into(),
+			],
+			data: hex!("0000000000000000000000000000000000000000000000000000000000000007").to_vec(),
+		};
+
+		let (event, parsed) = interface.parse_log(log).unwrap();
+		assert_eq!(event.name, "Transfer");
+		assert_eq!(parsed.params[2].value, Token::Uint(7.into()));
+		assert!(interface.parse_log(RawLog { topics: vec![], data: vec![] }).is_err());
+	}
+
+	#[test]
+	fn test_send_sync() {
+		fn assert_send_sync<T: Send + Sync>() {}
+		assert_send_sync::<Interface>();
+	}
+}
@@ -0,0 +1,119 @@
+// Copyright 2015-2020 Parity Technologies
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Normalization of the JSON ABIs of Vyper and old solc versions, see
+//! [`Contract::load_lenient_from_slice`](crate::Contract::load_lenient_from_slice).
+
+#[cfg(not(feature = "std"))]
+use crate::no_std_prelude::*;
+use serde_json::{Map, Value};
+
+/// Rewrites a bare ABI, an artifact holding it in its `abi` field or an ABI embedded as a JSON
+/// string into the standard form, which the strict deserializer accepts.
+///
+/// Anything else is left as it is, for the deserializer to report.
+pub(crate) fn normalize(value: &mut Value) {
+	match value {
+		Value::String(json) => {
+			if let Ok(mut abi) = serde_json::from_str::<Value>(json) {
+				normalize(&mut abi);
+				*value = abi;
+			}
+		}
+		Value::Object(artifact) => {
+			if let Some(abi) = artifact.get_mut("abi") {
+				normalize(abi);
+			}
+		}
+		Value::Array(operations) => {
+			for operation in operations.iter_mut().filter_map(Value::as_object_mut) {
+				normalize_operation(operation);
+			}
+		}
+		_ => (),
+	}
+}
+
+fn normalize_operation(operation: &mut Map<String, Value>) {
+	// the type defaults to function, which old solc versions left out
+	let kind = operation.entry("type").or_insert_with(|| "function".into()).as_str().unwrap_or_default().to_owned();
+	// e.g. `"gas": 21000` of Vyper, which is only an estimate
+	operation.remove("gas");
+	for flag in ["constant", "payable"] {
+		if let Some(value) = operation.get_mut(flag) {
+			if let Some(parsed) = value.as_str().and_then(|s| s.parse::<bool>().ok()) {
+				*value = Value::Bool(parsed);
+			}
+		}
+	}
+
+	let fields: &[&str] = match kind.as_str() {
+		"function" => &["inputs", "outputs"],
+		"constructor" | "event" | "error" => &["inputs"],
+		_ => &[],
+	};
+	for field in fields {
+		let params = operation.entry(*field).or_insert_with(|| Value::Array(vec![]));
+		if let Some(params) = params.as_array_mut() {
+			params.iter_mut().filter_map(Value::as_object_mut).for_each(normalize_param);
+		}
+	}
+}
+
+/// Drops the units of Vyper params, e.g. `"unit": "wei"` or the `(wei)` of `uint256(wei)`.
+fn normalize_param(param: &mut Map<String, Value>) {
+	param.remove("unit");
+	if let Some(Value::String(kind)) = param.get_mut("type") {
+		if !kind.starts_with('(') && kind.ends_with(')') {
+			if let Some(start) = kind.find('(') {
+				kind.truncate(start);
+			}
+		}
+	}
+	if let Some(components) = param.get_mut("components").and_then(Value::as_array_mut) {
+		components.iter_mut().filter_map(Value::as_object_mut).for_each(normalize_param);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::{Contract, ParamType, StateMutability};
+
+	#[test]
+	fn test_load_lenient() {
+		let abi = r#"[
+			{"name":"transfer","inputs":[{"name":"to","type":"address"},{"name":"value","type":"uint256","unit":"wei"}],"constant":"false","payable":"false","gas":36000},
+			{"type":"function","name":"balanceOf","inputs":[{"name":"owner","type":"address"}],"constant":"true","outputs":[{"name":"","type":"uint256(wei)"}]},
+			{"type":"event","name":"Transfer","inputs":[{"name":"value","type":"int128","unit":"sec","indexed":false}],"anonymous":false},
+			{"type":"function","name":"settle","inputs":[{"name":"order","type":"tuple","components":[{"name":"amount","type":"uint256(wei)"}]}]}
+		]"#;
+		assert!(Contract::load_from_slice(abi.as_bytes()).is_err());
+
+		let contract = Contract::load_lenient_from_slice(abi.as_bytes()).unwrap();
+		let transfer = contract.function("transfer").unwrap();
+		assert_eq!(transfer.signature(), "transfer(address,uint256)");
+		assert!(transfer.outputs.is_empty());
+		assert_eq!(transfer.state_mutability, StateMutability::NonPayable);
+
+		let balance_of = contract.function("balanceOf").unwrap();
+		assert_eq!(balance_of.outputs[0].kind, ParamType::Uint(256));
+		assert_eq!(balance_of.state_mutability, StateMutability::View);
+		assert_eq!(contract.event("Transfer").unwrap().inputs[0].kind, ParamType::Int(128));
+		assert_eq!(contract.function("settle").unwrap().inputs[0].kind, ParamType::Tuple(vec![ParamType::Uint(256)]));
+
+		// standard ABIs load the same, also as artifacts
+		let artifact = format!(r#"{{"contractName":"Token","abi":{}}}"#, abi);
+		assert_eq!(Contract::load_lenient_from_slice(artifact.as_bytes()).unwrap(), contract);
+		let standard = serde_json::to_string(&contract).unwrap();
+		assert_eq!(
+			Contract::load_lenient_from_slice(standard.as_bytes()).unwrap(),
+			Contract::load_from_slice(standard.as_bytes()).unwrap()
+		);
+		assert!(Contract::load_lenient_from_slice(br#"[{"type":"function"}]"#).is_err());
+	}
+}
@@ -7,48 +7,98 @@
 // except according to those terms.
 
 //! Ethereum ABI encoding decoding library.
+//!
+//! The crate is `no_std` when its default `std` feature is disabled, in which case loading
+//! ABIs from readers and directories is not available.
 
+#![cfg_attr(not(feature = "std"), no_std)]
 #![allow(clippy::module_inception)]
 #![warn(missing_docs)]
 
+#[cfg_attr(not(feature = "std"), macro_use)]
+extern crate alloc;
+
+#[cfg(not(feature = "std"))]
+mod no_std_prelude {
+	pub(crate) use alloc::{
+		borrow::ToOwned,
+		boxed::Box,
+		string::{String, ToString},
+		vec::Vec,
+	};
+}
+
+#[cfg(not(feature = "std"))]
+use crate::no_std_prelude::*;
+
+mod abi_error;
+mod artifact;
+mod builder;
+#[cfg(feature = "cache")]
+mod cache;
 mod constructor;
 mod contract;
+mod decoded_params;
 mod decoder;
+mod diff;
+pub mod eip712;
 mod encoder;
 mod errors;
 mod event;
 mod event_param;
 mod filter;
 mod function;
+#[cfg(feature = "fuzz")]
+pub mod fuzz;
+mod human_readable;
+mod interface;
+mod legacy;
 mod log;
+pub mod multicall;
 mod operation;
 mod param;
 pub mod param_type;
+mod selector_set;
 mod signature;
+mod state_mutability;
 pub mod token;
 mod tuple_param;
-mod util;
+pub mod util;
+#[cfg(feature = "wasm")]
+pub mod wasm;
 
 #[cfg(test)]
 mod tests;
 
 pub use crate::{
+	abi_error::AbiError,
+	artifact::Artifact,
+	builder::ContractBuilder,
 	constructor::Constructor,
-	contract::{Contract, Events, Functions},
-	decoder::decode,
-	encoder::encode,
+	contract::{build_selector_db, AbiErrors, Contract, DecodedInput, Events, Functions},
+	decoded_params::DecodedParams,
+	decoder::{decode, decode_borrowed, decode_iter, decode_lenient, decode_strict, ArrayDecoder, Decoder},
+	diff::{AbiEntry, ContractDiff},
+	encoder::{encode, encode_checked, encode_into, encode_packed, encoded_size, token_encoded_size},
 	errors::{Error, Result},
 	event::Event,
 	event_param::EventParam,
-	filter::{RawTopicFilter, Topic, TopicFilter},
-	function::Function,
+	filter::{RawTopicFilter, Topic, TopicFilter, TopicFilterBuilder},
+	function::{decode_signature, encode_call, format_call, Function},
+	interface::Interface,
 	log::{Log, LogFilter, LogParam, ParseLog, RawLog},
-	param::Param,
+	param::{InternalType, Param},
 	param_type::ParamType,
-	token::Token,
+	selector_set::SelectorSet,
+	signature::{canonical_signature, long_signature, short_signature},
+	state_mutability::StateMutability,
+	token::{Detokenizable, Token, TokenRef, Tokenizable},
 	tuple_param::TupleParam,
 };
 
+#[cfg(feature = "std")]
+pub use crate::{contract::LoadedDir, encoder::encode_to_writer};
+
 /// ABI word.
 pub type Word = [u8; 32];
 
@@ -70,6 +120,9 @@ pub type Uint = ethereum_types::U256;
 /// Commonly used FixedBytes of size 32
 pub type Hash = ethereum_types::H256;
 
+/// 2048 bits bloom of the addresses and topics of the logs of a block or receipt.
+pub type Bloom = ethereum_types::Bloom;
+
 /// Contract functions generated by ethabi-derive
 pub trait FunctionOutputDecoder {
 	/// Output types of the contract function
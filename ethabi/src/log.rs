@@ -6,7 +6,9 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
-use crate::{Bytes, Hash, Result, Token, TopicFilter};
+#[cfg(not(feature = "std"))]
+use crate::no_std_prelude::*;
+use crate::{function::format_token, Bytes, Hash, Result, Token, TopicFilter};
 
 /// Common filtering functions that are available for any event.
 pub trait LogFilter {
@@ -55,3 +57,73 @@ pub struct Log {
 	/// Log params.
 	pub params: Vec<LogParam>,
 }
+
+impl Log {
+	/// Returns the value of the first param named `name`.
+	pub fn param(&self, name: &str) -> Option<&Token> {
+		self.params.iter().find(|param| param.name == name).map(|param| &param.value)
+	}
+
+	/// Returns true if all params hold the default value of their type, see [`Token::is_zero_value`],
+	/// e.g. zero-value transfers. "Empty" means all-default, not literally without params.
+	pub fn is_empty(&self) -> bool {
+		self.params.iter().all(|param| param.value.is_zero_value())
+	}
+
+	/// Converts the params into CSV cells, in the order of the event's params.
+	///
+	/// Addresses and bytes are `0x`-prefixed hex, integers decimal and strings verbatim.
+	/// Arrays and tuples are rendered inline, e.g. `[1, 2]` or `(0x12, "foo")`.
+	pub fn to_csv_record(&self) -> Vec<String> {
+		self.params
+			.iter()
+			.map(|param| match param.value {
+				Token::String(ref s) => s.clone(),
+				ref token => format_token(token),
+			})
+			.collect()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::{Log, LogParam, Token};
+
+	#[test]
+	fn test_to_csv_record() {
+		let log = Log {
+			params: vec![
+				LogParam { name: "from".to_owned(), value: Token::Address([0x11u8; 20].into()) },
+				LogParam { name: "value".to_owned(), value: Token::Uint(1000.into()) },
+				LogParam { name: "memo".to_owned(), value: Token::String("gm, fren".to_owned()) },
+				LogParam { name: "data".to_owned(), value: Token::Bytes(vec![0x12, 0x34]) },
+				LogParam {
+					name: "ids".to_owned(),
+					value: Token::Array(vec![Token::Int(1.into()), Token::Int(!crate::Int::zero())]),
+				},
+			],
+		};
+
+		assert_eq!(
+			log.to_csv_record(),
+			vec!["0x1111111111111111111111111111111111111111", "1000", "gm, fren", "0x1234", "[1, -1]"]
+		);
+	}
+
+	#[test]
+	fn test_is_empty() {
+		let mut log = Log {
+			params: vec![
+				LogParam { name: "from".to_owned(), value: Token::Address(Default::default()) },
+				LogParam { name: "value".to_owned(), value: Token::Uint(0.into()) },
+			],
+		};
+		assert!(log.is_empty());
+		assert!(Log { params: vec![] }.is_empty());
+
+		log.params[1].value = Token::Uint(1.into());
+		assert!(!log.is_empty());
+		assert_eq!(log.param("value"), Some(&Token::Uint(1.into())));
+		assert_eq!(log.param("to"), None);
+	}
+}
@@ -0,0 +1,176 @@
+// Copyright 2015-2020 Parity Technologies
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Batching of calls through the Multicall and Multicall3 contracts.
+//!
+//! [`encode_aggregate`] and [`encode_aggregate3`] build the calldata of `aggregate` and
+//! `aggregate3`, and their decoding counterparts split the returned data back into the
+//! outputs of each call.
+
+#[cfg(not(feature = "std"))]
+use crate::no_std_prelude::*;
+use crate::{Address, Bytes, Error, Function, Result, Token, Uint};
+use anyhow::anyhow;
+
+/// Call of `function` with `tokens` on the contract at `target`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Call<'a> {
+	/// Called contract.
+	pub target: Address,
+	/// Called function.
+	pub function: &'a Function,
+	/// Arguments of the call.
+	pub tokens: Vec<Token>,
+	/// Whether `aggregate3` may let the call revert, ignored by `aggregate`.
+	pub allow_failure: bool,
+}
+
+impl<'a> Call<'a> {
+	/// Call of `function` with `tokens` on `target`, which must not revert.
+	pub fn new(target: Address, function: &'a Function, tokens: Vec<Token>) -> Self {
+		Call { target, function, tokens, allow_failure: false }
+	}
+}
+
+fn aggregate() -> Function {
+	Function::parse("aggregate((address,bytes)[] calls) returns (uint256 blockNumber, bytes[] returnData)")
+		.expect("valid signature; qed")
+}
+
+fn aggregate3() -> Function {
+	Function::parse("aggregate3((address,bool,bytes)[] calls) payable returns ((bool,bytes)[] returnData)")
+		.expect("valid signature; qed")
+}
+
+/// Encodes the calldata of `aggregate((address,bytes)[])`, which reverts if any call does.
+pub fn encode_aggregate(calls: &[Call]) -> Result<Bytes> {
+	let calls = calls
+		.iter()
+		.map(|call| {
+			let data = call.function.encode_input(&call.tokens)?;
+			Ok(Token::Tuple(vec![Token::Address(call.target), Token::Bytes(data)]))
+		})
+		.collect::<Result<_>>()?;
+	aggregate().encode_input(&[Token::Array(calls)])
+}
+
+/// Decodes the output of `aggregate`, returning the block number and the outputs of `calls`.
+pub fn decode_aggregate(calls: &[Call], data: &[u8]) -> Result<(Uint, Vec<Vec<Token>>)> {
+	let mut tokens = aggregate().decode_output(data)?.into_iter();
+	let (block_number, return_data) = match (tokens.next(), tokens.next()) {
+		(Some(Token::Uint(block_number)), Some(Token::Array(return_data))) => (block_number, return_data),
+		_ => return Err(Error::InvalidData),
+	};
+	if return_data.len() != calls.len() {
+		return Err(anyhow!("{} calls returned {} results", calls.len(), return_data.len()).into());
+	}
+
+	let outputs = calls
+		.iter()
+		.zip(return_data)
+		.map(|(call, data)| {
+			let data = data.into_bytes().ok_or(Error::InvalidData)?;
+			call.function.decode_output(&data)
+		})
+		.collect::<Result<_>>()?;
+	Ok((block_number, outputs))
+}
+
+/// Encodes the calldata of `aggregate3((address,bool,bytes)[])`, which only reverts if a call
+/// not allowed to fail does.
+pub fn encode_aggregate3(calls: &[Call]) -> Result<Bytes> {
+	let calls = calls
+		.iter()
+		.map(|call| {
+			let data = call.function.encode_input(&call.tokens)?;
+			Ok(Token::Tuple(vec![Token::Address(call.target), Token::Bool(call.allow_failure), Token::Bytes(data)]))
+		})
+		.collect::<Result<_>>()?;
+	aggregate3().encode_input(&[Token::Array(calls)])
+}
+
+/// Decodes the output of `aggregate3` into the outputs of `calls`, or the revert data of the
+/// ones which failed.
+pub fn decode_aggregate3(calls: &[Call], data: &[u8]) -> Result<Vec<core::result::Result<Vec<Token>, Bytes>>> {
+	let results = match aggregate3().decode_output(data)?.pop() {
+		Some(Token::Array(results)) => results,
+		_ => return Err(Error::InvalidData),
+	};
+	if results.len() != calls.len() {
+		return Err(anyhow!("{} calls returned {} results", calls.len(), results.len()).into());
+	}
+
+	calls
+		.iter()
+		.zip(results)
+		.map(|(call, result)| {
+			let mut result = result.into_tuple().ok_or(Error::InvalidData)?.into_iter();
+			match (result.next(), result.next().and_then(Token::into_bytes)) {
+				(Some(Token::Bool(true)), Some(data)) => Ok(Ok(call.function.decode_output(&data)?)),
+				(Some(Token::Bool(false)), Some(data)) => Ok(Err(data)),
+				_ => Err(Error::InvalidData),
+			}
+		})
+		.collect()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{decode_aggregate, decode_aggregate3, encode_aggregate, encode_aggregate3, Call};
+	use crate::{encode, Address, Function, Token};
+	use hex_literal::hex;
+
+	#[test]
+	fn test_aggregate() {
+		let balance_of = Function::parse("balanceOf(address owner) view returns (uint256)").unwrap();
+		let owner = Token::Address([0x11; 20].into());
+		let calls = [
+			Call::new(Address::from([0xaa; 20]), &balance_of, vec![owner.clone()]),
+			Call::new(Address::from([0xbb; 20]), &balance_of, vec![owner.clone()]),
+		];
+
+		let data = encode_aggregate(&calls).unwrap();
+		assert_eq!(data[..4], hex!("252dba42"));
+		let inner = balance_of.encode_input(&[owner]).unwrap();
+		let expected = encode(&[Token::Array(vec![
+			Token::Tuple(vec![Token::Address(calls[0].target), Token::Bytes(inner.clone())]),
+			Token::Tuple(vec![Token::Address(calls[1].target), Token::Bytes(inner)]),
+		])]);
+		assert_eq!(data[4..], expected[..]);
+
+		let output = encode(&[
+			Token::Uint(100.into()),
+			Token::Array(vec![
+				Token::Bytes(encode(&[Token::Uint(1.into())])),
+				Token::Bytes(encode(&[Token::Uint(2.into())])),
+			]),
+		]);
+		let (block_number, outputs) = decode_aggregate(&calls, &output).unwrap();
+		assert_eq!(block_number, 100.into());
+		assert_eq!(outputs, vec![vec![Token::Uint(1.into())], vec![Token::Uint(2.into())]]);
+		assert!(decode_aggregate(&calls[..1], &output).is_err());
+	}
+
+	#[test]
+	fn test_aggregate3() {
+		let total_supply = Function::parse("totalSupply() view returns (uint256)").unwrap();
+		let mut call = Call::new(Address::from([0xaa; 20]), &total_supply, vec![]);
+		call.allow_failure = true;
+		let calls = [call.clone(), call];
+
+		let data = encode_aggregate3(&calls).unwrap();
+		assert_eq!(data[..4], hex!("82ad56cb"));
+
+		let output = encode(&[Token::Array(vec![
+			Token::Tuple(vec![Token::Bool(true), Token::Bytes(encode(&[Token::Uint(7.into())]))]),
+			Token::Tuple(vec![Token::Bool(false), Token::Bytes(vec![0xde, 0xad])]),
+		])]);
+		let results = decode_aggregate3(&calls, &output).unwrap();
+		assert_eq!(results, vec![Ok(vec![Token::Uint(7.into())]), Err(vec![0xde, 0xad])]);
+	}
+}
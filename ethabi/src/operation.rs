@@ -8,8 +8,10 @@
 
 //! Operation type.
 
-use crate::{Constructor, Event, Function};
-use serde::{de::Error as SerdeError, Deserialize, Deserializer};
+#[cfg(not(feature = "std"))]
+use crate::no_std_prelude::*;
+use crate::{AbiError, Constructor, Event, Function, StateMutability};
+use serde::{de::Error as SerdeError, ser::SerializeMap, Deserialize, Deserializer, Serialize, Serializer};
 use serde_json::{value::from_value, Value};
 
 /// Operation type.
@@ -21,8 +23,12 @@ pub enum Operation {
 	Function(Function),
 	/// Contract event.
 	Event(Event),
-	/// Fallback, ignored.
-	Fallback,
+	/// Contract custom error.
+	Error(AbiError),
+	/// Fallback function, with its state mutability.
+	Fallback(StateMutability),
+	/// Receive ether function.
+	Receive,
 }
 
 impl<'a> Deserialize<'a> for Operation {
@@ -52,17 +58,55 @@ impl<'a> Deserialize<'a> for Operation {
 				sanitize_name(&mut e.name);
 				Operation::Event(e)
 			}),
-			"fallback" => Ok(Operation::Fallback),
+			"error" => from_value(v).map(Operation::Error),
+			"fallback" => match map.get("stateMutability") {
+				Some(state_mutability) => from_value(state_mutability.clone()).map(Operation::Fallback),
+				None => {
+					let payable = map.get("payable").and_then(Value::as_bool).unwrap_or(false);
+					Ok(Operation::Fallback(StateMutability::from_legacy(false, payable).expect("not constant; qed")))
+				}
+			},
+			"receive" => Ok(Operation::Receive),
 			_ => Err(SerdeError::custom("Invalid operation type.")),
 		};
 		result.map_err(|e| D::Error::custom(e.to_string()))
 	}
 }
 
+impl Serialize for Operation {
+	fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		let (kind, state_mutability) = match self {
+			Operation::Constructor(constructor) => return constructor.serialize(serializer),
+			Operation::Function(function) => return function.serialize(serializer),
+			Operation::Event(event) => return event.serialize(serializer),
+			Operation::Error(error) => return error.serialize(serializer),
+			Operation::Fallback(state_mutability) => ("fallback", *state_mutability),
+			Operation::Receive => ("receive", StateMutability::Payable),
+		};
+		let mut map = serializer.serialize_map(Some(2))?;
+		map.serialize_entry("type", kind)?;
+		map.serialize_entry("stateMutability", &state_mutability)?;
+		map.end()
+	}
+}
+
 #[cfg(test)]
 mod tests {
 	use super::Operation;
-	use crate::{Function, Param, ParamType};
+	use crate::{Function, Param, ParamType, StateMutability};
+
+	#[test]
+	fn deserialize_fallback_and_receive() {
+		let fallback: Operation = serde_json::from_str(r#"{"type":"fallback","stateMutability":"payable"}"#).unwrap();
+		assert_eq!(fallback, Operation::Fallback(StateMutability::Payable));
+
+		// legacy ABIs only have the `payable` flag
+		let fallback: Operation = serde_json::from_str(r#"{"type":"fallback","payable":false}"#).unwrap();
+		assert_eq!(fallback, Operation::Fallback(StateMutability::NonPayable));
+
+		let receive: Operation = serde_json::from_str(r#"{"type":"receive","stateMutability":"payable"}"#).unwrap();
+		assert_eq!(receive, Operation::Receive);
+	}
 
 	#[test]
 	fn deserialize_operation() {
@@ -82,9 +126,15 @@ mod tests {
 			deserialized,
 			Operation::Function(Function {
 				name: "foo".to_owned(),
-				inputs: vec![Param { name: "a".to_owned(), kind: ParamType::Address }],
+				inputs: vec![Param {
+					name: "a".to_owned(),
+					kind: ParamType::Address,
+					internal_type: None,
+					components: vec![]
+				}],
 				outputs: vec![],
 				constant: false,
+				state_mutability: StateMutability::NonPayable,
 			})
 		);
 	}
@@ -8,13 +8,19 @@
 
 //! Function param.
 
+#[cfg(not(feature = "std"))]
+use crate::no_std_prelude::*;
+use core::fmt;
 use serde::{
-	de::{Error, MapAccess, Visitor},
-	Deserialize, Deserializer,
+	de::{Error, IgnoredAny, MapAccess, Visitor},
+	ser::SerializeMap,
+	Deserialize, Deserializer, Serialize, Serializer,
 };
-use std::fmt;
 
-use crate::{ParamType, TupleParam};
+use crate::{
+	tuple_param::{serialize_param, with_components},
+	ParamType, TupleParam,
+};
 
 /// Function param.
 #[derive(Debug, Clone, PartialEq)]
@@ -23,6 +29,67 @@ pub struct Param {
 	pub name: String,
 	/// Param type.
 	pub kind: ParamType,
+	/// Solidity type of the param as given by the `internalType` of the JSON ABI, e.g.
+	/// `struct Market.Order[]`, if the ABI has it.
+	pub internal_type: Option<String>,
+	/// Names and types of the components of a tuple param, possibly nested in arrays, empty for
+	/// other types.
+	pub components: Vec<TupleParam>,
+}
+
+impl Param {
+	/// Solidity type of the param, see [`InternalType`].
+	pub fn internal_type(&self) -> Option<InternalType<'_>> {
+		self.internal_type.as_deref().map(InternalType::parse)
+	}
+}
+
+/// Solidity type of a param, which the ABI type loses for structs, enums and contracts.
+///
+/// Array suffixes are dropped, `struct Market.Order[2][]` being the struct `Order` of `Market`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum InternalType<'a> {
+	/// Struct `name`, declared in `contract` unless it is a file level struct.
+	Struct {
+		/// Contract declaring the struct.
+		contract: Option<&'a str>,
+		/// Name of the struct.
+		name: &'a str,
+	},
+	/// Enum `name`, declared in `contract` unless it is a file level enum.
+	Enum {
+		/// Contract declaring the enum.
+		contract: Option<&'a str>,
+		/// Name of the enum.
+		name: &'a str,
+	},
+	/// Contract or interface, represented by its address.
+	Contract(&'a str),
+	/// Elementary type, e.g. `uint256` or `address payable`.
+	Other(&'a str),
+}
+
+impl<'a> InternalType<'a> {
+	/// Parses an `internalType` of the JSON ABI.
+	pub fn parse(internal_type: &'a str) -> Self {
+		let base = internal_type.find('[').map_or(internal_type, |pos| &internal_type[..pos]);
+		let qualified = |name: &'a str| match name.rsplit_once('.') {
+			Some((contract, name)) => (Some(contract), name),
+			None => (None, name),
+		};
+		if let Some(name) = base.strip_prefix("struct ") {
+			let (contract, name) = qualified(name);
+			return InternalType::Struct { contract, name };
+		}
+		if let Some(name) = base.strip_prefix("enum ") {
+			let (contract, name) = qualified(name);
+			return InternalType::Enum { contract, name };
+		}
+		match base.strip_prefix("contract ") {
+			Some(name) => InternalType::Contract(name),
+			None => InternalType::Other(base),
+		}
+	}
 }
 
 impl<'a> Deserialize<'a> for Param {
@@ -34,6 +101,17 @@ impl<'a> Deserialize<'a> for Param {
 	}
 }
 
+impl Serialize for Param {
+	fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		let mut map = serializer.serialize_map(None)?;
+		serialize_param(&mut map, &self.name, &self.kind, &self.components)?;
+		if let Some(ref internal_type) = self.internal_type {
+			map.serialize_entry("internalType", internal_type)?;
+		}
+		map.end()
+	}
+}
+
 struct ParamVisitor;
 
 impl<'a> Visitor<'a> for ParamVisitor {
@@ -50,6 +128,7 @@ impl<'a> Visitor<'a> for ParamVisitor {
 		let mut name = None;
 		let mut kind = None;
 		let mut components = None;
+		let mut internal_type = None;
 
 		while let Some(ref key) = map.next_key::<String>()? {
 			match key.as_ref() {
@@ -69,47 +148,33 @@ impl<'a> Visitor<'a> for ParamVisitor {
 					if components.is_some() {
 						return Err(Error::duplicate_field("components"));
 					}
-					let component: Vec<TupleParam> = map.next_value()?;
-					components = Some(component)
+					components = map.next_value::<Option<Vec<TupleParam>>>()?;
+				}
+				"internalType" => {
+					if internal_type.is_some() {
+						return Err(Error::duplicate_field("internalType"));
+					}
+					internal_type = map.next_value()?;
+				}
+				_ => {
+					map.next_value::<IgnoredAny>()?;
 				}
-				_ => {}
 			}
 		}
-		let name = name.ok_or_else(|| Error::missing_field("name"))?;
-		let kind =
-			kind.ok_or_else(|| Error::missing_field("kind")).and_then(|param_type: ParamType| match param_type {
-				ParamType::Tuple(_) => {
-					let tuple_params = components.ok_or_else(|| Error::missing_field("components"))?;
-					Ok(ParamType::Tuple(tuple_params.into_iter().map(|param| param.kind).collect()))
-				}
-				ParamType::Array(inner_param_type) => match *inner_param_type {
-					ParamType::Tuple(_) => {
-						let tuple_params = components.ok_or_else(|| Error::missing_field("components"))?;
-						Ok(ParamType::Array(Box::new(ParamType::Tuple(
-							tuple_params.into_iter().map(|param| param.kind).collect(),
-						))))
-					}
-					_ => Ok(ParamType::Array(inner_param_type)),
-				},
-				ParamType::FixedArray(inner_param_type, size) => match *inner_param_type {
-					ParamType::Tuple(_) => {
-						let tuple_params = components.ok_or_else(|| Error::missing_field("components"))?;
-						Ok(ParamType::FixedArray(
-							Box::new(ParamType::Tuple(tuple_params.into_iter().map(|param| param.kind).collect())),
-							size,
-						))
-					}
-					_ => Ok(ParamType::FixedArray(inner_param_type, size)),
-				},
-				_ => Ok(param_type),
-			})?;
-		Ok(Param { name, kind })
+		let name: String = name.ok_or_else(|| Error::missing_field("name"))?;
+		let kind = kind.ok_or_else(|| Error::missing_field("kind"))?;
+		let (kind, components) = with_components(kind, components, &name)?;
+		Ok(Param { name, kind, internal_type, components })
 	}
 }
 
 #[cfg(test)]
 mod tests {
-	use crate::{Param, ParamType};
+	use crate::{InternalType, Param, ParamType, TupleParam};
+
+	fn component(name: &str, kind: ParamType) -> TupleParam {
+		TupleParam { name: Some(name.to_owned()), kind, internal_type: None, components: vec![] }
+	}
 
 	#[test]
 	fn param_deserialization() {
@@ -120,7 +185,10 @@ mod tests {
 
 		let deserialized: Param = serde_json::from_str(s).unwrap();
 
-		assert_eq!(deserialized, Param { name: "foo".to_owned(), kind: ParamType::Address });
+		assert_eq!(
+			deserialized,
+			Param { name: "foo".to_owned(), kind: ParamType::Address, internal_type: None, components: vec![] }
+		);
 	}
 
 	#[test]
@@ -153,6 +221,14 @@ mod tests {
 			Param {
 				name: "foo".to_owned(),
 				kind: ParamType::Tuple(vec![ParamType::Uint(48), ParamType::Tuple(vec![ParamType::Address])]),
+				internal_type: None,
+				components: vec![
+					component("amount", ParamType::Uint(48)),
+					TupleParam {
+						components: vec![component("baseTupleParam", ParamType::Address)],
+						..component("things", ParamType::Tuple(vec![ParamType::Address]))
+					},
+				],
 			}
 		);
 	}
@@ -189,6 +265,12 @@ mod tests {
 					ParamType::Address,
 					ParamType::Address
 				]))),
+				internal_type: None,
+				components: vec![
+					component("amount", ParamType::Uint(48)),
+					component("to", ParamType::Address),
+					component("from", ParamType::Address),
+				],
 			}
 		);
 	}
@@ -224,7 +306,49 @@ mod tests {
 					Box::new(ParamType::Tuple(vec![ParamType::Uint(48), ParamType::Address, ParamType::Address])),
 					2
 				),
+				internal_type: None,
+				components: vec![
+					component("amount", ParamType::Uint(48)),
+					component("to", ParamType::Address),
+					component("from", ParamType::Address),
+				],
 			}
 		);
 	}
+
+	#[test]
+	fn param_internal_type() {
+		let s = r#"{
+			"name": "orders",
+			"type": "tuple[]",
+			"internalType": "struct Market.Order[]",
+			"components": [
+				{ "name": "maker", "type": "address", "internalType": "address" },
+				{ "name": "side", "type": "uint8", "internalType": "enum Side" }
+			]
+		}"#;
+
+		let deserialized: Param = serde_json::from_str(s).unwrap();
+
+		assert_eq!(deserialized.internal_type.as_deref(), Some("struct Market.Order[]"));
+		assert_eq!(
+			deserialized.internal_type(),
+			Some(InternalType::Struct { contract: Some("Market"), name: "Order" })
+		);
+		let serialized = serde_json::to_value(&deserialized).unwrap();
+		assert_eq!(serialized["internalType"], "struct Market.Order[]");
+		assert_eq!(serde_json::from_value::<Param>(serialized).unwrap(), deserialized);
+	}
+
+	#[test]
+	fn internal_type_parse() {
+		assert_eq!(InternalType::parse("struct Order"), InternalType::Struct { contract: None, name: "Order" });
+		assert_eq!(
+			InternalType::parse("enum Market.Side[2]"),
+			InternalType::Enum { contract: Some("Market"), name: "Side" }
+		);
+		assert_eq!(InternalType::parse("contract IERC20"), InternalType::Contract("IERC20"));
+		assert_eq!(InternalType::parse("address payable"), InternalType::Other("address payable"));
+		assert_eq!(InternalType::parse("uint256[]"), InternalType::Other("uint256"));
+	}
 }
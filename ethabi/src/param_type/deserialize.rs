@@ -7,11 +7,13 @@
 // except according to those terms.
 
 use super::{ParamType, Reader};
+#[cfg(not(feature = "std"))]
+use crate::no_std_prelude::*;
+use core::fmt;
 use serde::{
 	de::{Error as SerdeError, Visitor},
 	Deserialize, Deserializer,
 };
-use std::fmt;
 
 impl<'a> Deserialize<'a> for ParamType {
 	fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
@@ -52,8 +54,7 @@ mod tests {
 
 	#[test]
 	fn param_type_deserialization() {
-		let s =
-			r#"["address", "bytes", "bytes32", "bool", "string", "int", "uint", "address[]", "uint[3]", "bool[][5]"]"#;
+		let s = r#"["address", "bytes", "bytes32", "bool", "string", "int", "uint", "address[]", "uint[3]", "bool[][5]", "function", "fixed128x18"]"#;
 		let deserialized: Vec<ParamType> = serde_json::from_str(s).unwrap();
 		assert_eq!(
 			deserialized,
@@ -67,7 +68,9 @@ mod tests {
 				ParamType::Uint(256),
 				ParamType::Array(Box::new(ParamType::Address)),
 				ParamType::FixedArray(Box::new(ParamType::Uint(256)), 3),
-				ParamType::FixedArray(Box::new(ParamType::Array(Box::new(ParamType::Bool))), 5)
+				ParamType::FixedArray(Box::new(ParamType::Array(Box::new(ParamType::Bool))), 5),
+				ParamType::Function,
+				ParamType::Fixed(128, 18),
 			]
 		);
 	}
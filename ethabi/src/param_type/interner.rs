@@ -0,0 +1,138 @@
+// Copyright 2015-2020 Parity Technologies
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Param type interner.
+
+use super::ParamType;
+use std::{collections::HashSet, sync::Arc};
+
+/// Deduplicates param types, so that tools keeping the types of many ABIs keep a shared
+/// [`Arc`] per distinct type instead of a copy per param.
+///
+/// Loading does not go through the interner: [`Param::kind`](crate::Param::kind) still owns its
+/// type, so only the types kept as interned `Arc`s are shared. Whole types are shared, the types
+/// nested in arrays and tuples are not deduplicated on their own.
+#[derive(Debug, Default)]
+pub struct ParamTypeInterner {
+	types: HashSet<Arc<ParamType>>,
+}
+
+impl ParamTypeInterner {
+	/// Creates an empty interner.
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Returns the shared instance of `kind`, allocating it on first use.
+	pub fn intern(&mut self, kind: &ParamType) -> Arc<ParamType> {
+		if let Some(interned) = self.types.get(kind) {
+			return interned.clone();
+		}
+
+		let interned = Arc::new(kind.clone());
+		self.types.insert(interned.clone());
+		interned
+	}
+
+	/// Returns the number of distinct types interned so far.
+	pub fn len(&self) -> usize {
+		self.types.len()
+	}
+
+	/// Returns true if no types were interned yet.
+	pub fn is_empty(&self) -> bool {
+		self.types.is_empty()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::ParamTypeInterner;
+	use crate::{Contract, ParamType};
+	use std::{
+		alloc::{GlobalAlloc, Layout, System},
+		cell::Cell,
+		sync::Arc,
+	};
+
+	/// Counts the bytes allocated by each thread, so that tests running in parallel do not
+	/// disturb each other's count.
+	struct CountingAllocator;
+
+	thread_local! {
+		static ALLOCATED: Cell<usize> = const { Cell::new(0) };
+	}
+
+	unsafe impl GlobalAlloc for CountingAllocator {
+		unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+			let _ = ALLOCATED.try_with(|allocated| allocated.set(allocated.get() + layout.size()));
+			System.alloc(layout)
+		}
+
+		unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+			System.dealloc(ptr, layout)
+		}
+	}
+
+	#[global_allocator]
+	static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+	/// Returns the value built by `f` along with the bytes allocated while building it.
+	fn allocated<T>(f: impl FnOnce() -> T) -> (T, usize) {
+		let before = ALLOCATED.with(Cell::get);
+		let value = f();
+		(value, ALLOCATED.with(Cell::get) - before)
+	}
+
+	#[test]
+	fn identical_types_are_shared() {
+		let mut interner = ParamTypeInterner::new();
+		let a = interner.intern(&ParamType::Array(Box::new(ParamType::Uint(256))));
+		let b = interner.intern(&ParamType::Array(Box::new(ParamType::Uint(256))));
+		let c = interner.intern(&ParamType::Array(Box::new(ParamType::Uint(8))));
+
+		assert!(Arc::ptr_eq(&a, &b));
+		assert!(!Arc::ptr_eq(&a, &c));
+		assert_eq!(interner.len(), 2);
+	}
+
+	#[test]
+	fn abi_corpus_deduplication() {
+		let corpus = [
+			&include_bytes!("../../../res/eip20.abi")[..],
+			include_bytes!("../../../res/Operations.abi"),
+			include_bytes!("../../../res/Validators.abi"),
+			include_bytes!("../../../res/urlhint.abi"),
+			include_bytes!("../../../res/test.abi"),
+		];
+
+		let contracts = corpus.iter().map(|abi| Contract::load(*abi).unwrap()).collect::<Vec<_>>();
+		let kinds = contracts
+			.iter()
+			.flat_map(|contract| {
+				contract
+					.functions()
+					.flat_map(|f| f.inputs.iter().chain(&f.outputs).map(|p| &p.kind))
+					.chain(contract.events().flat_map(|e| e.inputs.iter().map(|p| &p.kind)))
+			})
+			.collect::<Vec<_>>();
+		assert!(kinds.len() > 100, "{}", kinds.len());
+
+		// bytes allocated to keep the type of every param, as copies or as interned types
+		let (copies, copied) = allocated(|| kinds.iter().map(|kind| (*kind).clone()).collect::<Vec<_>>());
+		let ((interner, shared), interned) = allocated(|| {
+			let mut interner = ParamTypeInterner::new();
+			let shared = kinds.iter().map(|kind| interner.intern(kind)).collect::<Vec<_>>();
+			(interner, shared)
+		});
+
+		assert_eq!(copies.len(), shared.len());
+		assert!(interner.len() * 5 < kinds.len(), "{} distinct of {}", interner.len(), kinds.len());
+		assert!(interned * 3 < copied * 2, "{} bytes interned, {} bytes copied", interned, copied);
+	}
+}
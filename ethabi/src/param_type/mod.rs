@@ -9,8 +9,13 @@
 //! Function and event param types.
 
 mod deserialize;
+#[cfg(feature = "std")]
+mod interner;
 mod param_type;
 mod reader;
+mod serialize;
 mod writer;
 
+#[cfg(feature = "std")]
+pub use self::interner::ParamTypeInterner;
 pub use self::{param_type::ParamType, reader::Reader, writer::Writer};
@@ -9,10 +9,12 @@
 //! Function and event param types.
 
 use super::Writer;
-use std::fmt;
+#[cfg(not(feature = "std"))]
+use crate::no_std_prelude::*;
+use core::fmt;
 
 /// Function and event param types.
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum ParamType {
 	/// Address.
 	Address,
@@ -34,6 +36,18 @@ pub enum ParamType {
 	FixedArray(Box<ParamType>, usize),
 	/// Tuple containing different types
 	Tuple(Vec<ParamType>),
+	/// External function, an address followed by a selector.
+	///
+	/// Its values are 24 bytes `Token::FixedBytes`, padded like `bytes24` when encoded.
+	Function,
+	/// Signed fixed point number of the given bits and decimals.
+	///
+	/// Its values are `Token::Int` of the number scaled by `10^decimals`.
+	Fixed(usize, usize),
+	/// Unsigned fixed point number of the given bits and decimals.
+	///
+	/// Its values are `Token::Uint` of the number scaled by `10^decimals`.
+	Ufixed(usize, usize),
 }
 
 impl fmt::Display for ParamType {
@@ -63,6 +77,19 @@ impl ParamType {
 			_ => false,
 		}
 	}
+
+	/// returns whether indexed event params of this type are stored
+	/// in their topic as the keccak256 of their value
+	pub fn is_hashed_in_topic(&self) -> bool {
+		matches!(
+			self,
+			ParamType::String
+				| ParamType::Bytes
+				| ParamType::Array(_)
+				| ParamType::FixedArray(..)
+				| ParamType::Tuple(_)
+		)
+	}
 }
 
 #[cfg(test)]
@@ -89,16 +116,16 @@ mod tests {
 
 	#[test]
 	fn test_is_dynamic() {
-		assert_eq!(ParamType::Address.is_dynamic(), false);
-		assert_eq!(ParamType::Bytes.is_dynamic(), true);
-		assert_eq!(ParamType::FixedBytes(32).is_dynamic(), false);
-		assert_eq!(ParamType::Uint(256).is_dynamic(), false);
-		assert_eq!(ParamType::Int(64).is_dynamic(), false);
-		assert_eq!(ParamType::Bool.is_dynamic(), false);
-		assert_eq!(ParamType::String.is_dynamic(), true);
-		assert_eq!(ParamType::Array(Box::new(ParamType::Bool)).is_dynamic(), true);
-		assert_eq!(ParamType::FixedArray(Box::new(ParamType::Uint(256)), 2).is_dynamic(), false);
-		assert_eq!(ParamType::FixedArray(Box::new(ParamType::String), 2).is_dynamic(), true);
-		assert_eq!(ParamType::FixedArray(Box::new(ParamType::Array(Box::new(ParamType::Bool))), 2).is_dynamic(), true);
+		assert!(!ParamType::Address.is_dynamic());
+		assert!(ParamType::Bytes.is_dynamic());
+		assert!(!ParamType::FixedBytes(32).is_dynamic());
+		assert!(!ParamType::Uint(256).is_dynamic());
+		assert!(!ParamType::Int(64).is_dynamic());
+		assert!(!ParamType::Bool.is_dynamic());
+		assert!(ParamType::String.is_dynamic());
+		assert!(ParamType::Array(Box::new(ParamType::Bool)).is_dynamic());
+		assert!(!ParamType::FixedArray(Box::new(ParamType::Uint(256)), 2).is_dynamic());
+		assert!(ParamType::FixedArray(Box::new(ParamType::String), 2).is_dynamic());
+		assert!(ParamType::FixedArray(Box::new(ParamType::Array(Box::new(ParamType::Bool))), 2).is_dynamic());
 	}
 }
@@ -6,6 +6,8 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
+#[cfg(not(feature = "std"))]
+use crate::no_std_prelude::*;
 use crate::{Error, ParamType};
 
 /// Used to convert param type represented as a string to rust structure.
@@ -27,7 +29,7 @@ impl Reader {
 				let mut last_item = 1;
 
 				// Iterate over name and build the nested tuple structure
-				for (pos, c) in name.chars().enumerate() {
+				for (pos, c) in name.char_indices() {
 					match c {
 						'(' => {
 							nested += 1;
@@ -117,7 +119,7 @@ impl Reader {
 					return Ok(ParamType::Array(Box::new(subtype)));
 				} else {
 					// it's a fixed array.
-					let len = usize::from_str_radix(&num, 10)?;
+					let len = num.parse::<usize>()?;
 					let subtype = Reader::read(&name[..count - num.len() - 2])?;
 					return Ok(ParamType::FixedArray(Box::new(subtype), len));
 				}
@@ -130,21 +132,26 @@ impl Reader {
 			"bytes" => ParamType::Bytes,
 			"bool" => ParamType::Bool,
 			"string" => ParamType::String,
+			"function" => ParamType::Function,
 			"int" => ParamType::Int(256),
 			"tuple" => ParamType::Tuple(vec![]),
 			"uint" => ParamType::Uint(256),
-			s if s.starts_with("int") => {
-				let len = usize::from_str_radix(&s[3..], 10)?;
-				ParamType::Int(len)
+			"fixed" => ParamType::Fixed(128, 18),
+			"ufixed" => ParamType::Ufixed(128, 18),
+			s if s.starts_with("fixed") => {
+				let (bits, decimals) = read_fixed(s, &s[5..])?;
+				ParamType::Fixed(bits, decimals)
 			}
-			s if s.starts_with("uint") => {
-				let len = usize::from_str_radix(&s[4..], 10)?;
-				ParamType::Uint(len)
-			}
-			s if s.starts_with("bytes") => {
-				let len = usize::from_str_radix(&s[5..], 10)?;
-				ParamType::FixedBytes(len)
+			s if s.starts_with("ufixed") => {
+				let (bits, decimals) = read_fixed(s, &s[6..])?;
+				ParamType::Ufixed(bits, decimals)
 			}
+			s if s.starts_with("int") => ParamType::Int(read_bits(s, &s[3..])?),
+			s if s.starts_with("uint") => ParamType::Uint(read_bits(s, &s[4..])?),
+			s if s.starts_with("bytes") => match s[5..].parse::<usize>()? {
+				len @ 1..=32 => ParamType::FixedBytes(len),
+				_ => return Err(Error::InvalidName(name.to_owned())),
+			},
 			_ => {
 				return Err(Error::InvalidName(name.to_owned()));
 			}
@@ -154,6 +161,26 @@ impl Reader {
 	}
 }
 
+/// Reads the bit size suffix of the integer type `name`, a multiple of 8 up to 256.
+fn read_bits(name: &str, suffix: &str) -> Result<usize, Error> {
+	match suffix.parse::<usize>()? {
+		bits if bits > 0 && bits <= 256 && bits % 8 == 0 => Ok(bits),
+		_ => Err(Error::InvalidName(name.to_owned())),
+	}
+}
+
+/// Reads the `MxN` bits and decimals suffix of the fixed point type `name`, with at most 80
+/// decimals.
+fn read_fixed(name: &str, suffix: &str) -> Result<(usize, usize), Error> {
+	match suffix.split_once('x') {
+		Some((bits, decimals)) => match decimals.parse()? {
+			decimals @ 0..=80 => Ok((read_bits(name, bits)?, decimals)),
+			_ => Err(Error::InvalidName(name.to_owned())),
+		},
+		None => Err(Error::InvalidName(name.to_owned())),
+	}
+}
+
 #[cfg(test)]
 mod tests {
 	use super::Reader;
@@ -166,10 +193,19 @@ mod tests {
 		assert_eq!(Reader::read("bytes32").unwrap(), ParamType::FixedBytes(32));
 		assert_eq!(Reader::read("bool").unwrap(), ParamType::Bool);
 		assert_eq!(Reader::read("string").unwrap(), ParamType::String);
+		assert_eq!(Reader::read("function").unwrap(), ParamType::Function);
 		assert_eq!(Reader::read("int").unwrap(), ParamType::Int(256));
 		assert_eq!(Reader::read("uint").unwrap(), ParamType::Uint(256));
 		assert_eq!(Reader::read("int32").unwrap(), ParamType::Int(32));
 		assert_eq!(Reader::read("uint32").unwrap(), ParamType::Uint(32));
+		assert_eq!(Reader::read("fixed").unwrap(), ParamType::Fixed(128, 18));
+		assert_eq!(Reader::read("ufixed").unwrap(), ParamType::Ufixed(128, 18));
+		assert_eq!(Reader::read("fixed168x10").unwrap(), ParamType::Fixed(168, 10));
+		assert_eq!(Reader::read("ufixed128x18[]").unwrap(), ParamType::Array(Box::new(ParamType::Ufixed(128, 18))));
+		assert!(Reader::read("fixed128").is_err());
+		for invalid in ["int0", "uint7", "int264", "uint512", "fixed0x0", "ufixed256x81", "bytes0", "bytes33"] {
+			assert!(Reader::read(invalid).is_err(), "{}", invalid);
+		}
 	}
 
 	#[test]
@@ -0,0 +1,52 @@
+// Copyright 2015-2020 Parity Technologies
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use super::{ParamType, Writer};
+use serde::{Serialize, Serializer};
+
+impl Serialize for ParamType {
+	fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+	where
+		S: Serializer,
+	{
+		serializer.serialize_str(&Writer::write(self))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::ParamType;
+
+	#[test]
+	fn param_type_serialization() {
+		let types = vec![
+			ParamType::Address,
+			ParamType::Bytes,
+			ParamType::FixedBytes(32),
+			ParamType::Bool,
+			ParamType::String,
+			ParamType::Int(256),
+			ParamType::Uint(8),
+			ParamType::Array(Box::new(ParamType::Address)),
+			ParamType::FixedArray(Box::new(ParamType::Array(Box::new(ParamType::Bool))), 5),
+			ParamType::Array(Box::new(ParamType::Tuple(vec![
+				ParamType::Uint(256),
+				ParamType::Tuple(vec![ParamType::Address, ParamType::String]),
+			]))),
+		];
+
+		let serialized = serde_json::to_string(&types).unwrap();
+		assert_eq!(
+			serialized,
+			r#"["address","bytes","bytes32","bool","string","int256","uint8","address[]","bool[][5]","(uint256,(address,string))[]"]"#
+		);
+
+		let deserialized: Vec<ParamType> = serde_json::from_str(&serialized).unwrap();
+		assert_eq!(deserialized, types);
+	}
+}
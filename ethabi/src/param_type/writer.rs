@@ -6,6 +6,8 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
+#[cfg(not(feature = "std"))]
+use crate::no_std_prelude::*;
 use crate::ParamType;
 
 /// Output formatter for param type.
@@ -20,8 +22,11 @@ impl Writer {
 			ParamType::FixedBytes(len) => format!("bytes{}", len),
 			ParamType::Int(len) => format!("int{}", len),
 			ParamType::Uint(len) => format!("uint{}", len),
+			ParamType::Fixed(bits, decimals) => format!("fixed{}x{}", bits, decimals),
+			ParamType::Ufixed(bits, decimals) => format!("ufixed{}x{}", bits, decimals),
 			ParamType::Bool => "bool".to_owned(),
 			ParamType::String => "string".to_owned(),
+			ParamType::Function => "function".to_owned(),
 			ParamType::FixedArray(ref param, len) => format!("{}[{}]", Writer::write(param), len),
 			ParamType::Array(ref param) => format!("{}[]", Writer::write(param)),
 			ParamType::Tuple(ref params) => {
@@ -45,6 +50,9 @@ mod tests {
 		assert_eq!(Writer::write(&ParamType::Int(64)), "int64".to_owned());
 		assert_eq!(Writer::write(&ParamType::Bool), "bool".to_owned());
 		assert_eq!(Writer::write(&ParamType::String), "string".to_owned());
+		assert_eq!(Writer::write(&ParamType::Function), "function".to_owned());
+		assert_eq!(Writer::write(&ParamType::Fixed(128, 18)), "fixed128x18".to_owned());
+		assert_eq!(Writer::write(&ParamType::Ufixed(64, 10)), "ufixed64x10".to_owned());
 		assert_eq!(Writer::write(&ParamType::Array(Box::new(ParamType::Bool))), "bool[]".to_owned());
 		assert_eq!(Writer::write(&ParamType::FixedArray(Box::new(ParamType::String), 2)), "string[2]".to_owned());
 		assert_eq!(
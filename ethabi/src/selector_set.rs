@@ -0,0 +1,88 @@
+// Copyright 2015-2020 Parity Technologies
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Set of function selectors.
+
+use crate::Function;
+use alloc::collections::BTreeSet;
+use core::iter::FromIterator;
+
+/// Set of 4 bytes function selectors, e.g. to check calldata against an allowlist.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SelectorSet(BTreeSet<[u8; 4]>);
+
+impl SelectorSet {
+	/// Creates an empty set.
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Adds a selector to the set. Returns false if it was already present.
+	pub fn insert(&mut self, selector: [u8; 4]) -> bool {
+		self.0.insert(selector)
+	}
+
+	/// Returns true if the set contains `selector`.
+	pub fn contains(&self, selector: &[u8; 4]) -> bool {
+		self.0.contains(selector)
+	}
+
+	/// Returns true if the selector of the call `data` is in the set.
+	pub fn contains_calldata(&self, data: &[u8]) -> bool {
+		let mut selector = [0u8; 4];
+		match data.get(..4) {
+			Some(prefix) => {
+				selector.copy_from_slice(prefix);
+				self.contains(&selector)
+			}
+			None => false,
+		}
+	}
+
+	/// Returns the number of selectors in the set.
+	pub fn len(&self) -> usize {
+		self.0.len()
+	}
+
+	/// Returns true if the set is empty.
+	pub fn is_empty(&self) -> bool {
+		self.0.is_empty()
+	}
+}
+
+impl FromIterator<[u8; 4]> for SelectorSet {
+	fn from_iter<I: IntoIterator<Item = [u8; 4]>>(iter: I) -> Self {
+		SelectorSet(iter.into_iter().collect())
+	}
+}
+
+impl<'a> FromIterator<&'a Function> for SelectorSet {
+	fn from_iter<I: IntoIterator<Item = &'a Function>>(iter: I) -> Self {
+		iter.into_iter().map(Function::short_signature).collect()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::SelectorSet;
+	use crate::Function;
+	use hex_literal::hex;
+
+	#[test]
+	fn test_selector_set() {
+		let transfer = Function::parse("transfer(address,uint256)").unwrap();
+		let approve = Function::parse("approve(address,uint256)").unwrap();
+		let set = vec![&transfer].into_iter().collect::<SelectorSet>();
+
+		assert!(set.contains(&hex!("a9059cbb")));
+		assert!(!set.contains(&approve.short_signature()));
+		assert!(set.contains_calldata(&hex!("a9059cbb0000")));
+		assert!(!set.contains_calldata(&hex!("a9059c")));
+		assert_eq!(set.len(), 1);
+	}
+}
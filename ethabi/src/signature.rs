@@ -6,40 +6,64 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
+#[cfg(not(feature = "std"))]
+use crate::no_std_prelude::*;
 use crate::{
 	param_type::{ParamType, Writer},
 	Hash,
 };
 use sha3::{Digest, Keccak256};
 
+/// 4 bytes selector of the function or error `name` taking `params`, the first bytes of the
+/// keccak256 of its [canonical signature](canonical_signature).
 pub fn short_signature(name: &str, params: &[ParamType]) -> [u8; 4] {
 	let mut result = [0u8; 4];
 	fill_signature(name, params, &mut result);
 	result
 }
 
+/// Keccak256 of the [canonical signature](canonical_signature) of the event `name` taking
+/// `params`, its first topic unless anonymous.
 pub fn long_signature(name: &str, params: &[ParamType]) -> Hash {
 	let mut result = [0u8; 32];
 	fill_signature(name, params, &mut result);
 	result.into()
 }
 
-fn fill_signature(name: &str, params: &[ParamType], result: &mut [u8]) {
+/// Canonical signature of the function, event or error `name` taking `params`, e.g.
+/// `transfer(address,uint256)`, which is the form hashed into selectors and event topics.
+///
+/// Types are written in full, e.g. `uint256` for `uint`, and tuples as `(address,bool)`.
+pub fn canonical_signature(name: &str, params: &[ParamType]) -> String {
 	let types = params.iter().map(Writer::write).collect::<Vec<String>>().join(",");
+	format!("{}({})", name, types)
+}
 
-	let data: Vec<u8> = From::from(format!("{}({})", name, types).as_str());
-
-	result.copy_from_slice(&Keccak256::digest(&data)[..result.len()])
+fn fill_signature(name: &str, params: &[ParamType], result: &mut [u8]) {
+	let signature = canonical_signature(name, params);
+	result.copy_from_slice(&Keccak256::digest(signature.as_bytes())[..result.len()])
 }
 
 #[cfg(test)]
 mod tests {
-	use super::short_signature;
+	use super::{canonical_signature, long_signature, short_signature};
 	use crate::ParamType;
 	use hex_literal::hex;
 
 	#[test]
 	fn test_signature() {
 		assert_eq!(hex!("cdcd77c0"), short_signature("baz", &[ParamType::Uint(32), ParamType::Bool]));
+		assert_eq!(
+			long_signature("Transfer", &[ParamType::Address, ParamType::Address, ParamType::Uint(256)]),
+			hex!("ddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef").into()
+		);
+	}
+
+	#[test]
+	fn test_canonical_signature() {
+		let order = ParamType::Tuple(vec![ParamType::Address, ParamType::Uint(256)]);
+		let params = [ParamType::Array(Box::new(order)), ParamType::FixedBytes(32)];
+		assert_eq!(canonical_signature("settle", &params), "settle((address,uint256)[],bytes32)");
+		assert_eq!(canonical_signature("hello", &[]), "hello()");
 	}
 }
@@ -0,0 +1,44 @@
+// Copyright 2015-2020 Parity Technologies
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Function state mutability.
+
+use serde::{Deserialize, Serialize};
+
+/// Whether a function reads or modifies the blockchain state, and if it accepts ether.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StateMutability {
+	/// Does not read nor modify the state.
+	Pure,
+	/// Reads but does not modify the state.
+	View,
+	/// Modifies the state and does not accept ether.
+	#[default]
+	NonPayable,
+	/// Modifies the state and accepts ether.
+	Payable,
+}
+
+impl StateMutability {
+	/// Resolves the mutability from the `constant` and `payable` flags of ABIs predating
+	/// `stateMutability`. Returns `None` for the contradictory constant and payable combination.
+	pub fn from_legacy(constant: bool, payable: bool) -> Option<Self> {
+		match (constant, payable) {
+			(true, false) => Some(StateMutability::View),
+			(false, true) => Some(StateMutability::Payable),
+			(false, false) => Some(StateMutability::NonPayable),
+			(true, true) => None,
+		}
+	}
+
+	/// Returns true if the function does not modify the state, i.e. is `pure` or `view`.
+	pub fn is_constant(self) -> bool {
+		matches!(self, StateMutability::Pure | StateMutability::View)
+	}
+}
@@ -431,6 +431,53 @@ test_encode_decode! {
 		0000000000000000000000000000000000000000000000000000000000000000"
 }
 
+test_encode_decode! {
+	name: dynamic_array_of_static_tuples,
+	types: [
+		ParamType::Bool,
+		ParamType::Array(Box::new(ParamType::Tuple(vec![ParamType::Uint(256), ParamType::Address]))),
+	],
+	tokens: {
+		let s1 = Token::Tuple(vec![Token::Uint(1.into()), Token::Address([0x11u8; 20].into())]);
+		let s2 = Token::Tuple(vec![Token::Uint(2.into()), Token::Address([0x22u8; 20].into())]);
+		[Token::Bool(true), Token::Array(vec![s1, s2])]
+	},
+	data: "
+		0000000000000000000000000000000000000000000000000000000000000001
+		0000000000000000000000000000000000000000000000000000000000000040
+		0000000000000000000000000000000000000000000000000000000000000002
+		0000000000000000000000000000000000000000000000000000000000000001
+		0000000000000000000000001111111111111111111111111111111111111111
+		0000000000000000000000000000000000000000000000000000000000000002
+		0000000000000000000000002222222222222222222222222222222222222222
+	"
+}
+
+test_encode_decode! {
+	name: dynamic_array_of_static_tuples_with_fixed_arrays,
+	types: [
+		ParamType::Array(Box::new(ParamType::Tuple(vec![
+			ParamType::Uint(8),
+			ParamType::FixedArray(Box::new(ParamType::Bool), 2),
+		]))),
+	],
+	tokens: {
+		let s1 = Token::Tuple(vec![Token::Uint(3.into()), Token::FixedArray(vec![Token::Bool(true), Token::Bool(false)])]);
+		let s2 = Token::Tuple(vec![Token::Uint(4.into()), Token::FixedArray(vec![Token::Bool(false), Token::Bool(true)])]);
+		[Token::Array(vec![s1, s2])]
+	},
+	data: "
+		0000000000000000000000000000000000000000000000000000000000000020
+		0000000000000000000000000000000000000000000000000000000000000002
+		0000000000000000000000000000000000000000000000000000000000000003
+		0000000000000000000000000000000000000000000000000000000000000001
+		0000000000000000000000000000000000000000000000000000000000000000
+		0000000000000000000000000000000000000000000000000000000000000004
+		0000000000000000000000000000000000000000000000000000000000000000
+		0000000000000000000000000000000000000000000000000000000000000001
+	"
+}
+
 // test fixed array
 test_encode_decode! {
 	name: fixed_array_of_addresses,
@@ -497,6 +544,65 @@ test_encode_decode! {
 		0000000000000000000000004444444444444444444444444444444444444444"
 }
 
+test_encode_decode! {
+	name: fixed_array_of_strings,
+	types: [
+		ParamType::Uint(256),
+		ParamType::FixedArray(Box::new(ParamType::String), 2)
+	],
+	tokens: [
+		Token::Uint(9.into()),
+		Token::FixedArray(vec![Token::String("foo".to_owned()), Token::String("bar".to_owned())])
+	],
+	data: "
+		0000000000000000000000000000000000000000000000000000000000000009
+		0000000000000000000000000000000000000000000000000000000000000040
+		0000000000000000000000000000000000000000000000000000000000000040
+		0000000000000000000000000000000000000000000000000000000000000080
+		0000000000000000000000000000000000000000000000000000000000000003
+		666f6f0000000000000000000000000000000000000000000000000000000000
+		0000000000000000000000000000000000000000000000000000000000000003
+		6261720000000000000000000000000000000000000000000000000000000000"
+}
+test_encode_decode! {
+	name: fixed_array_of_bytes,
+	types: [ParamType::FixedArray(Box::new(ParamType::Bytes), 2)],
+	tokens: [Token::FixedArray(vec![Token::Bytes(vec![0x12, 0x34]), Token::Bytes(vec![])])],
+	data: "
+		0000000000000000000000000000000000000000000000000000000000000020
+		0000000000000000000000000000000000000000000000000000000000000040
+		0000000000000000000000000000000000000000000000000000000000000080
+		0000000000000000000000000000000000000000000000000000000000000002
+		1234000000000000000000000000000000000000000000000000000000000000
+		0000000000000000000000000000000000000000000000000000000000000000"
+}
+test_encode_decode! {
+	name: fixed_array_of_dynamic_tuples,
+	types: [
+		ParamType::Uint(256),
+		ParamType::FixedArray(Box::new(ParamType::Tuple(vec![ParamType::Uint(256), ParamType::Bytes])), 2)
+	],
+	tokens: [
+		Token::Uint(9.into()),
+		Token::FixedArray(vec![
+			Token::Tuple(vec![Token::Uint(1.into()), Token::Bytes(vec![0xaa])]),
+			Token::Tuple(vec![Token::Uint(2.into()), Token::Bytes(vec![])])
+		])
+	],
+	data: "
+		0000000000000000000000000000000000000000000000000000000000000009
+		0000000000000000000000000000000000000000000000000000000000000040
+		0000000000000000000000000000000000000000000000000000000000000040
+		00000000000000000000000000000000000000000000000000000000000000c0
+		0000000000000000000000000000000000000000000000000000000000000001
+		0000000000000000000000000000000000000000000000000000000000000040
+		0000000000000000000000000000000000000000000000000000000000000001
+		aa00000000000000000000000000000000000000000000000000000000000000
+		0000000000000000000000000000000000000000000000000000000000000002
+		0000000000000000000000000000000000000000000000000000000000000040
+		0000000000000000000000000000000000000000000000000000000000000000"
+}
+
 // test fixed bytes
 test_encode_decode! {
 	name: fixed_bytes,
@@ -505,6 +611,25 @@ test_encode_decode! {
 	data: "1234000000000000000000000000000000000000000000000000000000000000"
 }
 
+// test external function, an address followed by a selector
+test_encode_decode! {
+	name: function,
+	types: [ParamType::Function],
+	tokens: [Token::FixedBytes(hex!("1111111111111111111111111111111111111111a9059cbb").to_vec())],
+	data: "1111111111111111111111111111111111111111a9059cbb0000000000000000"
+}
+
+// test fixed point numbers, as their raw scaled integers
+test_encode_decode! {
+	name: fixed_point,
+	types: [ParamType::Fixed(128, 18), ParamType::Ufixed(128, 18)],
+	tokens: [Token::Int([0xffu8; 32].into()), Token::Uint(1_500_000_000_000_000_000u64.into())],
+	data: "
+		ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff
+		00000000000000000000000000000000000000000000000014d1120d7b160000
+	"
+}
+
 // comprehensive test
 test_encode_decode! {
 	name: comprehensive_test,
@@ -6,19 +6,94 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
+#[cfg(not(feature = "std"))]
+use crate::no_std_prelude::*;
 use crate::{
 	errors::Error,
-	token::{StrictTokenizer, Tokenizer},
+	token::{strict::decode_hex, StrictTokenizer, Tokenizer},
 	Uint,
 };
 use anyhow::anyhow;
 
 /// Tries to parse string as a token. Does not require string to clearly represent the value.
+///
+/// `0x` prefixed addresses and integers shorter than their type are left padded, and
+/// integers may be decimal, negative ones included. Decimals may have a fraction, an exponent
+/// and an ether unit as long as they denote an integer, e.g. `1e18`, `-2.5e3` or `1.5 ether`.
 pub struct LenientTokenizer;
 
+/// Parses a decimal such as `1.5e3 gwei` into the integer it denotes.
+fn parse_decimal(value: &str) -> Result<Uint, Error> {
+	let invalid = || anyhow!("`{}` is not a decimal integer", value);
+	let number = value.trim_end_matches(|c: char| c.is_ascii_alphabetic());
+	let unit = match &value[number.len()..] {
+		"" | "wei" => 0,
+		"kwei" => 3,
+		"mwei" => 6,
+		"gwei" => 9,
+		"szabo" => 12,
+		"finney" => 15,
+		"ether" => 18,
+		_ => return Err(invalid().into()),
+	};
+
+	let number = number.trim_end();
+	let (mantissa, exponent) = match number.find(['e', 'E']) {
+		Some(pos) => (&number[..pos], number[pos + 1..].parse::<i64>().map_err(|_| invalid())?),
+		None => (number, 0),
+	};
+	let (integer, fraction) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+	let digits = format!("{}{}", integer, fraction);
+	if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+		return Err(invalid().into());
+	}
+
+	let exponent = exponent.checked_add(unit - fraction.len() as i64).ok_or_else(invalid)?;
+	let digits = match exponent < 0 {
+		true => {
+			let dropped = exponent.unsigned_abs().min(digits.len() as u64) as usize;
+			let (kept, dropped) = digits.split_at(digits.len() - dropped);
+			if dropped.bytes().any(|b| b != b'0') {
+				return Err(anyhow!("`{}` is not an integer", value).into());
+			}
+			kept
+		}
+		false => &digits,
+	};
+	let overflow = || anyhow!("`{}` does not fit in 256 bits", value);
+	let mantissa = match digits.is_empty() {
+		true => Uint::zero(),
+		false => Uint::from_dec_str(digits).map_err(|_| overflow())?,
+	};
+	let scale = Uint::from(10).checked_pow(exponent.max(0).into()).ok_or_else(overflow)?;
+	Ok(mantissa.checked_mul(scale).ok_or_else(overflow)?)
+}
+
+/// Decodes `0x` prefixed hex of at most `N` bytes, left padded to `N` bytes. Returns `None`
+/// if `value` is not `0x` prefixed.
+fn left_padded_hex<const N: usize>(value: &str) -> Option<Result<[u8; N], Error>> {
+	let digits = value.strip_prefix("0x")?;
+	let padded = match digits.len() % 2 {
+		0 => decode_hex(digits),
+		_ => decode_hex(&format!("0{}", digits)),
+	};
+	Some(padded.and_then(|bytes| match bytes.len() <= N {
+		true => {
+			let mut result = [0u8; N];
+			result[N - bytes.len()..].copy_from_slice(&bytes);
+			Ok(result)
+		}
+		false => Err(anyhow!("`{}` does not fit in {} bytes", value, N).into()),
+	}))
+}
+
 impl Tokenizer for LenientTokenizer {
 	fn tokenize_address(value: &str) -> Result<[u8; 20], Error> {
-		StrictTokenizer::tokenize_address(value)
+		match value.len() {
+			// full width addresses may be checksummed
+			42 => StrictTokenizer::tokenize_address(value),
+			_ => left_padded_hex(value).unwrap_or_else(|| StrictTokenizer::tokenize_address(value)),
+		}
 	}
 
 	fn tokenize_string(value: &str) -> Result<String, Error> {
@@ -38,12 +113,16 @@ impl Tokenizer for LenientTokenizer {
 	}
 
 	fn tokenize_uint(value: &str) -> Result<[u8; 32], Error> {
+		if let Some(result) = left_padded_hex(value) {
+			return result;
+		}
+
 		let result = StrictTokenizer::tokenize_uint(value);
 		if result.is_ok() {
 			return result;
 		}
 
-		let uint = Uint::from_dec_str(value)?;
+		let uint = parse_decimal(value)?;
 		Ok(uint.into())
 	}
 
@@ -51,14 +130,23 @@ impl Tokenizer for LenientTokenizer {
 	// out of it and check that it's within the lower/upper bound of a hypothetical I256 type: half
 	// the `U256::max_value().
 	fn tokenize_int(value: &str) -> Result<[u8; 32], Error> {
+		if let Some(result) = left_padded_hex(value) {
+			return result;
+		}
+
 		let result = StrictTokenizer::tokenize_int(value);
 		if result.is_ok() {
 			return result;
 		}
 
-		let abs = Uint::from_dec_str(value.trim_start_matches('-'))?;
+		// a single sign, so that `--5` is no number
+		let (negative, abs) = match value.strip_prefix('-') {
+			Some(abs) => (true, abs),
+			None => (false, value),
+		};
+		let abs = parse_decimal(abs)?;
 		let max = Uint::max_value() / 2;
-		let int = if value.starts_with('-') {
+		let int = if negative {
 			if abs.is_zero() {
 				return Ok(abs.into());
 			} else if abs > max + 1 {
@@ -74,3 +162,81 @@ impl Tokenizer for LenientTokenizer {
 		Ok(int.into())
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use crate::{
+		token::{LenientTokenizer, StrictTokenizer, Token, Tokenizer},
+		ParamType, Uint,
+	};
+
+	#[test]
+	fn tokenize_short_hex() {
+		let mut address = [0u8; 20];
+		address[18..].copy_from_slice(&[0x12, 0x34]);
+		assert_eq!(LenientTokenizer::tokenize(&ParamType::Address, "0x1234").unwrap(), Token::Address(address.into()));
+		assert_eq!(LenientTokenizer::tokenize(&ParamType::Uint(256), "0x1234").unwrap(), Token::Uint(0x1234.into()));
+		assert_eq!(LenientTokenizer::tokenize(&ParamType::Uint(256), "0xabc").unwrap(), Token::Uint(0xabc.into()));
+		assert!(LenientTokenizer::tokenize(&ParamType::Address, &format!("0x{}", "11".repeat(21))).is_err());
+		assert!(LenientTokenizer::tokenize(&ParamType::Address, "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAeD").is_err());
+
+		// strict requires the exact width
+		assert!(StrictTokenizer::tokenize(&ParamType::Uint(256), "0x1234").is_err());
+		assert!(StrictTokenizer::tokenize(&ParamType::Address, "0x1234").is_err());
+		assert_eq!(
+			StrictTokenizer::tokenize(&ParamType::Address, "0x1111111111111111111111111111111111111111").unwrap(),
+			Token::Address([0x11u8; 20].into())
+		);
+	}
+
+	#[test]
+	fn tokenize_decimal_ints() {
+		assert_eq!(
+			LenientTokenizer::tokenize(&ParamType::Uint(256), "1000000").unwrap(),
+			Token::Uint(1_000_000.into())
+		);
+		assert_eq!(LenientTokenizer::tokenize(&ParamType::Int(256), "-1").unwrap(), Token::Int(Uint::MAX));
+		assert_eq!(LenientTokenizer::tokenize(&ParamType::Int(256), "-0").unwrap(), Token::Int(0.into()));
+		assert!(LenientTokenizer::tokenize(&ParamType::Uint(256), "-1").is_err());
+		assert!(LenientTokenizer::tokenize(&ParamType::Int(256), "--5").is_err());
+		assert!(LenientTokenizer::tokenize(&ParamType::Int(256), "---1 ether").is_err());
+		assert!(StrictTokenizer::tokenize(&ParamType::Uint(256), "1000000").is_err());
+	}
+
+	#[test]
+	fn tokenize_decimal_notations() {
+		let uint = |value: &str| LenientTokenizer::tokenize(&ParamType::Uint(256), value);
+		let ether = Uint::from(10).pow(18.into());
+		assert_eq!(uint("1e18").unwrap(), Token::Uint(ether));
+		assert_eq!(uint("1E18").unwrap(), Token::Uint(ether));
+		assert_eq!(uint("1.5 ether").unwrap(), Token::Uint(ether * 3 / 2));
+		assert_eq!(uint("10gwei").unwrap(), Token::Uint(10_000_000_000u64.into()));
+		assert_eq!(uint("2.50e1").unwrap(), Token::Uint(25.into()));
+		assert_eq!(uint("1500e-2").unwrap(), Token::Uint(15.into()));
+		assert_eq!(uint("0.000e-9").unwrap(), Token::Uint(0.into()));
+		assert_eq!(
+			LenientTokenizer::tokenize(&ParamType::Int(256), "-2.5e3").unwrap(),
+			Token::Int(!Uint::from(2500) + 1)
+		);
+
+		assert_eq!(uint("1.5").unwrap_err().to_string(), "`1.5` is not an integer");
+		assert_eq!(uint("1 parsec").unwrap_err().to_string(), "`1 parsec` is not a decimal integer");
+		assert!(uint("1e78").is_err());
+		assert!(uint("e18").is_err());
+		assert!(uint(".").is_err());
+	}
+
+	#[test]
+	fn tokenize_arrays() {
+		let uints = ParamType::Array(Box::new(ParamType::Uint(256)));
+		assert_eq!(LenientTokenizer::tokenize(&uints, "[]").unwrap(), Token::Array(vec![]));
+		assert_eq!(
+			LenientTokenizer::tokenize(&ParamType::Array(Box::new(uints)), "[[1,0x2],[],[0]]").unwrap(),
+			Token::Array(vec![
+				Token::Array(vec![Token::Uint(1.into()), Token::Uint(2.into())]),
+				Token::Array(vec![]),
+				Token::Array(vec![Token::Uint(0.into())]),
+			])
+		);
+	}
+}
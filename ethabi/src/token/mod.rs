@@ -9,12 +9,22 @@
 //! ABI param and parsing for it.
 
 mod lenient;
+mod serialization;
 mod strict;
 mod token;
-
-use std::cmp::Ordering::{Equal, Less};
-
-pub use self::{lenient::LenientTokenizer, strict::StrictTokenizer, token::Token};
+mod token_ref;
+mod tokenizable;
+
+#[cfg(not(feature = "std"))]
+use crate::no_std_prelude::*;
+
+pub use self::{
+	lenient::LenientTokenizer,
+	strict::StrictTokenizer,
+	token::Token,
+	token_ref::TokenRef,
+	tokenizable::{Detokenizable, DetokenizableItem, Tokenizable, TokenizableItem},
+};
 use crate::{Error, ParamType};
 
 /// This trait should be used to parse string values as tokens.
@@ -27,14 +37,28 @@ pub trait Tokenizer {
 			ParamType::Bool => Self::tokenize_bool(value).map(Token::Bool),
 			ParamType::Bytes => Self::tokenize_bytes(value).map(Token::Bytes),
 			ParamType::FixedBytes(len) => Self::tokenize_fixed_bytes(value, len).map(Token::FixedBytes),
+			ParamType::Function => Self::tokenize_fixed_bytes(value, 24).map(Token::FixedBytes),
 			ParamType::Uint(_) => Self::tokenize_uint(value).map(Into::into).map(Token::Uint),
 			ParamType::Int(_) => Self::tokenize_int(value).map(Into::into).map(Token::Int),
+			// fixed point values are given as their scaled integer
+			ParamType::Ufixed(..) => Self::tokenize_uint(value).map(Into::into).map(Token::Uint),
+			ParamType::Fixed(..) => Self::tokenize_int(value).map(Into::into).map(Token::Int),
 			ParamType::Array(ref p) => Self::tokenize_array(value, p).map(Token::Array),
 			ParamType::FixedArray(ref p, len) => Self::tokenize_fixed_array(value, p, len).map(Token::FixedArray),
 			ParamType::Tuple(ref p) => Self::tokenize_struct(value, p).map(Token::Tuple),
 		}
 	}
 
+	/// Tries to parse a literal formatted by [`Token::to_literal`] as a token of given type.
+	///
+	/// Unlike [`Tokenizer::tokenize`], strings must be quoted even outside of arrays and tuples.
+	fn tokenize_literal(param: &ParamType, value: &str) -> Result<Token, Error> {
+		match *param {
+			ParamType::String => value.strip_prefix('"').ok_or(Error::InvalidData).and_then(unquote).map(Token::String),
+			_ => Self::tokenize(param, value),
+		}
+	}
+
 	/// Tries to parse a value as a vector of tokens of fixed size.
 	fn tokenize_fixed_array(value: &str, param: &ParamType, len: usize) -> Result<Vec<Token>, Error> {
 		let result = Self::tokenize_array(value, param)?;
@@ -44,114 +68,18 @@ pub trait Tokenizer {
 		}
 	}
 
-	/// Tried to parse a struct as a vector of tokens
+	/// Tries to parse a tuple literal, e.g. `(0x11..11,5,[1,2])`, as a vector of tokens.
 	fn tokenize_struct(value: &str, param: &[ParamType]) -> Result<Vec<Token>, Error> {
-		if !value.starts_with('(') || !value.ends_with(')') {
+		let items = split_items(value, '(', ')')?;
+		if items.len() != param.len() {
 			return Err(Error::InvalidData);
 		}
-
-		if value.chars().count() == 2 {
-			return Ok(vec![]);
-		}
-
-		let mut result = vec![];
-		let mut nested = 0isize;
-		let mut ignore = false;
-		let mut last_item = 1;
-		let mut params = param.iter();
-		for (pos, ch) in value.chars().enumerate() {
-			match ch {
-				'(' if !ignore => {
-					nested += 1;
-				}
-				')' if !ignore => {
-					nested -= 1;
-
-					match nested.cmp(&0) {
-						Less => {
-							return Err(Error::InvalidData);
-						}
-						Equal => {
-							let sub = &value[last_item..pos];
-							let token = Self::tokenize(params.next().ok_or(Error::InvalidData)?, sub)?;
-							result.push(token);
-							last_item = pos + 1;
-						}
-						_ => {}
-					}
-				}
-				'"' => {
-					ignore = !ignore;
-				}
-				',' if nested == 1 && !ignore => {
-					let sub = &value[last_item..pos];
-					let token = Self::tokenize(params.next().ok_or(Error::InvalidData)?, sub)?;
-					result.push(token);
-					last_item = pos + 1;
-				}
-				_ => (),
-			}
-		}
-
-		if ignore {
-			return Err(Error::InvalidData);
-		}
-
-		Ok(result)
+		param.iter().zip(items).map(|(param, item)| tokenize_item::<Self>(param, item)).collect()
 	}
 
-	/// Tries to parse a value as a vector of tokens.
+	/// Tries to parse an array literal, e.g. `[[1,2],[3]]`, as a vector of tokens.
 	fn tokenize_array(value: &str, param: &ParamType) -> Result<Vec<Token>, Error> {
-		if !value.starts_with('[') || !value.ends_with(']') {
-			return Err(Error::InvalidData);
-		}
-
-		if value.chars().count() == 2 {
-			return Ok(vec![]);
-		}
-
-		let mut result = vec![];
-		let mut nested = 0isize;
-		let mut ignore = false;
-		let mut last_item = 1;
-		for (i, ch) in value.chars().enumerate() {
-			match ch {
-				'[' if !ignore => {
-					nested += 1;
-				}
-				']' if !ignore => {
-					nested -= 1;
-					match nested.cmp(&0) {
-						Less => {
-							return Err(Error::InvalidData);
-						}
-						Equal => {
-							let sub = &value[last_item..i];
-							let token = Self::tokenize(param, sub)?;
-							result.push(token);
-							last_item = i + 1;
-						}
-						_ => {}
-					}
-				}
-				'"' => {
-					ignore = !ignore;
-				}
-				',' if nested == 1 && !ignore => {
-					let sub = &value[last_item..i];
-					let token = Self::tokenize(param, sub)?;
-					result.push(token);
-					last_item = i + 1;
-				}
-				_ => (),
-			}
-		}
-
-		if ignore {
-			return Err(Error::InvalidData);
-		}
-
-		Ok(result)
+		split_items(value, '[', ']')?.into_iter().map(|item| tokenize_item::<Self>(param, item)).collect()
 	}
 
 	/// Tries to parse a value as an address.
@@ -176,9 +104,79 @@ pub trait Tokenizer {
 	fn tokenize_int(value: &str) -> Result<[u8; 32], Error>;
 }
 
+/// Splits the items of the array or tuple literal `value` at its top level commas, trimming them.
+///
+/// Items may be nested arrays and tuples, or strings quoted with `"` in which `\"` and `\\`
+/// are escapes.
+fn split_items(value: &str, open: char, close: char) -> Result<Vec<&str>, Error> {
+	let inner = value.strip_prefix(open).and_then(|value| value.strip_suffix(close)).ok_or(Error::InvalidData)?;
+	if inner.trim().is_empty() {
+		return Ok(vec![]);
+	}
+
+	let mut items = vec![];
+	let mut closing = vec![];
+	let mut quoted = false;
+	let mut escaped = false;
+	let mut start = 0;
+	for (pos, ch) in inner.char_indices() {
+		if quoted {
+			match ch {
+				_ if escaped => escaped = false,
+				'\\' => escaped = true,
+				'"' => quoted = false,
+				_ => (),
+			}
+			continue;
+		}
+
+		match ch {
+			'"' => quoted = true,
+			'[' => closing.push(']'),
+			'(' => closing.push(')'),
+			']' | ')' if closing.pop() != Some(ch) => return Err(Error::InvalidData),
+			',' if closing.is_empty() => {
+				items.push(inner[start..pos].trim());
+				start = pos + 1;
+			}
+			_ => (),
+		}
+	}
+
+	if quoted || !closing.is_empty() {
+		return Err(Error::InvalidData);
+	}
+	items.push(inner[start..].trim());
+	Ok(items)
+}
+
+/// Tokenizes an item of an array or tuple literal, unquoting it if it is a quoted string.
+fn tokenize_item<T: Tokenizer + ?Sized>(param: &ParamType, item: &str) -> Result<Token, Error> {
+	match (param, item.strip_prefix('"')) {
+		(ParamType::String, Some(quoted)) => T::tokenize_string(&unquote(quoted)?).map(Token::String),
+		_ => T::tokenize(param, item),
+	}
+}
+
+/// Unescapes the rest of a string after its opening quote, which must end with the closing quote.
+fn unquote(quoted: &str) -> Result<String, Error> {
+	let mut result = String::new();
+	let mut chars = quoted.chars();
+	while let Some(ch) = chars.next() {
+		match ch {
+			'\\' => result.push(chars.next().ok_or(Error::InvalidData)?),
+			'"' if chars.as_str().is_empty() => return Ok(result),
+			'"' => return Err(Error::InvalidData),
+			ch => result.push(ch),
+		}
+	}
+	Err(Error::InvalidData)
+}
+
 #[cfg(test)]
 mod test {
-	use super::{LenientTokenizer, ParamType, Tokenizer};
+	use super::{LenientTokenizer, ParamType, Token, Tokenizer};
+	use crate::Address;
 	#[test]
 	fn single_quoted_in_array_must_error() {
 		assert!(LenientTokenizer::tokenize_array("[1,\"0,false]", &ParamType::Bool).is_err());
@@ -187,4 +185,38 @@ mod test {
 		assert!(LenientTokenizer::tokenize_array("[1,\"0\",false]", &ParamType::Bool).is_err());
 		assert!(LenientTokenizer::tokenize_array("[1,0]", &ParamType::Bool).is_ok());
 	}
+
+	#[test]
+	fn tokenize_nested_literals() {
+		let uints = ParamType::Array(Box::new(ParamType::Uint(256)));
+		let uint = |i: u64| Token::Uint(i.into());
+		assert_eq!(
+			LenientTokenizer::tokenize(&ParamType::Array(Box::new(uints.clone())), "[[1, 2], [3,4]]").unwrap(),
+			Token::Array(vec![Token::Array(vec![uint(1), uint(2)]), Token::Array(vec![uint(3), uint(4)])])
+		);
+
+		let order = ParamType::Tuple(vec![ParamType::Address, ParamType::Uint(256), uints]);
+		assert_eq!(
+			LenientTokenizer::tokenize(&order, "(0x11, 5, [1,2])").unwrap(),
+			Token::Tuple(vec![
+				Token::Address(Address::from_low_u64_be(0x11)),
+				uint(5),
+				Token::Array(vec![uint(1), uint(2)]),
+			])
+		);
+
+		let pairs = ParamType::FixedArray(Box::new(ParamType::Tuple(vec![ParamType::Uint(8), ParamType::String])), 2);
+		assert_eq!(
+			LenientTokenizer::tokenize(&pairs, r#"[(1,"a, (b]"),(2,"c\"d\\")]"#).unwrap(),
+			Token::FixedArray(vec![
+				Token::Tuple(vec![uint(1), Token::String("a, (b]".to_owned())]),
+				Token::Tuple(vec![uint(2), Token::String(r#"c"d\"#.to_owned())]),
+			])
+		);
+
+		assert!(LenientTokenizer::tokenize(&pairs, "[(1,a),(2,b)").is_err());
+		assert!(LenientTokenizer::tokenize(&pairs, "[(1,a],(2,b))]").is_err());
+		assert!(LenientTokenizer::tokenize(&pairs, "[(1,a,b),(2,b)]").is_err());
+		assert!(LenientTokenizer::tokenize(&pairs, r#"[(1,"a"b),(2,b)]"#).is_err());
+	}
 }
@@ -0,0 +1,211 @@
+// Copyright 2015-2020 Parity Technologies
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Serde support of tokens.
+//!
+//! Tokens are tagged with their kind, e.g. `{"type":"uint","value":"1"}`. Addresses and bytes
+//! are `0x` prefixed hex strings and integers are decimal strings, so that they survive the
+//! precision of JSON numbers.
+
+use super::Token;
+#[cfg(not(feature = "std"))]
+use crate::no_std_prelude::*;
+use crate::{Address, Uint};
+use serde::{
+	de::{self, Error as _},
+	Deserialize, Deserializer, Serialize, Serializer,
+};
+
+#[derive(Serialize)]
+#[serde(tag = "type", content = "value", rename_all = "camelCase")]
+enum TokenRef<'a> {
+	Address(#[serde(serialize_with = "serialize_hex")] &'a [u8]),
+	FixedBytes(#[serde(serialize_with = "serialize_hex")] &'a [u8]),
+	Bytes(#[serde(serialize_with = "serialize_hex")] &'a [u8]),
+	Int(#[serde(serialize_with = "serialize_int")] &'a Uint),
+	Uint(#[serde(serialize_with = "serialize_uint")] &'a Uint),
+	Bool(bool),
+	String(&'a str),
+	FixedArray(&'a [Token]),
+	Array(&'a [Token]),
+	Tuple(&'a [Token]),
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "type", content = "value", rename_all = "camelCase")]
+enum TokenRepr {
+	Address(#[serde(deserialize_with = "deserialize_hex")] Vec<u8>),
+	FixedBytes(#[serde(deserialize_with = "deserialize_hex")] Vec<u8>),
+	Bytes(#[serde(deserialize_with = "deserialize_hex")] Vec<u8>),
+	Int(String),
+	Uint(String),
+	Bool(bool),
+	String(String),
+	FixedArray(Vec<Token>),
+	Array(Vec<Token>),
+	Tuple(Vec<Token>),
+}
+
+impl Serialize for Token {
+	fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		let token = match *self {
+			Token::Address(ref address) => TokenRef::Address(address.as_bytes()),
+			Token::FixedBytes(ref bytes) => TokenRef::FixedBytes(bytes),
+			Token::Bytes(ref bytes) => TokenRef::Bytes(bytes),
+			Token::Int(ref int) => TokenRef::Int(int),
+			Token::Uint(ref uint) => TokenRef::Uint(uint),
+			Token::Bool(b) => TokenRef::Bool(b),
+			Token::String(ref string) => TokenRef::String(string),
+			Token::FixedArray(ref tokens) => TokenRef::FixedArray(tokens),
+			Token::Array(ref tokens) => TokenRef::Array(tokens),
+			Token::Tuple(ref tokens) => TokenRef::Tuple(tokens),
+		};
+		token.serialize(serializer)
+	}
+}
+
+impl<'a> Deserialize<'a> for Token {
+	fn deserialize<D: Deserializer<'a>>(deserializer: D) -> Result<Self, D::Error> {
+		let token = match TokenRepr::deserialize(deserializer)? {
+			TokenRepr::Address(bytes) if bytes.len() == 20 => Token::Address(Address::from_slice(&bytes)),
+			TokenRepr::Address(bytes) => {
+				return Err(D::Error::custom(format!("address is {} bytes long, expected 20", bytes.len())))
+			}
+			TokenRepr::FixedBytes(bytes) => Token::FixedBytes(bytes),
+			TokenRepr::Bytes(bytes) => Token::Bytes(bytes),
+			TokenRepr::Int(int) => Token::Int(parse_int(&int)?),
+			TokenRepr::Uint(uint) => Token::Uint(parse_uint(&uint)?),
+			TokenRepr::Bool(b) => Token::Bool(b),
+			TokenRepr::String(string) => Token::String(string),
+			TokenRepr::FixedArray(tokens) => Token::FixedArray(tokens),
+			TokenRepr::Array(tokens) => Token::Array(tokens),
+			TokenRepr::Tuple(tokens) => Token::Tuple(tokens),
+		};
+		Ok(token)
+	}
+}
+
+fn serialize_hex<S: Serializer>(bytes: &&[u8], serializer: S) -> Result<S::Ok, S::Error> {
+	serializer.serialize_str(&format!("0x{}", hex::encode(bytes)))
+}
+
+fn serialize_uint<S: Serializer>(uint: &&Uint, serializer: S) -> Result<S::Ok, S::Error> {
+	serializer.serialize_str(&uint.to_string())
+}
+
+fn serialize_int<S: Serializer>(int: &&Uint, serializer: S) -> Result<S::Ok, S::Error> {
+	match int.bit(255) {
+		true => serializer.serialize_str(&format!("-{}", !**int + 1)),
+		false => serializer.serialize_str(&int.to_string()),
+	}
+}
+
+fn deserialize_hex<'a, D: Deserializer<'a>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+	let value = String::deserialize(deserializer)?;
+	let digits = value.strip_prefix("0x").ok_or_else(|| D::Error::custom(format!("`{}` is not 0x prefixed", value)))?;
+	hex::decode(digits).map_err(D::Error::custom)
+}
+
+fn parse_uint<E: de::Error>(value: &str) -> Result<Uint, E> {
+	Uint::from_dec_str(value).map_err(|_| E::custom(format!("`{}` is not a decimal uint256", value)))
+}
+
+fn parse_int<E: de::Error>(value: &str) -> Result<Uint, E> {
+	let invalid = || E::custom(format!("`{}` is not a decimal int256", value));
+	let (negative, digits) = match value.strip_prefix('-') {
+		Some(digits) => (true, digits),
+		None => (false, value),
+	};
+	let abs = Uint::from_dec_str(digits).map_err(|_| invalid())?;
+	let min = Uint::one() << 255;
+	match negative {
+		true if abs <= min => Ok(!abs + 1),
+		false if abs < min => Ok(abs),
+		_ => Err(invalid()),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::{Token, Uint};
+	use serde_json::json;
+
+	fn assert_round_trip(token: Token, expected: serde_json::Value) {
+		assert_eq!(serde_json::to_value(&token).unwrap(), expected);
+		assert_eq!(serde_json::from_value::<Token>(expected).unwrap(), token);
+	}
+
+	#[test]
+	fn test_round_trip_values() {
+		assert_round_trip(
+			Token::Address([0x11u8; 20].into()),
+			json!({"type": "address", "value": "0x1111111111111111111111111111111111111111"}),
+		);
+		assert_round_trip(Token::FixedBytes(vec![0xab, 0xcd]), json!({"type": "fixedBytes", "value": "0xabcd"}));
+		assert_round_trip(Token::Bytes(vec![]), json!({"type": "bytes", "value": "0x"}));
+		assert_round_trip(Token::Bool(true), json!({"type": "bool", "value": true}));
+		assert_round_trip(Token::String("abc".to_owned()), json!({"type": "string", "value": "abc"}));
+		assert_round_trip(
+			Token::Uint(Uint::MAX),
+			json!({
+				"type": "uint",
+				"value": "115792089237316195423570985008687907853269984665640564039457584007913129639935",
+			}),
+		);
+	}
+
+	#[test]
+	fn test_round_trip_int() {
+		assert_round_trip(Token::Int(42.into()), json!({"type": "int", "value": "42"}));
+		assert_round_trip(Token::Int(Uint::MAX), json!({"type": "int", "value": "-1"}));
+
+		let min = Uint::one() << 255;
+		let min_json = json!({
+			"type": "int",
+			"value": "-57896044618658097711785492504343953926634992332820282019728792003956564819968",
+		});
+		assert_round_trip(Token::Int(min), min_json);
+
+		// 2^255 only fits negated
+		let overflow = json!({
+			"type": "int",
+			"value": "57896044618658097711785492504343953926634992332820282019728792003956564819968",
+		});
+		assert!(serde_json::from_value::<Token>(overflow).is_err());
+	}
+
+	#[test]
+	fn test_round_trip_nested() {
+		let tuple =
+			|i: u64| Token::Tuple(vec![Token::Uint(i.into()), Token::Array(vec![Token::Bool(i.is_multiple_of(2))])]);
+		let token = Token::FixedArray(vec![tuple(1), tuple(2)]);
+		let tuple_json = |i: u64| {
+			json!({
+				"type": "tuple",
+				"value": [
+					{"type": "uint", "value": i.to_string()},
+					{"type": "array", "value": [{"type": "bool", "value": i.is_multiple_of(2)}]},
+				],
+			})
+		};
+		assert_round_trip(token, json!({"type": "fixedArray", "value": [tuple_json(1), tuple_json(2)]}));
+	}
+
+	#[test]
+	fn test_deserialize_invalid() {
+		for invalid in &[
+			json!({"type": "address", "value": "0x11"}),
+			json!({"type": "bytes", "value": "abcd"}),
+			json!({"type": "uint", "value": 1}),
+			json!({"type": "uint", "value": "-1"}),
+			json!({"type": "foo", "value": "1"}),
+		] {
+			assert!(serde_json::from_value::<Token>(invalid.clone()).is_err());
+		}
+	}
+}
@@ -6,19 +6,30 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
-use crate::{errors::Error, token::Tokenizer};
+#[cfg(not(feature = "std"))]
+use crate::no_std_prelude::*;
+use crate::{errors::Error, token::Tokenizer, util::check_address_checksum};
 
 /// Tries to parse string as a token. Require string to clearly represent the value.
+///
+/// Hex values may be `0x` prefixed but must have the exact width of their type. Mixed case
+/// addresses must carry a valid EIP-55 checksum.
 pub struct StrictTokenizer;
 
+/// Decodes hex digits, optionally `0x` prefixed.
+pub(crate) fn decode_hex(value: &str) -> Result<Vec<u8>, Error> {
+	hex::decode(value.strip_prefix("0x").unwrap_or(value)).map_err(Into::into)
+}
+
 impl Tokenizer for StrictTokenizer {
 	fn tokenize_address(value: &str) -> Result<[u8; 20], Error> {
-		let hex: Vec<u8> = hex::decode(value)?;
+		let hex: Vec<u8> = decode_hex(value)?;
 		match hex.len() == 20 {
 			false => Err(Error::InvalidData),
 			true => {
 				let mut address = [0u8; 20];
 				address.copy_from_slice(&hex);
+				check_address_checksum(value, &address.into())?;
 				Ok(address)
 			}
 		}
@@ -37,11 +48,11 @@ impl Tokenizer for StrictTokenizer {
 	}
 
 	fn tokenize_bytes(value: &str) -> Result<Vec<u8>, Error> {
-		hex::decode(value).map_err(Into::into)
+		decode_hex(value)
 	}
 
 	fn tokenize_fixed_bytes(value: &str, len: usize) -> Result<Vec<u8>, Error> {
-		let hex: Vec<u8> = hex::decode(value)?;
+		let hex: Vec<u8> = decode_hex(value)?;
 		match hex.len() == len {
 			true => Ok(hex),
 			false => Err(Error::InvalidData),
@@ -49,7 +60,7 @@ impl Tokenizer for StrictTokenizer {
 	}
 
 	fn tokenize_uint(value: &str) -> Result<[u8; 32], Error> {
-		let hex: Vec<u8> = hex::decode(value)?;
+		let hex: Vec<u8> = decode_hex(value)?;
 		match hex.len() == 32 {
 			true => {
 				let mut uint = [0u8; 32];
@@ -82,6 +93,11 @@ mod tests {
 			StrictTokenizer::tokenize(&ParamType::Address, "2222222222222222222222222222222222222222").unwrap(),
 			Token::Address([0x22u8; 20].into())
 		);
+		let checksummed = "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed";
+		let address = Token::Address(checksummed[2..].parse().unwrap());
+		assert_eq!(StrictTokenizer::tokenize(&ParamType::Address, checksummed).unwrap(), address);
+		assert_eq!(StrictTokenizer::tokenize(&ParamType::Address, &checksummed.to_lowercase()).unwrap(), address);
+		assert!(StrictTokenizer::tokenize(&ParamType::Address, "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAeD").is_err());
 	}
 
 	#[test]
@@ -7,8 +7,15 @@
 // except according to those terms.
 
 //! Ethereum ABI params.
-use crate::{Address, Bytes, FixedBytes, ParamType, Uint};
-use std::fmt;
+#[cfg(not(feature = "std"))]
+use crate::no_std_prelude::*;
+use crate::{
+	function::in_range,
+	util::{decode_prefixed_hex, to_checksum_string},
+	Address, Bytes, FixedBytes, ParamType, Result, Uint,
+};
+use anyhow::anyhow;
+use core::fmt;
 
 /// Ethereum ABI params.
 #[derive(Debug, PartialEq, Clone)]
@@ -63,29 +70,191 @@ pub enum Token {
 	Tuple(Vec<Token>),
 }
 
+/// Formats integers in hex without prefix, or in decimal with the alternate flag (`{:#}`), and
+/// addresses in their EIP-55 checksummed form without prefix.
 impl fmt::Display for Token {
 	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		let radix = if f.alternate() { Radix::Decimal } else { Radix::Hex };
+		self.write(f, radix)
+	}
+}
+
+/// Formats integers in lower case hex, prefixed with `0x` with the alternate flag (`{:#x}`).
+/// Other tokens are formatted as with `Display`.
+impl fmt::LowerHex for Token {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		self.write(f, Radix::LowerHex)
+	}
+}
+
+/// Formats integers in upper case hex, prefixed with `0x` with the alternate flag (`{:#X}`).
+/// Other tokens are formatted as with `Display`.
+impl fmt::UpperHex for Token {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		self.write(f, Radix::UpperHex)
+	}
+}
+
+/// How integers are formatted.
+#[derive(Clone, Copy)]
+enum Radix {
+	/// Lower case hex, never prefixed.
+	Hex,
+	/// Decimal, signed for `Int`.
+	Decimal,
+	/// Lower case hex honoring the formatter flags.
+	LowerHex,
+	/// Upper case hex honoring the formatter flags.
+	UpperHex,
+}
+
+impl Token {
+	/// Creates a `bytesN` token holding `bytes`, e.g. a 32 bytes hash.
+	pub fn fixed_bytes<T: AsRef<[u8]>>(bytes: T) -> Token {
+		Token::FixedBytes(bytes.as_ref().to_vec())
+	}
+
+	/// Parses a `0x` prefixed hex string as an `address`, `bytes`, `bytesN` or `function` token.
+	///
+	/// `address`, `bytesN` and `function` values must have the exact width of their type.
+	pub fn from_hex(param_type: &ParamType, value: &str) -> Result<Token> {
+		let bytes = match *param_type {
+			ParamType::Address | ParamType::Bytes | ParamType::FixedBytes(_) | ParamType::Function => {
+				decode_prefixed_hex(value)?
+			}
+			_ => return Err(anyhow!("`{}` values can not be parsed from hex", param_type).into()),
+		};
+		match *param_type {
+			ParamType::Address if bytes.len() == 20 => Ok(Token::Address(Address::from_slice(&bytes))),
+			ParamType::FixedBytes(len) if bytes.len() == len => Ok(Token::FixedBytes(bytes)),
+			ParamType::Function if bytes.len() == 24 => Ok(Token::FixedBytes(bytes)),
+			ParamType::Bytes => Ok(Token::Bytes(bytes)),
+			_ => Err(anyhow!("`{}` value is {} bytes long", param_type, bytes.len()).into()),
+		}
+	}
+
+	/// Creates an `Array` token, checking that all `tokens` have the same shape.
+	///
+	/// Prefer this over constructing `Token::Array` directly, which accepts mixed elements
+	/// that can not be encoded as any ABI type.
+	pub fn array(tokens: Vec<Token>) -> Result<Token> {
+		Self::check_homogeneous(&tokens)?;
+		Ok(Token::Array(tokens))
+	}
+
+	/// Creates a `FixedArray` token, checking that all `tokens` have the same shape.
+	pub fn fixed_array(tokens: Vec<Token>) -> Result<Token> {
+		Self::check_homogeneous(&tokens)?;
+		Ok(Token::FixedArray(tokens))
+	}
+
+	fn check_homogeneous(tokens: &[Token]) -> Result<()> {
+		match tokens.iter().position(|token| !token.same_shape(&tokens[0])) {
+			Some(pos) => Err(anyhow!(
+				"array element {} `{:?}` differs from the first element `{:?}`",
+				pos,
+				tokens[pos],
+				tokens[0]
+			)
+			.into()),
+			None => Ok(()),
+		}
+	}
+
+	/// Returns true if both tokens can be values of the same ABI type.
+	fn same_shape(&self, other: &Token) -> bool {
+		match (self, other) {
+			(Token::Address(_), Token::Address(_))
+			| (Token::Bytes(_), Token::Bytes(_))
+			| (Token::Int(_), Token::Int(_))
+			| (Token::Uint(_), Token::Uint(_))
+			| (Token::Bool(_), Token::Bool(_))
+			| (Token::String(_), Token::String(_)) => true,
+			(Token::FixedBytes(a), Token::FixedBytes(b)) => a.len() == b.len(),
+			(Token::FixedArray(a), Token::FixedArray(b)) | (Token::Tuple(a), Token::Tuple(b)) => {
+				a.len() == b.len() && a.iter().zip(b).all(|(a, b)| a.same_shape(b))
+			}
+			(Token::Array(a), Token::Array(b)) => match (a.first(), b.first()) {
+				(Some(a), Some(b)) => a.same_shape(b),
+				_ => true,
+			},
+			_ => false,
+		}
+	}
+
+	/// Calls `visitor` on the token and then, recursively, on every element of arrays and tuples.
+	pub fn walk<F: FnMut(&Token)>(&self, visitor: &mut F) {
+		visitor(self);
+		if let Token::Array(ref tokens) | Token::FixedArray(ref tokens) | Token::Tuple(ref tokens) = *self {
+			for token in tokens {
+				token.walk(visitor);
+			}
+		}
+	}
+
+	/// Like [`Token::walk`], but allows the visitor to modify the tokens in place.
+	///
+	/// Elements are visited after the visitor had a chance to replace their parent.
+	pub fn walk_mut<F: FnMut(&mut Token)>(&mut self, visitor: &mut F) {
+		visitor(self);
+		if let Token::Array(ref mut tokens) | Token::FixedArray(ref mut tokens) | Token::Tuple(ref mut tokens) = *self {
+			for token in tokens {
+				token.walk_mut(visitor);
+			}
+		}
+	}
+
+	fn write(&self, f: &mut fmt::Formatter, radix: Radix) -> fmt::Result {
 		match *self {
 			Token::Bool(b) => write!(f, "{}", b),
 			Token::String(ref s) => write!(f, "{}", s),
-			Token::Address(ref a) => write!(f, "{:x}", a),
-			Token::Bytes(ref bytes) | Token::FixedBytes(ref bytes) => write!(f, "{}", hex::encode(&bytes)),
-			Token::Uint(ref i) | Token::Int(ref i) => write!(f, "{:x}", i),
-			Token::Array(ref arr) | Token::FixedArray(ref arr) => {
-				let s = arr.iter().map(|ref t| format!("{}", t)).collect::<Vec<String>>().join(",");
-
-				write!(f, "[{}]", s)
+			Token::Address(ref a) => write!(f, "{}", &to_checksum_string(a)[2..]),
+			Token::Bytes(ref bytes) | Token::FixedBytes(ref bytes) => write!(f, "{}", hex::encode(bytes)),
+			Token::Uint(ref i) | Token::Int(ref i) => match radix {
+				Radix::Hex => write!(f, "{:x}", i),
+				Radix::Decimal if matches!(*self, Token::Int(_)) && i.bit(255) => write!(f, "-{}", !*i + 1),
+				Radix::Decimal => write!(f, "{}", i),
+				Radix::LowerHex => fmt::LowerHex::fmt(i, f),
+				Radix::UpperHex => fmt::UpperHex::fmt(i, f),
+			},
+			Token::Array(ref tokens) | Token::FixedArray(ref tokens) | Token::Tuple(ref tokens) => {
+				let (open, close) = if let Token::Tuple(_) = *self { ('(', ')') } else { ('[', ']') };
+				write!(f, "{}", open)?;
+				for (i, token) in tokens.iter().enumerate() {
+					if i > 0 {
+						write!(f, ",")?;
+					}
+					token.write(f, radix)?;
+				}
+				write!(f, "{}", close)
 			}
-			Token::Tuple(ref s) => {
-				let s = s.iter().map(|ref t| format!("{}", t)).collect::<Vec<String>>().join(",");
+		}
+	}
 
-				write!(f, "({})", s)
+	/// Formats the token as a Solidity-style literal, which [`Tokenizer::tokenize_literal`] parses
+	/// back, e.g. `(0x5aAe..BeAed,[1,-2],"a \"b\"")`.
+	///
+	/// Addresses are checksummed and bytes are `0x` prefixed hex, integers are in decimal,
+	/// signed for `Int`, and strings are quoted with `"` and `\` escaped.
+	///
+	/// [`Tokenizer::tokenize_literal`]: crate::token::Tokenizer::tokenize_literal
+	pub fn to_literal(&self) -> String {
+		match *self {
+			Token::Bool(b) => b.to_string(),
+			Token::String(ref s) => format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\"")),
+			Token::Address(ref a) => to_checksum_string(a),
+			Token::Bytes(ref bytes) | Token::FixedBytes(ref bytes) => format!("0x{}", hex::encode(bytes)),
+			Token::Uint(_) | Token::Int(_) => format!("{:#}", self),
+			Token::Array(ref tokens) | Token::FixedArray(ref tokens) | Token::Tuple(ref tokens) => {
+				let items = tokens.iter().map(Token::to_literal).collect::<Vec<_>>().join(",");
+				match *self {
+					Token::Tuple(_) => format!("({})", items),
+					_ => format!("[{}]", items),
+				}
 			}
 		}
 	}
-}
 
-impl Token {
 	/// Check whether the type of the token matches the given parameter type.
 	///
 	/// Numeric types (`Int` and `Uint`) type check if the size of the token
@@ -95,20 +264,18 @@ impl Token {
 			Token::Address(_) => *param_type == ParamType::Address,
 			Token::Bytes(_) => *param_type == ParamType::Bytes,
 			Token::Int(_) => {
-				matches!(*param_type, ParamType::Int(_))
+				matches!(*param_type, ParamType::Int(_) | ParamType::Fixed(..))
 			}
 			Token::Uint(_) => {
-				matches!(*param_type, ParamType::Uint(_))
+				matches!(*param_type, ParamType::Uint(_) | ParamType::Ufixed(..))
 			}
 			Token::Bool(_) => *param_type == ParamType::Bool,
 			Token::String(_) => *param_type == ParamType::String,
-			Token::FixedBytes(ref bytes) => {
-				if let ParamType::FixedBytes(size) = *param_type {
-					size >= bytes.len()
-				} else {
-					false
-				}
-			}
+			Token::FixedBytes(ref bytes) => match *param_type {
+				ParamType::FixedBytes(size) => size >= bytes.len(),
+				ParamType::Function => bytes.len() == 24,
+				_ => false,
+			},
 			Token::Array(ref tokens) => {
 				if let ParamType::Array(ref param_type) = *param_type {
 					tokens.iter().all(|t| t.type_check(param_type))
@@ -125,7 +292,7 @@ impl Token {
 			}
 			Token::Tuple(ref tokens) => {
 				if let ParamType::Tuple(ref param_type) = *param_type {
-					tokens.iter().enumerate().all(|(i, t)| t.type_check(&param_type[i]))
+					tokens.len() == param_type.len() && tokens.iter().zip(param_type).all(|(t, p)| t.type_check(p))
 				} else {
 					false
 				}
@@ -149,6 +316,14 @@ impl Token {
 		}
 	}
 
+	/// Converts token to fixed bytes of exactly `size` bytes.
+	pub fn into_fixed_bytes_exact(self, size: usize) -> Option<Vec<u8>> {
+		match self {
+			Token::FixedBytes(bytes) if bytes.len() == size => Some(bytes),
+			_ => None,
+		}
+	}
+
 	/// Converts token to...
 	pub fn into_bytes(self) -> Option<Vec<u8>> {
 		match self {
@@ -165,6 +340,34 @@ impl Token {
 		}
 	}
 
+	/// Signed integer token of `magnitude`, negated if `negative`, in two's complement.
+	///
+	/// Returns `None` if the value does not fit in an `int256`.
+	pub fn signed(negative: bool, magnitude: Uint) -> Option<Token> {
+		let min = Uint::one() << 255;
+		match negative {
+			true if magnitude <= min => Some(Token::Int((!magnitude).overflowing_add(Uint::one()).0)),
+			false if magnitude < min => Some(Token::Int(magnitude)),
+			_ => None,
+		}
+	}
+
+	/// Converts a signed integer token to its sign, true if negative, and magnitude.
+	pub fn into_signed(self) -> Option<(bool, Uint)> {
+		match self {
+			Token::Int(int) if int.bit(255) => Some((true, (!int).overflowing_add(Uint::one()).0)),
+			Token::Int(int) => Some((false, int)),
+			_ => None,
+		}
+	}
+
+	/// Check if the integers of the token fit in the bits of `kind`, e.g. `-129` does not fit
+	/// in an `int8`, and if its fixed bytes have the exact size of their type. Other values
+	/// always fit.
+	pub fn fits(&self, kind: &ParamType) -> bool {
+		in_range(kind, self)
+	}
+
 	/// Converts token to...
 	pub fn into_uint(self) -> Option<Uint> {
 		match self {
@@ -205,6 +408,14 @@ impl Token {
 		}
 	}
 
+	/// Converts token to...
+	pub fn into_tuple(self) -> Option<Vec<Token>> {
+		match self {
+			Token::Tuple(tuple) => Some(tuple),
+			_ => None,
+		}
+	}
+
 	/// Check if all the types of the tokens match the given parameter types.
 	pub fn types_check(tokens: &[Token], param_types: &[ParamType]) -> bool {
 		param_types.len() == tokens.len() && {
@@ -221,11 +432,76 @@ impl Token {
 			_ => false,
 		}
 	}
+
+	/// Check if the token holds the default value of its type: zero numbers and addresses,
+	/// `false`, empty bytes, strings and arrays, zeroed fixed bytes, and fixed arrays and
+	/// tuples of such values.
+	pub fn is_zero_value(&self) -> bool {
+		match self {
+			Token::Address(address) => address.is_zero(),
+			Token::FixedBytes(bytes) => bytes.iter().all(|b| *b == 0),
+			Token::Int(value) | Token::Uint(value) => value.is_zero(),
+			Token::Bool(b) => !b,
+			Token::Bytes(bytes) => bytes.is_empty(),
+			Token::String(s) => s.is_empty(),
+			Token::Array(tokens) => tokens.is_empty(),
+			Token::FixedArray(tokens) | Token::Tuple(tokens) => tokens.iter().all(Token::is_zero_value),
+		}
+	}
 }
 
 #[cfg(test)]
 mod tests {
-	use crate::{ParamType, Token};
+	use crate::{
+		token::{LenientTokenizer, Tokenizer},
+		ParamType, Token, Tokenizable, Uint,
+	};
+
+	#[test]
+	fn test_signed() {
+		let min = Uint::one() << 255;
+		assert_eq!(Token::signed(true, 1.into()), Some(Token::Int(Uint::MAX)));
+		assert_eq!(Token::signed(true, 0.into()), Some(Token::Int(0.into())));
+		assert_eq!(Token::signed(true, min), Some(Token::Int(min)));
+		assert_eq!(Token::signed(true, min + 1), None);
+		assert_eq!(Token::signed(false, min), None);
+
+		assert_eq!((-1i64).into_token(), Token::Int(Uint::MAX));
+		assert_eq!(Token::Int(Uint::MAX).into_signed(), Some((true, 1.into())));
+		assert_eq!(Token::Int(min).into_signed(), Some((true, min)));
+		assert_eq!(Token::Int(7.into()).into_signed(), Some((false, 7.into())));
+		assert_eq!(Token::Uint(7.into()).into_signed(), None);
+	}
+
+	#[test]
+	fn test_fits() {
+		let int = |value: i64| value.into_token();
+		assert!(int(-128).fits(&ParamType::Int(8)));
+		assert!(int(127).fits(&ParamType::Int(8)));
+		assert!(!int(-129).fits(&ParamType::Int(8)));
+		assert!(!int(128).fits(&ParamType::Int(8)));
+		assert!(int(-129).fits(&ParamType::Int(248)));
+		assert!(!Token::Uint(256.into()).fits(&ParamType::Uint(8)));
+		assert!(Token::Array(vec![int(-1)]).fits(&ParamType::Array(Box::new(ParamType::Int(8)))));
+		assert!(!Token::FixedBytes(vec![1]).fits(&ParamType::FixedBytes(2)));
+	}
+
+	#[test]
+	fn test_from_hex() {
+		assert_eq!(
+			Token::from_hex(&ParamType::Address, "0x1111111111111111111111111111111111111111").unwrap(),
+			Token::Address([0x11u8; 20].into())
+		);
+		assert_eq!(Token::from_hex(&ParamType::Bytes, "0x").unwrap(), Token::Bytes(vec![]));
+		assert_eq!(Token::from_hex(&ParamType::FixedBytes(2), "0xabcd").unwrap(), Token::FixedBytes(vec![0xab, 0xcd]));
+
+		let error = |param_type, value| Token::from_hex(&param_type, value).unwrap_err().to_string();
+		assert_eq!(error(ParamType::Address, "0x1111"), "`address` value is 2 bytes long");
+		assert_eq!(error(ParamType::FixedBytes(1), "0xabcd"), "`bytes1` value is 2 bytes long");
+		assert_eq!(error(ParamType::Bytes, "abcd"), "hex value is missing the `0x` prefix");
+		assert_eq!(error(ParamType::Bytes, "0xabc"), "invalid hex value: Odd number of digits");
+		assert_eq!(error(ParamType::Uint(256), "0x01"), "`uint256` values can not be parsed from hex");
+	}
 
 	#[test]
 	fn test_type_check() {
@@ -251,6 +527,9 @@ mod tests {
 		assert_type_check(vec![Token::FixedBytes(vec![0, 0, 0])], vec![ParamType::FixedBytes(4)]);
 		assert_not_type_check(vec![Token::FixedBytes(vec![0, 0, 0, 0])], vec![ParamType::FixedBytes(3)]);
 
+		assert_type_check(vec![Token::FixedBytes(vec![0; 24])], vec![ParamType::Function]);
+		assert_not_type_check(vec![Token::FixedBytes(vec![0; 20])], vec![ParamType::Function]);
+
 		assert_type_check(
 			vec![Token::Array(vec![Token::Bool(false), Token::Bool(true)])],
 			vec![ParamType::Array(Box::new(ParamType::Bool))],
@@ -282,18 +561,138 @@ mod tests {
 		);
 	}
 
+	#[test]
+	fn test_into_fixed_bytes_exact() {
+		assert_eq!(Token::FixedBytes(vec![1, 2]).into_fixed_bytes_exact(2), Some(vec![1, 2]));
+		assert_eq!(Token::FixedBytes(vec![1, 2]).into_fixed_bytes_exact(3), None);
+		assert_eq!(Token::Bytes(vec![1, 2]).into_fixed_bytes_exact(2), None);
+	}
+
 	#[test]
 	fn test_is_dynamic() {
-		assert_eq!(Token::Address("0000000000000000000000000000000000000000".parse().unwrap()).is_dynamic(), false);
-		assert_eq!(Token::Bytes(vec![0, 0, 0, 0]).is_dynamic(), true);
-		assert_eq!(Token::FixedBytes(vec![0, 0, 0, 0]).is_dynamic(), false);
-		assert_eq!(Token::Uint(0.into()).is_dynamic(), false);
-		assert_eq!(Token::Int(0.into()).is_dynamic(), false);
-		assert_eq!(Token::Bool(false).is_dynamic(), false);
-		assert_eq!(Token::String("".into()).is_dynamic(), true);
-		assert_eq!(Token::Array(vec![Token::Bool(false)]).is_dynamic(), true);
-		assert_eq!(Token::FixedArray(vec![Token::Uint(0.into())]).is_dynamic(), false);
-		assert_eq!(Token::FixedArray(vec![Token::String("".into())]).is_dynamic(), true);
-		assert_eq!(Token::FixedArray(vec![Token::Array(vec![Token::Bool(false)])]).is_dynamic(), true);
+		assert!(!Token::Address("0000000000000000000000000000000000000000".parse().unwrap()).is_dynamic());
+		assert!(Token::Bytes(vec![0, 0, 0, 0]).is_dynamic());
+		assert!(!Token::FixedBytes(vec![0, 0, 0, 0]).is_dynamic());
+		assert!(!Token::Uint(0.into()).is_dynamic());
+		assert!(!Token::Int(0.into()).is_dynamic());
+		assert!(!Token::Bool(false).is_dynamic());
+		assert!(Token::String("".into()).is_dynamic());
+		assert!(Token::Array(vec![Token::Bool(false)]).is_dynamic());
+		assert!(!Token::FixedArray(vec![Token::Uint(0.into())]).is_dynamic());
+		assert!(Token::FixedArray(vec![Token::String("".into())]).is_dynamic());
+		assert!(Token::FixedArray(vec![Token::Array(vec![Token::Bool(false)])]).is_dynamic());
+	}
+
+	#[test]
+	fn test_checked_array_constructors() {
+		let uints = vec![Token::Uint(1.into()), Token::Uint(2.into())];
+		assert_eq!(Token::array(uints.clone()).unwrap(), Token::Array(uints.clone()));
+		assert_eq!(Token::fixed_array(uints.clone()).unwrap(), Token::FixedArray(uints));
+		assert_eq!(Token::array(vec![]).unwrap(), Token::Array(vec![]));
+
+		assert!(Token::array(vec![Token::Uint(1.into()), Token::Bool(true)]).is_err());
+		assert!(Token::array(vec![Token::FixedBytes(vec![0; 2]), Token::FixedBytes(vec![0; 3])]).is_err());
+		assert!(Token::fixed_array(vec![
+			Token::Tuple(vec![Token::Bool(true), Token::String("a".to_owned())]),
+			Token::Tuple(vec![Token::Bool(true), Token::Bytes(vec![])]),
+		])
+		.is_err());
+		assert!(Token::array(vec![
+			Token::Array(vec![]),
+			Token::Array(vec![Token::Bool(true)]),
+			Token::Array(vec![Token::Bool(false)]),
+		])
+		.is_ok());
+	}
+
+	#[test]
+	fn test_walk() {
+		let address = Token::Address([0x11u8; 20].into());
+		let mut token = Token::Tuple(vec![
+			Token::Uint(1.into()),
+			Token::Array(vec![address.clone(), address.clone()]),
+			Token::FixedArray(vec![Token::Tuple(vec![address, Token::Bool(true)])]),
+		]);
+
+		let mut visited = 0;
+		token.walk(&mut |_| visited += 1);
+		assert_eq!(visited, 9);
+
+		token.walk_mut(&mut |token| {
+			if let Token::Address(ref mut address) = *token {
+				*address = Default::default();
+			}
+		});
+		let mut addresses = vec![];
+		token.walk(&mut |token| {
+			if let Token::Address(address) = *token {
+				addresses.push(address);
+			}
+		});
+		assert_eq!(addresses, vec![Default::default(); 3]);
+	}
+
+	#[test]
+	fn test_is_zero_value() {
+		assert!(Token::Address(Default::default()).is_zero_value());
+		assert!(Token::FixedBytes(vec![0; 4]).is_zero_value());
+		assert!(Token::Uint(0.into()).is_zero_value());
+		assert!(Token::Bool(false).is_zero_value());
+		assert!(Token::String(String::new()).is_zero_value());
+		assert!(Token::Array(vec![]).is_zero_value());
+		assert!(Token::Tuple(vec![Token::Int(0.into()), Token::FixedArray(vec![Token::Bytes(vec![])])]).is_zero_value());
+
+		assert!(!Token::Address([1u8; 20].into()).is_zero_value());
+		assert!(!Token::FixedBytes(vec![0, 1]).is_zero_value());
+		assert!(!Token::Int(1.into()).is_zero_value());
+		assert!(!Token::Bool(true).is_zero_value());
+		assert!(!Token::Bytes(vec![0]).is_zero_value());
+		assert!(!Token::Array(vec![Token::Uint(0.into())]).is_zero_value());
+		assert!(!Token::Tuple(vec![Token::Int(0.into()), Token::String("a".to_owned())]).is_zero_value());
+	}
+
+	#[test]
+	fn test_integer_radix() {
+		let token = Token::Uint(0xbeef.into());
+		assert_eq!(format!("{}", token), "beef");
+		assert_eq!(format!("{:#}", token), "48879");
+		assert_eq!(format!("{:x}", token), "beef");
+		assert_eq!(format!("{:#x}", token), "0xbeef");
+		assert_eq!(format!("{:#X}", token), "0xBEEF");
+
+		let minus_one = Token::Int(!crate::Uint::zero());
+		assert_eq!(format!("{:#}", minus_one), "-1");
+
+		let tuple = Token::Tuple(vec![Token::Array(vec![token, minus_one]), Token::Bool(true)]);
+		assert_eq!(format!("{:#}", tuple), "([48879,-1],true)");
+		assert_eq!(format!("{:#x}", tuple), format!("([0xbeef,0x{}],true)", "f".repeat(64)));
+	}
+
+	#[test]
+	fn test_to_literal() {
+		let token = Token::Tuple(vec![
+			Token::Address("5aaeb6053f3e94c9b9a09f33669435e7ef1beaed".parse().unwrap()),
+			Token::Array(vec![Token::Int(5.into()), Token::Int(!Uint::one())]),
+			Token::String(r#"a, "(b]" \"#.to_owned()),
+			Token::FixedBytes(vec![0xab, 0xcd]),
+			Token::Bytes(vec![]),
+			Token::Bool(true),
+		]);
+		let literal = token.to_literal();
+		assert_eq!(literal, r#"(0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed,[5,-2],"a, \"(b]\" \\",0xabcd,0x,true)"#);
+
+		let param = ParamType::Tuple(vec![
+			ParamType::Address,
+			ParamType::Array(Box::new(ParamType::Int(256))),
+			ParamType::String,
+			ParamType::FixedBytes(2),
+			ParamType::Bytes,
+			ParamType::Bool,
+		]);
+		assert_eq!(LenientTokenizer::tokenize_literal(&param, &literal).unwrap(), token);
+
+		let string = Token::String("\"quoted\"".to_owned());
+		assert_eq!(string.to_literal(), r#""\"quoted\"""#);
+		assert_eq!(LenientTokenizer::tokenize_literal(&ParamType::String, &string.to_literal()).unwrap(), string);
 	}
 }
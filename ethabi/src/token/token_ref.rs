@@ -0,0 +1,87 @@
+// Copyright 2015-2020 Parity Technologies
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Tokens borrowing their values from the encoded data.
+
+use super::Token;
+#[cfg(not(feature = "std"))]
+use crate::no_std_prelude::*;
+use crate::{Address, Uint};
+
+/// Ethereum ABI params borrowing their bytes and strings from the encoded data, as decoded
+/// by [`decode_borrowed`](crate::decode_borrowed).
+///
+/// The variants mirror the ones of [`Token`].
+#[derive(Debug, PartialEq, Clone)]
+pub enum TokenRef<'a> {
+	/// Address.
+	Address(Address),
+	/// Vector of bytes with known size.
+	FixedBytes(&'a [u8]),
+	/// Vector of bytes of unknown size.
+	Bytes(&'a [u8]),
+	/// Signed integer.
+	Int(Uint),
+	/// Unsigned integer.
+	Uint(Uint),
+	/// Boolean value.
+	Bool(bool),
+	/// String.
+	String(&'a str),
+	/// Array with known size.
+	FixedArray(Vec<TokenRef<'a>>),
+	/// Array of params with unknown size.
+	Array(Vec<TokenRef<'a>>),
+	/// Tuple of params of variable types.
+	Tuple(Vec<TokenRef<'a>>),
+}
+
+impl TokenRef<'_> {
+	/// Copies the borrowed values into an owned [`Token`].
+	pub fn to_token(&self) -> Token {
+		let tokens = |tokens: &[TokenRef]| tokens.iter().map(TokenRef::to_token).collect();
+		match *self {
+			TokenRef::Address(address) => Token::Address(address),
+			TokenRef::FixedBytes(bytes) => Token::FixedBytes(bytes.to_vec()),
+			TokenRef::Bytes(bytes) => Token::Bytes(bytes.to_vec()),
+			TokenRef::Int(int) => Token::Int(int),
+			TokenRef::Uint(uint) => Token::Uint(uint),
+			TokenRef::Bool(b) => Token::Bool(b),
+			TokenRef::String(string) => Token::String(string.to_owned()),
+			TokenRef::FixedArray(ref inner) => Token::FixedArray(tokens(inner)),
+			TokenRef::Array(ref inner) => Token::Array(tokens(inner)),
+			TokenRef::Tuple(ref inner) => Token::Tuple(tokens(inner)),
+		}
+	}
+}
+
+impl From<TokenRef<'_>> for Token {
+	fn from(token: TokenRef<'_>) -> Self {
+		token.to_token()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::token::{Token, TokenRef};
+
+	#[test]
+	fn test_to_token() {
+		let token = TokenRef::Tuple(vec![
+			TokenRef::Bytes(&[1, 2]),
+			TokenRef::Array(vec![TokenRef::String("abc"), TokenRef::String("")]),
+			TokenRef::Uint(7.into()),
+		]);
+		let expected = Token::Tuple(vec![
+			Token::Bytes(vec![1, 2]),
+			Token::Array(vec![Token::String("abc".to_owned()), Token::String(String::new())]),
+			Token::Uint(7.into()),
+		]);
+		assert_eq!(Token::from(token), expected);
+	}
+}
@@ -0,0 +1,335 @@
+// Copyright 2015-2020 Parity Technologies
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Conversions of Rust values to and from tokens.
+//!
+//! `u8` is not tokenizable on its own, so that `Vec<u8>` and `[u8; N]` are `bytes` and
+//! `bytesN` rather than arrays of `uint8`.
+
+use super::Token;
+#[cfg(not(feature = "std"))]
+use crate::no_std_prelude::*;
+use crate::{Address, Error, Hash, Uint};
+use core::convert::TryInto;
+
+/// Values convertible into a token.
+///
+/// Structs are converted into tuples with `#[derive(EthabiTokenize)]`.
+pub trait Tokenizable {
+	/// Converts the value into a token.
+	fn into_token(self) -> Token;
+}
+
+/// Values convertible from a token.
+pub trait Detokenizable: Sized {
+	/// Converts a token into a value, failing with [`Error::InvalidData`] if it does not hold one.
+	fn from_token(token: Token) -> Result<Self, Error>;
+}
+
+impl Tokenizable for Token {
+	fn into_token(self) -> Token {
+		self
+	}
+}
+
+impl Detokenizable for Token {
+	fn from_token(token: Token) -> Result<Self, Error> {
+		Ok(token)
+	}
+}
+
+impl Tokenizable for Address {
+	fn into_token(self) -> Token {
+		Token::Address(self)
+	}
+}
+
+impl Detokenizable for Address {
+	fn from_token(token: Token) -> Result<Self, Error> {
+		token.into_address().ok_or(Error::InvalidData)
+	}
+}
+
+impl Tokenizable for Hash {
+	fn into_token(self) -> Token {
+		Token::FixedBytes(self.as_bytes().to_vec())
+	}
+}
+
+impl Detokenizable for Hash {
+	fn from_token(token: Token) -> Result<Self, Error> {
+		token.into_fixed_bytes_exact(32).map(|bytes| Hash::from_slice(&bytes)).ok_or(Error::InvalidData)
+	}
+}
+
+impl Tokenizable for Uint {
+	fn into_token(self) -> Token {
+		Token::Uint(self)
+	}
+}
+
+impl Detokenizable for Uint {
+	fn from_token(token: Token) -> Result<Self, Error> {
+		match token {
+			Token::Uint(uint) | Token::Int(uint) => Ok(uint),
+			_ => Err(Error::InvalidData),
+		}
+	}
+}
+
+impl Tokenizable for bool {
+	fn into_token(self) -> Token {
+		Token::Bool(self)
+	}
+}
+
+impl Detokenizable for bool {
+	fn from_token(token: Token) -> Result<Self, Error> {
+		token.into_bool().ok_or(Error::InvalidData)
+	}
+}
+
+impl Tokenizable for String {
+	fn into_token(self) -> Token {
+		Token::String(self)
+	}
+}
+
+impl Detokenizable for String {
+	fn from_token(token: Token) -> Result<Self, Error> {
+		token.into_string().ok_or(Error::InvalidData)
+	}
+}
+
+impl Tokenizable for &str {
+	fn into_token(self) -> Token {
+		Token::String(self.to_owned())
+	}
+}
+
+impl Tokenizable for Vec<u8> {
+	fn into_token(self) -> Token {
+		Token::Bytes(self)
+	}
+}
+
+impl Detokenizable for Vec<u8> {
+	fn from_token(token: Token) -> Result<Self, Error> {
+		token.into_bytes().ok_or(Error::InvalidData)
+	}
+}
+
+impl<const N: usize> Tokenizable for [u8; N] {
+	fn into_token(self) -> Token {
+		Token::FixedBytes(self.to_vec())
+	}
+}
+
+impl<const N: usize> Detokenizable for [u8; N] {
+	fn from_token(token: Token) -> Result<Self, Error> {
+		let bytes = token.into_fixed_bytes_exact(N).ok_or(Error::InvalidData)?;
+		bytes.as_slice().try_into().map_err(|_| Error::InvalidData)
+	}
+}
+
+macro_rules! impl_uints {
+	($($uint: ty),*) => {
+		$(
+			impl Tokenizable for $uint {
+				fn into_token(self) -> Token {
+					Token::Uint(self.into())
+				}
+			}
+
+			impl Detokenizable for $uint {
+				fn from_token(token: Token) -> Result<Self, Error> {
+					let uint = token.into_uint().ok_or(Error::InvalidData)?;
+					match uint.bits() <= <$uint>::BITS as usize {
+						true => Ok(uint.low_u128() as $uint),
+						false => Err(Error::InvalidData),
+					}
+				}
+			}
+		)*
+	};
+}
+
+macro_rules! impl_ints {
+	($($int: ty),*) => {
+		$(
+			impl Tokenizable for $int {
+				fn into_token(self) -> Token {
+					// two's complement, sign extended to 256 bits
+					match self < 0 {
+						true => Token::Int(!Uint::from(!(self as i128) as u128)),
+						false => Token::Int(Uint::from(self as u128)),
+					}
+				}
+			}
+
+			impl Detokenizable for $int {
+				fn from_token(token: Token) -> Result<Self, Error> {
+					let int = token.into_int().ok_or(Error::InvalidData)?;
+					let negative = int.bit(255);
+					let magnitude = match negative {
+						true => !int,
+						false => int,
+					};
+					if magnitude.bits() >= <$int>::BITS as usize {
+						return Err(Error::InvalidData);
+					}
+					match negative {
+						true => Ok(!(magnitude.low_u128() as i128) as $int),
+						false => Ok(magnitude.low_u128() as $int),
+					}
+				}
+			}
+		)*
+	};
+}
+
+impl_uints!(u16, u32, u64, u128);
+impl_ints!(i8, i16, i32, i64, i128);
+
+/// Marks the types whose vectors and arrays are ABI arrays, all but `u8`.
+pub trait TokenizableItem: Tokenizable {}
+
+/// [`TokenizableItem`] counterpart of [`Detokenizable`].
+pub trait DetokenizableItem: Detokenizable {}
+
+macro_rules! impl_items {
+	($($item: ty),*) => {
+		$(
+			impl TokenizableItem for $item {}
+			impl DetokenizableItem for $item {}
+		)*
+	};
+}
+
+impl_items!(Token, Address, Hash, Uint, bool, String, Vec<u8>, u16, u32, u64, u128, i8, i16, i32, i64, i128);
+impl TokenizableItem for &str {}
+impl<const N: usize> TokenizableItem for [u8; N] {}
+impl<const N: usize> DetokenizableItem for [u8; N] {}
+impl<T: TokenizableItem> TokenizableItem for Vec<T> {}
+impl<T: DetokenizableItem> DetokenizableItem for Vec<T> {}
+impl<T: TokenizableItem, const N: usize> TokenizableItem for [T; N] {}
+impl<T: DetokenizableItem, const N: usize> DetokenizableItem for [T; N] {}
+
+impl<T: TokenizableItem> Tokenizable for Vec<T> {
+	fn into_token(self) -> Token {
+		Token::Array(self.into_iter().map(Tokenizable::into_token).collect())
+	}
+}
+
+impl<T: DetokenizableItem> Detokenizable for Vec<T> {
+	fn from_token(token: Token) -> Result<Self, Error> {
+		token.into_array().ok_or(Error::InvalidData)?.into_iter().map(T::from_token).collect()
+	}
+}
+
+impl<T: TokenizableItem, const N: usize> Tokenizable for [T; N] {
+	fn into_token(self) -> Token {
+		Token::FixedArray(IntoIterator::into_iter(self).map(Tokenizable::into_token).collect())
+	}
+}
+
+impl<T: DetokenizableItem, const N: usize> Detokenizable for [T; N] {
+	fn from_token(token: Token) -> Result<Self, Error> {
+		let tokens = token.into_fixed_array().ok_or(Error::InvalidData)?;
+		if tokens.len() != N {
+			return Err(Error::InvalidData);
+		}
+		let values = tokens.into_iter().map(T::from_token).collect::<Result<Vec<_>, _>>()?;
+		values.try_into().map_err(|_| Error::InvalidData)
+	}
+}
+
+macro_rules! impl_tuples {
+	($(($($name: ident),+)),*) => {
+		$(
+			impl<$($name: TokenizableItem),+> TokenizableItem for ($($name,)+) {}
+			impl<$($name: DetokenizableItem),+> DetokenizableItem for ($($name,)+) {}
+
+			impl<$($name: Tokenizable),+> Tokenizable for ($($name,)+) {
+				#[allow(non_snake_case)]
+				fn into_token(self) -> Token {
+					let ($($name,)+) = self;
+					Token::Tuple(vec![$($name.into_token()),+])
+				}
+			}
+
+			impl<$($name: Detokenizable),+> Detokenizable for ($($name,)+) {
+				fn from_token(token: Token) -> Result<Self, Error> {
+					let mut tokens = token.into_tuple().ok_or(Error::InvalidData)?.into_iter();
+					let value = ($($name::from_token(tokens.next().ok_or(Error::InvalidData)?)?,)+);
+					match tokens.next() {
+						Some(_) => Err(Error::InvalidData),
+						None => Ok(value),
+					}
+				}
+			}
+		)*
+	};
+}
+
+impl_tuples!(
+	(A),
+	(A, B),
+	(A, B, C),
+	(A, B, C, D),
+	(A, B, C, D, E),
+	(A, B, C, D, E, F),
+	(A, B, C, D, E, F, G),
+	(A, B, C, D, E, F, G, H)
+);
+
+#[cfg(test)]
+mod tests {
+	use super::{Detokenizable, Tokenizable};
+	use crate::{Address, Error, Token, Uint};
+
+	fn round_trip<T: Tokenizable + Detokenizable + Clone + PartialEq + core::fmt::Debug>(value: T, expected: Token) {
+		assert_eq!(value.clone().into_token(), expected);
+		assert_eq!(T::from_token(expected).unwrap(), value);
+	}
+
+	#[test]
+	fn test_round_trip() {
+		round_trip(Address::from([0x11u8; 20]), Token::Address([0x11u8; 20].into()));
+		round_trip(vec![1u8, 2], Token::Bytes(vec![1, 2]));
+		round_trip([1u8, 2], Token::FixedBytes(vec![1, 2]));
+		round_trip(vec![1u16, 2], Token::Array(vec![Token::Uint(1.into()), Token::Uint(2.into())]));
+		round_trip([true], Token::FixedArray(vec![Token::Bool(true)]));
+		round_trip(
+			(String::from("a"), vec![[1u8; 2]]),
+			Token::Tuple(vec![Token::String("a".to_owned()), Token::Array(vec![Token::FixedBytes(vec![1, 1])])]),
+		);
+	}
+
+	#[test]
+	fn test_integers() {
+		round_trip(-1i8, Token::Int(Uint::MAX));
+		round_trip(i128::MIN, Token::Int(!Uint::from(i128::MAX as u128)));
+		round_trip(u128::MAX, Token::Uint(u128::MAX.into()));
+
+		assert!(matches!(u16::from_token(Token::Uint(0x10000.into())), Err(Error::InvalidData)));
+		assert!(matches!(i8::from_token(Token::Int(128.into())), Err(Error::InvalidData)));
+		assert!(matches!(i8::from_token(Token::Int(!Uint::from(128))), Err(Error::InvalidData)));
+		assert_eq!(i8::from_token(Token::Int(!Uint::from(127))).unwrap(), i8::MIN);
+	}
+
+	#[test]
+	fn test_mismatches() {
+		assert!(matches!(bool::from_token(Token::Uint(1.into())), Err(Error::InvalidData)));
+		assert!(matches!(<[bool; 2]>::from_token(Token::FixedArray(vec![Token::Bool(true)])), Err(Error::InvalidData)));
+		assert!(matches!(
+			<(bool,)>::from_token(Token::Tuple(vec![Token::Bool(true), Token::Bool(true)])),
+			Err(Error::InvalidData)
+		));
+	}
+}
@@ -8,12 +8,15 @@
 
 //! Tuple param type.
 
-use crate::ParamType;
+#[cfg(not(feature = "std"))]
+use crate::no_std_prelude::*;
+use crate::{param_type::Writer, InternalType, ParamType};
+use core::fmt;
 use serde::{
-	de::{Error, MapAccess, Visitor},
-	Deserialize, Deserializer,
+	de::{Error, IgnoredAny, MapAccess, Visitor},
+	ser::{SerializeMap, SerializeSeq},
+	Deserialize, Deserializer, Serialize, Serializer,
 };
-use std::fmt;
 
 /// Tuple params specification
 #[derive(Debug, Clone, PartialEq)]
@@ -23,6 +26,21 @@ pub struct TupleParam {
 
 	/// Param type.
 	pub kind: ParamType,
+
+	/// Solidity type of the param as given by the `internalType` of the JSON ABI, e.g.
+	/// `struct Market.Order`, if the ABI has it.
+	pub internal_type: Option<String>,
+
+	/// Names and types of the components of a tuple param, possibly nested in arrays, empty for
+	/// other types.
+	pub components: Vec<TupleParam>,
+}
+
+impl TupleParam {
+	/// Solidity type of the param, see [`InternalType`].
+	pub fn internal_type(&self) -> Option<InternalType<'_>> {
+		self.internal_type.as_deref().map(InternalType::parse)
+	}
 }
 
 impl<'a> Deserialize<'a> for TupleParam {
@@ -50,6 +68,7 @@ impl<'a> Visitor<'a> for TupleParamVisitor {
 		let mut name = None;
 		let mut kind = None;
 		let mut components = None;
+		let mut internal_type = None;
 
 		while let Some(ref key) = map.next_key::<String>()? {
 			match key.as_ref() {
@@ -69,23 +88,121 @@ impl<'a> Visitor<'a> for TupleParamVisitor {
 					if components.is_some() {
 						return Err(Error::duplicate_field("components"));
 					}
-					let component: Vec<TupleParam> = map.next_value()?;
-					components = Some(component)
+					components = map.next_value::<Option<Vec<TupleParam>>>()?;
+				}
+				"internalType" => {
+					if internal_type.is_some() {
+						return Err(Error::duplicate_field("internalType"));
+					}
+					internal_type = map.next_value()?;
+				}
+				_ => {
+					map.next_value::<IgnoredAny>()?;
 				}
-				_ => {}
 			}
 		}
 
-		let kind = kind.ok_or_else(|| Error::missing_field("kind")).and_then(|param_type| {
-			if let ParamType::Tuple(_) = param_type {
-				let tuple_params = components.ok_or_else(|| Error::missing_field("components"))?;
-				Ok(ParamType::Tuple(tuple_params.into_iter().map(|param| param.kind).collect()))
-			} else {
-				Ok(param_type)
+		let kind = kind.ok_or_else(|| Error::missing_field("kind"))?;
+		let (kind, components) = with_components(kind, components, name.as_deref().unwrap_or_default())?;
+
+		Ok(TupleParam { name, kind, internal_type, components })
+	}
+}
+
+impl Serialize for TupleParam {
+	fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		let mut map = serializer.serialize_map(None)?;
+		serialize_param(&mut map, self.name.as_deref().unwrap_or_default(), &self.kind, &self.components)?;
+		if let Some(ref internal_type) = self.internal_type {
+			map.serialize_entry("internalType", internal_type)?;
+		}
+		map.end()
+	}
+}
+
+/// Writes the `name`, `type` and `components` entries of a param as in the JSON ABI, where
+/// tuples, possibly nested in arrays, are `tuple` types followed by their components.
+///
+/// The components are named after `components` when there is one per component of the tuple,
+/// and unnamed otherwise, e.g. for params built by hand.
+pub(crate) fn serialize_param<M: SerializeMap>(
+	map: &mut M,
+	name: &str,
+	kind: &ParamType,
+	components: &[TupleParam],
+) -> Result<(), M::Error> {
+	let (kind, kinds) = abi_type(kind);
+	map.serialize_entry("name", name)?;
+	map.serialize_entry("type", &kind)?;
+	match kinds {
+		Some(kinds) if kinds.len() == components.len() => map.serialize_entry("components", components)?,
+		Some(kinds) => map.serialize_entry("components", &Components(kinds))?,
+		None => (),
+	}
+	Ok(())
+}
+
+fn abi_type(kind: &ParamType) -> (String, Option<&[ParamType]>) {
+	match kind {
+		ParamType::Tuple(components) => ("tuple".to_owned(), Some(components)),
+		ParamType::Array(inner) => {
+			let (inner, components) = abi_type(inner);
+			(format!("{}[]", inner), components)
+		}
+		ParamType::FixedArray(inner, size) => {
+			let (inner, components) = abi_type(inner);
+			(format!("{}[{}]", inner, size), components)
+		}
+		kind => (Writer::write(kind), None),
+	}
+}
+
+/// Unnamed components of a tuple.
+struct Components<'a>(&'a [ParamType]);
+
+impl Serialize for Components<'_> {
+	fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		struct Component<'a>(&'a ParamType);
+
+		impl Serialize for Component<'_> {
+			fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+				let mut map = serializer.serialize_map(None)?;
+				serialize_param(&mut map, "", self.0, &[])?;
+				map.end()
 			}
-		})?;
+		}
+
+		let mut seq = serializer.serialize_seq(Some(self.0.len()))?;
+		for kind in self.0 {
+			seq.serialize_element(&Component(kind))?;
+		}
+		seq.end()
+	}
+}
 
-		Ok(TupleParam { name, kind })
+/// Fills the placeholder of a `tuple` type, possibly nested in arrays, with the declared
+/// `components` of the param named `name`, which are returned along with the type for tuples.
+pub(crate) fn with_components<E: Error>(
+	kind: ParamType,
+	components: Option<Vec<TupleParam>>,
+	name: &str,
+) -> Result<(ParamType, Vec<TupleParam>), E> {
+	match kind {
+		ParamType::Tuple(ref placeholder) if placeholder.is_empty() => match components {
+			Some(components) if !components.is_empty() => {
+				Ok((ParamType::Tuple(components.iter().map(|param| param.kind.clone()).collect()), components))
+			}
+			_ => Err(E::custom(format!("tuple param `{}` has no components", name))),
+		},
+		ParamType::Array(inner) => {
+			let (inner, components) = with_components(*inner, components, name)?;
+			Ok((ParamType::Array(Box::new(inner)), components))
+		}
+		ParamType::FixedArray(inner, size) => {
+			let (inner, components) = with_components(*inner, components, name)?;
+			Ok((ParamType::FixedArray(Box::new(inner), size), components))
+		}
+		kind => Ok((kind, vec![])),
 	}
 }
 
@@ -114,10 +231,25 @@ mod tests {
 		assert_eq!(
 			deserialized,
 			vec![
-				TupleParam { name: Some(String::from("foo")), kind: ParamType::Address },
-				TupleParam { name: Some(String::from("bar")), kind: ParamType::Address },
-				TupleParam { name: Some(String::from("baz")), kind: ParamType::Address },
-				TupleParam { name: None, kind: ParamType::Bool },
+				TupleParam {
+					name: Some(String::from("foo")),
+					kind: ParamType::Address,
+					internal_type: None,
+					components: vec![]
+				},
+				TupleParam {
+					name: Some(String::from("bar")),
+					kind: ParamType::Address,
+					internal_type: None,
+					components: vec![]
+				},
+				TupleParam {
+					name: Some(String::from("baz")),
+					kind: ParamType::Address,
+					internal_type: None,
+					components: vec![]
+				},
+				TupleParam { name: None, kind: ParamType::Bool, internal_type: None, components: vec![] },
 			]
 		);
 	}
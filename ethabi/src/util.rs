@@ -6,37 +6,92 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
-//! Utils used by different modules.
+//! Utils used by different modules, and helpers for ABI-adjacent data like signatures.
 
-use crate::{Error, Word};
-
-/// Converts a vector of bytes with len equal n * 32, to a vector of slices.
-pub fn slice_data(data: &[u8]) -> Result<Vec<Word>, Error> {
-	if data.len() % 32 != 0 {
-		return Err(Error::InvalidData);
-	}
-
-	let times = data.len() / 32;
-	let mut result = Vec::with_capacity(times);
-	for i in 0..times {
-		let mut slice = [0u8; 32];
-		let offset = 32 * i;
-		slice.copy_from_slice(&data[offset..offset + 32]);
-		result.push(slice);
-	}
-	Ok(result)
-}
+#[cfg(not(feature = "std"))]
+use crate::no_std_prelude::*;
+use crate::{Address, Result, Word};
+use anyhow::anyhow;
+use sha3::{Digest, Keccak256};
 
 /// Converts a u32 to a right aligned array of 32 bytes.
-pub fn pad_u32(value: u32) -> Word {
+pub(crate) fn pad_u32(value: u32) -> Word {
 	let mut padded = [0u8; 32];
 	padded[28..32].copy_from_slice(&value.to_be_bytes());
 	padded
 }
 
+/// Decodes `0x` prefixed hex, as used by JSON-RPC.
+pub(crate) fn decode_prefixed_hex(value: &str) -> Result<Vec<u8>> {
+	let digits = value.strip_prefix("0x").ok_or_else(|| anyhow!("hex value is missing the `0x` prefix"))?;
+	hex::decode(digits).map_err(|err| anyhow!("invalid hex value: {}", err).into())
+}
+
+/// Encodes `bytes` as `0x` prefixed hex.
+pub(crate) fn encode_prefixed_hex(bytes: &[u8]) -> String {
+	format!("0x{}", hex::encode(bytes))
+}
+
+/// EIP-55 checksummed, `0x` prefixed form of `address`, in which a letter is uppercase if the
+/// matching nibble of the keccak256 of the lowercase hex is at least 8.
+pub fn to_checksum_string(address: &Address) -> String {
+	let digits = hex::encode(address);
+	let hash = Keccak256::digest(digits.as_bytes());
+	let checksummed: String = digits
+		.char_indices()
+		.map(|(i, c)| match (hash[i / 2] >> (4 * (1 - i % 2))) & 0x0f >= 8 {
+			true => c.to_ascii_uppercase(),
+			false => c,
+		})
+		.collect();
+	format!("0x{}", checksummed)
+}
+
+/// Checks the EIP-55 checksum of the hex `digits` of `address`.
+///
+/// Digits all in the same case carry no checksum and are accepted, which is how a mistyped
+/// checksum can be bypassed.
+pub(crate) fn check_address_checksum(digits: &str, address: &Address) -> Result<()> {
+	let digits = digits.strip_prefix("0x").unwrap_or(digits);
+	let mixed_case =
+		digits.contains(|c: char| c.is_ascii_lowercase()) && digits.contains(|c: char| c.is_ascii_uppercase());
+	let checksummed = to_checksum_string(address);
+	match !mixed_case || checksummed[2..] == *digits {
+		true => Ok(()),
+		false => Err(anyhow!("`{}` has an invalid EIP-55 checksum, expected `{}`", digits, checksummed).into()),
+	}
+}
+
+/// Splits an ECDSA signature, usually passed around as `bytes`, into its `(r, s, v)` parts.
+///
+/// Both the 65 bytes `r ‖ s ‖ v` form and the 64 bytes EIP-2098 compact `r ‖ yParity ‖ s`
+/// form are accepted. For the latter `v` is returned as `27 + yParity`.
+pub fn split_signature(signature: &[u8]) -> Result<([u8; 32], [u8; 32], u8)> {
+	let mut r = [0u8; 32];
+	let mut s = [0u8; 32];
+	match signature.len() {
+		65 => {
+			r.copy_from_slice(&signature[..32]);
+			s.copy_from_slice(&signature[32..64]);
+			Ok((r, s, signature[64]))
+		}
+		64 => {
+			r.copy_from_slice(&signature[..32]);
+			s.copy_from_slice(&signature[32..]);
+			let v = 27 + (s[0] >> 7);
+			s[0] &= 0x7f;
+			Ok((r, s, v))
+		}
+		len => Err(anyhow!("invalid signature length {}, expected 64 or 65 bytes", len).into()),
+	}
+}
+
 #[cfg(test)]
 mod tests {
-	use super::pad_u32;
+	use super::{
+		check_address_checksum, decode_prefixed_hex, encode_prefixed_hex, pad_u32, split_signature, to_checksum_string,
+	};
+	use crate::Address;
 	use hex_literal::hex;
 
 	#[test]
@@ -59,4 +114,61 @@ mod tests {
 			hex!("00000000000000000000000000000000000000000000000000000000ffffffff").to_vec()
 		);
 	}
+
+	#[test]
+	fn test_decode_prefixed_hex() {
+		assert_eq!(decode_prefixed_hex("0x").unwrap(), Vec::<u8>::new());
+		assert_eq!(decode_prefixed_hex("0xabCD").unwrap(), vec![0xab, 0xcd]);
+		assert_eq!(encode_prefixed_hex(&[0xab, 0xcd]), "0xabcd");
+
+		let error = |value| decode_prefixed_hex(value).unwrap_err().to_string();
+		assert_eq!(error("abcd"), "hex value is missing the `0x` prefix");
+		assert_eq!(error("0xabc"), "invalid hex value: Odd number of digits");
+		assert_eq!(error("0xabcg"), "invalid hex value: Invalid character 'g' at position 3");
+		assert!(decode_prefixed_hex("0xéé").is_err());
+	}
+
+	#[test]
+	fn test_to_checksum_string() {
+		for checksummed in [
+			"0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed",
+			"0xfB6916095ca1df60bB79Ce92cE3Ea74c37c5d359",
+			"0xdbF03B407c01E7cD3CBea99509d93f8DDDC8C6FB",
+			"0xD1220A0cf47c7B9Be7A2E6BA89F429762e7b9aDb",
+		] {
+			let address: Address = checksummed[2..].parse().unwrap();
+			assert_eq!(to_checksum_string(&address), checksummed);
+			assert!(check_address_checksum(checksummed, &address).is_ok());
+			assert!(check_address_checksum(&checksummed.to_lowercase(), &address).is_ok());
+			assert!(check_address_checksum(&checksummed[2..].to_uppercase(), &address).is_ok());
+		}
+
+		let address: Address = "5aaeb6053f3e94c9b9a09f33669435e7ef1beaed".parse().unwrap();
+		assert_eq!(
+			check_address_checksum("0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAeD", &address).unwrap_err().to_string(),
+			"`5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAeD` has an invalid EIP-55 checksum, expected \
+			 `0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed`"
+		);
+	}
+
+	#[test]
+	fn test_split_signature() {
+		let r = hex!("68a020a209d3d56c46f38cc50a33f704f4a9a10a59377f8dd762ac66910e9b90");
+		let s = hex!("7e865ad05c4035ab5792787d4a0297a43617ae897930a6fe4d822b8faea52064");
+		let expected = (r, s, 27);
+
+		let full = [&r[..], &s[..], &[27]].concat();
+		assert_eq!(split_signature(&full).unwrap(), expected);
+
+		// EIP-2098 compact form, yParity 0
+		let compact = [&r[..], &s[..]].concat();
+		assert_eq!(split_signature(&compact).unwrap(), expected);
+
+		// EIP-2098 compact form, yParity 1
+		let mut compact = compact;
+		compact[32] |= 0x80;
+		assert_eq!(split_signature(&compact).unwrap(), (r, s, 28));
+
+		assert!(split_signature(&full[..63]).is_err());
+	}
 }
@@ -0,0 +1,122 @@
+// Copyright 2015-2020 Parity Technologies
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! String based entry points, e.g. for a block explorer running in the browser.
+//!
+//! They only take and return strings, JSON ABIs and values and `0x` prefixed hex, which keeps
+//! bindings to JavaScript thin. ethabi does not provide such bindings itself. Nothing here touches
+//! the filesystem or the environment, only the `std` loaders like `Contract::load_dir` do.
+
+#[cfg(not(feature = "std"))]
+use crate::no_std_prelude::*;
+use crate::{
+	function::{json_to_token, token_to_json},
+	util::{decode_prefixed_hex, encode_prefixed_hex},
+	Contract, Hash, RawLog, Result,
+};
+use anyhow::anyhow;
+use serde_json::{json, Map, Value};
+
+/// Encodes the call of the function `name` of the JSON ABI `abi_json` with the JSON array of
+/// arguments `args_json`, returning the `0x` prefixed calldata.
+///
+/// Overloads are told apart by their number of arguments. Arrays and tuples are JSON arrays,
+/// integers are numbers or decimal or `0x` prefixed hex strings, and addresses and bytes are
+/// `0x` prefixed hex strings.
+pub fn encode_function_call(abi_json: &str, name: &str, args_json: &str) -> Result<String> {
+	let contract = Contract::load_from_str(abi_json)?;
+	let args: Vec<Value> = serde_json::from_str(args_json)?;
+	let function = contract
+		.functions_by_name(name)?
+		.iter()
+		.find(|function| function.inputs.len() == args.len())
+		.ok_or_else(|| anyhow!("`{}` does not take {} arguments", name, args.len()))?;
+
+	let tokens = function
+		.inputs
+		.iter()
+		.zip(&args)
+		.map(|(param, arg)| json_to_token(&param.kind, arg))
+		.collect::<Result<Vec<_>>>()?;
+	function.encode_input(&tokens).map(|data| encode_prefixed_hex(&data))
+}
+
+/// Decodes a log emitted by an event of the JSON ABI `abi_json`, found by its first topic,
+/// from its `0x` prefixed hex `topics` and `data`.
+///
+/// Returns a JSON object of the name of the event and its params keyed by name, e.g.
+/// `{"event":"Transfer","params":{"from":"0x..","to":"0x..","value":"100"}}`, in which values
+/// are formatted as [`Function::decode_output_as`](crate::Function::decode_output_as) does.
+pub fn decode_log(abi_json: &str, topics: &[String], data: &str) -> Result<String> {
+	let contract = Contract::load_from_str(abi_json)?;
+	let topics = topics
+		.iter()
+		.map(|topic| {
+			let bytes = decode_prefixed_hex(topic)?;
+			match bytes.len() {
+				32 => Ok(Hash::from_slice(&bytes)),
+				len => Err(anyhow!("topic `{}` has {} bytes instead of 32", topic, len).into()),
+			}
+		})
+		.collect::<Result<_>>()?;
+	let (event, log) = contract.parse_log(RawLog { topics, data: decode_prefixed_hex(data)? })?;
+
+	let params = log.params.into_iter().map(|param| (param.name, token_to_json(param.value))).collect::<Map<_, _>>();
+	Ok(json!({ "event": event.name, "params": params }).to_string())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{decode_log, encode_function_call};
+	use crate::{encode, Address, Contract, Token};
+
+	const ABI: &str = r#"[
+		{"type":"function","name":"transfer","inputs":[{"name":"to","type":"address"},{"name":"amount","type":"uint256"}],"outputs":[{"name":"","type":"bool"}]},
+		{"type":"function","name":"batch","inputs":[{"name":"calls","type":"(address,bool)[]"},{"name":"tag","type":"string"}],"outputs":[]},
+		{"type":"event","name":"Transfer","anonymous":false,"inputs":[{"name":"from","type":"address","indexed":true},{"name":"to","type":"address","indexed":true},{"name":"value","type":"uint256","indexed":false}]}
+	]"#;
+
+	#[test]
+	fn test_encode_function_call() {
+		let to = format!("0x{}", "11".repeat(20));
+		let data = encode_function_call(ABI, "transfer", &format!(r#"["{}", 100]"#, to)).unwrap();
+		let contract = Contract::load_from_str(ABI).unwrap();
+		let expected = contract
+			.function("transfer")
+			.unwrap()
+			.encode_input(&[Token::Address(Address::from([0x11; 20])), Token::Uint(100.into())])
+			.unwrap();
+		assert_eq!(data, format!("0x{}", hex::encode(expected)));
+
+		assert!(encode_function_call(ABI, "batch", &format!(r#"[[["{}", true]], "tag"]"#, to)).is_ok());
+		assert!(encode_function_call(ABI, "batch", &format!(r#"[[["{}"]], "tag"]"#, to)).is_err());
+		assert!(encode_function_call(ABI, "transfer", "[]").is_err());
+		assert!(encode_function_call(ABI, "missing", "[]").is_err());
+	}
+
+	#[test]
+	fn test_decode_log() {
+		let contract = Contract::load_from_str(ABI).unwrap();
+		let topics = vec![
+			format!("{:?}", contract.event("Transfer").unwrap().signature()),
+			format!("0x{}{}", "00".repeat(12), "11".repeat(20)),
+			format!("0x{}{}", "00".repeat(12), "22".repeat(20)),
+		];
+		let data = format!("0x{}", hex::encode(encode(&[Token::Uint(100.into())])));
+
+		let decoded = decode_log(ABI, &topics, &data).unwrap();
+		let expected = format!(
+			r#"{{"event":"Transfer","params":{{"from":"0x{}","to":"0x{}","value":"100"}}}}"#,
+			"11".repeat(20),
+			"22".repeat(20)
+		);
+		assert_eq!(decoded, expected);
+		assert!(decode_log(ABI, &topics[..1], &data).is_err());
+		assert!(decode_log(ABI, &topics, "0x00").is_err());
+	}
+}
@@ -0,0 +1,13 @@
+//! Sets the abi path read by `use_contract!(.., path_env = ..)` and generates the bindings
+//! included by `generated`.
+
+use std::{env, path::Path};
+
+fn main() {
+	println!("cargo:rustc-env=ETHABI_TESTS_EIP20_ABI=../res/eip20.abi");
+	println!("cargo:rerun-if-changed=build.rs");
+	println!("cargo:rerun-if-changed=../res/eip20.abi");
+
+	let out = Path::new(&env::var("OUT_DIR").unwrap()).join("eip20.rs");
+	ethabi_codegen::generate_contract_module("../res/eip20.abi", out).unwrap();
+}
@@ -12,22 +12,69 @@ use_contract!(validators, "../res/Validators.abi");
 use_contract!(operations, "../res/Operations.abi");
 use_contract!(urlhint, "../res/urlhint.abi");
 use_contract!(test_rust_keywords, "../res/test_rust_keywords.abi");
+use_contract!(signed, "../res/signed.abi");
+use_contract!(tuples, "../res/tuples.abi");
+use_contract!(tuples_serde, "../res/tuples.abi", serde = true);
+use_contract!(fixed_arrays, "../res/fixed_arrays.abi");
+use_contract!(callbacks, "../res/callbacks.abi");
+use_contract!(errors, "../res/errors.abi");
+use_contract!(overloads, "../res/overloads.abi");
+use_contract!(indexed_events, "../res/indexed_events.abi");
+use_contract!(natives, "../res/natives.abi", integers = "native");
+use_contract!(eip20_builders, "../res/eip20.abi", builders = true);
+use_contract!(eip20_serde, "../res/eip20.abi", serde = true);
+use_contract!(urlhint_structs, "../res/urlhint.abi", structs = true, serde = true);
+use_contract!(eip20_from_env, path_env = "ETHABI_TESTS_EIP20_ABI");
+use_contract!(pub(crate) eip20_reexported, "../res/eip20.abi", crate = "crate::reexport::ethabi");
+
+/// Re-export of ethabi, as a crate wrapping it would have.
+mod reexport {
+	pub use ethabi;
+}
+
+#[allow(dead_code)]
+#[allow(missing_docs)]
+#[allow(unused_imports)]
+#[allow(unused_mut)]
+#[allow(unused_variables)]
+#[allow(clippy::all)]
+pub mod generated {
+	include!(concat!(env!("OUT_DIR"), "/eip20.rs"));
+}
+use_contract!(
+	inline,
+	abi = r#"[
+		{"type":"function","name":"ping","inputs":[{"name":"value","type":"uint256"}],"outputs":[],"stateMutability":"nonpayable"},
+		{"type":"receive","stateMutability":"payable"},
+		{"type":"fallback","stateMutability":"payable"}
+	]"#
+);
+use_contract!(
+	deployable,
+	abi = r#"[{"type":"constructor","inputs":[{"name":"supply","type":"uint256"}],"stateMutability":"nonpayable"}]"#,
+	bytecode_path = "../res/deployable.bin"
+);
 
 #[cfg(test)]
 mod tests {
-	use crate::{eip20, validators};
+	use crate::{
+		callbacks, deployable, eip20, eip20_builders, eip20_from_env, eip20_reexported, eip20_serde, errors,
+		fixed_arrays, generated, indexed_events, inline, natives, overloads, signed, tuples, tuples_serde,
+		urlhint_structs, validators,
+	};
 	use ethabi::{Address, Uint};
 	use hex_literal::hex;
 
 	struct Wrapper([u8; 20]);
 
-	impl Into<Address> for Wrapper {
-		fn into(self) -> Address {
-			self.0.into()
+	impl From<Wrapper> for Address {
+		fn from(wrapper: Wrapper) -> Self {
+			wrapper.0.into()
 		}
 	}
 
 	#[test]
+	#[allow(clippy::useless_conversion)]
 	fn test_encoding_function_input_as_array() {
 		use validators::functions;
 
@@ -44,6 +91,34 @@ mod tests {
 		assert_eq!(expected, hex::encode(&encoded_from_vec_wrapped));
 	}
 
+	#[test]
+	fn test_abi_path_from_env() {
+		let to = Address::from([0x11u8; 20]);
+		assert_eq!(
+			eip20_from_env::functions::transfer::encode_input(to, 5),
+			eip20::functions::transfer::encode_input(to, 5)
+		);
+	}
+
+	#[test]
+	fn test_crate_path() {
+		let to = Address::from([0x11u8; 20]);
+		assert_eq!(
+			eip20_reexported::functions::transfer::encode_input(to, 5),
+			eip20::functions::transfer::encode_input(to, 5)
+		);
+	}
+
+	#[test]
+	fn test_build_script_bindings() {
+		let to = Address::from([0x11u8; 20]);
+		assert_eq!(
+			generated::functions::transfer::encode_input(to, 5),
+			eip20::functions::transfer::encode_input(to, 5)
+		);
+		assert_eq!(generated::functions::transfer::SELECTOR, eip20::functions::transfer::SELECTOR);
+	}
+
 	#[test]
 	fn test_decoding_function_output() {
 		// Make sure that the output param type of the derived contract is correct
@@ -62,6 +137,401 @@ mod tests {
 	}
 
 	#[test]
+	fn test_native_integers() {
+		let encoded = natives::functions::echo::encode_input(
+			255u8,
+			-300i16,
+			u64::MAX,
+			i64::MIN,
+			vec![Uint::from(0xffffff), Uint::zero()],
+			(7u32, -1i8),
+			Uint::from(u128::MAX),
+		);
+		let args = &encoded[4..];
+		assert_eq!(args[32..64], [0xff; 30].iter().chain(&(-300i16).to_be_bytes()).copied().collect::<Vec<_>>()[..]);
+
+		// the outputs have the same types as the inputs
+		let decoded = natives::functions::echo::decode_output(args).unwrap();
+		assert_eq!(
+			decoded,
+			(255, -300, u64::MAX, i64::MIN, vec![Uint::from(0xffffff), Uint::zero()], (7, -1), Uint::from(u128::MAX))
+		);
+
+		// values which do not fit the declared width fail to decode
+		let mut out_of_range = args.to_vec();
+		out_of_range[30] = 1;
+		assert!(natives::functions::echo::decode_output(&out_of_range).is_err());
+
+		let mut out_of_range = args.to_vec();
+		out_of_range[32 + 29] = 0;
+		assert!(natives::functions::echo::decode_output(&out_of_range).is_err());
+	}
+
+	#[test]
+	fn test_decode_input_round_trip() {
+		use eip20::functions::{approve, transfer};
+		let to = Address::from_low_u64_be(7);
+		let data = transfer::encode_input(to, 42);
+		assert_eq!(transfer::decode_input(&data).unwrap(), (to, Uint::from(42)));
+
+		// calls to other functions and truncated call data are errors
+		assert!(approve::decode_input(&data).is_err());
+		assert!(transfer::decode_input(&data[..3]).is_err());
+		assert!(transfer::decode_input(&data[..40]).is_err());
+
+		let orders = vec![(Address::from_low_u64_be(1), Uint::from(10))];
+		let nested = (Uint::from(7), (Address::from_low_u64_be(3), true));
+		let data = tuples::functions::settle::encode_input(orders.clone(), nested);
+		assert_eq!(tuples::functions::settle::decode_input(&data).unwrap(), (orders, nested));
+	}
+
+	#[test]
+	fn test_call_builder() {
+		use eip20_builders::functions::transfer_from;
+		let from = Address::from_low_u64_be(1);
+		let to = Address::from_low_u64_be(2);
+
+		// setters can be called in any order
+		let built = transfer_from::builder().with_value(42).with_to(to).with_from(from).encode().unwrap();
+		assert_eq!(built, transfer_from::encode_input(from, to, 42));
+
+		match transfer_from::builder().with_from(from).with_value(42).encode() {
+			Err(ethabi::Error::MissingArgument(name)) => assert_eq!(name, "to"),
+			other => panic!("expected a missing `to`, got {:?}", other),
+		}
+	}
+
+	#[test]
+	fn test_inline_abi() {
+		use inline::functions;
+		assert_eq!(functions::ping::SELECTOR, hex!("773acdef"));
+		let data = functions::ping::encode_input(1);
+		assert_eq!(data.len(), 4 + 32);
+		assert_eq!(data[4 + 31], 1);
+	}
+
+	#[test]
+	fn test_parse_transfer_log() {
+		let from = Address::from_low_u64_be(1);
+		let to = Address::from_low_u64_be(2);
+		let log = ethabi::RawLog {
+			topics: vec![
+				hex!("ddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef").into(),
+				from.into(),
+				to.into(),
+			],
+			data: hex!("000000000000000000000000000000000000000000000000000000000000002a").to_vec(),
+		};
+
+		assert_eq!(log.topics[0], eip20::events::transfer::SIGNATURE);
+		let transfer = eip20::events::transfer::parse_log(log.clone()).unwrap();
+		assert_eq!(transfer, eip20::logs::Transfer { from, to, value: 42.into() });
+
+		// the signature topic has to match the event
+		let mut approval = log;
+		approval.topics[0] = [0u8; 32].into();
+		assert!(eip20::events::transfer::parse_log(approval).is_err());
+	}
+
+	#[test]
+	fn test_output_structs() {
+		use urlhint_structs::functions::entries;
+
+		let owner = Address::from_low_u64_be(1);
+		let output = ethabi::encode(&[
+			ethabi::Token::String("foo/bar".to_owned()),
+			ethabi::Token::FixedBytes(vec![0x11; 20]),
+			ethabi::Token::Address(owner),
+		]);
+		let entry = entries::decode_output(&output).unwrap();
+		assert_eq!(entry, entries::Output { account_slash_repo: "foo/bar".to_owned(), commit: [0x11; 20], owner });
+		assert_eq!(
+			serde_json::to_value(&entry).unwrap(),
+			serde_json::json!({
+				"account_slash_repo": "foo/bar",
+				"commit": vec![0x11; 20],
+				"owner": "0x0000000000000000000000000000000000000001",
+			})
+		);
+
+		let transfer = eip20_serde::logs::Transfer { from: owner, to: owner, value: 42.into() };
+		assert_eq!(serde_json::to_value(&transfer).unwrap()["value"], "0x2a");
+	}
+
+	/// Answers every call with the balance of its owner argument, checking the called contract.
+	struct BalanceCaller(Address);
+
+	impl eip20::Caller for BalanceCaller {
+		type Future = std::future::Ready<ethabi::Result<ethabi::Bytes>>;
+
+		fn call(&self, to: Address, data: ethabi::Bytes) -> Self::Future {
+			assert_eq!(to, self.0);
+			assert_eq!(data[..4], eip20::functions::balance_of::SELECTOR);
+			std::future::ready(Ok(data[4..].to_vec()))
+		}
+	}
+
+	#[test]
+	fn test_instance() {
+		use std::{
+			future::Future,
+			pin::pin,
+			task::{Context, Poll, Waker},
+		};
+
+		let token = Address::from_low_u64_be(1);
+		let owner = Address::from_low_u64_be(42);
+		let instance = eip20::Instance { caller: BalanceCaller(token), address: token };
+		let mut balance = pin!(instance.balance_of(owner));
+		let poll = balance.as_mut().poll(&mut Context::from_waker(Waker::noop()));
+		assert!(matches!(poll, Poll::Ready(Ok(balance)) if balance == Uint::from(42)));
+	}
+
+	#[test]
+	fn test_parse_log_with_indexed_string() {
+		let owner = Address::from_low_u64_be(3);
+		// keccak256("alice")
+		let name_hash = hex!("9c0257114eb9399a2985f8e75dad7600c5d89fe3824ffa99ec1c3eb8bf3b0501");
+		let log = ethabi::RawLog {
+			topics: vec![
+				hex!("50f74ca45caac8020b8d891bd13ea5a2d79564986ee6a839f0d914896388322d").into(),
+				name_hash.into(),
+			],
+			data: ethabi::encode(&[ethabi::Token::Address(owner)]),
+		};
+
+		let registered = indexed_events::events::registered::parse_log(log).unwrap();
+		assert_eq!(registered, indexed_events::logs::Registered { name: name_hash.into(), owner });
+	}
+
+	#[test]
+	fn test_function_selectors() {
+		assert_eq!(eip20::functions::transfer::SELECTOR, hex!("a9059cbb"));
+
+		// overloads get their own module and selector
+		use overloads::functions::{balance_of_0, balance_of_1};
+		assert_eq!(balance_of_0::SELECTOR, hex!("70a08231"));
+		assert_eq!(balance_of_1::SELECTOR, hex!("00fdd58e"));
+
+		let data = balance_of_1::encode_input(Address::zero(), 1);
+		assert_eq!(data[..4], balance_of_1::SELECTOR);
+	}
+
+	#[test]
+	fn test_match_on_selectors_and_topics() {
+		let dispatch = |data: &[u8]| match [data[0], data[1], data[2], data[3]] {
+			eip20::functions::transfer::SELECTOR => "transfer",
+			eip20::functions::approve::SELECTOR => "approve",
+			_ => "unknown",
+		};
+		assert_eq!(dispatch(&eip20::functions::approve::encode_input(Address::zero(), 1)), "approve");
+		assert_eq!(dispatch(&[0; 4]), "unknown");
+
+		let topic = eip20::events::transfer::SIGNATURE;
+		assert!(matches!(topic.0, eip20::events::transfer::TOPIC0));
+		assert!(!matches!(topic.0, eip20::events::approval::TOPIC0));
+
+		assert_eq!(errors::errors::unauthorized::SELECTOR, hex!("82b42900"));
+		let data = errors::errors::insufficient_balance::encode(1, 2);
+		assert_eq!(data[..4], errors::errors::insufficient_balance::SELECTOR);
+	}
+
+	#[test]
+	fn test_canonical_signatures() {
+		use tuples::functions::settle;
+		assert_eq!(settle::CANONICAL_SIGNATURE, "settle((address,uint256)[],(uint256,(address,bool)))");
+		assert_eq!(settle::SELECTOR, hex!("ac71b40d"));
+
+		assert_eq!(eip20::functions::transfer::CANONICAL_SIGNATURE, "transfer(address,uint256)");
+		assert_eq!(eip20::events::transfer::CANONICAL_SIGNATURE, "Transfer(address,address,uint256)");
+	}
+
+	#[test]
+	fn test_overloads_do_not_clobber() {
+		// `Foo` comes first in the ABI order and clashes with the `foo` overloads once snake cased
+		use overloads::functions::{foo_0, foo_1, foo_2};
+		assert_eq!(foo_0::SELECTOR, hex!("41b98498"));
+		assert_eq!(foo_1::encode_input(), hex!("c2985578").to_vec());
+		assert_eq!(foo_2::encode_input(7)[..4], hex!("2fbebd38"));
+
+		let owner = Address::from_low_u64_be(1);
+		let log = ethabi::RawLog {
+			topics: vec![hex!("e1fffcc4923d04b559f4d29a8bfc6cda04eb5b0d3c460751c2402c5c5cc9109c").into(), owner.into()],
+			data: ethabi::encode(&[ethabi::Token::Uint(5.into())]),
+		};
+		assert!(overloads::events::deposit_0::parse_log(log.clone()).is_err());
+		let deposit = overloads::events::deposit_1::parse_log(log).unwrap();
+		assert_eq!(deposit, overloads::logs::Deposit1 { owner, amount: 5.into() });
+	}
+
+	#[test]
+	fn test_custom_errors() {
+		let data = errors::errors::insufficient_balance::encode(1, 2);
+		assert_eq!(errors::errors::insufficient_balance::decode(&data).unwrap(), (Uint::from(1), Uint::from(2)));
+		assert!(errors::errors::unauthorized::decode(&data).is_err());
+
+		let data = errors::errors::unauthorized::encode();
+		assert_eq!(data, hex!("82b42900").to_vec());
+		errors::errors::unauthorized::decode(&data).unwrap();
+	}
+
+	#[test]
+	fn test_fixed_array_round_trip() {
+		let values = [1u8, 2, 3].map(Uint::from);
+		let codes = [*b"abc", *b"def", *b"ghi", *b"jkl"];
+
+		let encoded = fixed_arrays::functions::echo::encode_input(values, codes);
+
+		// the outputs have the same types as the inputs
+		let decoded: ([Uint; 3], [[u8; 3]; 4]) = fixed_arrays::functions::echo::decode_output(&encoded[4..]).unwrap();
+		assert_eq!(decoded, (values, codes));
+
+		// truncated output is an error rather than a panic
+		for len in [0, 32, 7 * 32 - 1] {
+			assert!(fixed_arrays::functions::echo::decode_output(&encoded[4..4 + len]).is_err());
+		}
+	}
+
+	#[test]
+	fn test_function_type_round_trip() {
+		// an address followed by a selector
+		let callback = hex!("1111111111111111111111111111111111111111a9059cbb");
+
+		let encoded = callbacks::functions::register::encode_input(callback);
+		assert_eq!(
+			encoded,
+			hex!(
+				"
+				b9ee2f63
+				1111111111111111111111111111111111111111a9059cbb0000000000000000
+			"
+			)
+		);
+
+		// the output has the same type as the input
+		assert_eq!(callbacks::functions::register::decode_output(&encoded[4..]).unwrap(), callback);
+		assert_eq!(callbacks::functions::register::decode_input(&encoded).unwrap(), callback);
+	}
+
+	#[test]
+	fn test_tuple_round_trip() {
+		use tuples::types::{Order, Owner, Settlement};
+
+		// the structs named after the `internalType` of the params convert into their tuples
+		let orders = vec![
+			Order { maker: Address::from_low_u64_be(1), amount: Uint::from(10) },
+			Order { maker: Address::from_low_u64_be(2), amount: Uint::from(20) },
+		];
+		let nested =
+			Settlement { id: Uint::from(7), inner: Owner { owner: Address::from_low_u64_be(3), active: true } };
+
+		let encoded = tuples::functions::settle::encode_input(orders.clone(), nested.clone());
+		// settle((address,uint256)[],(uint256,(address,bool)))
+		assert_eq!(encoded[..4], hex!("ac71b40d"));
+
+		// and back from the tuples of the outputs
+		let (decoded_orders, decoded_nested) = tuples::functions::settle::decode_output(&encoded[4..]).unwrap();
+		assert_eq!(decoded_orders.into_iter().map(Order::from).collect::<Vec<_>>(), orders);
+		assert_eq!(Settlement::from(decoded_nested), nested);
+
+		let token = ethabi::Tokenizable::into_token(nested.clone());
+		assert_eq!(
+			token,
+			ethabi::Token::Tuple(vec![
+				ethabi::Token::Uint(7.into()),
+				ethabi::Token::Tuple(vec![
+					ethabi::Token::Address(Address::from_low_u64_be(3)),
+					ethabi::Token::Bool(true)
+				]),
+			])
+		);
+		assert_eq!(<Settlement as ethabi::Detokenizable>::from_token(token).unwrap(), nested);
+		assert!(<Settlement as ethabi::Detokenizable>::from_token(ethabi::Token::Tuple(vec![])).is_err());
+	}
+
+	#[test]
+	fn test_serde_structs() {
+		use tuples_serde::types::{Order, Owner, Settlement};
+
+		let nested =
+			Settlement { id: Uint::from(7), inner: Owner { owner: Address::from_low_u64_be(3), active: true } };
+		let encoded = tuples_serde::functions::settle::encode_input(Vec::<Order>::new(), nested.clone());
+		let (_, decoded) = tuples_serde::functions::settle::decode_output(&encoded[4..]).unwrap();
+		let decoded = Settlement::from(decoded);
+
+		// structs decoded from the outputs serialize with a field per component
+		let json = serde_json::to_value(&decoded).unwrap();
+		assert_eq!(
+			json,
+			serde_json::json!({
+				"id": "0x7",
+				"inner": { "owner": "0x0000000000000000000000000000000000000003", "active": true },
+			})
+		);
+		assert_eq!(serde_json::from_value::<Settlement>(json).unwrap(), nested);
+	}
+
+	#[test]
+	fn test_corrupted_output_is_an_error() {
+		let orders = vec![(Address::from_low_u64_be(1), Uint::from(10))];
+		let nested = (Uint::from(7), (Address::from_low_u64_be(3), true));
+		let output = tuples::functions::settle::encode_input(orders, nested)[4..].to_vec();
+
+		// every single corrupted byte either decodes to other values or fails, without panicking
+		for index in 0..output.len() {
+			let mut corrupted = output.clone();
+			corrupted[index] ^= 0xff;
+			let _ = tuples::functions::settle::decode_output(&corrupted);
+		}
+
+		// the bool of the nested tuple
+		let mut corrupted = output.clone();
+		corrupted[5 * 32 - 1] = 2;
+		assert!(tuples::functions::settle::decode_output(&corrupted).is_err());
+
+		// the offset of the orders
+		let mut corrupted = output;
+		corrupted[0] = 0xff;
+		assert!(tuples::functions::settle::decode_output(&corrupted).is_err());
+	}
+
+	#[test]
+	fn test_decoding_negative_int_array_output() {
+		let output = hex!(
+			"
+			0000000000000000000000000000000000000000000000000000000000000020
+			0000000000000000000000000000000000000000000000000000000000000003
+			ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff
+			0000000000000000000000000000000000000000000000000000000000000002
+			fffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffd
+		"
+		)
+		.to_vec();
+
+		let decoded_output = signed::functions::deltas::decode_output(&output).unwrap();
+
+		// signed integers are two's complement
+		let minus = |x: u64| !Uint::from(x) + 1;
+		assert_eq!(decoded_output, vec![minus(1), 2.into(), minus(3)]);
+	}
+
+	#[test]
+	fn test_encoding_deployment() {
+		let code = hex!("6080604052").to_vec();
+
+		let encoded = deployable::constructor(code.clone(), 1000);
+		assert_eq!(encoded[..5], code[..]);
+		assert_eq!(encoded[5..], hex!("00000000000000000000000000000000000000000000000000000000000003e8"));
+
+		assert_eq!(deployable::BYTECODE, &code[..]);
+		assert_eq!(deployable::deploy_data(1000), encoded);
+
+		// without a constructor in the ABI the code is deployed as is
+		assert_eq!(inline::constructor(code.clone()), code);
+	}
+
+	#[test]
+	#[allow(clippy::useless_conversion)]
 	fn test_encoding_constructor_as_array() {
 		use validators::constructor;
 
@@ -114,4 +584,39 @@ mod tests {
 		let wildcard_filter_sugared = eip20::events::transfer::wildcard_filter();
 		assert_eq!(wildcard_filter, wildcard_filter_sugared);
 	}
+
+	#[derive(Debug, Clone, PartialEq, ethabi_derive::EthabiTokenize)]
+	struct Order {
+		maker: Address,
+		amount: Uint,
+	}
+
+	#[derive(Debug, Clone, PartialEq, ethabi_derive::EthabiTokenize)]
+	struct Inner(Address, bool);
+
+	#[derive(Debug, Clone, PartialEq, ethabi_derive::EthabiTokenize)]
+	struct Nested {
+		id: u64,
+		inner: Inner,
+	}
+
+	#[test]
+	fn test_tokenize_structs() {
+		use ethabi::{param_type::Reader, Detokenizable, Tokenizable};
+
+		let orders = vec![Order { maker: Address::from_low_u64_be(1), amount: 10.into() }];
+		let nested = Nested { id: 7, inner: Inner(Address::from_low_u64_be(3), true) };
+		let encoded = ethabi::encode(&[orders.clone().into_token(), nested.clone().into_token()]);
+		let expected = tuples::functions::settle::encode_input(
+			vec![(Address::from_low_u64_be(1), Uint::from(10))],
+			(Uint::from(7), (Address::from_low_u64_be(3), true)),
+		);
+		assert_eq!(encoded, expected[4..]);
+
+		let types = [Reader::read("(address,uint256)[]").unwrap(), Reader::read("(uint256,(address,bool))").unwrap()];
+		let mut tokens = ethabi::decode(&types, &encoded).unwrap().into_iter();
+		assert_eq!(Vec::<Order>::from_token(tokens.next().unwrap()).unwrap(), orders);
+		assert_eq!(Nested::from_token(tokens.next().unwrap()).unwrap(), nested);
+		assert!(Inner::from_token(Order { maker: Address::zero(), amount: 1.into() }.into_token()).is_err());
+	}
 }